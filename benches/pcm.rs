@@ -0,0 +1,46 @@
+//! Float to 16-bit PCM conversion, compared with the per-sample loop it replaced.
+//!
+//! Run with `cargo bench --bench pcm`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+// Only part of the module is exercised here, and its unit tests do not run without the harness
+#[allow(dead_code, unused_imports)]
+#[path = "../src/pcm.rs"]
+mod pcm;
+
+/// The original conversion: `f32::round` per sample and a growing `Vec`
+fn scalar_pcm16(samples: &[f32]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(samples.len() * 2);
+    for &sample in samples {
+        let clamped = sample.clamp(-1.0, 1.0);
+        let value = if clamped <= -1.0 {
+            i16::MIN
+        } else {
+            (clamped * i16::MAX as f32).round() as i16
+        };
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
+
+fn bench_pcm16(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pcm16");
+    // A short sentence, a paragraph, and a few minutes of narration at 24 kHz
+    for seconds in [2, 30, 300] {
+        let samples: Vec<f32> = (0..seconds * 24_000)
+            .map(|i| (i as f32 * 0.013).sin() * 0.8)
+            .collect();
+        group.throughput(Throughput::Elements(samples.len() as u64));
+        group.bench_with_input(BenchmarkId::new("scalar", seconds), &samples, |b, s| {
+            b.iter(|| scalar_pcm16(black_box(s)))
+        });
+        group.bench_with_input(BenchmarkId::new("blocked", seconds), &samples, |b, s| {
+            b.iter(|| pcm::samples_to_pcm16(black_box(s)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_pcm16);
+criterion_main!(benches);
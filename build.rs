@@ -0,0 +1,100 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "grpc")]
+    {
+        // Use the bundled protoc so building with gRPC needs no system protobuf install
+        if std::env::var_os("PROTOC").is_none() {
+            std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+        }
+        tonic_prost_build::configure()
+            .build_client(false)
+            .compile_protos(&["proto/kokoro.proto"], &["proto"])?;
+    }
+
+    #[cfg(feature = "embedded-espeak-data")]
+    embed_espeak_data()?;
+
+    emit_build_info();
+
+    println!("cargo:rerun-if-changed=proto/kokoro.proto");
+    Ok(())
+}
+
+/// Record the commit, target, profile, and compiler for GET /version
+fn emit_build_info() {
+    use std::process::Command;
+
+    // Builds from a source archive have no .git; packagers can pass the commit instead
+    const GIT_SHA_ENV: &str = "KOKORO_BUILD_GIT_SHA";
+    println!("cargo:rerun-if-env-changed={GIT_SHA_ENV}");
+    let git_sha = std::env::var(GIT_SHA_ENV).ok().or_else(|| {
+        let output = Command::new("git")
+            .args(["rev-parse", "--short=12", "HEAD"])
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    });
+    println!(
+        "cargo:rustc-env={GIT_SHA_ENV}={}",
+        git_sha.unwrap_or_else(|| "unknown".to_string())
+    );
+    if let Ok(head) = std::fs::read_to_string(".git/HEAD") {
+        println!("cargo:rerun-if-changed=.git/HEAD");
+        if let Some(reference) = head.trim().strip_prefix("ref: ") {
+            let path = std::path::Path::new(".git").join(reference);
+            if path.exists() {
+                println!("cargo:rerun-if-changed={}", path.display());
+            }
+        }
+    }
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=KOKORO_BUILD_RUSTC={rustc_version}");
+
+    for (name, key) in [
+        ("TARGET", "KOKORO_BUILD_TARGET"),
+        ("PROFILE", "KOKORO_BUILD_PROFILE"),
+    ] {
+        let value = std::env::var(name).unwrap_or_else(|_| "unknown".to_string());
+        println!("cargo:rustc-env={key}={value}");
+    }
+}
+
+/// Pack espeak-ng-data into `OUT_DIR` so the binary can carry it
+#[cfg(feature = "embedded-espeak-data")]
+fn embed_espeak_data() -> Result<(), Box<dyn std::error::Error>> {
+    use flate2::{write::GzEncoder, Compression};
+    use std::path::{Path, PathBuf};
+
+    const DATA_DIR_ENV: &str = "KOKORO_EMBED_ESPEAK_DATA_DIRECTORY";
+    println!("cargo:rerun-if-env-changed={DATA_DIR_ENV}");
+
+    let home = std::env::var_os(DATA_DIR_ENV).ok_or_else(|| {
+        format!("the embedded-espeak-data feature needs {DATA_DIR_ENV} set to a directory containing espeak-ng-data")
+    })?;
+    let data = Path::new(&home).join("espeak-ng-data");
+    if !data.is_dir() {
+        return Err(format!(
+            "{} does not contain espeak-ng-data",
+            Path::new(&home).display()
+        )
+        .into());
+    }
+    println!("cargo:rerun-if-changed={}", data.display());
+
+    let out = PathBuf::from(std::env::var("OUT_DIR")?).join("espeak-ng-data.tar.gz");
+    let encoder = GzEncoder::new(std::fs::File::create(&out)?, Compression::best());
+    let mut archive = tar::Builder::new(encoder);
+    archive.append_dir_all("espeak-ng-data", &data)?;
+    archive.into_inner()?.finish()?;
+    Ok(())
+}
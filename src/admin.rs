@@ -0,0 +1,291 @@
+use crate::{
+    api::{constant_time_eq, reload_handler, request_span, AppState},
+    backend::KokoroBackend,
+    cache::CacheStats,
+    config::MAX_WORKERS,
+    debug_tap,
+    error::{ApiResult, AppError},
+    extract::ApiJson,
+    keys::{KeySummary, NewKey},
+    load_shed::{read_gpu_stats, GpuStats, ShedStatus},
+    metrics::MetricsSnapshot,
+};
+use axum::{
+    extract::{Path, Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tower_http::trace::TraceLayer;
+use tracing::{error, info};
+
+/// Response body for GET /admin/stats
+#[derive(Debug, Serialize)]
+pub struct StatsResponse {
+    /// The full model's pool
+    #[serde(flatten)]
+    pub backend: BackendStats,
+    /// The draft model's pool; absent without KOKORO_DRAFT_MODEL_PATH
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub draft: Option<BackendStats>,
+    /// Absent when the audio cache is disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CacheStats>,
+    /// Absent when load shedding is disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_shedding: Option<ShedStatus>,
+    /// GPU inference runs on; absent unless the CUDA provider is in use and `nvidia-smi` can be
+    /// queried
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gpu: Option<GpuStats>,
+}
+
+/// Load state and counters of one model's worker pool
+#[derive(Debug, Serialize)]
+pub struct BackendStats {
+    pub loaded: bool,
+    pub ready: bool,
+    pub execution_provider: String,
+    pub in_flight: usize,
+    pub queued: usize,
+    pub inference_threads: usize,
+    pub synthesis: MetricsSnapshot,
+}
+
+impl BackendStats {
+    fn of(backend: &KokoroBackend) -> Self {
+        Self {
+            loaded: backend.is_loaded(),
+            ready: backend.is_ready(),
+            execution_provider: backend.execution_provider().to_string(),
+            in_flight: backend.in_flight(),
+            queued: backend.queued(),
+            inference_threads: backend.inference_threads(),
+            synthesis: backend.metrics().snapshot(backend.sample_rate()),
+        }
+    }
+}
+
+/// Response body for POST /admin/cache/purge
+#[derive(Debug, Serialize)]
+pub struct PurgeResponse {
+    pub memory_entries: usize,
+    pub disk_entries: usize,
+}
+
+/// Request body for POST /admin/keys
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateKeyRequest {
+    /// Label shown when listing keys
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Response body for GET /admin/keys
+#[derive(Debug, Serialize)]
+pub struct KeysResponse {
+    pub object: &'static str,
+    pub data: Vec<KeySummary>,
+}
+
+/// Request body for PATCH /admin/config; settings left out are unchanged
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigPatch {
+    /// Inference workers of the full model, from 1 to `MAX_WORKERS`; the draft model's pool
+    /// keeps its startup size
+    #[serde(default)]
+    pub workers: Option<usize>,
+}
+
+/// Response body for GET and PATCH /admin/config
+#[derive(Debug, Serialize)]
+pub struct RuntimeConfig {
+    pub workers: usize,
+    /// "reloading" while a model reload or worker resize is loading engines; `workers` changes
+    /// once a resize completes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<&'static str>,
+}
+
+/// Create the admin router served on the dedicated admin listener.
+///
+/// Requests must present `token` as a bearer token; without a token (Unix
+/// socket only) access is governed by the socket's file permissions.
+pub fn create_admin_router(state: AppState, token: Option<String>) -> Router {
+    Router::new()
+        .route("/admin/reload", post(reload_handler))
+        .route("/admin/stats", get(stats_handler))
+        .route(
+            "/admin/config",
+            get(config_handler).patch(patch_config_handler),
+        )
+        .route("/admin/cache/purge", post(purge_cache_handler))
+        .route(
+            "/admin/keys",
+            get(list_keys_handler).post(create_key_handler),
+        )
+        .route("/admin/keys/{key_id}", delete(revoke_key_handler))
+        .route(
+            "/admin/debug/requests",
+            get(debug_tap::list_handler).delete(debug_tap::clear_handler),
+        )
+        .route(
+            "/admin/debug/requests/{request_id}/audio",
+            get(debug_tap::audio_handler),
+        )
+        .layer(TraceLayer::new_for_http().make_span_with(request_span))
+        .layer(middleware::from_fn_with_state(
+            token.map(Arc::<str>::from),
+            admin_auth_middleware,
+        ))
+        .with_state(state)
+}
+
+/// Whether the request carries the admin bearer token
+fn is_authorized(headers: &HeaderMap, token: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    headers
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .is_some_and(|provided| constant_time_eq(provided, token))
+}
+
+async fn admin_auth_middleware(
+    State(token): State<Option<Arc<str>>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if is_authorized(request.headers(), token.as_deref()) {
+        Ok(next.run(request).await)
+    } else {
+        Err(AppError::Unauthorized)
+    }
+}
+
+async fn stats_handler(State(state): State<AppState>) -> Json<StatsResponse> {
+    Json(StatsResponse {
+        backend: BackendStats::of(&state.backend),
+        draft: state.draft_backend.as_deref().map(BackendStats::of),
+        cache: state.cache.as_ref().map(|cache| cache.stats()),
+        load_shedding: state.load_shedder.as_ref().map(|shedder| shedder.status()),
+        gpu: match state.backend.cuda_device() {
+            Some(device) => read_gpu_stats(device).await,
+            None => None,
+        },
+    })
+}
+
+async fn config_handler(State(state): State<AppState>) -> Json<RuntimeConfig> {
+    Json(RuntimeConfig {
+        workers: state.backend.worker_limit(),
+        status: state.backend.is_reloading().then_some("reloading"),
+    })
+}
+
+/// Change settings that can be adjusted without a restart
+async fn patch_config_handler(
+    State(state): State<AppState>,
+    ApiJson(patch): ApiJson<ConfigPatch>,
+) -> ApiResult<impl IntoResponse> {
+    let current = state.backend.worker_limit();
+    let Some(workers) = patch.workers.filter(|&workers| workers != current) else {
+        return Ok((StatusCode::OK, config_handler(State(state)).await));
+    };
+    if workers == 0 || workers > MAX_WORKERS {
+        return Err(AppError::invalid_request(format!(
+            "workers must be between 1 and {}",
+            MAX_WORKERS
+        )));
+    }
+
+    if !state.backend.start_resize(workers) {
+        return Err(AppError::conflict(
+            "A model reload or worker resize is already in progress",
+        ));
+    }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(RuntimeConfig {
+            workers: current,
+            status: Some("reloading"),
+        }),
+    ))
+}
+
+/// Drop every cached clip from memory and disk
+async fn purge_cache_handler(State(state): State<AppState>) -> ApiResult<Json<PurgeResponse>> {
+    let cache = state
+        .cache
+        .clone()
+        .ok_or_else(|| AppError::invalid_request("The audio cache is disabled"))?;
+
+    let (memory_entries, disk_entries) = tokio::task::spawn_blocking(move || cache.purge())
+        .await
+        .map_err(|e| {
+        error!(error = %e, "Cache purge task failed");
+        AppError::Internal
+    })?;
+    info!(memory_entries, disk_entries, "Audio cache purged");
+
+    Ok(Json(PurgeResponse {
+        memory_entries,
+        disk_entries,
+    }))
+}
+
+async fn list_keys_handler(State(state): State<AppState>) -> Json<KeysResponse> {
+    Json(KeysResponse {
+        object: "list",
+        data: state.api_keys.list(),
+    })
+}
+
+/// Issue an API key for the public port; the secret is only returned here
+async fn create_key_handler(
+    State(state): State<AppState>,
+    req: Option<ApiJson<CreateKeyRequest>>,
+) -> (StatusCode, Json<NewKey>) {
+    let req = req.map(|ApiJson(req)| req).unwrap_or_default();
+    let key = state.api_keys.issue(req.name);
+    info!(key_id = %key.summary.id, "API key issued");
+    (StatusCode::CREATED, Json(key))
+}
+
+async fn revoke_key_handler(
+    State(state): State<AppState>,
+    Path(key_id): Path<String>,
+) -> ApiResult<impl IntoResponse> {
+    if !state.api_keys.revoke(&key_id) {
+        return Err(AppError::key_not_found(key_id));
+    }
+    info!(key_id = %key_id, "API key revoked");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_is_authorized() {
+        let mut headers = HeaderMap::new();
+        assert!(is_authorized(&headers, None));
+        assert!(!is_authorized(&headers, Some("admin")));
+
+        headers.insert("authorization", HeaderValue::from_static("Bearer wrong"));
+        assert!(!is_authorized(&headers, Some("admin")));
+
+        headers.insert("authorization", HeaderValue::from_static("Bearer admin"));
+        assert!(is_authorized(&headers, Some("admin")));
+    }
+}
@@ -1,15 +1,41 @@
 use crate::{
-    backend::KokoroBackend,
-    error::{ApiResult, AppError},
+    backend::{AudioData, BackendUnavailable, KokoroBackend, QueueFull, SynthesisTimeout},
+    cache::{content_type_for_format, AudioCache, CacheKey, CachedAudio},
+    config::{KOKORO_DRAFT_MODEL_ID, KOKORO_MODEL_ID, MAX_SENTENCE_GAP_MS},
+    debug_tap::DebugTap,
+    effects::{Compressor, Effects, Filter, COMPRESSOR_PRESETS, FILTER_PRESETS},
+    error::{ApiResult, AppError, OpenAIError},
+    extract::{ApiBody, ApiJson, ApiQuery, FormFields},
+    fetch::FetchPolicy,
+    icecast::RadioQueue,
+    jobs::JobStore,
+    keys::ApiKeys,
+    language::Language,
+    load_shed::LoadShedder,
+    model_assets::{model_quantization, ModelFiles},
+    normalize::{normalize, NormalizeRules, NumberStyle, NUMBER_STYLES},
+    pcm::{pcm_i16_from_f32, pcm_i24_from_f32, samples_to_pcm16, BitDepth},
+    plan::{estimated_output_bytes, plan, SpeechPlan},
+    presets::{silence_samples, NamedPreset, Presets},
+    redact,
+    speed_curve::{SpeedCurve, SpeedPoint},
+    spell::spell_out,
+    streaming::{ChunkProfiles, ChunkRetryPolicy, ChunkRules},
+    style::{Style, STYLE_PRESETS},
+    tags::{tag_audio, AudioMetadata},
     validation::{
-        get_available_voices, openai_alias_voices, validate_input, validate_model,
-        validate_response_format, validate_speed, validate_voice, Voice,
+        sanitize_input, validate_bit_depth, validate_input, validate_language, validate_metadata,
+        validate_model, validate_profile, validate_quality, validate_response_format,
+        validate_speed, validate_voice_or_fallback, Quality, Voice, VoiceAliases,
     },
+    version::{asset_fingerprints, version, AssetFingerprints, VersionResponse},
+    voice_blend::VoiceSpec,
+    voice_pack,
 };
 use axum::{
     body::{Body, Bytes},
-    extract::{Json, State},
-    http::{header, StatusCode},
+    extract::{DefaultBodyLimit, Json, Path, State},
+    http::{header, HeaderMap, StatusCode, Uri},
     middleware::{self, Next},
     response::{IntoResponse, Response},
     routing::{get, post},
@@ -17,49 +43,137 @@ use axum::{
 };
 use kokoros::utils::{mp3::pcm_to_mp3, opus::pcm_to_opus_ogg};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::{debug, error, info, warn};
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi, ToSchema,
+};
+use utoipa_swagger_ui::SwaggerUi;
 use uuid::Uuid;
 
 /// Request body for POST /v1/audio/speech
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
 pub struct SpeechRequest {
     /// Model ID ("tts-1" or "kokoro")
     pub model: String,
     /// Input text to synthesize
     pub input: String,
-    /// Voice ID
-    #[serde(default = "default_voice")]
-    pub voice: String,
-    /// Response format ("wav", "pcm", "mp3", "opus")
-    #[serde(default = "default_response_format")]
-    pub response_format: String,
-    /// Speed multiplier (0.25 to 4.0, default 1.0)
-    #[serde(default = "default_speed")]
-    pub speed: f32,
+    /// Voice ID (default "af_alloy"), or a blend of voices: `af_heart.6+am_adam.4` or
+    /// `[{"id": "af_heart", "weight": 0.6}, {"id": "am_adam", "weight": 0.4}]`
+    #[serde(default, deserialize_with = "crate::voice_blend::deserialize_voice")]
+    #[schema(value_type = Option<VoiceSpec>)]
+    pub voice: Option<String>,
+    /// Response format ("wav", "pcm", "mp3", "opus"; default "wav"); also accepted as
+    /// `format`, which is shorter in query strings
+    #[serde(default, alias = "format")]
+    pub response_format: Option<String>,
+    /// Speed multiplier (0.25 to 4.0); defaults to the voice's preset, then 1.0
+    #[serde(default)]
+    pub speed: Option<f32>,
+    /// Speed varying across the input, as `{"at": 0.0-1.0, "speed": ...}` points in order of
+    /// position; each chunk is read at the curve's speed at its middle, in place of `speed`
+    #[serde(default)]
+    pub speed_curve: Option<Vec<SpeedPoint>>,
+    /// Output profile for a specific consumer ("discord"); overrides `response_format`
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Read `*word*` and `**word**` with stress, slower and louder, instead of as plain text
+    #[serde(default)]
+    pub emphasis: Option<bool>,
+    /// Read the input one character at a time, for codes, call signs, and serial numbers
+    #[serde(default)]
+    pub spell_out: Option<bool>,
+    /// How whole numbers are read: "cardinal" (default), "ordinal", "year", or "digits"
+    #[serde(default)]
+    pub number_style: Option<String>,
+    /// Silence inserted between chunks in milliseconds (at most 5000), for even pauses between
+    /// sentences; defaults to KOKORO_SENTENCE_GAP_MS
+    #[serde(default)]
+    pub sentence_gap_ms: Option<u32>,
+    /// Fill leading, trailing, and inter-sentence silence with very quiet noise instead of
+    /// digital zero, for telephony; defaults to KOKORO_COMFORT_NOISE
+    #[serde(default)]
+    pub comfort_noise: Option<bool>,
+    /// Stop synthesis once the audio runs longer than this many seconds; can lower
+    /// KOKORO_MAX_DURATION_S but not raise it
+    #[serde(default)]
+    pub max_duration_s: Option<u32>,
+    /// Delivery style ("newscaster", "calm", "excited", "whisper"), approximated with speed,
+    /// pitch, EQ, and compression; `effects` and `compressor` replace its own
+    #[serde(default)]
+    pub style: Option<String>,
+    /// "high" (default) or "draft"; draft synthesizes with the quantized model from
+    /// KOKORO_DRAFT_MODEL_PATH in longer chunks, for previews. The "kokoro-draft" model
+    /// selects it too
+    #[serde(default)]
+    pub quality: Option<String>,
     /// Leading silence in samples (applied once per request)
     #[serde(default)]
     pub initial_silence: Option<usize>,
     /// Whether to stream the response
     #[serde(default)]
     pub stream: Option<bool>,
+    /// How a streamed response is framed: "audio" (raw bytes, default) or "sse" (Server-Sent
+    /// Events with base64 PCM and progress); "sse" implies `stream`
+    #[serde(default)]
+    pub stream_format: Option<String>,
+    /// Named preset from the presets file supplying any of the settings above left unset
+    #[serde(default)]
+    pub preset: Option<String>,
+    /// Dynamic range compressor preset ("gentle", "speech", "phone")
+    #[serde(default)]
+    pub compressor: Option<String>,
+    /// Filters applied in order before compression ("highpass", "presence", "telephone")
+    #[serde(default)]
+    pub effects: Option<Vec<String>>,
+    /// Sample depth of WAV output: 16 (default), 24, or 32 (float)
+    #[serde(default)]
+    pub bit_depth: Option<u16>,
+    /// Language of the input ("en-us", "es", "ja", ...); defaults to the voice's language
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Tags written into the file: ID3v2 for "mp3", Vorbis comments for "opus", LIST/INFO
+    /// for "wav"; not supported for "pcm" or streamed responses
+    #[serde(default)]
+    pub metadata: Option<AudioMetadata>,
 }
 
-fn default_voice() -> String {
-    "af_alloy".to_string()
-}
-
-fn default_response_format() -> String {
-    "wav".to_string()
+impl FormFields for SpeechRequest {
+    const JSON_FIELDS: &'static [&'static str] = &[
+        "speed",
+        "speed_curve",
+        "emphasis",
+        "spell_out",
+        "sentence_gap_ms",
+        "comfort_noise",
+        "max_duration_s",
+        "initial_silence",
+        "stream",
+        "effects",
+        "bit_depth",
+        "metadata",
+    ];
 }
 
-fn default_speed() -> f32 {
-    1.0
+/// Request body for POST /admin/reload
+#[derive(Debug, Default, Deserialize)]
+pub struct ReloadRequest {
+    /// Model file to load, inside the startup model's directory and relative to it unless
+    /// absolute (defaults to the currently loaded model)
+    #[serde(default)]
+    pub model_path: Option<PathBuf>,
+    /// Voices file to load, resolved like `model_path` (defaults to `voices.json` next to the
+    /// model)
+    #[serde(default)]
+    pub voices_path: Option<PathBuf>,
 }
 
-fn constant_time_eq(a: &str, b: &str) -> bool {
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
     let a_bytes = a.as_bytes();
     let b_bytes = b.as_bytes();
 
@@ -71,23 +185,14 @@ fn constant_time_eq(a: &str, b: &str) -> bool {
     diff == 0
 }
 
-fn pcm_i16_from_f32(sample: f32) -> i16 {
-    let clamped = sample.clamp(-1.0, 1.0);
-    if clamped <= -1.0 {
-        i16::MIN
-    } else {
-        (clamped * i16::MAX as f32).round() as i16
-    }
-}
-
 /// Response body for GET /v1/models
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ModelsResponse {
     pub object: String,
     pub data: Vec<Model>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct Model {
     pub id: String,
     pub object: String,
@@ -95,41 +200,321 @@ pub struct Model {
     pub owned_by: String,
 }
 
+/// Response body for GET /v1/models/{model_id}
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModelDetail {
+    #[serde(flatten)]
+    pub model: Model,
+    pub kokoro: ModelMetadata,
+}
+
+/// Properties of the Kokoro model that serves a model ID
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModelMetadata {
+    /// Weight precision, e.g. "fp32", "fp16", or "int8"
+    pub quantization: &'static str,
+    pub sample_rate: u32,
+    /// ONNX Runtime execution provider, e.g. "cpu" or "cuda"
+    pub execution_provider: String,
+    /// Whether the model is resident (false while lazily unloaded)
+    pub loaded: bool,
+}
+
 /// Response body for GET /v1/audio/voices
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct VoicesResponse {
     pub object: String,
     pub data: Vec<Voice>,
 }
 
+/// Response body for GET /health
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HealthResponse {
+    /// "healthy" or "unhealthy"
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_loaded: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assets: Option<AssetFingerprints>,
+}
+
+/// Response body for GET /readyz
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    /// "ready", "warming_up", or "recovering"
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warmup_ms: Option<u64>,
+}
+
+/// OpenAPI document for the OpenAI-compatible routes, served at /openapi.json
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Kokoro OpenAI TTS Server",
+        description = "OpenAI-compatible text-to-speech backed by Kokoro"
+    ),
+    paths(
+        speech_handler,
+        speech_get_handler,
+        crate::signed_url::sign_speech_url_handler,
+        speech_plan_handler,
+        crate::phonemes::phonemes_handler,
+        crate::batch::batch_speech_handler,
+        list_voices_handler,
+        list_models_handler,
+        retrieve_model_handler,
+        health_handler,
+        readyz_handler,
+        version_handler,
+        crate::audiobook::create_audiobook_handler,
+        crate::jobs::list_jobs_handler,
+        crate::jobs::get_job_handler,
+        crate::jobs::cancel_job_handler,
+        crate::jobs::job_content_handler,
+        crate::jobs::job_chapter_handler,
+        crate::feeds::feed_handler,
+        crate::feeds::sign_feed_url_handler
+    ),
+    components(schemas(OpenAIError)),
+    modifiers(&ApiKeyAuth),
+    security(("api_key" = []))
+)]
+pub struct ApiDoc;
+
+/// Declares the bearer scheme checked by `auth_middleware` when `API_KEY` is set
+struct ApiKeyAuth;
+
+impl Modify for ApiKeyAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "api_key",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+        }
+    }
+}
+
 /// Application state shared across handlers
 #[derive(Clone)]
 pub struct AppState {
     pub backend: Arc<KokoroBackend>,
-    pub api_key: Option<String>,
+    /// Quantized model serving draft-quality requests, present when KOKORO_DRAFT_MODEL_PATH is set
+    pub draft_backend: Option<Arc<KokoroBackend>>,
+    /// Keys accepted on the public port; auth is off while there are none
+    pub api_keys: Arc<ApiKeys>,
     pub max_input_chars: usize,
+    /// Longest audiobook document, in characters
+    pub max_document_chars: usize,
+    /// Largest estimated non-streamed response, in bytes; `None` disables the check
+    pub max_response_bytes: Option<u64>,
+    /// Longest audio a speech request may produce, in seconds; `None` leaves it unlimited
+    pub max_duration_s: Option<u32>,
+    /// Model IDs accepted by the speech endpoint and listed by `/v1/models`
+    pub model_ids: Arc<[String]>,
+    /// Alias voice names accepted in place of Kokoro voice IDs
+    pub voice_aliases: Arc<VoiceAliases>,
+    /// Voice used instead of rejecting unknown voice IDs
+    pub fallback_voice: Option<String>,
+    /// Per-voice delivery defaults from the presets file
+    pub presets: Arc<Presets>,
+    /// Source of a downloaded model; its file name identifies the quantization
+    pub model_url: String,
+    pub chunk_retry: ChunkRetryPolicy,
+    /// Let streams merge planned chunks once they are ahead of real time
+    pub adaptive_chunks: bool,
+    /// Silence between chunks for requests that do not set their own
+    pub sentence_gap_ms: Option<u32>,
+    /// Fill silences with comfort noise for requests that do not say otherwise
+    pub comfort_noise: bool,
+    /// Where the chunker splits text in each language
+    pub chunk_profiles: Arc<ChunkProfiles>,
+    /// Compose request text to NFC and fold typographic punctuation to ASCII
+    pub fold_punctuation: bool,
+    /// How request text is rewritten before synthesis; `None` passes it through unchanged
+    pub normalize_rules: Option<NormalizeRules>,
+    pub cache: Option<Arc<AudioCache>>,
+    /// Announcement queue, present when Icecast source mode is enabled
+    pub radio: Option<Arc<RadioQueue>>,
+    /// Audiobook jobs and their results
+    pub jobs: Arc<JobStore>,
+    /// Pages audiobook requests may name by `url`, present when KOKORO_FETCH_ALLOW is set
+    pub fetch: Option<Arc<FetchPolicy>>,
+    /// Recent syntheses kept for the admin API, present when the debug tap is enabled
+    pub debug_tap: Option<Arc<DebugTap>>,
+    /// Refuses batch-priority work under pressure, present when a shedding threshold is set
+    pub load_shedder: Option<Arc<LoadShedder>>,
+    /// Build links from `X-Forwarded-Proto` and `X-Forwarded-Host`, set by a trusted proxy
+    pub trust_forwarded_headers: bool,
 }
 
-/// Create the API router
-pub fn create_router(
-    backend: Arc<KokoroBackend>,
-    api_key: Option<String>,
-    max_input_chars: usize,
-) -> Router {
-    let state = AppState {
-        backend,
-        api_key,
-        max_input_chars,
-    };
+impl AppState {
+    /// Model that synthesizes requests of `quality`
+    pub fn backend_for(&self, quality: Quality) -> &Arc<KokoroBackend> {
+        match (quality, &self.draft_backend) {
+            (Quality::Draft, Some(draft)) => draft,
+            _ => &self.backend,
+        }
+    }
 
-    Router::new()
+    /// The full model, then the draft model when one is loaded
+    pub fn backends(&self) -> impl Iterator<Item = &Arc<KokoroBackend>> {
+        std::iter::once(&self.backend).chain(&self.draft_backend)
+    }
+}
+
+#[cfg(all(test, feature = "mock-backend"))]
+impl AppState {
+    /// State around a mock backend with the default configuration and no optional features
+    pub async fn for_tests() -> Self {
+        use crate::config::{accepted_model_ids, BackendKind, Config};
+
+        let config = Config {
+            backend: BackendKind::Mock,
+            ..Config::for_tests()
+        };
+        let dir = std::env::temp_dir().join(format!("kokoro-state-{}", Uuid::new_v4()));
+        let files = ModelFiles::from_model_path(dir.join("kokoro.onnx"));
+        Self {
+            backend: Arc::new(KokoroBackend::new(&config, &files).await.unwrap()),
+            draft_backend: None,
+            api_keys: Arc::new(ApiKeys::new(None)),
+            max_input_chars: config.max_input_chars,
+            max_document_chars: config.max_document_chars,
+            max_response_bytes: None,
+            max_duration_s: None,
+            model_ids: accepted_model_ids(&config.model_aliases, false).into(),
+            voice_aliases: Arc::new(VoiceAliases::default()),
+            fallback_voice: None,
+            presets: Arc::new(Presets::default()),
+            model_url: config.model_url.clone(),
+            chunk_retry: ChunkRetryPolicy {
+                retries: 0,
+                backoff: std::time::Duration::ZERO,
+                skip_failed: false,
+                stall_timeout: None,
+            },
+            adaptive_chunks: false,
+            sentence_gap_ms: None,
+            comfort_noise: false,
+            chunk_profiles: Arc::new(ChunkProfiles::new(ChunkRules::default(), false)),
+            fold_punctuation: false,
+            normalize_rules: None,
+            cache: None,
+            radio: None,
+            jobs: Arc::new(
+                JobStore::open(dir.join("jobs"), std::time::Duration::from_secs(3600), true)
+                    .unwrap(),
+            ),
+            fetch: None,
+            debug_tap: None,
+            load_shedder: None,
+            trust_forwarded_headers: false,
+        }
+    }
+}
+
+/// Voice used when neither the request nor its preset names one
+const DEFAULT_VOICE: &str = "af_alloy";
+const DEFAULT_RESPONSE_FORMAT: &str = "wav";
+/// Speed used when neither the request nor the voice's preset sets one
+const DEFAULT_SPEED: f32 = 1.0;
+
+/// Response header on streamed speech predicting the audio length before it is known
+pub const ESTIMATED_DURATION_HEADER: &str = "X-Estimated-Duration-Ms";
+
+/// Response header naming the fallback voice used in place of an unknown requested voice
+pub const VOICE_SUBSTITUTED_HEADER: &str = "X-Voice-Substituted";
+
+/// Create the API router.
+///
+/// Ops endpoints such as `/admin/reload` are only served by the admin listener, so they are never
+/// reachable from the public port.
+pub fn create_router(state: AppState) -> Router {
+    let mut router = Router::new()
         .route("/", get(root_handler))
         .route("/health", get(health_handler))
+        .route("/readyz", get(readyz_handler))
+        .route("/version", get(version_handler))
+        .route("/metrics", get(metrics_handler))
         .route("/v1", get(root_handler))
         .route("/v1/models", get(list_models_handler))
-        .route("/v1/audio/speech", post(speech_handler))
+        .route("/v1/models/{model_id}", get(retrieve_model_handler))
+        .route(
+            "/v1/audio/speech",
+            post(speech_handler).get(speech_get_handler),
+        )
+        .route(
+            "/v1/audio/speech/sign",
+            post(crate::signed_url::sign_speech_url_handler),
+        )
+        .route("/v1/audio/speech/plan", post(speech_plan_handler))
+        .route(
+            "/v1/audio/phonemes",
+            post(crate::phonemes::phonemes_handler),
+        )
+        .route(
+            "/v1/audio/speech/batch",
+            post(crate::batch::batch_speech_handler),
+        )
         .route("/v1/audio/voices", get(list_voices_handler))
-        .layer(TraceLayer::new_for_http())
+        .route(
+            "/v1/audio/audiobooks",
+            post(crate::audiobook::create_audiobook_handler)
+                .layer(DefaultBodyLimit::max(crate::audiobook::DOCUMENT_BODY_LIMIT)),
+        )
+        .route("/v1/audio/jobs", get(crate::jobs::list_jobs_handler))
+        .route(
+            "/v1/audio/jobs/{job_id}",
+            get(crate::jobs::get_job_handler).delete(crate::jobs::cancel_job_handler),
+        )
+        .route(
+            "/v1/audio/jobs/{job_id}/content",
+            get(crate::jobs::job_content_handler),
+        )
+        .route(
+            "/v1/audio/jobs/{job_id}/chapters/{index}",
+            get(crate::jobs::job_chapter_handler),
+        )
+        .route("/feeds/{feed}", get(crate::feeds::feed_handler))
+        .route(
+            "/feeds/{feed}/sign",
+            post(crate::feeds::sign_feed_url_handler),
+        )
+        .route("/v1/audio/twilio", get(crate::twilio::media_stream_handler))
+        .route(
+            "/v1/text-to-speech/{voice_id}",
+            post(crate::elevenlabs::text_to_speech_handler),
+        )
+        .route(
+            "/v1/text-to-speech/{voice_id}/stream",
+            post(crate::elevenlabs::text_to_speech_handler),
+        )
+        .route("/v1/voices", get(crate::elevenlabs::list_voices_handler))
+        .route("/v1/speech", post(crate::polly::synthesize_speech_handler))
+        .route(
+            "/cognitiveservices/v1",
+            post(crate::azure::synthesize_handler),
+        )
+        .route(
+            "/sts/v1.0/issueToken",
+            post(crate::azure::issue_token_handler),
+        )
+        .route(
+            "/v1/text:synthesize",
+            post(crate::google::synthesize_handler),
+        )
+        .merge(SwaggerUi::new("/docs").url("/openapi.json", ApiDoc::openapi()));
+    if state.radio.is_some() {
+        router = router.route(
+            "/v1/radio/queue",
+            get(crate::icecast::queue_status_handler).post(crate::icecast::enqueue_handler),
+        );
+    }
+
+    router
+        .layer(TraceLayer::new_for_http().make_span_with(request_span))
         .layer(CorsLayer::permissive())
         .layer(middleware::from_fn_with_state(
             state.clone(),
@@ -138,38 +523,118 @@ pub fn create_router(
         .with_state(state)
 }
 
+/// Span for each HTTP request, like tower-http's default but with the URI passed through
+/// [`redact::uri`], so query-string keys and text stay out of the logs
+pub fn request_span(req: &axum::http::Request<Body>) -> tracing::Span {
+    tracing::debug_span!(
+        "request",
+        method = %req.method(),
+        uri = %redact::uri(req.uri()),
+        version = ?req.version(),
+    )
+}
+
+/// API key presented with a request.
+///
+/// ElevenLabs, Azure and Google clients send it in their own headers. Google REST clients may
+/// pass `?key=` instead of a bearer token.
+pub fn presented_api_key<'a>(headers: &'a HeaderMap, uri: &'a Uri) -> Option<&'a str> {
+    headers
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .or_else(|| {
+            ["xi-api-key", "ocp-apim-subscription-key", "x-goog-api-key"]
+                .into_iter()
+                .find_map(|name| headers.get(name).and_then(|h| h.to_str().ok()))
+        })
+        .or_else(|| {
+            (uri.path() == "/v1/text:synthesize")
+                .then(|| uri.query())
+                .flatten()
+                .and_then(|query| query.split('&').find_map(|pair| pair.strip_prefix("key=")))
+        })
+}
+
+/// Largest body buffered to check a SigV4 signature, matching axum's default body limit
+const SIGV4_BODY_LIMIT: usize = 2 * 1024 * 1024;
+
 /// Authentication middleware
 async fn auth_middleware(
     State(state): State<AppState>,
     req: axum::http::Request<Body>,
     next: Next,
 ) -> Response {
-    // Skip auth for root and health endpoints
+    // Skip auth for root, health and API docs endpoints; Twilio streams authenticate with a stream
+    // parameter because Twilio cannot send an Authorization header
     let path = req.uri().path();
-    if path == "/" || path == "/health" || path.starts_with("/v1/audio/voices") {
+    if path == "/"
+        || path == "/health"
+        || path == "/readyz"
+        || path.starts_with("/v1/audio/voices")
+        || path == "/openapi.json"
+        || path.starts_with("/docs")
+        || path == "/v1/audio/twilio"
+    {
         return next.run(req).await;
     }
 
-    // Check API key if configured
-    if let Some(ref expected_key) = state.api_key {
-        let auth_header = req
-            .headers()
-            .get("authorization")
-            .and_then(|h| h.to_str().ok());
+    if !state.api_keys.is_required() {
+        return next.run(req).await;
+    }
 
-        match auth_header {
-            Some(header) if header.starts_with("Bearer ") => {
-                let provided_key = &header[7..];
-                if !constant_time_eq(provided_key, expected_key) {
-                    warn!("Invalid API key provided");
-                    return AppError::Unauthorized.into_response();
-                }
+    // Polly SDKs sign requests with SigV4 using an API key as the secret access key
+    let sigv4 = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|header| header.starts_with("AWS4-HMAC-SHA256 "));
+    if sigv4 && path == "/v1/speech" {
+        let (parts, body) = req.into_parts();
+        let Ok(body) = axum::body::to_bytes(body, SIGV4_BODY_LIMIT).await else {
+            return AppError::invalid_request("Request body too large").into_response();
+        };
+        let valid = state
+            .api_keys
+            .secrets()
+            .iter()
+            .any(|secret| crate::polly::verify_sigv4(&parts, &body, secret));
+        if !valid {
+            warn!("Invalid SigV4 signature provided");
+            return AppError::Unauthorized.into_response();
+        }
+        return next
+            .run(axum::http::Request::from_parts(parts, Body::from(body)))
+            .await;
+    }
+
+    // Signed URLs stand in for a key where only a URL can be given, as in `<audio src>` or a
+    // podcast app
+    if req.method() == axum::http::Method::GET && crate::signed_url::signable(path) {
+        if let Some(query) = req
+            .uri()
+            .query()
+            .filter(|query| query.contains("signature="))
+        {
+            if crate::signed_url::verify(&state.api_keys.secrets(), path, query).is_none() {
+                warn!("Invalid or expired URL signature");
+                return AppError::Unauthorized.into_response();
             }
-            _ => {
-                warn!("Missing or invalid Authorization header");
+            return next.run(req).await;
+        }
+    }
+
+    match presented_api_key(req.headers(), req.uri()) {
+        Some(provided_key) => {
+            if !state.api_keys.verify(provided_key) {
+                warn!("Invalid API key provided");
                 return AppError::Unauthorized.into_response();
             }
         }
+        _ => {
+            warn!("Missing or invalid Authorization header");
+            return AppError::Unauthorized.into_response();
+        }
     }
 
     next.run(req).await
@@ -180,68 +645,196 @@ async fn root_handler() -> impl IntoResponse {
     Json(serde_json::json!({
         "message": "Kokoro OpenAI TTS Server",
         "version": env!("CARGO_PKG_VERSION"),
-        "docs": "/v1/models"
+        "docs": "/docs"
     }))
 }
 
 /// Health check handler
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    security(()),
+    responses(
+        (status = 200, description = "Backend is healthy", body = HealthResponse),
+        (status = 503, description = "Backend is unhealthy", body = HealthResponse)
+    )
+)]
 async fn health_handler(State(state): State<AppState>) -> impl IntoResponse {
-    let healthy = state.backend.is_healthy().await;
+    let mut healthy = true;
+    for backend in state.backends() {
+        healthy &= backend.is_healthy().await;
+    }
 
     if healthy {
         (
             StatusCode::OK,
-            Json(serde_json::json!({"status": "healthy"})),
+            Json(HealthResponse {
+                status: "healthy",
+                model_loaded: Some(state.backend.is_loaded()),
+                assets: Some(asset_fingerprints(&state.backend)),
+            }),
         )
     } else {
         (
             StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"status": "unhealthy"})),
+            Json(HealthResponse {
+                status: "unhealthy",
+                model_loaded: None,
+                assets: None,
+            }),
         )
     }
 }
 
-/// List available models
-async fn list_models_handler() -> ApiResult<impl IntoResponse> {
-    let models = vec![
-        Model {
-            id: "tts-1".to_string(),
-            object: "model".to_string(),
-            created: 1704067200, // 2024-01-01
-            owned_by: "kokoro".to_string(),
-        },
-        Model {
-            id: "tts-1-hd".to_string(),
-            object: "model".to_string(),
-            created: 1704067200,
-            owned_by: "kokoro".to_string(),
-        },
-        Model {
-            id: "kokoro".to_string(),
-            object: "model".to_string(),
-            created: 1704067200,
-            owned_by: "kokoro".to_string(),
-        },
-        Model {
-            id: "gpt-4o-mini-tts".to_string(),
-            object: "model".to_string(),
-            created: 1704067200,
-            owned_by: "kokoro".to_string(),
-        },
-    ];
+/// Prometheus metrics handler
+async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let mut body = state
+        .backend
+        .metrics()
+        .render_prometheus(state.backend.sample_rate());
+    if let Some(cache) = &state.cache {
+        cache.render_prometheus(&mut body);
+    }
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
+
+/// Readiness handler; unavailable until startup warm-up completes
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    security(()),
+    responses(
+        (status = 200, description = "Warm-up complete", body = ReadinessResponse),
+        (status = 503, description = "Warming up or recovering", body = ReadinessResponse)
+    )
+)]
+async fn readyz_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let (status, response) = if state.backends().all(|backend| backend.is_ready()) {
+        (
+            StatusCode::OK,
+            ReadinessResponse {
+                status: "ready",
+                warmup_ms: state.backend.warmup_ms(),
+            },
+        )
+    } else if state.backends().any(|backend| backend.is_recovering()) {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            ReadinessResponse {
+                status: "recovering",
+                warmup_ms: None,
+            },
+        )
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            ReadinessResponse {
+                status: "warming_up",
+                warmup_ms: None,
+            },
+        )
+    };
+
+    (status, Json(response))
+}
+
+/// Build and runtime details for bug reports and fleet audits
+#[utoipa::path(
+    get,
+    path = "/version",
+    tag = "health",
+    responses(
+        (status = 200, description = "Build and runtime details", body = VersionResponse),
+        (status = 401, description = "Missing or invalid API key", body = OpenAIError)
+    )
+)]
+async fn version_handler(State(state): State<AppState>) -> Json<VersionResponse> {
+    Json(version(&state.backend, &state.model_url).await)
+}
 
+/// List available models
+#[utoipa::path(
+    get,
+    path = "/v1/models",
+    tag = "models",
+    responses(
+        (status = 200, description = "Model list", body = ModelsResponse),
+        (status = 401, description = "Missing or invalid API key", body = OpenAIError)
+    )
+)]
+async fn list_models_handler(State(state): State<AppState>) -> ApiResult<impl IntoResponse> {
     Ok(Json(ModelsResponse {
         object: "list".to_string(),
-        data: models,
+        data: available_models(&state.model_ids),
+    }))
+}
+
+/// Retrieve one model along with the Kokoro model that serves it
+#[utoipa::path(
+    get,
+    path = "/v1/models/{model_id}",
+    tag = "models",
+    params(("model_id" = String, Path, description = "Model ID from GET /v1/models")),
+    responses(
+        (status = 200, description = "Model details", body = ModelDetail),
+        (status = 401, description = "Missing or invalid API key", body = OpenAIError),
+        (status = 404, description = "Unknown model ID", body = OpenAIError)
+    )
+)]
+async fn retrieve_model_handler(
+    State(state): State<AppState>,
+    Path(model_id): Path<String>,
+) -> ApiResult<Json<ModelDetail>> {
+    let model = available_models(&state.model_ids)
+        .into_iter()
+        .find(|model| model.id == model_id)
+        .ok_or_else(|| AppError::model_does_not_exist(model_id))?;
+
+    // The draft model is always a local file, so its name alone gives the quantization
+    let (backend, model_url) = match model.id.as_str() {
+        KOKORO_DRAFT_MODEL_ID => (state.backend_for(Quality::Draft), ""),
+        _ => (&state.backend, state.model_url.as_str()),
+    };
+    Ok(Json(ModelDetail {
+        model,
+        kokoro: ModelMetadata {
+            quantization: model_quantization(&backend.model_files(), model_url),
+            sample_rate: backend.sample_rate(),
+            execution_provider: backend.execution_provider().to_string(),
+            loaded: backend.is_loaded(),
+        },
     }))
 }
 
+/// Model IDs accepted by the speech endpoint; all but `kokoro-draft` are served by the same
+/// Kokoro model
+fn available_models(ids: &[String]) -> Vec<Model> {
+    ids.iter()
+        .map(|id| Model {
+            id: id.clone(),
+            object: "model".to_string(),
+            created: 1704067200, // 2024-01-01
+            owned_by: "kokoro".to_string(),
+        })
+        .collect()
+}
+
 /// List available voices
-async fn list_voices_handler() -> impl IntoResponse {
-    let mut voices = get_available_voices().to_vec();
+#[utoipa::path(
+    get,
+    path = "/v1/audio/voices",
+    tag = "voices",
+    security(()),
+    responses((status = 200, description = "Voice list", body = VoicesResponse))
+)]
+async fn list_voices_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let mut voices = state.backend.voices().to_vec();
     let mut seen_ids: HashSet<String> = voices.iter().map(|voice| voice.id.clone()).collect();
 
-    for alias in openai_alias_voices() {
+    for alias in state.voice_aliases.voices(&state.backend.voices()) {
         if seen_ids.insert(alias.id.clone()) {
             voices.push(alias);
         }
@@ -253,63 +846,255 @@ async fn list_voices_handler() -> impl IntoResponse {
     })
 }
 
+/// Reload the model in the background without interrupting live traffic
+pub(crate) async fn reload_handler(
+    State(state): State<AppState>,
+    req: Option<ApiJson<ReloadRequest>>,
+) -> ApiResult<impl IntoResponse> {
+    let req = req.map(|ApiJson(req)| req).unwrap_or_default();
+    let model_dir = state.backend.model_dir().to_path_buf();
+    let files = resolve_reload_files(&model_dir, state.backend.model_files(), req);
+    let files = tokio::task::spawn_blocking(move || check_reload_files(&model_dir, files))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Reload file check panicked");
+            AppError::Internal
+        })??;
+
+    let file_name = |path: &std::path::Path| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+    };
+    let response = serde_json::json!({
+        "status": "reloading",
+        "model_file": file_name(&files.model_path),
+        "voices_file": file_name(&files.voices_path),
+    });
+
+    if !state.backend.start_reload(files) {
+        return Err(AppError::conflict("A model reload is already in progress"));
+    }
+
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
+
+/// Files a reload request names, with relative paths taken from `model_dir`
+fn resolve_reload_files(
+    model_dir: &std::path::Path,
+    current: ModelFiles,
+    req: ReloadRequest,
+) -> ModelFiles {
+    let model_path = req.model_path.map(|path| model_dir.join(path));
+    let voices_path = req.voices_path.map(|path| model_dir.join(path));
+    match (model_path, voices_path) {
+        (None, None) => current,
+        (Some(model_path), None) => ModelFiles::from_model_path(model_path),
+        (Some(model_path), Some(voices_path)) => ModelFiles {
+            model_path,
+            voices_path,
+        },
+        (None, Some(voices_path)) => ModelFiles {
+            model_path: current.model_path,
+            voices_path,
+        },
+    }
+}
+
+/// `files` resolved to regular files inside `model_dir`, with a readable voice pack.
+///
+/// Rejections name the request field only; the paths and underlying errors are logged.
+fn check_reload_files(model_dir: &std::path::Path, files: ModelFiles) -> ApiResult<ModelFiles> {
+    let root = model_dir.canonicalize().map_err(|e| {
+        error!(path = %model_dir.display(), error = %e, "Model directory is not readable");
+        AppError::Internal
+    })?;
+    let confine = |field: &str, path: &std::path::Path| match path.canonicalize() {
+        Ok(resolved) if resolved.starts_with(&root) && resolved.is_file() => Ok(resolved),
+        resolved => {
+            warn!(
+                path = %path.display(),
+                error = ?resolved.err().map(|e| e.to_string()),
+                "Reload file rejected"
+            );
+            Err(AppError::invalid_request(format!(
+                "{field} must name a file in the model directory"
+            )))
+        }
+    };
+    let files = ModelFiles {
+        model_path: confine("model_path", &files.model_path)?,
+        voices_path: confine("voices_path", &files.voices_path)?,
+    };
+    voice_pack::voice_names(&files.voices_path).map_err(|e| {
+        warn!(path = %files.voices_path.display(), error = %format!("{e:#}"), "Invalid voices file");
+        AppError::invalid_request("voices_path is not a valid voice pack")
+    })?;
+    Ok(files)
+}
+
 /// Text-to-speech handler
+#[utoipa::path(
+    post,
+    path = "/v1/audio/speech",
+    tag = "speech",
+    request_body(content(
+        (SpeechRequest = "application/json"),
+        (SpeechRequest = "multipart/form-data"),
+        (String = "text/plain")
+    )),
+    responses(
+        (status = 200, description = "Synthesized audio in the requested format", content(
+            ("audio/wav"),
+            ("audio/pcm"),
+            ("audio/mpeg"),
+            ("audio/opus")
+        )),
+        (status = 400, description = "Invalid request, model, voice, or format", body = OpenAIError),
+        (status = 401, description = "Missing or invalid API key", body = OpenAIError),
+        (status = 503, description = "Backend unavailable or overloaded", body = OpenAIError),
+        (status = 504, description = "Synthesis timed out", body = OpenAIError)
+    )
+)]
 async fn speech_handler(
     State(state): State<AppState>,
-    Json(req): Json<SpeechRequest>,
-) -> ApiResult<impl IntoResponse> {
+    headers: HeaderMap,
+    ApiBody(req): ApiBody<SpeechRequest>,
+) -> ApiResult<Response> {
+    speech_response(state, headers, req).await
+}
+
+/// Generate speech from query parameters, for clients that can only fetch a URL
+#[utoipa::path(
+    get,
+    path = "/v1/audio/speech",
+    tag = "speech",
+    params(
+        ("model" = String, Query, description = "Model ID"),
+        ("input" = String, Query, description = "Text to synthesize"),
+        ("voice" = Option<String>, Query, description = "Voice ID"),
+        ("format" = Option<String>, Query, description = "Response format, also accepted as `response_format`"),
+        ("speed" = Option<f32>, Query, description = "Speed multiplier"),
+        ("expires" = Option<u64>, Query, description = "Unix time a signed URL expires"),
+        ("signature" = Option<String>, Query, description = "Signature from `POST /v1/audio/speech/sign`, in place of an API key; must be the last parameter")
+    ),
+    responses(
+        (status = 200, description = "Synthesized audio in the requested format", content(
+            ("audio/wav"),
+            ("audio/pcm"),
+            ("audio/mpeg"),
+            ("audio/opus")
+        )),
+        (status = 400, description = "Invalid request, model, voice, or format", body = OpenAIError),
+        (status = 401, description = "Missing or invalid API key, or an invalid or expired signature", body = OpenAIError),
+        (status = 503, description = "Backend unavailable or overloaded", body = OpenAIError),
+        (status = 504, description = "Synthesis timed out", body = OpenAIError)
+    )
+)]
+async fn speech_get_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    ApiQuery(req): ApiQuery<SpeechRequest>,
+) -> ApiResult<Response> {
+    speech_response(state, headers, req).await
+}
+
+async fn speech_response(
+    state: AppState,
+    headers: HeaderMap,
+    req: SpeechRequest,
+) -> ApiResult<Response> {
     let request_id = Uuid::new_v4().to_string();
 
     debug!(
         request_id = %request_id,
         model = %req.model,
-        voice = %req.voice,
-        format = %req.response_format,
+        voice = ?req.voice,
+        format = ?req.response_format,
+        preset = ?req.preset,
         initial_silence = ?req.initial_silence,
         stream = ?req.stream,
         "Received speech request"
     );
 
-    // Validate model
-    let _model = validate_model(&req.model)?;
-
-    // Validate input
-    validate_input(&req.input, state.max_input_chars)?;
-
-    // Validate response format
-    let format = validate_response_format(&req.response_format)?;
-
-    // Validate voice
-    let voices = get_available_voices();
-    let voice = validate_voice(&req.voice, voices)?;
-
-    // Validate speed
-    let speed = validate_speed(req.speed)?;
-
-    // Check if streaming is requested
-    let stream = req.stream.unwrap_or(false);
+    let sse = match req.stream_format.as_deref() {
+        None | Some("audio") => false,
+        Some("sse") => true,
+        Some(other) => {
+            return Err(AppError::invalid_request(format!(
+                "stream_format '{}' not supported. Supported values: audio, sse",
+                other
+            )))
+        }
+    };
+    let stream = sse || req.stream.unwrap_or(false);
+    let params = validate_speech_request(&state, req)?;
 
     if stream {
-        if format != "wav" && format != "pcm" {
+        if params.format != "wav" && params.format != "pcm" {
             return Err(AppError::invalid_request(
                 "Streaming currently supports only 'wav' and 'pcm' response_format values",
             ));
         }
+        if params.metadata.is_some() {
+            return Err(AppError::invalid_request(
+                "metadata is not supported for streamed responses",
+            ));
+        }
 
-        let stream_parallelism = state.backend.worker_limit();
+        let backend = state.backend_for(params.quality).clone();
+        let stream_options = crate::streaming::StreamOptions {
+            parallelism: backend.worker_limit(),
+            retry_policy: state.chunk_retry,
+            // hyper only emits trailers when the request carried exactly `TE: trailers`; SSE
+            // reports failures as an event instead
+            trailers: !sse
+                && headers
+                    .get(header::TE)
+                    .is_some_and(|te| te.as_bytes() == b"trailers"),
+            trailing_silence: params.trailing_silence,
+            sample_rate: params.sample_rate,
+            effects: params.effects.clone(),
+            bit_depth: params.bit_depth,
+            adaptive_chunks: state.adaptive_chunks,
+            chunk_rules: params.chunk_rules.clone(),
+            language: params.language,
+            speed_curve: params.speed_curve.clone(),
+            emphasis: params.emphasis,
+            sentence_gap_ms: params.sentence_gap_ms,
+            comfort_noise: params.comfort_noise,
+            max_duration_s: params.max_duration_s,
+        };
+        let trailers = stream_options.trailers;
+        let substituted_voice = params.voice_substituted.then(|| params.voice.clone());
+        let estimated_duration_ms =
+            plan(&params, backend.sample_rate(), backend.estimator()).estimated_duration_ms;
 
         // Streaming response
-        let (content_type, body) = if format == "wav" {
+        let (content_type, body) = if sse {
+            (
+                "text/event-stream",
+                crate::streaming::create_sse_stream(
+                    backend.clone(),
+                    params.input,
+                    params.voice,
+                    params.speed,
+                    params.initial_silence,
+                    request_id.clone(),
+                    stream_options,
+                )
+                .await?,
+            )
+        } else if params.format == "wav" {
             (
                 "audio/wav",
                 crate::streaming::create_wav_stream(
-                    state.backend.clone(),
-                    req.input,
-                    voice,
-                    speed,
-                    req.initial_silence,
+                    backend.clone(),
+                    params.input,
+                    params.voice,
+                    params.speed,
+                    params.initial_silence,
                     request_id.clone(),
-                    stream_parallelism,
+                    stream_options,
                 )
                 .await?,
             )
@@ -317,13 +1102,13 @@ async fn speech_handler(
             (
                 "audio/pcm",
                 crate::streaming::create_pcm_stream(
-                    state.backend.clone(),
-                    req.input,
-                    voice,
-                    speed,
-                    req.initial_silence,
+                    backend.clone(),
+                    params.input,
+                    params.voice,
+                    params.speed,
+                    params.initial_silence,
                     request_id.clone(),
-                    stream_parallelism,
+                    stream_options,
                 )
                 .await?,
             )
@@ -334,70 +1119,752 @@ async fn speech_handler(
             "Streaming response initiated"
         );
 
-        Ok(Response::builder()
+        let mut response = Response::builder()
             .status(StatusCode::OK)
             .header(header::CONTENT_TYPE, content_type)
             .header("Transfer-Encoding", "chunked")
             .header("X-Accel-Buffering", "no")
             .header("Cache-Control", "no-cache")
             .header("X-Request-Id", request_id)
-            .body(body)
-            .map_err(|_e| AppError::Internal)?)
+            .header(ESTIMATED_DURATION_HEADER, estimated_duration_ms);
+        if let Some(voice) = substituted_voice {
+            response = response.header(VOICE_SUBSTITUTED_HEADER, voice);
+        }
+        if trailers {
+            response = response.header(
+                header::TRAILER,
+                format!(
+                    "{}, {}",
+                    crate::streaming::STREAM_STATUS_TRAILER,
+                    crate::streaming::STREAM_ERROR_TRAILER
+                ),
+            );
+        }
+
+        Ok(response.body(body).map_err(|_e| AppError::Internal)?)
     } else {
         // Non-streaming response
-        let audio_data = state
-            .backend
-            .synthesize(&req.input, &voice, speed, req.initial_silence)
+        let (audio, cache_status) = synthesize_clip(&state, &params, &request_id).await?;
+
+        let mut response = Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, audio.content_type)
+            .header("X-Request-Id", request_id);
+        if let Some(cache_status) = cache_status {
+            response = response.header("X-Cache", cache_status);
+        }
+        if params.voice_substituted {
+            response = response.header(VOICE_SUBSTITUTED_HEADER, params.voice);
+        }
+
+        Ok(response
+            .body(Body::from(audio.bytes))
+            .map_err(|_| AppError::Internal)?)
+    }
+}
+
+/// Show how a speech request would be chunked and how long it would run, without synthesizing it
+#[utoipa::path(
+    post,
+    path = "/v1/audio/speech/plan",
+    tag = "speech",
+    request_body(content(
+        (SpeechRequest = "application/json"),
+        (SpeechRequest = "multipart/form-data"),
+        (String = "text/plain")
+    )),
+    responses(
+        (status = 200, description = "Chunking plan and duration estimate", body = SpeechPlan),
+        (status = 400, description = "Invalid request, model, voice, or format", body = OpenAIError),
+        (status = 401, description = "Missing or invalid API key", body = OpenAIError)
+    )
+)]
+async fn speech_plan_handler(
+    State(state): State<AppState>,
+    ApiBody(req): ApiBody<SpeechRequest>,
+) -> ApiResult<Json<SpeechPlan>> {
+    let params = validate_speech_request(&state, req)?;
+    // Draft requests are estimated with the draft model's own pace
+    let backend = state.backend_for(params.quality);
+    Ok(Json(plan(
+        &params,
+        backend.sample_rate(),
+        backend.estimator(),
+    )))
+}
+
+/// Validated parameters of a speech request
+#[derive(Debug, Clone, Default)]
+pub struct SpeechParams {
+    pub input: String,
+    /// Resolved Kokoro voice ID
+    pub voice: String,
+    /// The requested voice was unknown and `voice` is the configured fallback
+    pub voice_substituted: bool,
+    /// Lowercased response format
+    pub format: String,
+    pub speed: f32,
+    /// Speed across the input, replacing `speed` for each chunk
+    pub speed_curve: Option<SpeedCurve>,
+    /// `*word*` and `**word**` in the input mark emphasis rather than being read as text
+    pub emphasis: bool,
+    /// Silence between chunks; `None` leaves the engine's own pauses and lets a clip be
+    /// synthesized in one call
+    pub sentence_gap_ms: Option<u32>,
+    /// Silence is filled with low-level noise rather than digital zero
+    pub comfort_noise: bool,
+    /// Longest audio the request may produce, in seconds
+    pub max_duration_s: Option<u32>,
+    pub initial_silence: Option<usize>,
+    /// Silence appended after the speech, in samples at the output rate
+    pub trailing_silence: usize,
+    /// Output sample rate when resampling `pcm` or `wav` output; `None` keeps the model's rate
+    pub sample_rate: Option<u32>,
+    /// Post-processing applied before resampling and encoding
+    pub effects: Effects,
+    /// Sample encoding of `wav` output
+    pub bit_depth: BitDepth,
+    /// Language the input is read in, from the request or the voice
+    pub language: Language,
+    /// Where the input is split into chunks when synthesized in pieces
+    pub chunk_rules: Arc<ChunkRules>,
+    /// Model tier the request is synthesized with
+    pub quality: Quality,
+    /// Tags written into the encoded clip, with at least one field set
+    pub metadata: Option<AudioMetadata>,
+}
+
+/// Language a request for `voice` is read in when it names none, for preparing text before
+/// the request is validated; a voice that does not validate gets the default, and validation
+/// reports it
+pub fn voice_language(state: &AppState, voice: &str) -> Language {
+    validate_voice_or_fallback(
+        voice,
+        &state.backend.voices(),
+        &state.voice_aliases,
+        state.fallback_voice.as_deref(),
+    )
+    .ok()
+    .and_then(|(voice, _)| Language::of_voice(&voice))
+    .unwrap_or_default()
+}
+
+/// Validate a speech request; shared by every transport so they accept the same inputs
+pub fn validate_speech_request(state: &AppState, req: SpeechRequest) -> ApiResult<SpeechParams> {
+    validate_speech_request_within(state, req, state.max_input_chars)
+}
+
+/// Validate a speech request whose input may run to `max_input_chars`, for documents that are
+/// synthesized in pieces rather than as one clip
+pub fn validate_speech_request_within(
+    state: &AppState,
+    mut req: SpeechRequest,
+    max_input_chars: usize,
+) -> ApiResult<SpeechParams> {
+    // Validate model and the quality tier it is served at
+    let model = validate_model(&req.model, &state.model_ids)?;
+    let quality = validate_quality(
+        req.quality.as_deref(),
+        &model,
+        state.draft_backend.is_some(),
+    )?;
+
+    // Validate input, without the characters that are never spoken
+    if let Cow::Owned(input) = sanitize_input(&req.input) {
+        req.input = input;
+    }
+    validate_input(&req.input, max_input_chars)?;
+
+    // A named preset supplies whatever the request leaves unset
+    let named = match &req.preset {
+        Some(name) => Some(
+            state
+                .presets
+                .named(name)
+                .ok_or_else(|| AppError::preset_not_found(name))?,
+        ),
+        None => None,
+    };
+    let requested_voice = req
+        .voice
+        .or_else(|| named.and_then(|preset| preset.voice.clone()))
+        .unwrap_or_else(|| DEFAULT_VOICE.to_string());
+
+    // Validate response format; a profile replaces it with its own encoding. A profile in
+    // the preset only applies when the request did not pick a format of its own.
+    let format = match (&req.profile, &req.response_format) {
+        (Some(profile), _) => validate_profile(profile)?,
+        (None, Some(format)) => validate_response_format(format)?,
+        (None, None) => match named {
+            Some(NamedPreset {
+                profile: Some(profile),
+                ..
+            }) => validate_profile(profile)?,
+            _ => validate_response_format(
+                named
+                    .and_then(|preset| preset.response_format.as_deref())
+                    .unwrap_or(DEFAULT_RESPONSE_FORMAT),
+            )?,
+        },
+    };
+
+    // Validate voice, substituting the fallback voice for unknown ones when configured
+    let (voice, voice_substituted) = validate_voice_or_fallback(
+        &requested_voice,
+        &state.backend.voices(),
+        &state.voice_aliases,
+        state.fallback_voice.as_deref(),
+    )?;
+    if voice_substituted {
+        debug!(requested = %requested_voice, voice = %voice, "Unknown voice replaced by fallback");
+    }
+    let language = validate_language(req.language.as_deref(), &voice)?;
+
+    // A delivery style supplies the EQ and compression the request and preset leave unset
+    let style = match req
+        .style
+        .as_deref()
+        .or_else(|| named.and_then(|preset| preset.style.as_deref()))
+    {
+        Some(name) => Some(Style::from_name(name).ok_or_else(|| {
+            AppError::invalid_request(format!(
+                "style '{}' not supported. Supported styles: {}",
+                name,
+                STYLE_PRESETS.join(", ")
+            ))
+        })?),
+        None => None,
+    };
+    let filters = match req
+        .effects
+        .or_else(|| named.and_then(|preset| preset.effects.clone()))
+    {
+        Some(names) => names
+            .iter()
+            .map(|name| {
+                Filter::from_name(name).ok_or_else(|| {
+                    AppError::invalid_request(format!(
+                        "effect '{}' not supported. Supported effects: {}",
+                        name,
+                        FILTER_PRESETS.join(", ")
+                    ))
+                })
+            })
+            .collect::<ApiResult<Vec<_>>>()?,
+        None => style.map(Style::filters).unwrap_or_default(),
+    };
+    let effects = Effects {
+        pitch_semitones: style.and_then(Style::pitch_semitones),
+        filters,
+        compressor: match req
+            .compressor
+            .or_else(|| named.and_then(|preset| preset.compressor.clone()))
+        {
+            Some(name) => {
+                let compressor = Compressor::preset(&name).ok_or_else(|| {
+                    AppError::invalid_request(format!(
+                        "compressor '{}' not supported. Supported values: {}",
+                        name,
+                        COMPRESSOR_PRESETS.join(", ")
+                    ))
+                })?;
+                Some((name.to_lowercase(), compressor))
+            }
+            None => style.and_then(Style::compressor),
+        },
+    };
+
+    // Resampling is only offered for the raw formats; the encoders pick their own rate
+    let sample_rate = named
+        .and_then(|preset| preset.sample_rate)
+        .filter(|_| format == "pcm" || format == "wav");
+    let output_rate = sample_rate.unwrap_or_else(|| state.backend.sample_rate());
+
+    // Deeper samples are only offered for WAV; a preset's depth is ignored for other formats
+    let bit_depth = match req.bit_depth {
+        Some(bits) => {
+            let bit_depth = validate_bit_depth(bits)?;
+            if format != "wav" && bit_depth != BitDepth::Int16 {
+                return Err(AppError::invalid_request(
+                    "bit_depth is only supported for 'wav' response_format",
+                ));
+            }
+            bit_depth
+        }
+        None => named
+            .and_then(|preset| preset.bit_depth)
+            .and_then(BitDepth::from_bits)
+            .filter(|_| format == "wav")
+            .unwrap_or_default(),
+    };
+
+    // The voice's preset fills in what the request and named preset leave unset
+    let preset = state.presets.voice(&voice);
+    let speed = validate_speed(
+        req.speed
+            .or(named.and_then(|preset| preset.speed))
+            .or(preset.and_then(|preset| preset.default_speed))
+            .unwrap_or(DEFAULT_SPEED),
+    )?;
+    let speed = style.map_or(speed, |style| {
+        (speed * style.speed_factor()).clamp(0.25, 4.0)
+    });
+    let speed_curve = req.speed_curve.map(SpeedCurve::new).transpose()?;
+    let trailing_silence = match named.and_then(|preset| preset.trailing_silence_ms) {
+        Some(ms) => silence_samples(ms, output_rate),
+        None => preset.map_or(0, |preset| preset.trailing_silence(output_rate)),
+    };
+
+    if state.backend.is_recovering() {
+        return Err(AppError::service_unavailable(
+            BackendUnavailable.to_string(),
+        ));
+    }
+
+    let metadata = req.metadata.filter(|metadata| !metadata.is_empty());
+    if let Some(metadata) = &metadata {
+        validate_metadata(metadata, &format)?;
+    }
+
+    let sentence_gap_ms = req.sentence_gap_ms.or(state.sentence_gap_ms);
+    if sentence_gap_ms > Some(MAX_SENTENCE_GAP_MS) {
+        return Err(AppError::invalid_request(format!(
+            "sentence_gap_ms must be at most {}",
+            MAX_SENTENCE_GAP_MS
+        )));
+    }
+
+    if req.max_duration_s == Some(0) {
+        return Err(AppError::invalid_request(
+            "max_duration_s must be at least 1",
+        ));
+    }
+    // A request can tighten the server's cap, never loosen it
+    let max_duration_s = match (req.max_duration_s, state.max_duration_s) {
+        (Some(requested), Some(limit)) => Some(requested.min(limit)),
+        (requested, limit) => requested.or(limit),
+    };
+
+    let number_style = match &req.number_style {
+        Some(name) => Some(NumberStyle::from_name(name).ok_or_else(|| {
+            AppError::invalid_request(format!(
+                "number_style '{}' not supported. Supported values: {}",
+                name,
+                NUMBER_STYLES.join(", ")
+            ))
+        })?),
+        None => None,
+    };
+
+    // Punctuation is folded first so the chunker and normalizer only see plain ASCII marks
+    let text = if state.fold_punctuation {
+        crate::punctuation::fold(&req.input)
+    } else {
+        req.input
+    };
+    // Spelled input is read exactly as written, so it is not normalized. Each character
+    // becomes a word, so the limit applies to the spelled text too.
+    let input = match &state.normalize_rules {
+        _ if req.spell_out == Some(true) => {
+            let spelled = spell_out(&text, language);
+            validate_input(&spelled, max_input_chars)?;
+            spelled
+        }
+        Some(rules) => normalize(
+            &text,
+            language,
+            &NormalizeRules {
+                number_style: number_style.unwrap_or_default(),
+                ..*rules
+            },
+        ),
+        None if number_style.is_some() => {
+            return Err(AppError::invalid_request(
+                "number_style requires text normalization, which is turned off on this server",
+            ))
+        }
+        None => text,
+    };
+
+    Ok(SpeechParams {
+        input,
+        voice,
+        voice_substituted,
+        format,
+        speed,
+        speed_curve,
+        emphasis: req.emphasis.unwrap_or(false),
+        sentence_gap_ms,
+        comfort_noise: req.comfort_noise.unwrap_or(state.comfort_noise),
+        max_duration_s,
+        initial_silence: req.initial_silence,
+        trailing_silence,
+        sample_rate,
+        effects,
+        bit_depth,
+        language,
+        // Drafts trade prosody across chunk boundaries for fewer, cheaper model calls
+        chunk_rules: match quality {
+            Quality::High => state.chunk_profiles.rules(language),
+            Quality::Draft => Arc::new(state.chunk_profiles.rules(language).coarser()),
+        },
+        quality,
+        metadata,
+    })
+}
+
+/// Refuse a clip whose estimated audio would exceed `KOKORO_MAX_RESPONSE_MB`, before it is
+/// synthesized and held in memory whole
+fn check_output_size(state: &AppState, params: &SpeechParams) -> ApiResult<()> {
+    let Some(limit) = state.max_response_bytes else {
+        return Ok(());
+    };
+    let estimated = estimated_response_bytes(state, params);
+    if estimated > limit {
+        return Err(AppError::OutputTooLarge { estimated, limit });
+    }
+    Ok(())
+}
+
+/// Fail with `output_limit_exceeded` once `audio` runs longer than `max_duration_s`
+pub fn check_duration(audio: &AudioData, max_duration_s: Option<u32>) -> ApiResult<()> {
+    match max_duration_s {
+        Some(limit)
+            if audio.samples.len() as u64 > u64::from(limit) * u64::from(audio.sample_rate) =>
+        {
+            Err(AppError::OutputLimitExceeded(limit))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fail with `output_limit_exceeded` when an encoded clip runs longer than `max_duration_s`
+fn check_clip_duration(audio: &CachedAudio, max_duration_s: Option<u32>) -> ApiResult<()> {
+    match max_duration_s {
+        Some(limit) if audio.duration_ms > u64::from(limit) * 1000 => {
+            Err(AppError::OutputLimitExceeded(limit))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Refuse a clip whose estimated audio already runs past `max_duration_s`, before synthesis
+fn check_estimated_duration(state: &AppState, params: &SpeechParams) -> ApiResult<()> {
+    match params.max_duration_s {
+        Some(limit) if estimated_duration_ms(state, params) > u64::from(limit) * 1000 => {
+            Err(AppError::OutputLimitExceeded(limit))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn estimated_duration_ms(state: &AppState, params: &SpeechParams) -> u64 {
+    let backend = state.backend_for(params.quality);
+    plan(params, backend.sample_rate(), backend.estimator()).estimated_duration_ms
+}
+
+/// Upper bound on the encoded size of a clip, from its estimated duration
+pub fn estimated_response_bytes(state: &AppState, params: &SpeechParams) -> u64 {
+    let sample_rate = state.backend_for(params.quality).sample_rate();
+    estimated_output_bytes(
+        estimated_duration_ms(state, params),
+        params.sample_rate.unwrap_or(sample_rate),
+        params.bit_depth,
+    )
+}
+
+/// Synthesize and encode a complete clip, going through the audio cache when it is enabled.
+///
+/// Returns the clip with its `X-Cache` status (`None` when caching is disabled).
+pub async fn synthesize_clip(
+    state: &AppState,
+    params: &SpeechParams,
+    request_id: &str,
+) -> ApiResult<(CachedAudio, Option<&'static str>)> {
+    let (audio, cache_status) = cached_clip(state, params, request_id).await?;
+    // The cache holds untagged clips, so requests with different metadata share them
+    let audio = match &params.metadata {
+        Some(metadata) => CachedAudio {
+            bytes: Bytes::from(tag_encoded(&params.format, &audio.bytes, metadata)?),
+            ..audio
+        },
+        None => audio,
+    };
+    Ok((audio, cache_status))
+}
+
+/// Cache key a clip for `params` is stored under
+fn clip_cache_key(state: &AppState, params: &SpeechParams) -> CacheKey {
+    CacheKey::new(
+        &params.input,
+        &params.voice,
+        params.speed,
+        &params.format,
+        params
+            .sample_rate
+            .unwrap_or_else(|| state.backend.sample_rate()),
+        params.initial_silence,
+        params.trailing_silence,
+    )
+    .with_effects(params.effects.describe())
+    .with_bit_depth(params.bit_depth.bits())
+    .with_speed_curve(
+        params
+            .speed_curve
+            .as_ref()
+            .map(SpeedCurve::describe)
+            .unwrap_or_default(),
+    )
+    .with_emphasis(params.emphasis)
+    .with_sentence_gap(params.sentence_gap_ms)
+    .with_comfort_noise(params.comfort_noise)
+    .with_draft(params.quality == Quality::Draft)
+    .with_model(&state.backend_for(params.quality).model_fingerprint())
+    .with_language(params.language.code())
+}
+
+async fn cached_clip(
+    state: &AppState,
+    params: &SpeechParams,
+    request_id: &str,
+) -> ApiResult<(CachedAudio, Option<&'static str>)> {
+    check_output_size(state, params)?;
+
+    let cache_key = state.cache.as_ref().map(|_| clip_cache_key(state, params));
+    let cached = match (&state.cache, &cache_key) {
+        (Some(cache), Some(key)) => cache.get(key).await,
+        _ => None,
+    };
+    if let Some(audio) = cached {
+        // The cap is not part of the key, so a clip cached under a looser one is checked here
+        check_clip_duration(&audio, params.max_duration_s)?;
+        debug!(request_id = %request_id, "Serving speech from cache");
+        if let Some(tap) = &state.debug_tap {
+            tap.record(request_id, params, &audio, None);
+        }
+        return Ok((audio, Some("HIT")));
+    }
+
+    let (audio, _duration_ms) = render_clip(state, params, request_id).await?;
+
+    let cache_status = match (&state.cache, cache_key) {
+        (Some(cache), Some(key)) => {
+            cache.insert(key, audio.clone());
+            Some("MISS")
+        }
+        _ => None,
+    };
+
+    Ok((audio, cache_status))
+}
+
+/// Synthesize and encode a clip without the cache, returning it with its length in milliseconds
+pub async fn render_clip(
+    state: &AppState,
+    params: &SpeechParams,
+    request_id: &str,
+) -> ApiResult<(CachedAudio, u64)> {
+    check_estimated_duration(state, params)?;
+    // A speed curve needs each chunk at its own speed, and a sentence gap needs the chunk
+    // boundaries, so either has the input synthesized in pieces
+    let synthesized = if params.speed_curve.is_some() || params.sentence_gap_ms.is_some() {
+        crate::audiobook::synthesize_chunks(
+            state,
+            request_id,
+            &params.input,
+            params,
+            params.initial_silence,
+            params.max_duration_s,
+        )
+        .await
+    } else if params.emphasis {
+        crate::emphasis::synthesize(
+            state.backend_for(params.quality),
+            &params.input,
+            &params.voice,
+            params.language,
+            params.speed,
+            params.initial_silence,
+        )
+        .await
+    } else {
+        state
+            .backend_for(params.quality)
+            .synthesize(
+                &params.input,
+                &params.voice,
+                params.language,
+                params.speed,
+                params.initial_silence,
+            )
             .await
-            .map_err(|e| {
-                error!("Synthesis failed: {}", e);
-                AppError::Backend(e.to_string())
-            })?;
-
-        // Encode to requested format
-        let (content_type, bytes) = match format.as_str() {
-            "wav" => (
-                "audio/wav",
-                encode_wav(&audio_data.samples, audio_data.sample_rate)?,
-            ),
-            "pcm" => ("audio/pcm", encode_pcm(&audio_data.samples)),
-            "mp3" => (
-                "audio/mpeg",
-                encode_mp3(&audio_data.samples, audio_data.sample_rate)?,
-            ),
-            "opus" => (
-                "audio/opus",
-                encode_opus(&audio_data.samples, audio_data.sample_rate)?,
-            ),
-            _ => return Err(AppError::unsupported_format(format)),
-        };
+    };
+    let mut audio_data = synthesized.map_err(synthesis_error)?;
+    params
+        .effects
+        .apply(&mut audio_data.samples, audio_data.sample_rate);
+    if let Some(sample_rate) = params.sample_rate {
+        audio_data = audio_data.resampled(sample_rate);
+    }
+    audio_data.pad_end(params.trailing_silence);
+    check_duration(&audio_data, params.max_duration_s)?;
+    if params.comfort_noise {
+        crate::comfort_noise::fill_silence(&mut audio_data.samples, audio_data.sample_rate);
+    }
+
+    let audio = encode_audio(&params.format, &audio_data, params.bit_depth)?;
+    let duration_ms = audio.duration_ms;
+    if let Some(tap) = &state.debug_tap {
+        tap.record(request_id, params, &audio, Some(duration_ms));
+    }
+
+    info!(
+        request_id = %request_id,
+        samples = audio_data.samples.len(),
+        duration_ms,
+        "Synthesis complete"
+    );
+
+    Ok((audio, duration_ms))
+}
 
+/// Synthesize every phrase/voice/format combination into the cache in the background.
+///
+/// Each combination goes through the same validation and cache lookup as a speech request with
+/// only `input`, `voice`, and `response_format` set, so it is stored under the key such a request
+/// looks up. Combinations already cached (including on disk from a previous run) are skipped.
+pub fn start_cache_prewarm(
+    state: AppState,
+    phrases: Vec<String>,
+    voices: Vec<String>,
+    formats: Vec<String>,
+) {
+    tokio::spawn(async move {
+        let started = std::time::Instant::now();
+        let (cached, skipped, failed) = prewarm_cache(&state, &phrases, &voices, &formats).await;
         info!(
-            request_id = %request_id,
-            samples = audio_data.samples.len(),
-            duration_ms = audio_data.samples.len() * 1000 / audio_data.sample_rate as usize,
-            "Synthesis complete"
+            cached,
+            skipped,
+            failed,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Cache pre-warm complete"
         );
+    });
+}
 
-        Ok(Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, content_type)
-            .header("X-Request-Id", request_id)
-            .body(Body::from(bytes))
-            .map_err(|_| AppError::Internal)?)
+/// Returns how many combinations were cached, skipped as already cached, and failed
+async fn prewarm_cache(
+    state: &AppState,
+    phrases: &[String],
+    voices: &[String],
+    formats: &[String],
+) -> (usize, usize, usize) {
+    let Some(cache) = &state.cache else {
+        return (0, 0, 0);
+    };
+    let (mut cached, mut skipped, mut failed) = (0usize, 0usize, 0usize);
+
+    for phrase in phrases {
+        for voice in voices {
+            for format in formats {
+                let request = SpeechRequest {
+                    model: KOKORO_MODEL_ID.to_string(),
+                    input: phrase.clone(),
+                    voice: Some(voice.clone()),
+                    response_format: Some(format.clone()),
+                    ..Default::default()
+                };
+                let result = match validate_speech_request(state, request) {
+                    // Checked up front so pre-warming does not count towards cache hit rates
+                    Ok(params) if cache.contains(&clip_cache_key(state, &params)).await => {
+                        skipped += 1;
+                        continue;
+                    }
+                    Ok(params) => cached_clip(state, &params, "cache-prewarm").await,
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(_) => cached += 1,
+                    Err(e) => {
+                        warn!(
+                            voice = %voice,
+                            format = %format,
+                            phrase = %redact::text(phrase),
+                            error = %redact::message(&e.to_string()),
+                            "Cache pre-warm failed"
+                        );
+                        failed += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    (cached, skipped, failed)
+}
+
+/// Encode synthesized samples in the requested response format; `bit_depth` applies to `wav`
+pub fn encode_audio(
+    format: &str,
+    audio: &AudioData,
+    bit_depth: BitDepth,
+) -> Result<CachedAudio, AppError> {
+    let content_type =
+        content_type_for_format(format).ok_or_else(|| AppError::unsupported_format(format))?;
+    let bytes = match format {
+        "wav" => encode_wav(&audio.samples, audio.sample_rate, bit_depth)?,
+        "pcm" => encode_pcm(&audio.samples),
+        "mp3" => encode_mp3(&audio.samples, audio.sample_rate)?,
+        "opus" => encode_opus(&audio.samples, audio.sample_rate)?,
+        "discord" => encode_discord(&audio.samples, audio.sample_rate)?,
+        _ => return Err(AppError::unsupported_format(format)),
+    };
+
+    Ok(CachedAudio {
+        content_type,
+        bytes,
+        duration_ms: audio.samples.len() as u64 * 1000 / u64::from(audio.sample_rate.max(1)),
+    })
+}
+
+/// Write metadata tags into audio already encoded as `format`
+pub fn tag_encoded(format: &str, audio: &[u8], metadata: &AudioMetadata) -> ApiResult<Vec<u8>> {
+    tag_audio(format, audio, metadata).map_err(|e| {
+        error!(error = %e, "Failed to write audio metadata");
+        AppError::Internal
+    })
+}
+
+/// Map a backend failure to the API error clients can branch on
+fn synthesis_error(e: anyhow::Error) -> AppError {
+    if let Some(AppError::OutputLimitExceeded(limit)) = e.downcast_ref::<AppError>() {
+        AppError::OutputLimitExceeded(*limit)
+    } else if e.is::<BackendUnavailable>() {
+        AppError::service_unavailable(e.to_string())
+    } else if e.is::<QueueFull>() {
+        AppError::overloaded(e.to_string())
+    } else if e.is::<SynthesisTimeout>() {
+        AppError::timeout(e.to_string())
+    } else {
+        error!("Synthesis failed: {}", redact::message(&e.to_string()));
+        AppError::Backend(e.to_string())
     }
 }
 
 /// Encode float samples to WAV format
-fn encode_wav(samples: &[f32], sample_rate: u32) -> Result<Bytes, AppError> {
-    use hound::{WavSpec, WavWriter};
+fn encode_wav(samples: &[f32], sample_rate: u32, bit_depth: BitDepth) -> Result<Bytes, AppError> {
+    use hound::{SampleFormat, WavSpec, WavWriter};
     use std::io::Cursor;
 
     let spec = WavSpec {
         channels: 1,
         sample_rate,
-        bits_per_sample: 16,
-        sample_format: hound::SampleFormat::Int,
+        bits_per_sample: bit_depth.bits(),
+        sample_format: if bit_depth.is_float() {
+            SampleFormat::Float
+        } else {
+            SampleFormat::Int
+        },
     };
 
     let mut cursor = Cursor::new(Vec::new());
@@ -405,10 +1872,12 @@ fn encode_wav(samples: &[f32], sample_rate: u32) -> Result<Bytes, AppError> {
         let mut writer = WavWriter::new(&mut cursor, spec).map_err(|_e| AppError::Internal)?;
 
         for &sample in samples {
-            let int_sample = pcm_i16_from_f32(sample);
-            writer
-                .write_sample(int_sample)
-                .map_err(|_e| AppError::Internal)?;
+            match bit_depth {
+                BitDepth::Int16 => writer.write_sample(pcm_i16_from_f32(sample)),
+                BitDepth::Int24 => writer.write_sample(pcm_i24_from_f32(sample)),
+                BitDepth::Float32 => writer.write_sample(sample.clamp(-1.0, 1.0)),
+            }
+            .map_err(|_e| AppError::Internal)?;
         }
 
         writer.finalize().map_err(|_e| AppError::Internal)?;
@@ -419,14 +1888,7 @@ fn encode_wav(samples: &[f32], sample_rate: u32) -> Result<Bytes, AppError> {
 
 /// Encode float samples to raw PCM (16-bit little-endian)
 fn encode_pcm(samples: &[f32]) -> Bytes {
-    let mut bytes = Vec::with_capacity(samples.len() * 2);
-
-    for &sample in samples {
-        let int_sample = pcm_i16_from_f32(sample);
-        bytes.extend_from_slice(&int_sample.to_le_bytes());
-    }
-
-    Bytes::from(bytes)
+    Bytes::from(samples_to_pcm16(samples))
 }
 
 /// Encode float samples to MP3 format
@@ -450,3 +1912,173 @@ fn encode_opus(samples: &[f32], sample_rate: u32) -> Result<Bytes, AppError> {
             AppError::Internal
         })
 }
+
+/// Encode float samples as 48 kHz stereo Ogg Opus for Discord voice connections
+fn encode_discord(samples: &[f32], sample_rate: u32) -> Result<Bytes, AppError> {
+    crate::discord::encode_discord_opus(samples, sample_rate)
+        .map(Bytes::from)
+        .map_err(|e| {
+            error!(error = %e, "Failed to encode Discord Opus");
+            AppError::Internal
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_document() {
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        assert!(doc["openapi"].as_str().unwrap().starts_with("3.1"));
+        for path in [
+            "/v1/audio/speech",
+            "/v1/audio/speech/batch",
+            "/v1/audio/voices",
+            "/v1/models",
+            "/v1/models/{model_id}",
+            "/health",
+            "/readyz",
+            "/version",
+            "/v1/audio/audiobooks",
+            "/v1/audio/jobs",
+            "/v1/audio/jobs/{job_id}",
+            "/feeds/{feed}",
+            "/v1/audio/speech/sign",
+        ] {
+            assert!(doc["paths"][path].is_object(), "missing {}", path);
+        }
+        assert!(doc["components"]["schemas"]["OpenAIError"].is_object());
+        assert!(doc["components"]["schemas"]["SpeechRequest"].is_object());
+        assert!(doc["components"]["securitySchemes"]["api_key"].is_object());
+    }
+
+    #[cfg(feature = "mock-backend")]
+    #[tokio::test]
+    async fn test_public_router_has_no_admin_routes() {
+        use tower::ServiceExt;
+
+        let router = create_router(AppState::for_tests().await);
+        let request = axum::http::Request::post("/admin/reload")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[cfg(feature = "mock-backend")]
+    #[tokio::test]
+    async fn test_cache_hit_checks_duration() {
+        let state = AppState {
+            cache: Some(Arc::new(AudioCache::new(1024 * 1024, None))),
+            ..AppState::for_tests().await
+        };
+        // The mock reads 15 characters a second, so this runs about 4 seconds
+        let request = SpeechRequest {
+            model: "kokoro".to_string(),
+            input: "word ".repeat(12),
+            ..Default::default()
+        };
+        let params = validate_speech_request(&state, request.clone()).unwrap();
+        let (_, status) = cached_clip(&state, &params, "test").await.unwrap();
+        assert_eq!(status, Some("MISS"));
+
+        let loose = validate_speech_request(
+            &state,
+            SpeechRequest {
+                max_duration_s: Some(10),
+                ..request.clone()
+            },
+        )
+        .unwrap();
+        let (audio, status) = cached_clip(&state, &loose, "test").await.unwrap();
+        assert_eq!(status, Some("HIT"));
+        assert!((3_000..5_000).contains(&audio.duration_ms));
+
+        let tight = validate_speech_request(
+            &state,
+            SpeechRequest {
+                max_duration_s: Some(2),
+                ..request
+            },
+        )
+        .unwrap();
+        let err = cached_clip(&state, &tight, "test").await.unwrap_err();
+        assert_eq!(err.code(), "output_limit_exceeded");
+    }
+
+    #[cfg(feature = "mock-backend")]
+    #[tokio::test]
+    async fn test_prewarmed_phrase_hits_cache() {
+        let state = AppState {
+            cache: Some(Arc::new(AudioCache::new(1024 * 1024, None))),
+            ..AppState::for_tests().await
+        };
+        let phrases = vec!["Please hold.".to_string()];
+        let voices = vec!["af_alloy".to_string()];
+        let formats = vec!["mp3".to_string()];
+        let counts = prewarm_cache(&state, &phrases, &voices, &formats).await;
+        assert_eq!(counts, (1, 0, 0));
+        let counts = prewarm_cache(&state, &phrases, &voices, &formats).await;
+        assert_eq!(counts, (0, 1, 0));
+
+        let request = SpeechRequest {
+            model: "kokoro".to_string(),
+            input: "Please hold.".to_string(),
+            voice: Some("af_alloy".to_string()),
+            response_format: Some("mp3".to_string()),
+            ..Default::default()
+        };
+        let params = validate_speech_request(&state, request).unwrap();
+        let (_, status) = cached_clip(&state, &params, "test").await.unwrap();
+        assert_eq!(status, Some("HIT"));
+    }
+
+    #[test]
+    fn test_reload_files_stay_in_model_dir() {
+        let root = std::env::temp_dir().join(format!("kokoro-reload-{}", Uuid::new_v4()));
+        let model_dir = root.join("models");
+        std::fs::create_dir_all(&model_dir).unwrap();
+        std::fs::write(model_dir.join("kokoro.onnx"), b"model").unwrap();
+        std::fs::write(model_dir.join("voices.json"), b"not a pack").unwrap();
+        std::fs::write(root.join("secret.onnx"), b"elsewhere").unwrap();
+        let current = ModelFiles::from_model_path(model_dir.join("kokoro.onnx"));
+        let check = |model_path: Option<&str>| {
+            let req = ReloadRequest {
+                model_path: model_path.map(PathBuf::from),
+                voices_path: None,
+            };
+            let files = resolve_reload_files(&model_dir, current.clone(), req);
+            check_reload_files(&model_dir, files)
+                .unwrap_err()
+                .to_string()
+        };
+
+        let outside = root.join("secret.onnx");
+        for path in ["../secret.onnx", outside.to_str().unwrap(), "missing.onnx"] {
+            let message = check(Some(path));
+            assert!(message.contains("model_path must name a file"), "{message}");
+            assert!(!message.contains("secret"), "{message}");
+        }
+        // A file inside the directory gets as far as the voice pack check
+        assert!(check(Some("kokoro.onnx")).contains("voices_path is not a valid voice pack"));
+        assert!(check(None).contains("voices_path is not a valid voice pack"));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_presented_api_key_in_query() {
+        let headers = HeaderMap::new();
+        let key = |uri: &str| {
+            let uri: Uri = uri.parse().unwrap();
+            presented_api_key(&headers, &uri).map(str::to_string)
+        };
+        assert_eq!(
+            key("/v1/text:synthesize?key=secret"),
+            Some("secret".to_string())
+        );
+        assert_eq!(key("/feeds/news.xml?key=secret"), None);
+        assert_eq!(key("/v1/audio/jobs/job_1/chapters/0?key=secret"), None);
+        assert_eq!(key("/v1/audio/speech?key=secret"), None);
+    }
+}
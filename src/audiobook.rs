@@ -0,0 +1,660 @@
+use crate::{
+    api::{
+        check_duration, encode_audio, validate_speech_request_within, AppState, SpeechParams,
+        SpeechRequest,
+    },
+    backend::{AudioData, BackendUnavailable},
+    chapters::{
+        read_article, read_epub, split_markdown, split_text, Chapter, DocumentFormat,
+        DOCUMENT_FORMATS,
+    },
+    error::{ApiResult, AppError},
+    extract::ApiJson,
+    feeds::valid_feed_id,
+    jobs::{Job, JobStatus, RunTicket},
+    load_shed::admit_batch,
+    normalize::normalize,
+    presets::silence_samples,
+    redact,
+    speed_curve::{chunk_speeds, SpeedPoint},
+    streaming::{chunk_text, trim_to_zero_crossings, ZERO_CROSSING_WINDOW_MS},
+    tags::{tag_audio, AudioMetadata},
+    validation::sanitize_input,
+};
+use anyhow::{Context, Result};
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use tracing::{debug, error, info, warn};
+use utoipa::ToSchema;
+use zip::write::SimpleFileOptions;
+
+/// Largest audiobook request body, enough for a base64-encoded illustrated EPUB
+pub const DOCUMENT_BODY_LIMIT: usize = 64 * 1024 * 1024;
+
+/// Chapter audio format when neither the request nor its preset picks one
+const DEFAULT_AUDIOBOOK_FORMAT: &str = "mp3";
+
+/// Longest chapter title used in a file name
+const MAX_FILE_TITLE_CHARS: usize = 60;
+
+/// Kind of the jobs this module runs
+const JOB_KIND: &str = "audiobook";
+
+/// Request body for POST /v1/audio/audiobooks
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AudiobookRequest {
+    /// Model ID ("tts-1" or "kokoro")
+    pub model: String,
+    /// Document text, or a base64-encoded EPUB file when `input_format` is "epub"; required
+    /// unless `url` is given
+    #[serde(default)]
+    pub input: String,
+    /// "text" (chapters separated by lines containing only `---`; default), "markdown" (split
+    /// at headings), or "epub"
+    #[serde(default)]
+    pub input_format: Option<String>,
+    /// Podcast feed to publish the chapters in once the job completes, read at
+    /// `/feeds/{feed}.xml` (letters, digits, `-`, and `_`)
+    #[serde(default)]
+    pub feed: Option<String>,
+    /// Web page to read instead of `input`; its article text is extracted and read as one
+    /// chapter. The host must be listed in KOKORO_FETCH_ALLOW.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Voice ID (default "af_alloy"), or a blend of voices as for /v1/audio/speech
+    #[serde(default, deserialize_with = "crate::voice_blend::deserialize_voice")]
+    #[schema(value_type = Option<crate::voice_blend::VoiceSpec>)]
+    pub voice: Option<String>,
+    /// Format of each chapter file ("mp3" by default, "wav", "pcm", "opus")
+    #[serde(default)]
+    pub response_format: Option<String>,
+    /// Speed multiplier (0.25 to 4.0); defaults to the voice's preset, then 1.0
+    #[serde(default)]
+    pub speed: Option<f32>,
+    /// Speed varying across each chapter, as in a speech request's `speed_curve`
+    #[serde(default)]
+    pub speed_curve: Option<Vec<SpeedPoint>>,
+    /// Named preset from the presets file supplying any of the settings above left unset
+    #[serde(default)]
+    pub preset: Option<String>,
+    /// Dynamic range compressor preset ("gentle", "speech", "phone")
+    #[serde(default)]
+    pub compressor: Option<String>,
+    /// Filters applied in order before compression ("highpass", "presence", "telephone")
+    #[serde(default)]
+    pub effects: Option<Vec<String>>,
+    /// Sample depth of WAV output: 16 (default), 24, or 32 (float)
+    #[serde(default)]
+    pub bit_depth: Option<u16>,
+    /// Language of the input ("en-us", "es", "ja", ...); defaults to the voice's language
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Tags written into every chapter file; each is titled by its chapter, and `title`
+    /// names the album unless `album` is given
+    #[serde(default)]
+    pub metadata: Option<AudioMetadata>,
+}
+
+/// What an audiobook job needs to run, recorded with the job so it can start again after a
+/// restart
+#[derive(Serialize, Deserialize)]
+struct AudiobookSpec {
+    /// Chapters as read, already normalized
+    chapters: Vec<Chapter>,
+    /// Settings of the speech request the job was validated as, without its input
+    request: SpeechRequest,
+}
+
+/// Synthesize a long document chapter by chapter as a background job
+#[utoipa::path(
+    post,
+    path = "/v1/audio/audiobooks",
+    tag = "jobs",
+    request_body = AudiobookRequest,
+    responses(
+        (status = 202, description = "Job created; poll the Location header for progress", body = Job),
+        (status = 400, description = "Invalid document, model, voice, or format", body = crate::error::OpenAIError),
+        (status = 401, description = "Missing or invalid API key", body = crate::error::OpenAIError),
+        (status = 503, description = "Refused while the server is shedding load", body = crate::error::OpenAIError)
+    )
+)]
+pub async fn create_audiobook_handler(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<AudiobookRequest>,
+) -> ApiResult<impl IntoResponse> {
+    admit_batch(&state)?;
+
+    if let Some(feed) = &req.feed {
+        if !valid_feed_id(feed) {
+            return Err(AppError::invalid_request(
+                "feed must be 1 to 64 letters, digits, '-', or '_'",
+            ));
+        }
+    }
+
+    let format = match req.input_format.as_deref() {
+        None => DocumentFormat::Text,
+        Some(name) => DocumentFormat::from_name(name).ok_or_else(|| {
+            AppError::invalid_request(format!(
+                "input_format '{}' not supported. Supported values: {}",
+                name,
+                DOCUMENT_FORMATS.join(", ")
+            ))
+        })?,
+    };
+    let mut chapters = if let Some(url) = &req.url {
+        if !req.input.is_empty() || req.input_format.is_some() {
+            return Err(AppError::invalid_request(
+                "url cannot be combined with input or input_format",
+            ));
+        }
+        let fetch = state.fetch.as_ref().ok_or_else(|| {
+            AppError::invalid_request("URL input is disabled; set KOKORO_FETCH_ALLOW to enable it")
+        })?;
+        let page = fetch
+            .fetch(url)
+            .await
+            .map_err(|e| AppError::invalid_request(format!("Failed to fetch url: {:#}", e)))?;
+        if page.html {
+            vec![read_article(&page.body)
+                .map_err(|e| AppError::invalid_request(format!("Unreadable page: {:#}", e)))?]
+        } else {
+            split_text(&page.body)
+        }
+    } else {
+        match format {
+            DocumentFormat::Text => split_text(&req.input),
+            DocumentFormat::Markdown => split_markdown(&req.input),
+            DocumentFormat::Epub => {
+                let bytes = BASE64
+                    .decode(req.input.trim())
+                    .map_err(|_| AppError::invalid_request("EPUB input must be base64-encoded"))?;
+                // Unpacking and parsing a large book would hold up the runtime
+                tokio::task::spawn_blocking(move || read_epub(&bytes))
+                    .await
+                    .map_err(|e| {
+                        error!(error = %e, "EPUB parsing panicked");
+                        AppError::Internal
+                    })?
+                    .map_err(|e| AppError::invalid_request(format!("Invalid EPUB: {:#}", e)))?
+            }
+        }
+    };
+
+    // Settings are checked once against the whole document, as a speech request would be
+    let request = SpeechRequest {
+        model: req.model,
+        input: String::new(),
+        voice: req.voice,
+        response_format: req.response_format.or_else(|| {
+            req.preset
+                .is_none()
+                .then(|| DEFAULT_AUDIOBOOK_FORMAT.to_string())
+        }),
+        speed: req.speed,
+        speed_curve: req.speed_curve,
+        preset: req.preset,
+        compressor: req.compressor,
+        effects: req.effects,
+        bit_depth: req.bit_depth,
+        language: req.language,
+        metadata: req.metadata,
+        ..Default::default()
+    };
+    let mut params = validate_speech_request_within(
+        &state,
+        SpeechRequest {
+            input: document_text(&chapters),
+            ..request.clone()
+        },
+        state.max_document_chars,
+    )?;
+    params.input.clear();
+    for chapter in &mut chapters {
+        chapter.text = sanitize_input(&chapter.text).into_owned();
+        if state.fold_punctuation {
+            chapter.text = crate::punctuation::fold(&chapter.text);
+        }
+    }
+    if let Some(rules) = &state.normalize_rules {
+        for chapter in &mut chapters {
+            chapter.text = normalize(&chapter.text, params.language, rules);
+        }
+    }
+
+    let spec = AudiobookSpec { chapters, request };
+    let job = state.jobs.create(
+        JOB_KIND,
+        spec.chapters
+            .iter()
+            .map(|chapter| chapter.title.clone())
+            .collect(),
+        req.feed,
+        &serde_json::to_string(&spec).map_err(|_| AppError::Internal)?,
+    );
+    let chapters = spec.chapters;
+    info!(
+        job_id = %job.id,
+        chapters = chapters.len(),
+        voice = %params.voice,
+        format = %params.format,
+        "Audiobook job queued"
+    );
+    let ticket = state.jobs.enqueue();
+    tokio::spawn(run_audiobook(
+        state,
+        job.id.clone(),
+        chapters,
+        params,
+        ticket,
+    ));
+
+    Ok((
+        StatusCode::ACCEPTED,
+        [(header::LOCATION, format!("/v1/audio/jobs/{}", job.id))],
+        Json(job),
+    ))
+}
+
+/// Start the audiobook jobs that were queued, or interrupted, when the server last stopped.
+/// They rejoin the runner's queue in the order they were first submitted.
+pub fn resume_jobs(state: &AppState) {
+    for (job_id, spec) in state.jobs.queued(JOB_KIND) {
+        let resumed = serde_json::from_str::<AudiobookSpec>(&spec)
+            .map_err(|e| format!("unreadable job record: {}", e))
+            .and_then(|spec| {
+                // The document was accepted when the job was created, so its length is not
+                // checked again
+                let mut params = validate_speech_request_within(
+                    state,
+                    SpeechRequest {
+                        input: document_text(&spec.chapters),
+                        ..spec.request
+                    },
+                    usize::MAX,
+                )
+                .map_err(|e| e.to_string())?;
+                params.input.clear();
+                Ok((spec.chapters, params))
+            });
+        match resumed {
+            Ok((chapters, params)) => {
+                info!(job_id = %job_id, chapters = chapters.len(), "Audiobook job resumed");
+                let ticket = state.jobs.enqueue();
+                tokio::spawn(run_audiobook(
+                    state.clone(),
+                    job_id,
+                    chapters,
+                    params,
+                    ticket,
+                ));
+            }
+            Err(e) => {
+                warn!(job_id = %job_id, error = %e, "Failed to resume audiobook job");
+                state
+                    .jobs
+                    .fail(&job_id, format!("Could not resume after restart: {}", e));
+            }
+        }
+    }
+}
+
+/// The whole document, for checking a job's settings against
+fn document_text(chapters: &[Chapter]) -> String {
+    chapters
+        .iter()
+        .map(|chapter| chapter.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+async fn run_audiobook(
+    state: AppState,
+    job_id: String,
+    chapters: Vec<Chapter>,
+    params: SpeechParams,
+    ticket: RunTicket,
+) {
+    let Some(mut cancelled) = state.jobs.cancellation(&job_id) else {
+        return;
+    };
+    // Dropping the job mid-chapter drops its synthesis, giving up the chunks still queued for
+    // the backend; a chunk the backend is already working on runs to completion
+    tokio::select! {
+        () = run_job(&state, &job_id, &chapters, &params, ticket) => {}
+        () = async { drop(cancelled.wait_for(|cancelled| *cancelled).await) } => {
+            let _ = tokio::fs::remove_file(part_path(&state.jobs.result_path(&job_id))).await;
+            info!(job_id = %job_id, "Audiobook job cancelled");
+        }
+    }
+}
+
+async fn run_job(
+    state: &AppState,
+    job_id: &str,
+    chapters: &[Chapter],
+    params: &SpeechParams,
+    ticket: RunTicket,
+) {
+    let jobs = &state.jobs;
+    // The job runs while it holds the ticket, which it does until it returns
+    if let Err(e) = ticket.turn().await {
+        warn!(job_id = %job_id, error = %e, "Audiobook job could not start");
+        jobs.fail(job_id, e.to_string());
+        return;
+    }
+    jobs.update(job_id, |job| job.status = JobStatus::Running);
+    let started = std::time::Instant::now();
+
+    match write_audiobook(state, job_id, chapters, params).await {
+        Ok(()) => {
+            info!(
+                job_id = %job_id,
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "Audiobook job complete"
+            );
+            jobs.complete(job_id);
+        }
+        Err(e) => {
+            let message = redact::message(&format!("{:#}", e)).into_owned();
+            warn!(job_id = %job_id, error = %message, "Audiobook job failed");
+            let _ = tokio::fs::remove_file(part_path(&jobs.result_path(job_id))).await;
+            jobs.fail(job_id, message);
+        }
+    }
+}
+
+/// Synthesize every chapter into a zip archive, one audio file per chapter in reading order
+async fn write_audiobook(
+    state: &AppState,
+    job_id: &str,
+    chapters: &[Chapter],
+    params: &SpeechParams,
+) -> Result<()> {
+    let jobs = &state.jobs;
+    tokio::fs::create_dir_all(jobs.dir())
+        .await
+        .with_context(|| format!("Failed to create {}", jobs.dir().display()))?;
+    let result_path = jobs.result_path(job_id);
+    let part_path = part_path(&result_path);
+    let file = std::fs::File::create(&part_path)
+        .with_context(|| format!("Failed to create {}", part_path.display()))?;
+    let mut writer = zip::ZipWriter::new(file);
+    let width = chapters.len().to_string().len().max(2);
+
+    for (index, chapter) in chapters.iter().enumerate() {
+        jobs.update(job_id, |job| {
+            job.chapters[index].status = JobStatus::Running
+        });
+        let audio = synthesize_chapter(state, job_id, index, &chapter.text, params).await?;
+        let duration_ms = audio.samples.len() as u64 * 1000 / u64::from(audio.sample_rate.max(1));
+
+        // Encoding a whole chapter and writing it out are too slow for the async runtime
+        let name = chapter_file_name(index, width, &chapter.title, &params.format);
+        let format = params.format.clone();
+        let bit_depth = params.bit_depth;
+        let metadata = params
+            .metadata
+            .as_ref()
+            .map(|metadata| chapter_metadata(metadata, &chapter.title));
+        writer = tokio::task::spawn_blocking(move || -> Result<_> {
+            let encoded = encode_audio(&format, &audio, bit_depth)?;
+            let bytes = match &metadata {
+                Some(metadata) => tag_audio(&format, &encoded.bytes, metadata)?,
+                None => encoded.bytes.to_vec(),
+            };
+            // Audio is already compressed, or would gain little from deflate
+            writer.start_file(
+                name,
+                SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored),
+            )?;
+            writer.write_all(&bytes)?;
+            Ok(writer)
+        })
+        .await
+        .context("Chapter encoding task panicked")??;
+
+        jobs.update(job_id, |job| {
+            job.chapters[index].status = JobStatus::Completed;
+            job.chapters[index].duration_ms = Some(duration_ms);
+            job.progress.completed = index + 1;
+        });
+        debug!(job_id = %job_id, chapter = index, duration_ms, "Audiobook chapter complete");
+    }
+
+    tokio::task::spawn_blocking(move || -> Result<()> {
+        writer.finish()?;
+        std::fs::rename(&part_path, &result_path)
+            .with_context(|| format!("Failed to move result to {}", result_path.display()))
+    })
+    .await
+    .context("Archive task panicked")?
+}
+
+/// Synthesize a chapter a chunk at a time and join the chunks, with effects applied to the
+/// whole chapter; `source` names the job or file the chapter belongs to in logs
+pub async fn synthesize_chapter(
+    state: &AppState,
+    source: &str,
+    chapter: usize,
+    text: &str,
+    params: &SpeechParams,
+) -> Result<AudioData> {
+    // Each chapter is held to the server's duration cap on its own
+    let mut chapter_audio = synthesize_chunks(
+        state,
+        &format!("{} chapter {}", source, chapter + 1),
+        text,
+        params,
+        None,
+        state.max_duration_s,
+    )
+    .await
+    .with_context(|| format!("Chapter {} failed to synthesize", chapter + 1))?;
+
+    params
+        .effects
+        .apply(&mut chapter_audio.samples, chapter_audio.sample_rate);
+    if let Some(sample_rate) = params.sample_rate {
+        chapter_audio = chapter_audio.resampled(sample_rate);
+    }
+    chapter_audio.pad_end(params.trailing_silence);
+    Ok(chapter_audio)
+}
+
+/// Synthesize `text` a chunk at a time, each at its speed along the request's speed curve, and
+/// join the chunks without post-processing, with the request's sentence gap between them.
+/// Synthesis stops with [`AppError::OutputLimitExceeded`] once the audio runs past
+/// `max_duration_s`.
+pub async fn synthesize_chunks(
+    state: &AppState,
+    source: &str,
+    text: &str,
+    params: &SpeechParams,
+    initial_silence: Option<usize>,
+    max_duration_s: Option<u32>,
+) -> Result<AudioData> {
+    let policy = state.chunk_retry;
+    let backend = state.backend_for(params.quality);
+    let mut joined = AudioData {
+        samples: Vec::new(),
+        sample_rate: backend.sample_rate(),
+    };
+
+    let chunks = chunk_text(text, &params.chunk_rules);
+    let speeds = chunk_speeds(params.speed, params.speed_curve.as_ref(), &chunks);
+    for (index, (chunk, speed)) in chunks.iter().zip(speeds).enumerate() {
+        // The leading silence belongs to the first chunk only
+        let silence = initial_silence.filter(|_| index == 0);
+        let mut retry = 0;
+        let mut audio = loop {
+            let synthesized = if params.emphasis {
+                crate::emphasis::synthesize(
+                    backend,
+                    chunk,
+                    &params.voice,
+                    params.language,
+                    speed,
+                    silence,
+                )
+                .await
+            } else {
+                backend
+                    .synthesize(chunk, &params.voice, params.language, speed, silence)
+                    .await
+            };
+            match synthesized {
+                Ok(audio) => break audio,
+                Err(e) if retry < policy.retries && !e.is::<BackendUnavailable>() => {
+                    let delay = policy.backoff_for(retry);
+                    retry += 1;
+                    warn!(
+                        source = %source,
+                        chunk = index,
+                        attempt = retry,
+                        retry_in_ms = delay.as_millis() as u64,
+                        error = %redact::message(&e.to_string()),
+                        "Chunk synthesis failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        let window = silence_samples(ZERO_CROSSING_WINDOW_MS, audio.sample_rate);
+        trim_to_zero_crossings(&mut audio.samples, window);
+        joined.sample_rate = audio.sample_rate;
+        joined.samples.extend_from_slice(&audio.samples);
+        if let Some(gap) = params.sentence_gap_ms.filter(|_| index + 1 < chunks.len()) {
+            let gap = silence_samples(gap, audio.sample_rate);
+            joined.samples.resize(joined.samples.len() + gap, 0.0);
+        }
+        check_duration(&joined, max_duration_s)?;
+    }
+    Ok(joined)
+}
+
+fn part_path(result_path: &std::path::Path) -> std::path::PathBuf {
+    let mut path = result_path.as_os_str().to_owned();
+    path.push(".part");
+    path.into()
+}
+
+/// Tags for one chapter file: the chapter title, with the book's title as the album
+fn chapter_metadata(metadata: &AudioMetadata, title: &str) -> AudioMetadata {
+    AudioMetadata {
+        title: Some(title.to_string()),
+        album: metadata.album.clone().or_else(|| metadata.title.clone()),
+        ..metadata.clone()
+    }
+}
+
+/// Numbered file name that sorts in reading order, e.g. `03 - The Storm.mp3`
+fn chapter_file_name(index: usize, width: usize, title: &str, format: &str) -> String {
+    let cleaned: String = title
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || " -_',.()".contains(c) {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    let cleaned = cleaned
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(MAX_FILE_TITLE_CHARS)
+        .collect::<String>();
+    let cleaned = cleaned.trim_matches(['.', ' ']);
+    if cleaned.is_empty() {
+        format!("{:0width$}.{}", index + 1, format)
+    } else {
+        format!("{:0width$} - {}.{}", index + 1, cleaned, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "mock-backend")]
+    #[tokio::test]
+    async fn test_chapter_held_to_duration_cap() {
+        let state = AppState {
+            max_duration_s: Some(2),
+            ..AppState::for_tests().await
+        };
+        let text = "One more sentence. ".repeat(10);
+        let mut params = validate_speech_request_within(
+            &state,
+            SpeechRequest {
+                model: "kokoro".to_string(),
+                input: text.clone(),
+                ..Default::default()
+            },
+            state.max_document_chars,
+        )
+        .unwrap();
+        // The server's cap applies even when the job's own settings carry none
+        params.max_duration_s = None;
+
+        let err = synthesize_chapter(&state, "test", 0, &text, &params)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AppError>(),
+            Some(AppError::OutputLimitExceeded(2))
+        ));
+    }
+
+    #[test]
+    fn test_chapter_file_name() {
+        assert_eq!(
+            chapter_file_name(2, 2, "Chapter 3: The Storm", "mp3"),
+            "03 - Chapter 3 The Storm.mp3"
+        );
+        assert_eq!(
+            chapter_file_name(9, 3, "../../etc/passwd", "wav"),
+            "010 - etc passwd.wav"
+        );
+        assert_eq!(chapter_file_name(0, 2, "***", "opus"), "01.opus");
+    }
+
+    #[test]
+    fn test_chapter_metadata() {
+        let book = AudioMetadata {
+            title: Some("The Sea".to_string()),
+            artist: Some("A. Writer".to_string()),
+            ..AudioMetadata::default()
+        };
+        let chapter = chapter_metadata(&book, "The Storm");
+        assert_eq!(chapter.title.as_deref(), Some("The Storm"));
+        assert_eq!(chapter.album.as_deref(), Some("The Sea"));
+        assert_eq!(chapter.artist.as_deref(), Some("A. Writer"));
+
+        let with_album = AudioMetadata {
+            album: Some("Collected Works".to_string()),
+            ..book
+        };
+        let chapter = chapter_metadata(&with_album, "The Storm");
+        assert_eq!(chapter.album.as_deref(), Some("Collected Works"));
+    }
+
+    #[test]
+    fn test_part_path() {
+        assert_eq!(
+            part_path(std::path::Path::new("/jobs/job_1.zip")),
+            std::path::PathBuf::from("/jobs/job_1.zip.part")
+        );
+    }
+}
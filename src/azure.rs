@@ -0,0 +1,191 @@
+use crate::{
+    api::{
+        presented_api_key, synthesize_clip, validate_speech_request, voice_language, AppState,
+        SpeechRequest, VOICE_SUBSTITUTED_HEADER,
+    },
+    error::{ApiResult, AppError},
+    pcm::{pcm16_to_wav, resample_pcm16},
+    ssml,
+    validation::VoiceVendor,
+};
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderMap, StatusCode, Uri},
+    response::Response,
+};
+use tracing::{debug, error};
+use uuid::Uuid;
+
+/// Voice used when the SSML has no `<voice>` element
+const DEFAULT_VOICE: &str = "af_alloy";
+
+/// Header selecting the audio format, e.g. `audio-24khz-48kbitrate-mono-mp3`
+const OUTPUT_FORMAT_HEADER: &str = "x-microsoft-outputformat";
+
+/// How an Azure output format is produced
+#[derive(Debug, PartialEq)]
+enum OutputFormat {
+    /// Encoded by the shared speech path: response format and content type
+    Encoded(&'static str, &'static str),
+    /// 16-bit mono PCM resampled to `sample_rate`, with a WAV header when `riff` is set
+    Pcm { sample_rate: u32, riff: bool },
+}
+
+/// Parse an `X-Microsoft-OutputFormat` value.
+///
+/// MP3 and Opus bitrates and sample rates are ignored; Kokoro encodes at its native rate.
+fn output_format(name: &str) -> ApiResult<OutputFormat> {
+    let name = name.trim().to_ascii_lowercase();
+    let unsupported = || {
+        AppError::invalid_request(format!(
+            "X-Microsoft-OutputFormat '{}' not supported. Supported formats: audio-*-mp3, \
+             ogg-*-opus, riff-*-16bit-mono-pcm, raw-*-16bit-mono-pcm",
+            name
+        ))
+    };
+
+    if name.starts_with("audio-") && name.ends_with("-mp3") {
+        return Ok(OutputFormat::Encoded("mp3", "audio/mpeg"));
+    }
+    if name.starts_with("ogg-") && name.ends_with("-opus") {
+        return Ok(OutputFormat::Encoded("opus", "audio/ogg"));
+    }
+
+    let (riff, rest) = if let Some(rest) = name.strip_prefix("riff-") {
+        (true, rest)
+    } else if let Some(rest) = name.strip_prefix("raw-") {
+        (false, rest)
+    } else {
+        return Err(unsupported());
+    };
+    let rate = rest
+        .strip_suffix("-16bit-mono-pcm")
+        .ok_or_else(unsupported)?;
+    let sample_rate = if let Some(khz) = rate.strip_suffix("khz") {
+        khz.parse::<u32>().ok().map(|khz| khz * 1000)
+    } else {
+        rate.strip_suffix("hz").and_then(|hz| hz.parse().ok())
+    }
+    .filter(|rate| (8_000..=48_000).contains(rate))
+    .ok_or_else(unsupported)?;
+
+    Ok(OutputFormat::Pcm { sample_rate, riff })
+}
+
+/// Azure-compatible speech synthesis from an SSML body
+pub async fn synthesize_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: String,
+) -> ApiResult<Response> {
+    let request_id = Uuid::new_v4().to_string();
+
+    let format_name = headers
+        .get(OUTPUT_FORMAT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| AppError::invalid_request("X-Microsoft-OutputFormat header is required"))?;
+    let format = output_format(format_name)?;
+    let voice_name = ssml::voice_name(&body);
+
+    debug!(
+        request_id = %request_id,
+        voice_name = ?voice_name,
+        output_format = %format_name,
+        "Received Azure speech request"
+    );
+
+    let response_format = match format {
+        OutputFormat::Encoded(response_format, _) => response_format,
+        OutputFormat::Pcm { .. } => "pcm",
+    };
+    let voice = VoiceVendor::Azure
+        .kokoro_voice(voice_name.as_deref().unwrap_or(DEFAULT_VOICE))
+        .to_string();
+    let params = validate_speech_request(
+        &state,
+        SpeechRequest {
+            model: "kokoro".to_string(),
+            input: ssml::to_plain_text(&body, voice_language(&state, &voice)),
+            voice: Some(voice),
+            response_format: Some(response_format.to_string()),
+            ..Default::default()
+        },
+    )?;
+    let (audio, cache_status) = synthesize_clip(&state, &params, &request_id).await?;
+
+    let (content_type, bytes) = match format {
+        OutputFormat::Encoded(_, content_type) => (content_type, audio.bytes),
+        OutputFormat::Pcm { sample_rate, riff } => {
+            let pcm = resample_pcm16(&audio.bytes, state.backend.sample_rate(), sample_rate);
+            if riff {
+                let wav = pcm16_to_wav(&pcm, sample_rate).map_err(|e| {
+                    error!(error = %e, "Failed to encode WAV");
+                    AppError::Internal
+                })?;
+                ("audio/wav", wav.into())
+            } else {
+                ("audio/pcm", pcm.into())
+            }
+        }
+    };
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .header("X-Request-Id", request_id);
+    if let Some(cache_status) = cache_status {
+        response = response.header("X-Cache", cache_status);
+    }
+    if params.voice_substituted {
+        response = response.header(VOICE_SUBSTITUTED_HEADER, &params.voice);
+    }
+
+    response
+        .body(Body::from(bytes))
+        .map_err(|_| AppError::Internal)
+}
+
+/// Token exchange used by Azure clients before calling the speech endpoint.
+///
+/// The returned token is the presented subscription key, which the auth
+/// middleware has already checked and accepts as a bearer token. Without API
+/// keys any token works, so a placeholder is returned.
+pub async fn issue_token_handler(headers: HeaderMap, uri: Uri) -> String {
+    presented_api_key(&headers, &uri)
+        .unwrap_or("kokoro")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_format() {
+        assert_eq!(
+            output_format("audio-24khz-48kbitrate-mono-mp3").unwrap(),
+            OutputFormat::Encoded("mp3", "audio/mpeg")
+        );
+        assert_eq!(
+            output_format("Ogg-48khz-16bit-mono-opus").unwrap(),
+            OutputFormat::Encoded("opus", "audio/ogg")
+        );
+        assert_eq!(
+            output_format("riff-16khz-16bit-mono-pcm").unwrap(),
+            OutputFormat::Pcm {
+                sample_rate: 16_000,
+                riff: true
+            }
+        );
+        assert_eq!(
+            output_format("raw-22050hz-16bit-mono-pcm").unwrap(),
+            OutputFormat::Pcm {
+                sample_rate: 22_050,
+                riff: false
+            }
+        );
+        assert!(output_format("raw-8khz-8bit-mono-mulaw").is_err());
+        assert!(output_format("webm-24khz-16bit-mono-opus").is_err());
+    }
+}
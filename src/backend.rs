@@ -1,9 +1,25 @@
-use crate::config::Config;
-use crate::validation::DEFAULT_SAMPLE_RATE;
+use crate::config::{AccelerationKind, ArenaExtendStrategy, BackendKind, Config};
+use crate::estimate::DurationEstimator;
+use crate::inference_pool::InferencePool;
+use crate::language::Language;
+use crate::metrics::{BackendMetrics, SynthesisTimings};
+#[cfg(feature = "mock-backend")]
+use crate::mock::MockEngine;
+use crate::model_assets::ModelFiles;
+use crate::pcm;
+use crate::validation::{
+    get_available_voices, voices_from_ids, Voice, VoiceFilter, DEFAULT_SAMPLE_RATE,
+};
+use crate::voice_pack;
 use anyhow::{Context, Result};
-use std::sync::Arc;
+use kokoros::tts::{koko::TTSKoko, phonemizer::Phonemizer, tokenize::tokenize};
+use ort::ep::{self, ExecutionProviderDispatch};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, RwLock};
+use std::time::{Duration, Instant};
 use tokio::sync::Semaphore;
-use tracing::{debug, info};
+use tracing::{debug, error, info, warn};
 
 /// Audio synthesis result
 #[derive(Debug, Clone)]
@@ -12,59 +28,688 @@ pub struct AudioData {
     pub sample_rate: u32,
 }
 
+impl AudioData {
+    /// Append `samples` of silence after the speech
+    pub fn pad_end(&mut self, samples: usize) {
+        self.samples.resize(self.samples.len() + samples, 0.0);
+    }
+
+    /// The same audio linearly resampled to `sample_rate`
+    pub fn resampled(&self, sample_rate: u32) -> Self {
+        Self {
+            samples: pcm::resample(&self.samples, self.sample_rate, sample_rate),
+            sample_rate,
+        }
+    }
+}
+
+const WARMUP_TEXT: &str = "Hello.";
+const RECOVERY_INITIAL_BACKOFF: Duration = Duration::from_secs(5);
+const RECOVERY_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Synthesis was rejected because the circuit breaker is open
+#[derive(Debug, thiserror::Error)]
+#[error("Backend is recovering from repeated synthesis failures")]
+pub struct BackendUnavailable;
+
+/// Synthesis was rejected because too many requests are already waiting for a worker
+#[derive(Debug, thiserror::Error)]
+#[error("Too many requests are waiting for an inference worker")]
+pub struct QueueFull;
+
+/// Synthesis did not finish within the configured timeout
+#[derive(Debug, thiserror::Error)]
+#[error("Synthesis did not finish within {0:?}")]
+pub struct SynthesisTimeout(pub Duration);
+
+/// Inference engine behind one worker slot
+enum Engine {
+    Kokoro(TTSKoko),
+    #[cfg(feature = "mock-backend")]
+    Mock(MockEngine),
+}
+
+impl Engine {
+    fn tts_raw_audio(
+        &self,
+        text: &str,
+        language: Language,
+        voice: &str,
+        speed: f32,
+        initial_silence: Option<usize>,
+    ) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        match self {
+            Self::Kokoro(engine) => engine.tts_raw_audio(
+                text,
+                language.espeak_code(),
+                voice,
+                speed,
+                initial_silence,
+                None, // request_id
+                None, // instance_id
+                None, // chunk_number
+            ),
+            #[cfg(feature = "mock-backend")]
+            Self::Mock(engine) => Ok(engine.synthesize(text, voice, speed, initial_silence)),
+        }
+    }
+}
+
+/// Pool of engine instances loaded from the same files, one per worker
+struct LoadedModel {
+    engines: Vec<Engine>,
+    /// Indices of engines not currently running inference
+    idle: Mutex<Vec<usize>>,
+}
+
+impl LoadedModel {
+    async fn load(kind: BackendKind, files: &ModelFiles, instances: usize) -> Self {
+        let mut engines = Vec::with_capacity(instances);
+        for _ in 0..instances {
+            engines.push(load_engine(kind, files).await);
+        }
+
+        Self {
+            idle: Mutex::new((0..engines.len()).rev().collect()),
+            engines,
+        }
+    }
+
+    fn checkout(self: &Arc<Self>) -> Option<EngineLease> {
+        let index = self
+            .idle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop()?;
+        Some(EngineLease {
+            model: self.clone(),
+            index,
+        })
+    }
+}
+
+/// Exclusive use of one pooled engine, returned to the pool on drop
+struct EngineLease {
+    model: Arc<LoadedModel>,
+    index: usize,
+}
+
+impl EngineLease {
+    fn engine(&self) -> &Engine {
+        &self.model.engines[self.index]
+    }
+}
+
+impl Drop for EngineLease {
+    fn drop(&mut self) {
+        self.model
+            .idle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(self.index);
+    }
+}
+
 /// Kokoro backend for TTS inference
 pub struct KokoroBackend {
-    /// TTS engine
-    tts_engine: Arc<kokoros::tts::koko::TTSKoko>,
+    /// Currently active model, swapped atomically on reload; `None` while unloaded
+    model: RwLock<Option<Arc<LoadedModel>>>,
+    /// Files to load the model from when it is not resident
+    files: RwLock<ModelFiles>,
+    /// [`ModelFiles::fingerprint`] of `files`, for cache keys
+    fingerprint: RwLock<Arc<str>>,
+    /// Directory of the startup model, which reloads may only load files from
+    model_dir: PathBuf,
+    /// Permitted voices in the current voice pack, listed even while the model is unloaded
+    voices: RwLock<Arc<[Voice]>>,
+    /// Operator allow/deny lists applied to every voice pack
+    voice_filter: VoiceFilter,
+    /// Serializes on-demand loads so concurrent first requests load once
+    load_lock: tokio::sync::Mutex<()>,
+    /// Reference point for `last_used_ms`
+    started: Instant,
+    /// Milliseconds since `started` when an engine was last checked out
+    last_used_ms: AtomicU64,
+    /// Set while a background reload is running
+    reloading: AtomicBool,
+    /// Set while the startup warm-up is running
+    warming_up: AtomicBool,
+    /// Warm-up duration, once it has completed
+    warmup_ms: OnceLock<u64>,
+    /// Synthesis failures since the last success
+    consecutive_failures: AtomicU32,
+    /// Consecutive failures that open the circuit; 0 disables the breaker
+    failure_threshold: u32,
+    /// Set while the circuit is open and the engines are being reinitialized
+    circuit_open: AtomicBool,
     /// Concurrency limiter
     semaphore: Arc<Semaphore>,
+    /// Threads inference runs on, at least one per worker
+    pool: InferencePool,
+    /// Requests currently waiting for a permit
+    queued: AtomicUsize,
+    /// Waiting requests allowed before new ones are rejected; `None` is unbounded
+    max_queued: Option<usize>,
+    /// Upper bound on a single synthesis call, including queue wait
+    synthesis_timeout: Option<Duration>,
     /// Sample rate (Kokoro default is 24000)
     sample_rate: u32,
-    /// Configured upper bound for concurrent synth jobs
-    worker_limit: usize,
+    /// Upper bound for concurrent synth jobs; the semaphore holds this many permits
+    worker_limit: AtomicUsize,
+    /// Execution provider requested in configuration, possibly `auto`
+    acceleration: AccelerationKind,
+    /// Execution provider inference was steered onto
+    execution_provider: AccelerationKind,
+    /// GPU the CUDA execution provider runs on, when it is the one inference was steered onto
+    cuda_device: Option<u32>,
+    /// Engine implementation loaded into each worker slot
+    kind: BackendKind,
+    metrics: BackendMetrics,
+    /// Per-voice speaking rates learned from completed syntheses
+    estimator: DurationEstimator,
 }
 
 impl KokoroBackend {
     /// Initialize the backend
-    pub async fn new(config: &Config) -> Result<Self> {
+    pub async fn new(config: &Config, files: &ModelFiles) -> Result<Self> {
         info!("Initializing Kokoro backend...");
 
-        // Determine model and voices paths
-        let (model_path, voices_path) = if let Some(ref path) = config.model_path {
-            let voices_path = path.parent().unwrap_or(path).join("voices.json");
-            (path.clone(), voices_path)
+        let model = if config.lazy_load {
+            info!("Lazy loading enabled; model loads on first request");
+            None
         } else {
-            // Use default path in cache directory
-            let cache_dir = dirs::cache_dir()
-                .context("Failed to determine cache directory")?
-                .join("kokoro-openai-server")
-                .join("models");
-
-            std::fs::create_dir_all(&cache_dir)?;
-            let model_path = cache_dir.join("kokoro.onnx");
-            let voices_path = cache_dir.join("voices.json");
-            (model_path, voices_path)
+            Some(Arc::new(
+                LoadedModel::load(config.backend, files, config.workers).await,
+            ))
         };
 
-        // Initialize TTS engine (async)
-        let model_path_str = model_path.to_string_lossy().to_string();
-        let voices_path_str = voices_path.to_string_lossy().to_string();
-
-        let tts_engine =
-            Arc::new(kokoros::tts::koko::TTSKoko::new(&model_path_str, &voices_path_str).await);
-
         info!("Backend initialized with {} workers", config.workers);
+        if !config.inference_cores.is_empty() {
+            info!(cores = ?config.inference_cores, "Pinning inference threads");
+        }
 
+        let voice_filter =
+            VoiceFilter::new(config.voices_allow.clone(), config.voices_deny.clone());
         Ok(Self {
-            tts_engine,
+            model: RwLock::new(model),
+            files: RwLock::new(files.clone()),
+            fingerprint: RwLock::new(files.fingerprint().await.into()),
+            model_dir: match files.model_path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+                _ => PathBuf::from("."),
+            },
+            voices: RwLock::new(enumerate_voices(config.backend, files, &voice_filter)),
+            voice_filter,
+            load_lock: tokio::sync::Mutex::new(()),
+            started: Instant::now(),
+            last_used_ms: AtomicU64::new(0),
+            reloading: AtomicBool::new(false),
+            warming_up: AtomicBool::new(false),
+            warmup_ms: OnceLock::new(),
+            consecutive_failures: AtomicU32::new(0),
+            failure_threshold: config.circuit_breaker_threshold,
+            circuit_open: AtomicBool::new(false),
             semaphore: Arc::new(Semaphore::new(config.workers)),
+            pool: InferencePool::new(
+                config.inference_threads.unwrap_or(0).max(config.workers),
+                config.inference_cores.clone(),
+            ),
+            queued: AtomicUsize::new(0),
+            max_queued: config.max_queued_requests,
+            synthesis_timeout: config.synthesis_timeout_secs.map(Duration::from_secs),
             sample_rate: DEFAULT_SAMPLE_RATE,
-            worker_limit: config.workers,
+            worker_limit: AtomicUsize::new(config.workers),
+            acceleration: config.acceleration,
+            execution_provider: primary_execution_provider(config.acceleration),
+            cuda_device: (config.backend == BackendKind::Kokoro
+                && primary_execution_provider(config.acceleration) == AccelerationKind::Cuda)
+                .then(|| config.cuda_devices.first().copied().unwrap_or(0)),
+            kind: config.backend,
+            metrics: BackendMetrics::default(),
+            estimator: DurationEstimator::default(),
         })
     }
 
     pub fn worker_limit(&self) -> usize {
-        self.worker_limit
+        self.worker_limit.load(Ordering::Acquire)
+    }
+
+    /// Threads running in the inference pool
+    pub fn inference_threads(&self) -> usize {
+        self.pool.threads()
+    }
+
+    /// Synthesis calls currently holding an inference worker
+    pub fn in_flight(&self) -> usize {
+        self.worker_limit()
+            .saturating_sub(self.semaphore.available_permits())
+    }
+
+    /// Synthesis calls waiting for an inference worker
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::Acquire)
+    }
+
+    /// Cumulative synthesis counters
+    pub fn metrics(&self) -> &BackendMetrics {
+        &self.metrics
+    }
+
+    /// Speaking-rate estimator for predicting audio length
+    pub fn estimator(&self) -> &DurationEstimator {
+        &self.estimator
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Execution provider registered first with ONNX Runtime, or CPU when none is compiled in.
+    ///
+    /// ONNX Runtime silently falls back to CPU if the provider fails to initialize at load time.
+    pub fn execution_provider(&self) -> AccelerationKind {
+        self.execution_provider
+    }
+
+    /// CUDA device index inference runs on, when the CUDA execution provider is in use
+    pub fn cuda_device(&self) -> Option<u32> {
+        self.cuda_device
+    }
+
+    /// Execution provider requested in configuration, before `auto` is resolved
+    pub fn requested_acceleration(&self) -> AccelerationKind {
+        self.acceleration
+    }
+
+    /// Engine implementation serving requests
+    pub fn kind(&self) -> BackendKind {
+        self.kind
+    }
+
+    /// Files backing the active model (or the model to load on demand)
+    pub fn model_files(&self) -> ModelFiles {
+        self.files
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Identity of the loaded model and voice pack; changes when a reload swaps either
+    pub fn model_fingerprint(&self) -> Arc<str> {
+        self.fingerprint
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Directory reloads may load files from
+    pub fn model_dir(&self) -> &Path {
+        &self.model_dir
+    }
+
+    /// Voices that validation and the voice listings accept
+    pub fn voices(&self) -> Arc<[Voice]> {
+        self.voices
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Load `files` in the background and swap them in once ready.
+    ///
+    /// Returns `false` without starting anything if a reload is already running. In-flight
+    /// requests keep the engine they started with until they finish. Files that fail
+    /// [`check_model_files`] are never loaded, and the current model keeps serving.
+    pub fn start_reload(self: &Arc<Self>, files: ModelFiles) -> bool {
+        if self.reloading.swap(true, Ordering::AcqRel) {
+            return false;
+        }
+
+        let backend = self.clone();
+        tokio::spawn(async move {
+            let started = Instant::now();
+            info!(
+                model_path = %files.model_path.display(),
+                voices_path = %files.voices_path.display(),
+                "Reloading Kokoro model"
+            );
+
+            let _load_guard = backend.load_lock.lock().await;
+            let loaded = match check_model_files_off_runtime(backend.kind, files.clone()).await {
+                Ok(()) => {
+                    load_model_off_runtime(backend.kind, files.clone(), backend.worker_limit())
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            match loaded {
+                Ok(model) => {
+                    let voices = enumerate_voices(backend.kind, &files, &backend.voice_filter);
+                    let fingerprint = files.fingerprint().await;
+                    *backend
+                        .model
+                        .write()
+                        .unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(model));
+                    *backend
+                        .voices
+                        .write()
+                        .unwrap_or_else(PoisonError::into_inner) = voices;
+                    *backend
+                        .files
+                        .write()
+                        .unwrap_or_else(PoisonError::into_inner) = files;
+                    *backend
+                        .fingerprint
+                        .write()
+                        .unwrap_or_else(PoisonError::into_inner) = fingerprint.into();
+                    backend.touch();
+                    info!(
+                        elapsed_ms = started.elapsed().as_millis() as u64,
+                        "Model reload complete"
+                    );
+                }
+                Err(e) => error!(error = %e, "Model reload failed, keeping previous model"),
+            }
+
+            backend.reloading.store(false, Ordering::Release);
+        });
+
+        true
+    }
+
+    /// Change how many syntheses run at once, loading a pool of `workers` engines in the
+    /// background and swapping it in like a reload.
+    ///
+    /// Returns `false` without starting anything if a reload or resize is already running.
+    /// In-flight requests keep their engine until they finish; shrinking the pool waits for
+    /// enough of them to finish first. An unloaded model is not loaded, only sized for its next
+    /// load.
+    pub fn start_resize(self: &Arc<Self>, workers: usize) -> bool {
+        if self.reloading.swap(true, Ordering::AcqRel) {
+            return false;
+        }
+
+        let backend = self.clone();
+        tokio::spawn(async move {
+            let started = Instant::now();
+            let previous = backend.worker_limit();
+            info!(from = previous, to = workers, "Resizing inference workers");
+
+            // Take permits out of circulation before the pool shrinks, so a permit always has
+            // an idle engine to check out. A request holding a permit may still need the load
+            // lock, so it is not taken until they are retired.
+            if workers < previous {
+                match backend
+                    .semaphore
+                    .acquire_many((previous - workers) as u32)
+                    .await
+                {
+                    Ok(permits) => permits.forget(),
+                    Err(e) => {
+                        error!(error = %e, "Worker resize failed");
+                        backend.reloading.store(false, Ordering::Release);
+                        return;
+                    }
+                }
+                backend.worker_limit.store(workers, Ordering::Release);
+            }
+
+            let load_guard = backend.load_lock.lock().await;
+            let resized = if backend.is_loaded() {
+                load_model_off_runtime(backend.kind, backend.model_files(), workers)
+                    .await
+                    .map(|model| {
+                        *backend
+                            .model
+                            .write()
+                            .unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(model));
+                    })
+            } else {
+                Ok(())
+            };
+            drop(load_guard);
+
+            match resized {
+                Ok(()) => {
+                    if workers > previous {
+                        backend.pool.ensure_threads(workers);
+                        backend.worker_limit.store(workers, Ordering::Release);
+                        backend.semaphore.add_permits(workers - previous);
+                    }
+                    info!(
+                        workers,
+                        elapsed_ms = started.elapsed().as_millis() as u64,
+                        "Worker resize complete"
+                    );
+                }
+                Err(e) => {
+                    if workers < previous {
+                        backend.worker_limit.store(previous, Ordering::Release);
+                        backend.semaphore.add_permits(previous - workers);
+                    }
+                    error!(error = %e, "Worker resize failed, keeping {} workers", previous);
+                }
+            }
+
+            backend.reloading.store(false, Ordering::Release);
+        });
+
+        true
+    }
+
+    /// Warm up every engine in the background by synthesizing a short phrase per voice.
+    ///
+    /// The backend reports not ready until this finishes, so the first real request does not
+    /// pay graph optimization and allocation costs.
+    pub fn start_warmup(self: &Arc<Self>, voices: Vec<String>) {
+        self.warming_up.store(true, Ordering::Release);
+
+        let backend = self.clone();
+        tokio::spawn(async move {
+            let started = Instant::now();
+            match backend.warm_up_all_engines(&voices).await {
+                Ok(()) => {
+                    let elapsed_ms = started.elapsed().as_millis() as u64;
+                    let _ = backend.warmup_ms.set(elapsed_ms);
+                    info!(
+                        elapsed_ms,
+                        engines = backend.worker_limit(),
+                        voices = voices.len(),
+                        "Warm-up complete"
+                    );
+                }
+                Err(e) => error!(error = %e, "Warm-up failed"),
+            }
+            backend.warming_up.store(false, Ordering::Release);
+        });
+    }
+
+    async fn warm_up_all_engines(&self, voices: &[String]) -> Result<()> {
+        let _permits = self
+            .semaphore
+            .acquire_many(self.worker_limit() as u32)
+            .await
+            .context("Failed to acquire warm-up permits")?;
+
+        let model = self.loaded_model().await?;
+        let mut tasks = Vec::with_capacity(self.worker_limit());
+        while let Some(lease) = model.checkout() {
+            let voices = voices.to_vec();
+            tasks.push(self.pool.run(move || {
+                for voice in &voices {
+                    lease
+                        .engine()
+                        .tts_raw_audio(WARMUP_TEXT, Language::AmericanEnglish, voice, 1.0, None)
+                        .map_err(|e| {
+                            anyhow::anyhow!("Warm-up with voice {} failed: {}", voice, e)
+                        })?;
+                }
+                Ok::<(), anyhow::Error>(())
+            }));
+        }
+
+        for result in futures::future::try_join_all(tasks).await? {
+            result?;
+        }
+        self.touch();
+
+        Ok(())
+    }
+
+    /// Whether startup work has finished and the backend can serve at full speed
+    pub fn is_ready(&self) -> bool {
+        !self.warming_up.load(Ordering::Acquire) && !self.is_recovering()
+    }
+
+    /// Whether a reload or worker resize is loading engines
+    pub fn is_reloading(&self) -> bool {
+        self.reloading.load(Ordering::Acquire)
+    }
+
+    /// Whether the circuit breaker is open and engines are being reinitialized
+    pub fn is_recovering(&self) -> bool {
+        self.circuit_open.load(Ordering::Acquire)
+    }
+
+    fn record_synthesis_outcome(self: &Arc<Self>, succeeded: bool) {
+        if succeeded {
+            self.consecutive_failures.store(0, Ordering::Release);
+            return;
+        }
+
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::AcqRel) + 1;
+        if self.failure_threshold == 0 || failures < self.failure_threshold {
+            return;
+        }
+
+        if !self.circuit_open.swap(true, Ordering::AcqRel) {
+            error!(
+                failures,
+                "Circuit breaker opened after consecutive synthesis failures; reinitializing engines"
+            );
+            self.start_recovery();
+        }
+    }
+
+    /// Reload the engines from the current files until it succeeds, then close the circuit
+    fn start_recovery(self: &Arc<Self>) {
+        let backend = self.clone();
+        tokio::spawn(async move {
+            let mut backoff = RECOVERY_INITIAL_BACKOFF;
+            loop {
+                let started = Instant::now();
+                let load_guard = backend.load_lock.lock().await;
+                match load_model_off_runtime(
+                    backend.kind,
+                    backend.model_files(),
+                    backend.worker_limit(),
+                )
+                .await
+                {
+                    Ok(model) => {
+                        *backend
+                            .model
+                            .write()
+                            .unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(model));
+                        backend.consecutive_failures.store(0, Ordering::Release);
+                        backend.circuit_open.store(false, Ordering::Release);
+                        info!(
+                            elapsed_ms = started.elapsed().as_millis() as u64,
+                            "Engines reinitialized, circuit breaker closed"
+                        );
+                        return;
+                    }
+                    Err(e) => {
+                        error!(
+                            error = %e,
+                            retry_in_secs = backoff.as_secs(),
+                            "Engine reinitialization failed"
+                        );
+                    }
+                }
+                drop(load_guard);
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(RECOVERY_MAX_BACKOFF);
+            }
+        });
+    }
+
+    /// Time spent warming up, if warm-up ran and completed
+    pub fn warmup_ms(&self) -> Option<u64> {
+        self.warmup_ms.get().copied()
+    }
+
+    /// Unload the model after `idle` without requests; it reloads on the next request.
+    pub fn start_idle_unloader(self: &Arc<Self>, idle: Duration) {
+        let backend = self.clone();
+        let check_every = (idle / 4).clamp(Duration::from_secs(1), Duration::from_secs(30));
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(check_every);
+            loop {
+                interval.tick().await;
+                backend.unload_if_idle(idle);
+            }
+        });
+    }
+
+    fn unload_if_idle(&self, idle: Duration) {
+        let idle_for = self.started.elapsed().saturating_sub(Duration::from_millis(
+            self.last_used_ms.load(Ordering::Acquire),
+        ));
+        let busy = self.semaphore.available_permits() < self.worker_limit();
+        if idle_for < idle || busy || self.reloading.load(Ordering::Acquire) {
+            return;
+        }
+
+        let mut model = self.model.write().unwrap_or_else(PoisonError::into_inner);
+        if model.take().is_some() {
+            info!(
+                idle_secs = idle_for.as_secs(),
+                "Unloaded idle model; it will reload on the next request"
+            );
+        }
+    }
+
+    /// Whether the model is currently resident in memory
+    pub fn is_loaded(&self) -> bool {
+        self.current_model().is_some()
+    }
+
+    fn current_model(&self) -> Option<Arc<LoadedModel>> {
+        self.model
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Return the resident model, loading it first if it was never loaded or was unloaded
+    async fn loaded_model(&self) -> Result<Arc<LoadedModel>> {
+        if let Some(model) = self.current_model() {
+            return Ok(model);
+        }
+
+        let _load_guard = self.load_lock.lock().await;
+        if let Some(model) = self.current_model() {
+            return Ok(model);
+        }
+
+        let started = Instant::now();
+        let model = Arc::new(
+            load_model_off_runtime(self.kind, self.model_files(), self.worker_limit()).await?,
+        );
+        *self.model.write().unwrap_or_else(PoisonError::into_inner) = Some(model.clone());
+        info!(
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Model loaded on demand"
+        );
+
+        Ok(model)
+    }
+
+    fn touch(&self) {
+        self.last_used_ms
+            .store(self.started.elapsed().as_millis() as u64, Ordering::Release);
     }
 
     /// Check if backend is healthy
@@ -72,56 +717,433 @@ impl KokoroBackend {
         self.sample_rate > 0 && !self.semaphore.is_closed()
     }
 
-    /// Synthesize speech from text
-    pub async fn synthesize(
+    /// Phonemes and model token IDs for each of `texts` read in `language`, as kokoros derives
+    /// them before inference. Only eSpeak-ng runs, so no engine or inference permit is taken.
+    pub async fn phonemize(
         &self,
+        texts: Vec<String>,
+        language: Language,
+    ) -> Result<Vec<(String, Vec<i64>)>> {
+        let kind = self.kind;
+        tokio::task::spawn_blocking(move || {
+            texts
+                .iter()
+                .map(|text| phonemize(kind, text, language))
+                .collect()
+        })
+        .await
+        .context("Phonemization task panicked")
+    }
+
+    /// Synthesize speech from text, phonemized in `language`
+    ///
+    /// Fails fast with [`BackendUnavailable`] while the circuit breaker is open.
+    pub async fn synthesize(
+        self: &Arc<Self>,
         text: &str,
         voice_id: &str,
+        language: Language,
         speed: f32,
         initial_silence: Option<usize>,
     ) -> Result<AudioData> {
-        // Acquire permit for concurrent limit
-        let _permit = self
-            .semaphore
-            .acquire()
-            .await
-            .context("Failed to acquire inference permit")?;
+        if self.is_recovering() {
+            return Err(BackendUnavailable.into());
+        }
+
+        let synthesis = self.synthesize_timed(text, voice_id, language, speed, initial_silence);
+        let result = match self.synthesis_timeout {
+            Some(limit) => tokio::time::timeout(limit, synthesis)
+                .await
+                .unwrap_or_else(|_| Err(SynthesisTimeout(limit).into())),
+            None => synthesis.await,
+        };
+
+        match result {
+            Ok((audio, timings)) => {
+                self.metrics.record_success(&timings);
+                self.record_synthesis_outcome(true);
+                let synthesis_secs = timings.synthesis.as_secs_f64();
+                let audio_secs = timings.samples as f64 / f64::from(audio.sample_rate);
+                let speech_samples = audio
+                    .samples
+                    .len()
+                    .saturating_sub(initial_silence.unwrap_or(0));
+                let speech_secs = speech_samples as f64 / f64::from(audio.sample_rate);
+                self.estimator.observe(voice_id, text, speed, speech_secs);
+                debug!(
+                    queue_wait_ms = timings.queue_wait.as_millis() as u64,
+                    synthesis_ms = timings.synthesis.as_millis() as u64,
+                    samples = timings.samples,
+                    real_time_factor = synthesis_secs / audio_secs.max(f64::EPSILON),
+                    "Synthesis complete"
+                );
+                Ok(audio)
+            }
+            Err(e) if e.is::<QueueFull>() => Err(e),
+            Err(e) => {
+                self.metrics.record_failure();
+                self.record_synthesis_outcome(false);
+                Err(e)
+            }
+        }
+    }
+
+    async fn synthesize_timed(
+        &self,
+        text: &str,
+        voice_id: &str,
+        language: Language,
+        speed: f32,
+        initial_silence: Option<usize>,
+    ) -> Result<(AudioData, SynthesisTimings)> {
+        let queued = Instant::now();
+
+        // Acquire permit for concurrent limit; it travels with the blocking task so the
+        // engine stays reserved even if the caller stops waiting
+        let permit = match self.semaphore.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let waiting = self.queued.fetch_add(1, Ordering::AcqRel);
+                let _queue_slot = QueueSlot(&self.queued);
+                if self.max_queued.is_some_and(|max| waiting >= max) {
+                    return Err(QueueFull.into());
+                }
+                self.semaphore
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .context("Failed to acquire inference permit")?
+            }
+        };
+        let lease = self
+            .loaded_model()
+            .await?
+            .checkout()
+            .context("No idle inference engine available")?;
+        self.touch();
+        let queue_wait = queued.elapsed();
 
         debug!(
             voice_id = %voice_id,
+            language = %language,
             text_chars = text.chars().count(),
             "Synthesizing speech"
         );
 
         // Clone data for the blocking task
-        let tts_engine = self.tts_engine.clone();
         let text = text.to_string();
         let voice_id = voice_id.to_string();
         let sample_rate = self.sample_rate;
 
-        // Run inference in blocking task
-        let samples = tokio::task::spawn_blocking(move || {
-            match tts_engine.tts_raw_audio(
-                &text,
-                "en-us", // Default language
-                &voice_id,
-                speed,
-                initial_silence,
-                None, // request_id
-                None, // instance_id
-                None, // chunk_number
-            ) {
-                Ok(audio) => Ok(audio),
-                Err(e) => Err(anyhow::anyhow!("TTS inference failed: {}", e)),
-            }
-        })
+        // Run inference on the inference pool. Phonemization happens inside kokoros, so its time
+        // is included in the measured synthesis time rather than reported separately.
+        let (samples, synthesis) = self
+            .pool
+            .run(move || {
+                // Locals drop in reverse order, so the engine is back in the pool before the permit
+                // lets the next request check one out
+                let _permit = permit;
+                let lease = lease;
+                let started = Instant::now();
+                let result = match lease.engine().tts_raw_audio(
+                    &text,
+                    language,
+                    &voice_id,
+                    speed,
+                    initial_silence,
+                ) {
+                    Ok(audio) => Ok(audio),
+                    Err(e) => Err(anyhow::anyhow!("TTS inference failed: {}", e)),
+                };
+                result.map(|audio| (audio, started.elapsed()))
+            })
+            .await?
+            .context("Inference failed")?;
+
+        let timings = SynthesisTimings {
+            queue_wait,
+            synthesis,
+            samples: samples.len() as u64,
+        };
+
+        Ok((
+            AudioData {
+                samples,
+                sample_rate,
+            },
+            timings,
+        ))
+    }
+}
+
+/// Releases a queue position when the waiting request gets a permit, fails, or is cancelled
+struct QueueSlot<'a>(&'a AtomicUsize);
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Load a model on a blocking thread so engine construction does not stall the async runtime
+async fn load_model_off_runtime(
+    kind: BackendKind,
+    files: ModelFiles,
+    instances: usize,
+) -> Result<LoadedModel> {
+    let handle = tokio::runtime::Handle::current();
+    tokio::task::spawn_blocking(move || handle.block_on(LoadedModel::load(kind, &files, instances)))
         .await
-        .context("Inference task panicked")?
-        .context("Inference failed")?;
+        .context("Model load task panicked")
+}
 
-        Ok(AudioData {
-            samples,
-            sample_rate,
-        })
+/// Inputs every Kokoro model takes besides its token IDs
+const KOKORO_MODEL_INPUTS: [&str; 2] = ["style", "speed"];
+
+/// Fail when `files` would not load.
+///
+/// kokoros panics on a voice pack or model it cannot load, and the release build aborts on
+/// panic, so files named by a reload are opened here first. The model is checked by opening an
+/// ONNX Runtime session on it and looking for the inputs of a Kokoro model.
+fn check_model_files(kind: BackendKind, files: &ModelFiles) -> Result<()> {
+    voice_pack::voice_names(&files.voices_path).with_context(|| {
+        format!(
+            "voices file at {} is not a valid Kokoro voice pack",
+            files.voices_path.display()
+        )
+    })?;
+    if kind != BackendKind::Kokoro {
+        return Ok(());
+    }
+
+    let session = ort::session::Session::builder()
+        .and_then(|builder| builder.commit_from_file(&files.model_path))
+        .with_context(|| format!("model at {} cannot be loaded", files.model_path.display()))?;
+    for input in KOKORO_MODEL_INPUTS {
+        if !session.inputs().iter().any(|outlet| outlet.name() == input) {
+            anyhow::bail!(
+                "model at {} is not a Kokoro model: it has no '{}' input",
+                files.model_path.display(),
+                input
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn check_model_files_off_runtime(kind: BackendKind, files: ModelFiles) -> Result<()> {
+    tokio::task::spawn_blocking(move || check_model_files(kind, &files))
+        .await
+        .context("Model check task panicked")?
+}
+
+/// Permitted voices in the pack at `files`, or in the built-in list when it cannot be enumerated
+fn enumerate_voices(kind: BackendKind, files: &ModelFiles, filter: &VoiceFilter) -> Arc<[Voice]> {
+    let mut voices = None;
+    if kind == BackendKind::Kokoro {
+        match voice_pack::voice_names(&files.voices_path) {
+            Ok(ids) => voices = Some(voices_from_ids(&ids)),
+            Err(e) => warn!(
+                error = %format!("{:#}", e),
+                "Could not list voices from the voice pack; using the built-in list"
+            ),
+        }
+    }
+    let voices = voices.unwrap_or_else(|| get_available_voices().to_vec());
+
+    let permitted = filter.apply(&voices);
+    if permitted.is_empty() {
+        warn!("KOKORO_VOICES_ALLOW / KOKORO_VOICES_DENY exclude every voice; all requests will be rejected");
+    }
+    info!(
+        count = permitted.len(),
+        hidden = voices.len() - permitted.len(),
+        "Voices available"
+    );
+    permitted.into()
+}
+
+fn phonemize(kind: BackendKind, text: &str, language: Language) -> (String, Vec<i64>) {
+    match kind {
+        #[cfg(feature = "mock-backend")]
+        BackendKind::Mock => crate::mock::phonemize(text, language),
+        _ => {
+            let phonemes = Phonemizer::new(language.espeak_code()).phonemize(text, true);
+            let tokens = tokenize(&phonemes);
+            (phonemes, tokens)
+        }
+    }
+}
+
+async fn load_engine(kind: BackendKind, files: &ModelFiles) -> Engine {
+    match kind {
+        #[cfg(feature = "mock-backend")]
+        BackendKind::Mock => Engine::Mock(MockEngine::new(DEFAULT_SAMPLE_RATE)),
+        // Without the feature, config validation rejects the mock backend
+        _ => {
+            let model_path = files.model_path.to_string_lossy().to_string();
+            let voices_path = files.voices_path.to_string_lossy().to_string();
+            Engine::Kokoro(TTSKoko::new(&model_path, &voices_path).await)
+        }
+    }
+}
+
+/// Register execution providers as ONNX Runtime environment defaults.
+///
+/// kokoros builds its sessions internally, so the environment is the only place to steer them
+/// onto an accelerator. Thread counts and graph optimization are session options the environment
+/// cannot set, so they stay at kokoros' choice. The environment is process-wide and can only be
+/// committed once, so this runs once at startup before the first backend creates a session.
+pub fn init_onnx_runtime(config: &Config) {
+    let acceleration = config.acceleration;
+    if !acceleration_compiled_in(acceleration) {
+        warn!(
+            acceleration = %acceleration,
+            "Binary was built without the matching cargo feature; inference will fall back to CPU"
+        );
+    }
+
+    let providers = execution_providers(config);
+    info!(
+        acceleration = %acceleration,
+        providers = ?providers,
+        "Configuring ONNX Runtime execution providers"
+    );
+
+    if !ort::init().with_execution_providers(providers).commit() {
+        warn!("ONNX Runtime environment was already initialized; execution providers unchanged");
+    }
+}
+
+fn execution_providers(config: &Config) -> Vec<ExecutionProviderDispatch> {
+    let gpu_mem_limit = config.ort_gpu_mem_limit_mb.map(|mb| mb * 1024 * 1024);
+
+    let cuda = || {
+        let mut provider = ep::CUDA::default()
+            .with_arena_extend_strategy(ort_arena_strategy(config.ort_arena_extend_strategy));
+        if let Some(&device) = config.cuda_devices.first() {
+            provider = provider.with_device_id(device as i32);
+        }
+        if let Some(limit) = gpu_mem_limit {
+            provider = provider.with_memory_limit(limit);
+        }
+        provider.build()
+    };
+    let rocm = || {
+        let mut provider = ep::ROCm::default()
+            .with_arena_extend_strategy(ort_arena_strategy(config.ort_arena_extend_strategy));
+        if let Some(limit) = gpu_mem_limit {
+            provider = provider.with_mem_limit(limit);
+        }
+        provider.build()
+    };
+    let openvino = || {
+        ep::OpenVINO::default()
+            .with_device_type(config.openvino_device.to_string())
+            .build()
+    };
+
+    let mut providers = match config.acceleration {
+        AccelerationKind::Cpu => Vec::new(),
+        AccelerationKind::CoreML => vec![ep::CoreML::default().build()],
+        AccelerationKind::Cuda => vec![cuda()],
+        AccelerationKind::DirectML => vec![ep::DirectML::default().build()],
+        AccelerationKind::Rocm => vec![rocm()],
+        AccelerationKind::OpenVino => vec![openvino()],
+        AccelerationKind::Auto => {
+            let mut providers = Vec::new();
+            if cfg!(feature = "cuda") {
+                providers.push(cuda());
+            }
+            if cfg!(feature = "rocm") {
+                providers.push(rocm());
+            }
+            if cfg!(all(feature = "coreml", target_os = "macos")) {
+                providers.push(ep::CoreML::default().build());
+            }
+            if cfg!(all(feature = "directml", target_os = "windows")) {
+                providers.push(ep::DirectML::default().build());
+            }
+            if cfg!(feature = "openvino") {
+                providers.push(openvino());
+            }
+            providers
+        }
+    };
+
+    if !config.ort_cpu_arena {
+        providers.push(ep::CPU::default().with_arena_allocator(false).build());
+    }
+
+    providers
+}
+
+/// The provider `execution_providers` registers first for `acceleration`; CPU if none is
+fn primary_execution_provider(acceleration: AccelerationKind) -> AccelerationKind {
+    match acceleration {
+        AccelerationKind::Auto => {
+            if cfg!(feature = "cuda") {
+                AccelerationKind::Cuda
+            } else if cfg!(feature = "rocm") {
+                AccelerationKind::Rocm
+            } else if cfg!(all(feature = "coreml", target_os = "macos")) {
+                AccelerationKind::CoreML
+            } else if cfg!(all(feature = "directml", target_os = "windows")) {
+                AccelerationKind::DirectML
+            } else if cfg!(feature = "openvino") {
+                AccelerationKind::OpenVino
+            } else {
+                AccelerationKind::Cpu
+            }
+        }
+        kind if acceleration_compiled_in(kind) => kind,
+        _ => AccelerationKind::Cpu,
+    }
+}
+
+fn ort_arena_strategy(strategy: ArenaExtendStrategy) -> ep::ArenaExtendStrategy {
+    match strategy {
+        ArenaExtendStrategy::NextPowerOfTwo => ep::ArenaExtendStrategy::NextPowerOfTwo,
+        ArenaExtendStrategy::SameAsRequested => ep::ArenaExtendStrategy::SameAsRequested,
+    }
+}
+
+fn acceleration_compiled_in(acceleration: AccelerationKind) -> bool {
+    match acceleration {
+        AccelerationKind::Auto | AccelerationKind::Cpu => true,
+        AccelerationKind::CoreML => cfg!(feature = "coreml"),
+        AccelerationKind::Cuda => cfg!(feature = "cuda"),
+        AccelerationKind::DirectML => cfg!(feature = "directml"),
+        AccelerationKind::Rocm => cfg!(feature = "rocm"),
+        AccelerationKind::OpenVino => cfg!(feature = "openvino"),
+    }
+}
+
+#[cfg(all(test, feature = "mock-backend"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reload_of_corrupt_files_keeps_current_model() {
+        let config = Config {
+            backend: BackendKind::Mock,
+            ..Config::for_tests()
+        };
+        let dir = std::env::temp_dir().join(format!("kokoro-reload-{}", uuid::Uuid::new_v4()));
+        let files = ModelFiles::from_model_path(dir.join("kokoro.onnx"));
+        let backend = Arc::new(KokoroBackend::new(&config, &files).await.unwrap());
+        let current = backend.current_model().unwrap();
+
+        let corrupt = ModelFiles::from_model_path(dir.join("corrupt").join("kokoro.onnx"));
+        std::fs::create_dir_all(dir.join("corrupt")).unwrap();
+        std::fs::write(&corrupt.voices_path, b"not a voice pack").unwrap();
+        assert!(backend.start_reload(corrupt));
+        while backend.is_reloading() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert!(Arc::ptr_eq(&backend.current_model().unwrap(), &current));
+        assert_eq!(backend.model_files().model_path, files.model_path);
+        std::fs::remove_dir_all(dir).unwrap();
     }
 }
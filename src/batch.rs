@@ -0,0 +1,330 @@
+use crate::{
+    api::{
+        estimated_response_bytes, render_clip, validate_speech_request, AppState, SpeechParams,
+        SpeechRequest,
+    },
+    cache::CachedAudio,
+    error::{ApiResult, AppError},
+    extract::ApiJson,
+    load_shed::admit_batch,
+};
+use axum::extract::State;
+use axum::{
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use futures::{StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::io::{Cursor, Write};
+use tracing::{debug, info};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+
+/// Most inputs accepted in one batch request
+pub const MAX_BATCH_INPUTS: usize = 100;
+
+/// Batch `response_format` that packages the clips in a zip archive
+const ZIP_FORMAT: &str = "zip";
+
+/// Name of the archive entry describing the other files
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Request body for POST /v1/audio/speech/batch
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchSpeechRequest {
+    /// Model ID ("tts-1" or "kokoro")
+    pub model: String,
+    /// Texts to synthesize, one clip each
+    pub inputs: Vec<String>,
+    /// Voice ID (default "af_alloy"), or a blend of voices as for /v1/audio/speech
+    #[serde(default, deserialize_with = "crate::voice_blend::deserialize_voice")]
+    #[schema(value_type = Option<crate::voice_blend::VoiceSpec>)]
+    pub voice: Option<String>,
+    /// "zip" for an archive of numbered files with a manifest, or an audio format ("wav",
+    /// "pcm", "mp3", "opus") for a JSON list of base64 clips (default "wav")
+    #[serde(default)]
+    pub response_format: Option<String>,
+    /// Format of the files in a "zip" response (default "wav")
+    #[serde(default)]
+    pub audio_format: Option<String>,
+    /// Speed multiplier (0.25 to 4.0); defaults to the voice's preset, then 1.0
+    #[serde(default)]
+    pub speed: Option<f32>,
+    /// Named preset from the presets file supplying any of the settings above left unset
+    #[serde(default)]
+    pub preset: Option<String>,
+    /// Dynamic range compressor preset ("gentle", "speech", "phone")
+    #[serde(default)]
+    pub compressor: Option<String>,
+    /// Filters applied in order before compression ("highpass", "presence", "telephone")
+    #[serde(default)]
+    pub effects: Option<Vec<String>>,
+    /// Sample depth of WAV output: 16 (default), 24, or 32 (float)
+    #[serde(default)]
+    pub bit_depth: Option<u16>,
+    /// Language of the input ("en-us", "es", "ja", ...); defaults to the voice's language
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+/// Response body for POST /v1/audio/speech/batch without `"response_format": "zip"`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchSpeechResponse {
+    pub object: &'static str,
+    pub data: Vec<BatchClip>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchClip {
+    /// Position of the input in `inputs`
+    pub index: usize,
+    pub content_type: &'static str,
+    pub duration_ms: u64,
+    /// Base64-encoded audio
+    pub audio: String,
+}
+
+/// `manifest.json` in a zip response
+#[derive(Debug, Serialize)]
+struct Manifest<'a> {
+    voice: &'a str,
+    format: &'a str,
+    files: Vec<ManifestEntry<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestEntry<'a> {
+    index: usize,
+    input: &'a str,
+    file: String,
+    duration_ms: u64,
+}
+
+/// Synthesize several inputs with the same settings in one request
+#[utoipa::path(
+    post,
+    path = "/v1/audio/speech/batch",
+    tag = "speech",
+    request_body = BatchSpeechRequest,
+    responses(
+        (status = 200, description = "Clips as base64 JSON, or a zip archive with manifest.json", body = BatchSpeechResponse),
+        (status = 400, description = "Invalid request, model, voice, or format", body = crate::error::OpenAIError),
+        (status = 401, description = "Missing or invalid API key", body = crate::error::OpenAIError),
+        (status = 503, description = "Backend unavailable or overloaded", body = crate::error::OpenAIError)
+    )
+)]
+pub async fn batch_speech_handler(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<BatchSpeechRequest>,
+) -> ApiResult<Response> {
+    let request_id = Uuid::new_v4().to_string();
+
+    admit_batch(&state)?;
+
+    if req.inputs.is_empty() {
+        return Err(AppError::invalid_request("inputs cannot be empty"));
+    }
+    if req.inputs.len() > MAX_BATCH_INPUTS {
+        return Err(AppError::invalid_request(format!(
+            "inputs cannot contain more than {} items",
+            MAX_BATCH_INPUTS
+        )));
+    }
+
+    let archive = req
+        .response_format
+        .as_deref()
+        .is_some_and(|format| format.eq_ignore_ascii_case(ZIP_FORMAT));
+    let audio_format = if archive {
+        req.audio_format.clone()
+    } else {
+        if req.audio_format.is_some() {
+            return Err(AppError::invalid_request(
+                "audio_format only applies when response_format is 'zip'",
+            ));
+        }
+        req.response_format.clone()
+    };
+
+    let params = req
+        .inputs
+        .iter()
+        .map(|input| {
+            validate_speech_request(
+                &state,
+                SpeechRequest {
+                    model: req.model.clone(),
+                    input: input.clone(),
+                    voice: req.voice.clone(),
+                    response_format: audio_format.clone(),
+                    speed: req.speed,
+                    preset: req.preset.clone(),
+                    compressor: req.compressor.clone(),
+                    effects: req.effects.clone(),
+                    bit_depth: req.bit_depth,
+                    language: req.language.clone(),
+                    ..Default::default()
+                },
+            )
+        })
+        .collect::<ApiResult<Vec<SpeechParams>>>()?;
+
+    // The whole batch is returned at once, so it shares one response size limit
+    if let Some(limit) = state.max_response_bytes {
+        let estimated = params
+            .iter()
+            .map(|params| estimated_response_bytes(&state, params))
+            .sum::<u64>();
+        if estimated > limit {
+            return Err(AppError::OutputTooLarge { estimated, limit });
+        }
+    }
+
+    debug!(
+        request_id = %request_id,
+        inputs = params.len(),
+        archive,
+        "Received batch speech request"
+    );
+
+    // Clips share the inference workers; results stay in input order
+    let clip_ids = (0..params.len())
+        .map(|index| format!("{}-{}", request_id, index))
+        .collect::<Vec<_>>();
+    let renders = params
+        .iter()
+        .zip(&clip_ids)
+        .map(|(params, clip_id)| render_clip(&state, params, clip_id))
+        .collect::<Vec<_>>();
+    let clips = futures::stream::iter(renders)
+        .buffered(state.backend.worker_limit())
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    info!(
+        request_id = %request_id,
+        clips = clips.len(),
+        "Batch synthesis complete"
+    );
+
+    if !archive {
+        return Ok(Json(BatchSpeechResponse {
+            object: "list",
+            data: clips
+                .into_iter()
+                .enumerate()
+                .map(|(index, (audio, duration_ms))| BatchClip {
+                    index,
+                    content_type: audio.content_type,
+                    duration_ms,
+                    audio: BASE64.encode(&audio.bytes),
+                })
+                .collect(),
+        })
+        .into_response());
+    }
+
+    let archive = build_archive(&clips, &req.inputs, &params[0].voice, &params[0].format)
+        .map_err(|_| AppError::Internal)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"speech.zip\"".to_string(),
+            ),
+            (header::HeaderName::from_static("x-request-id"), request_id),
+        ],
+        archive,
+    )
+        .into_response())
+}
+
+/// Zip the clips as numbered files in input order, followed by `manifest.json`
+fn build_archive(
+    clips: &[(CachedAudio, u64)],
+    inputs: &[String],
+    voice: &str,
+    format: &str,
+) -> zip::result::ZipResult<Vec<u8>> {
+    // Zero-padded so the files sort in input order
+    let width = clips.len().to_string().len().max(3);
+    let mut manifest = Manifest {
+        voice,
+        format,
+        files: Vec::with_capacity(clips.len()),
+    };
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    // Encoded audio barely compresses
+    let stored = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for (index, ((audio, duration_ms), input)) in clips.iter().zip(inputs).enumerate() {
+        let file = format!("{:0width$}.{}", index + 1, format);
+        writer.start_file(file.as_str(), stored)?;
+        writer.write_all(&audio.bytes)?;
+        manifest.files.push(ManifestEntry {
+            index,
+            input,
+            file,
+            duration_ms: *duration_ms,
+        });
+    }
+    let manifest = serde_json::to_vec_pretty(&manifest).map_err(std::io::Error::from)?;
+    writer.start_file(MANIFEST_FILE, SimpleFileOptions::default())?;
+    writer.write_all(&manifest)?;
+    Ok(writer.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use std::io::Read;
+
+    #[test]
+    fn test_build_archive() {
+        let clips = vec![
+            (
+                CachedAudio {
+                    content_type: "audio/wav",
+                    bytes: Bytes::from_static(b"first"),
+                    duration_ms: 1200,
+                },
+                1200,
+            ),
+            (
+                CachedAudio {
+                    content_type: "audio/wav",
+                    bytes: Bytes::from_static(b"second"),
+                    duration_ms: 800,
+                },
+                800,
+            ),
+        ];
+        let inputs = vec!["Hello.".to_string(), "World.".to_string()];
+        let archive = build_archive(&clips, &inputs, "af_heart", "wav").unwrap();
+
+        let mut zip = zip::ZipArchive::new(Cursor::new(archive)).unwrap();
+        let names = zip.file_names().map(str::to_string).collect::<Vec<_>>();
+        assert_eq!(names, ["001.wav", "002.wav", "manifest.json"]);
+
+        let mut audio = String::new();
+        zip.by_name("002.wav")
+            .unwrap()
+            .read_to_string(&mut audio)
+            .unwrap();
+        assert_eq!(audio, "second");
+
+        let manifest: serde_json::Value =
+            serde_json::from_reader(zip.by_name(MANIFEST_FILE).unwrap()).unwrap();
+        assert_eq!(manifest["voice"], "af_heart");
+        assert_eq!(manifest["format"], "wav");
+        assert_eq!(manifest["files"][0]["input"], "Hello.");
+        assert_eq!(manifest["files"][0]["file"], "001.wav");
+        assert_eq!(manifest["files"][1]["index"], 1);
+        assert_eq!(manifest["files"][1]["duration_ms"], 800);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::{api::AppState, config::BenchArgs, language::Language};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Text synthesized when `--text` is not given: two sentences, about five seconds of speech
+const DEFAULT_BENCH_TEXT: &str = "The quick brown fox jumps over the lazy dog. \
+     Benchmarks measure how long each request takes and how much audio the server produces.";
+
+/// Result of a `bench` run, printed as text or JSON
+#[derive(Debug, Serialize)]
+struct BenchReport {
+    execution_provider: String,
+    workers: usize,
+    concurrency: usize,
+    requests: usize,
+    failed: usize,
+    latency_ms: Latency,
+    requests_per_second: f64,
+    audio_seconds: f64,
+    wall_seconds: f64,
+    /// Wall time over audio produced; below 1.0 is faster than realtime
+    realtime_factor: f64,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct Latency {
+    p50: u64,
+    p95: u64,
+    p99: u64,
+    max: u64,
+}
+
+/// One timed request: its latency and the length of the audio it produced
+type Sample = (Duration, Duration);
+
+/// Drive the backend directly with `--concurrency` requests in flight until `--requests` have
+/// completed, after one untimed warm-up request per slot
+pub async fn run(state: AppState, args: &BenchArgs) -> Result<()> {
+    if args.concurrency == 0 || args.requests == 0 {
+        anyhow::bail!("--concurrency and --requests must be at least 1");
+    }
+    let backend = state.backend.clone();
+    let text: Arc<str> = args.text.as_deref().unwrap_or(DEFAULT_BENCH_TEXT).into();
+    let voice: Arc<str> = args.voice.as_str().into();
+    let language = Language::of_voice(&voice).unwrap_or_default();
+
+    info!(
+        "Warming up {} slot(s) on {}",
+        args.concurrency,
+        backend.execution_provider()
+    );
+    futures::future::try_join_all(
+        (0..args.concurrency)
+            .map(|_| backend.synthesize(&text, &voice, language, args.speed, None)),
+    )
+    .await
+    .context("Warm-up request failed")?;
+
+    let next = Arc::new(AtomicUsize::new(0));
+    let started = Instant::now();
+    let slots = (0..args.concurrency)
+        .map(|_| {
+            let (backend, text, voice, next) =
+                (backend.clone(), text.clone(), voice.clone(), next.clone());
+            let (requests, speed) = (args.requests, args.speed);
+            tokio::spawn(async move {
+                let mut samples = Vec::new();
+                let mut failed = 0;
+                while next.fetch_add(1, Ordering::Relaxed) < requests {
+                    let request_started = Instant::now();
+                    match backend
+                        .synthesize(&text, &voice, language, speed, None)
+                        .await
+                    {
+                        Ok(audio) => samples.push((
+                            request_started.elapsed(),
+                            Duration::from_secs_f64(
+                                audio.samples.len() as f64 / f64::from(audio.sample_rate.max(1)),
+                            ),
+                        )),
+                        Err(e) => {
+                            warn!("Benchmark request failed: {:#}", e);
+                            failed += 1;
+                        }
+                    }
+                }
+                (samples, failed)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut samples = Vec::with_capacity(args.requests);
+    let mut failed = 0;
+    for slot in slots {
+        let (slot_samples, slot_failed) = slot.await.context("Benchmark task panicked")?;
+        samples.extend(slot_samples);
+        failed += slot_failed;
+    }
+    let wall = started.elapsed();
+
+    let report = report(
+        backend.execution_provider().to_string(),
+        backend.worker_limit(),
+        args.concurrency,
+        &samples,
+        failed,
+        wall,
+    );
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
+
+fn report(
+    execution_provider: String,
+    workers: usize,
+    concurrency: usize,
+    samples: &[Sample],
+    failed: usize,
+    wall: Duration,
+) -> BenchReport {
+    let mut latencies = samples
+        .iter()
+        .map(|(latency, _)| latency.as_millis() as u64)
+        .collect::<Vec<_>>();
+    latencies.sort_unstable();
+    let audio_seconds = samples
+        .iter()
+        .map(|(_, audio)| audio.as_secs_f64())
+        .sum::<f64>();
+    let wall_seconds = wall.as_secs_f64();
+
+    BenchReport {
+        execution_provider,
+        workers,
+        concurrency,
+        requests: samples.len() + failed,
+        failed,
+        latency_ms: Latency {
+            p50: percentile(&latencies, 50),
+            p95: percentile(&latencies, 95),
+            p99: percentile(&latencies, 99),
+            max: latencies.last().copied().unwrap_or(0),
+        },
+        requests_per_second: samples.len() as f64 / wall_seconds.max(f64::EPSILON),
+        audio_seconds,
+        wall_seconds,
+        realtime_factor: if audio_seconds > 0.0 {
+            wall_seconds / audio_seconds
+        } else {
+            0.0
+        },
+    }
+}
+
+fn print_report(report: &BenchReport) {
+    println!(
+        "provider    {} ({} worker(s))",
+        report.execution_provider, report.workers
+    );
+    println!(
+        "requests    {} at concurrency {}, {} failed",
+        report.requests, report.concurrency, report.failed
+    );
+    println!(
+        "latency     p50 {} ms, p95 {} ms, p99 {} ms, max {} ms",
+        report.latency_ms.p50, report.latency_ms.p95, report.latency_ms.p99, report.latency_ms.max
+    );
+    println!(
+        "throughput  {:.2} requests/s, {:.1} s of audio in {:.1} s (realtime factor {:.3})",
+        report.requests_per_second,
+        report.audio_seconds,
+        report.wall_seconds,
+        report.realtime_factor
+    );
+}
+
+/// Nearest-rank percentile of sorted values; 0 when there are none
+fn percentile(sorted: &[u64], percent: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (percent * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let values = (1..=100).collect::<Vec<u64>>();
+        assert_eq!(percentile(&values, 50), 50);
+        assert_eq!(percentile(&values, 95), 95);
+        assert_eq!(percentile(&values, 99), 99);
+        assert_eq!(percentile(&[7], 95), 7);
+        assert_eq!(percentile(&[1, 2, 3], 50), 2);
+        assert_eq!(percentile(&[], 50), 0);
+    }
+
+    #[test]
+    fn test_report() {
+        let samples = [
+            (Duration::from_millis(100), Duration::from_secs(2)),
+            (Duration::from_millis(300), Duration::from_secs(2)),
+        ];
+        let report = report("cpu".to_string(), 2, 2, &samples, 1, Duration::from_secs(1));
+        assert_eq!(report.requests, 3);
+        assert_eq!(report.failed, 1);
+        assert_eq!(
+            report.latency_ms,
+            Latency {
+                p50: 100,
+                p95: 300,
+                p99: 300,
+                max: 300
+            }
+        );
+        assert_eq!(report.requests_per_second, 2.0);
+        assert_eq!(report.audio_seconds, 4.0);
+        assert_eq!(report.realtime_factor, 0.25);
+    }
+}
@@ -0,0 +1,835 @@
+use crate::metrics::write_metric;
+use anyhow::{Context, Result};
+use axum::body::Bytes;
+use lru::LruCache;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info, warn};
+
+/// Bump when the key layout, file layout, or encoders change so stale disk entries are not reused
+const DISK_KEY_VERSION: &str = "v3";
+/// Each disk entry starts with the clip's duration in milliseconds, little-endian
+const DISK_HEADER_LEN: usize = 8;
+
+/// Identity of a synthesized clip; requests with equal keys produce identical audio
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    text: String,
+    voice: String,
+    speed_bits: u32,
+    format: String,
+    sample_rate: u32,
+    initial_silence: Option<usize>,
+    trailing_silence: usize,
+    /// Post-processing chain from [`crate::effects::Effects::describe`]
+    effects: String,
+    /// WAV sample depth in bits
+    bit_depth: u16,
+    /// Speed curve from [`crate::speed_curve::SpeedCurve::describe`]
+    speed_curve: String,
+    /// Emphasis markup read as stress rather than text
+    emphasis: bool,
+    /// Silence inserted between chunks, in milliseconds
+    sentence_gap_ms: Option<u32>,
+    /// Silence filled with comfort noise
+    comfort_noise: bool,
+    /// Synthesized by the draft model
+    draft: bool,
+    /// [`crate::model_assets::ModelFiles::fingerprint`] of the model and voice pack
+    model: String,
+    /// Language the text was phonemized in
+    language: &'static str,
+}
+
+impl CacheKey {
+    pub fn new(
+        text: &str,
+        voice: &str,
+        speed: f32,
+        format: &str,
+        sample_rate: u32,
+        initial_silence: Option<usize>,
+        trailing_silence: usize,
+    ) -> Self {
+        Self {
+            text: normalize_text(text),
+            voice: voice.to_string(),
+            speed_bits: speed.to_bits(),
+            format: format.to_string(),
+            sample_rate,
+            initial_silence,
+            trailing_silence,
+            effects: String::new(),
+            bit_depth: 16,
+            speed_curve: String::new(),
+            emphasis: false,
+            sentence_gap_ms: None,
+            comfort_noise: false,
+            draft: false,
+            model: String::new(),
+            language: "",
+        }
+    }
+
+    /// Key for the same clip with post-processing effects applied
+    pub fn with_effects(mut self, effects: String) -> Self {
+        self.effects = effects;
+        self
+    }
+
+    /// Key for the same clip encoded at another WAV sample depth
+    pub fn with_bit_depth(mut self, bits: u16) -> Self {
+        self.bit_depth = bits;
+        self
+    }
+
+    /// Key for the same clip read along a speed curve
+    pub fn with_speed_curve(mut self, speed_curve: String) -> Self {
+        self.speed_curve = speed_curve;
+        self
+    }
+
+    /// Key for the same clip with emphasis markup read as stress
+    pub fn with_emphasis(mut self, emphasis: bool) -> Self {
+        self.emphasis = emphasis;
+        self
+    }
+
+    /// Key for the same clip with fixed silence between chunks
+    pub fn with_sentence_gap(mut self, sentence_gap_ms: Option<u32>) -> Self {
+        self.sentence_gap_ms = sentence_gap_ms;
+        self
+    }
+
+    /// Key for the same clip with its silences filled with comfort noise
+    pub fn with_comfort_noise(mut self, comfort_noise: bool) -> Self {
+        self.comfort_noise = comfort_noise;
+        self
+    }
+
+    /// Key for the same clip synthesized by the draft model
+    pub fn with_draft(mut self, draft: bool) -> Self {
+        self.draft = draft;
+        self
+    }
+
+    /// Key for the same clip from the model and voice pack with `fingerprint`
+    pub fn with_model(mut self, fingerprint: &str) -> Self {
+        self.model = fingerprint.to_string();
+        self
+    }
+
+    /// Key for the same clip phonemized in the language with eSpeak-ng `code`
+    pub fn with_language(mut self, code: &'static str) -> Self {
+        self.language = code;
+        self
+    }
+
+    fn weight(&self) -> usize {
+        self.text.len()
+            + self.model.len()
+            + self.voice.len()
+            + self.format.len()
+            + self.effects.len()
+            + self.speed_curve.len()
+    }
+
+    /// Content address used for the on-disk file name
+    fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(DISK_KEY_VERSION);
+        for part in [
+            self.text.as_str(),
+            self.voice.as_str(),
+            self.format.as_str(),
+        ] {
+            hasher.update([0]);
+            hasher.update(part);
+        }
+        hasher.update(self.speed_bits.to_le_bytes());
+        hasher.update(self.sample_rate.to_le_bytes());
+        hasher.update(format!("{:?}", self.initial_silence));
+        // Only hashed when set so clips cached before presets existed keep their address
+        if self.trailing_silence > 0 {
+            hasher.update(self.trailing_silence.to_le_bytes());
+        }
+        if !self.effects.is_empty() {
+            hasher.update([0]);
+            hasher.update(&self.effects);
+        }
+        if self.bit_depth != 16 {
+            hasher.update(self.bit_depth.to_le_bytes());
+        }
+        if !self.speed_curve.is_empty() {
+            hasher.update([1]);
+            hasher.update(&self.speed_curve);
+        }
+        if self.emphasis {
+            hasher.update([2]);
+        }
+        if let Some(gap) = self.sentence_gap_ms {
+            hasher.update([3]);
+            hasher.update(gap.to_le_bytes());
+        }
+        if self.comfort_noise {
+            hasher.update([4]);
+        }
+        if self.draft {
+            hasher.update([5]);
+        }
+        if !self.model.is_empty() {
+            hasher.update([6]);
+            hasher.update(&self.model);
+        }
+        if !self.language.is_empty() {
+            hasher.update([7]);
+            hasher.update(self.language);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// Content type served for an encoded `response_format`
+pub fn content_type_for_format(format: &str) -> Option<&'static str> {
+    match format {
+        "wav" => Some("audio/wav"),
+        "pcm" => Some("audio/pcm"),
+        "mp3" => Some("audio/mpeg"),
+        "opus" | "discord" => Some("audio/opus"),
+        _ => None,
+    }
+}
+
+/// Audio cache counters and sizes
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub bytes: usize,
+    pub max_bytes: usize,
+    /// Bytes on disk; absent without a disk tier
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk_bytes: Option<u64>,
+    pub hits: u64,
+    pub disk_hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+/// Encoded audio ready to send as a response body
+#[derive(Debug, Clone)]
+pub struct CachedAudio {
+    pub content_type: &'static str,
+    pub bytes: Bytes,
+    /// Length of the audio, checked against a request's duration cap on a cache hit
+    pub duration_ms: u64,
+}
+
+struct CacheState {
+    entries: LruCache<CacheKey, CachedAudio>,
+    bytes: usize,
+}
+
+/// Encoded responses cached in a size-bounded in-memory LRU, optionally backed by a disk tier
+pub struct AudioCache {
+    max_bytes: usize,
+    state: Mutex<CacheState>,
+    disk: Option<Arc<DiskCache>>,
+    hits: AtomicU64,
+    disk_hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl AudioCache {
+    /// `max_bytes` of 0 keeps nothing in memory, leaving only the disk tier
+    pub fn new(max_bytes: usize, disk: Option<DiskCache>) -> Self {
+        Self {
+            max_bytes,
+            state: Mutex::new(CacheState {
+                entries: LruCache::unbounded(),
+                bytes: 0,
+            }),
+            disk: disk.map(Arc::new),
+            hits: AtomicU64::new(0),
+            disk_hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    /// Look up a clip in memory, then on disk, marking it most recently used
+    pub async fn get(&self, key: &CacheKey) -> Option<CachedAudio> {
+        let found = self
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entries
+            .get(key)
+            .cloned();
+        if found.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return found;
+        }
+
+        if let Some(disk) = &self.disk {
+            let disk = disk.clone();
+            let disk_key = key.clone();
+            if let Ok(Some(audio)) = tokio::task::spawn_blocking(move || disk.read(&disk_key)).await
+            {
+                self.disk_hits.fetch_add(1, Ordering::Relaxed);
+                self.insert_memory(key.clone(), audio.clone());
+                return Some(audio);
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    /// Whether a clip is cached in either tier, without touching hit/miss counters
+    pub async fn contains(&self, key: &CacheKey) -> bool {
+        let in_memory = self
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entries
+            .contains(key);
+        if in_memory {
+            return true;
+        }
+
+        match &self.disk {
+            Some(disk) => {
+                let disk = disk.clone();
+                let key = key.clone();
+                tokio::task::spawn_blocking(move || disk.contains(&key))
+                    .await
+                    .unwrap_or(false)
+            }
+            None => false,
+        }
+    }
+
+    /// Store a clip in memory and, in the background, on disk
+    pub fn insert(&self, key: CacheKey, audio: CachedAudio) {
+        if let Some(disk) = &self.disk {
+            let disk = disk.clone();
+            let disk_key = key.clone();
+            let disk_audio = audio.clone();
+            tokio::task::spawn_blocking(move || disk.write(&disk_key, &disk_audio));
+        }
+        self.insert_memory(key, audio);
+    }
+
+    /// Evict least recently used entries to stay under the memory cap
+    fn insert_memory(&self, key: CacheKey, audio: CachedAudio) {
+        let size = key.weight() + audio.bytes.len();
+        if size > self.max_bytes {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some((old_key, old)) = state.entries.push(key, audio) {
+            state.bytes -= old_key.weight() + old.bytes.len();
+        }
+        state.bytes += size;
+
+        while state.bytes > self.max_bytes {
+            let Some((old_key, old)) = state.entries.pop_lru() else {
+                break;
+            };
+            state.bytes -= old_key.weight() + old.bytes.len();
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Current counters and sizes, as reported by the admin stats endpoint
+    pub fn stats(&self) -> CacheStats {
+        let (entries, bytes) = {
+            let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            (state.entries.len(), state.bytes)
+        };
+
+        CacheStats {
+            entries,
+            bytes,
+            max_bytes: self.max_bytes,
+            disk_bytes: self
+                .disk
+                .as_ref()
+                .map(|disk| *disk.bytes.lock().unwrap_or_else(PoisonError::into_inner)),
+            hits: self.hits.load(Ordering::Relaxed),
+            disk_hits: self.disk_hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Drop every cached clip from both tiers.
+    ///
+    /// Returns the number of clips removed from memory and from disk. Disk removal is
+    /// blocking file I/O, so call this off the async runtime.
+    pub fn purge(&self) -> (usize, usize) {
+        let memory = {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            let removed = state.entries.len();
+            state.entries.clear();
+            state.bytes = 0;
+            removed
+        };
+        let disk = self.disk.as_ref().map_or(0, |disk| disk.purge());
+        (memory, disk)
+    }
+
+    /// Append cache counters in Prometheus text format
+    pub fn render_prometheus(&self, out: &mut String) {
+        let (entries, bytes) = {
+            let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            (state.entries.len(), state.bytes)
+        };
+
+        write_metric(
+            out,
+            "kokoro_cache_hits_total",
+            "counter",
+            "Speech requests served from the audio cache",
+            self.hits.load(Ordering::Relaxed) as f64,
+        );
+        write_metric(
+            out,
+            "kokoro_cache_disk_hits_total",
+            "counter",
+            "Speech requests served from the on-disk audio cache",
+            self.disk_hits.load(Ordering::Relaxed) as f64,
+        );
+        write_metric(
+            out,
+            "kokoro_cache_misses_total",
+            "counter",
+            "Speech requests that missed the audio cache",
+            self.misses.load(Ordering::Relaxed) as f64,
+        );
+        write_metric(
+            out,
+            "kokoro_cache_evictions_total",
+            "counter",
+            "Entries evicted to stay under the cache size cap",
+            self.evictions.load(Ordering::Relaxed) as f64,
+        );
+        write_metric(
+            out,
+            "kokoro_cache_entries",
+            "gauge",
+            "Clips currently cached",
+            entries as f64,
+        );
+        write_metric(
+            out,
+            "kokoro_cache_bytes",
+            "gauge",
+            "Approximate memory held by cached clips",
+            bytes as f64,
+        );
+    }
+}
+
+/// Default disk cache location under the user cache directory
+pub fn default_disk_cache_dir() -> Result<PathBuf> {
+    Ok(dirs::cache_dir()
+        .context("Failed to determine cache directory")?
+        .join("kokoro-openai-server")
+        .join("audio"))
+}
+
+/// Content-addressed clip files under a directory, with TTL and size-based eviction
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    ttl: Option<Duration>,
+    /// Total size of cached files, kept in step with writes and removals
+    bytes: Mutex<u64>,
+}
+
+impl DiskCache {
+    /// Open (creating if needed) a cache directory, dropping expired entries
+    pub fn open(dir: PathBuf, max_bytes: u64, ttl: Option<Duration>) -> Result<Self> {
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create disk cache at {}", dir.display()))?;
+
+        let cache = Self {
+            dir,
+            max_bytes,
+            ttl,
+            bytes: Mutex::new(0),
+        };
+
+        let mut total = 0;
+        let mut expired = 0;
+        for entry in cache.entries() {
+            if cache.is_expired(entry.modified) {
+                let _ = fs::remove_file(&entry.path);
+                expired += 1;
+            } else {
+                total += entry.len;
+            }
+        }
+        *cache.bytes.lock().unwrap_or_else(PoisonError::into_inner) = total;
+        info!(
+            path = %cache.dir.display(),
+            bytes = total,
+            expired,
+            "Disk audio cache ready"
+        );
+        cache.evict_if_needed();
+
+        Ok(cache)
+    }
+
+    fn path_for(&self, key: &CacheKey) -> PathBuf {
+        let digest = key.digest();
+        self.dir
+            .join(&digest[..2])
+            .join(format!("{}.{}", digest, key.format))
+    }
+
+    fn contains(&self, key: &CacheKey) -> bool {
+        fs::metadata(self.path_for(key))
+            .is_ok_and(|metadata| !self.is_expired(metadata.modified().ok()))
+    }
+
+    fn read(&self, key: &CacheKey) -> Option<CachedAudio> {
+        let content_type = content_type_for_format(&key.format)?;
+        let path = self.path_for(key);
+        let metadata = fs::metadata(&path).ok()?;
+
+        if self.is_expired(metadata.modified().ok()) {
+            if fs::remove_file(&path).is_ok() {
+                self.release(metadata.len());
+            }
+            return None;
+        }
+
+        match fs::read(&path) {
+            Ok(mut bytes) if bytes.len() >= DISK_HEADER_LEN => {
+                // Refresh the modification time so eviction keeps recently used clips
+                if let Err(e) = File::options()
+                    .write(true)
+                    .open(&path)
+                    .and_then(|file| file.set_modified(SystemTime::now()))
+                {
+                    debug!(path = %path.display(), error = %e, "Failed to touch cached clip");
+                }
+                let audio = bytes.split_off(DISK_HEADER_LEN);
+                let header = bytes.try_into().ok()?;
+                Some(CachedAudio {
+                    content_type,
+                    bytes: Bytes::from(audio),
+                    duration_ms: u64::from_le_bytes(header),
+                })
+            }
+            Ok(_) => {
+                warn!(path = %path.display(), "Cached clip is truncated");
+                None
+            }
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Failed to read cached clip");
+                None
+            }
+        }
+    }
+
+    fn write(&self, key: &CacheKey, audio: &CachedAudio) {
+        let len = (DISK_HEADER_LEN + audio.bytes.len()) as u64;
+        if len > self.max_bytes {
+            return;
+        }
+
+        let mut contents = Vec::with_capacity(len as usize);
+        contents.extend_from_slice(&audio.duration_ms.to_le_bytes());
+        contents.extend_from_slice(&audio.bytes);
+        let path = self.path_for(key);
+        let replaced = fs::metadata(&path).map(|metadata| metadata.len()).ok();
+        if let Err(e) = write_atomically(&path, &contents) {
+            warn!(path = %path.display(), error = %e, "Failed to write cached clip");
+            return;
+        }
+
+        if let Some(old_len) = replaced {
+            self.release(old_len);
+        }
+        *self.bytes.lock().unwrap_or_else(PoisonError::into_inner) += len;
+        self.evict_if_needed();
+    }
+
+    /// Remove every clip, returning how many files were deleted
+    fn purge(&self) -> usize {
+        let mut removed = 0;
+        let mut remaining = 0;
+        for entry in self.entries() {
+            if fs::remove_file(&entry.path).is_ok() {
+                removed += 1;
+            } else {
+                remaining += entry.len;
+            }
+        }
+        *self.bytes.lock().unwrap_or_else(PoisonError::into_inner) = remaining;
+        removed
+    }
+
+    /// Remove the oldest clips until the directory is back under its size cap
+    fn evict_if_needed(&self) {
+        if *self.bytes.lock().unwrap_or_else(PoisonError::into_inner) <= self.max_bytes {
+            return;
+        }
+
+        let mut entries = self.entries();
+        entries.sort_by_key(|entry| entry.modified);
+        let mut total: u64 = entries.iter().map(|entry| entry.len).sum();
+        let mut removed = 0;
+        for entry in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            if fs::remove_file(&entry.path).is_ok() {
+                total -= entry.len;
+                removed += 1;
+            }
+        }
+
+        *self.bytes.lock().unwrap_or_else(PoisonError::into_inner) = total;
+        debug!(
+            removed,
+            bytes = total,
+            "Evicted clips from disk audio cache"
+        );
+    }
+
+    fn release(&self, len: u64) {
+        let mut bytes = self.bytes.lock().unwrap_or_else(PoisonError::into_inner);
+        *bytes = bytes.saturating_sub(len);
+    }
+
+    fn is_expired(&self, modified: Option<SystemTime>) -> bool {
+        match (self.ttl, modified) {
+            (Some(ttl), Some(modified)) => modified.elapsed().is_ok_and(|age| age > ttl),
+            _ => false,
+        }
+    }
+
+    fn entries(&self) -> Vec<DiskEntry> {
+        let Ok(shards) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+
+        shards
+            .flatten()
+            .filter(|shard| shard.path().is_dir())
+            .filter_map(|shard| fs::read_dir(shard.path()).ok())
+            .flat_map(|files| files.flatten())
+            .filter_map(|file| {
+                let metadata = file.metadata().ok()?;
+                let path = file.path();
+                let is_temp = path.extension().is_some_and(|ext| ext == "tmp");
+                (metadata.is_file() && !is_temp).then(|| DiskEntry {
+                    path,
+                    len: metadata.len(),
+                    modified: metadata.modified().ok(),
+                })
+            })
+            .collect()
+    }
+}
+
+struct DiskEntry {
+    path: PathBuf,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+fn write_atomically(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4().simple()));
+    fs::write(&temp, bytes)?;
+    fs::rename(&temp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp);
+    })
+}
+
+/// Read a pre-warm phrase list: one phrase per line, blank lines and `#` comments ignored
+pub fn load_phrases(path: &Path) -> Result<Vec<String>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read phrase list {}", path.display()))?;
+
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Collapse runs of whitespace so formatting differences share a cache entry
+pub fn normalize_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(text: &str) -> CacheKey {
+        CacheKey::new(text, "af_alloy", 1.0, "wav", 24_000, None, 0)
+    }
+
+    fn audio(len: usize) -> CachedAudio {
+        CachedAudio {
+            content_type: "audio/wav",
+            bytes: Bytes::from(vec![0u8; len]),
+            duration_ms: len as u64,
+        }
+    }
+
+    #[test]
+    fn test_cache_key_normalizes_whitespace() {
+        assert_eq!(key("Hello   world\n"), key(" Hello world"));
+        assert_ne!(
+            key("Hello"),
+            CacheKey::new("Hello", "af_alloy", 1.25, "wav", 24_000, None, 0)
+        );
+
+        // Only a non-default depth changes the on-disk address
+        assert_eq!(
+            key("Hello").with_bit_depth(16).digest(),
+            key("Hello").digest()
+        );
+        assert_ne!(
+            key("Hello").with_bit_depth(24).digest(),
+            key("Hello").digest()
+        );
+        assert_ne!(
+            key("Hello")
+                .with_speed_curve("0:1,1:0.85".to_string())
+                .digest(),
+            key("Hello").digest()
+        );
+        assert_ne!(
+            key("Hello").with_emphasis(true).digest(),
+            key("Hello").digest()
+        );
+        assert_ne!(
+            key("Hello").with_sentence_gap(Some(0)).digest(),
+            key("Hello").digest()
+        );
+        assert_ne!(
+            key("Hello").with_comfort_noise(true).digest(),
+            key("Hello").digest()
+        );
+        assert_ne!(
+            key("Hello").with_draft(true).digest(),
+            key("Hello").digest()
+        );
+        // A reload to another model or voice pack changes every key
+        assert_ne!(
+            key("Hello").with_model("100@1/20@1"),
+            key("Hello").with_model("100@2/20@1")
+        );
+        assert_ne!(
+            key("Hello").with_model("100@1/20@1").digest(),
+            key("Hello").with_model("100@1/20@2").digest()
+        );
+        assert_ne!(
+            key("Hello").with_language("es").digest(),
+            key("Hello").with_language("fr-fr").digest()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cache_evicts_least_recently_used() {
+        let cache = AudioCache::new(250, None);
+        cache.insert(key("a"), audio(100));
+        cache.insert(key("b"), audio(100));
+        assert!(cache.get(&key("a")).await.is_some());
+
+        cache.insert(key("c"), audio(100));
+        assert!(cache.get(&key("a")).await.is_some());
+        assert!(cache.get(&key("b")).await.is_none());
+        assert!(cache.get(&key("c")).await.is_some());
+
+        cache.insert(key("huge"), audio(1_000));
+        assert!(cache.get(&key("huge")).await.is_none());
+    }
+
+    #[test]
+    fn test_disk_cache_round_trip_and_eviction() {
+        let dir = std::env::temp_dir().join(format!("kokoro-disk-cache-{}", uuid::Uuid::new_v4()));
+        let disk = DiskCache::open(dir.clone(), 250, None).unwrap();
+
+        disk.write(&key("a"), &audio(100));
+        let cached = disk.read(&key("a")).unwrap();
+        assert_eq!(cached.content_type, "audio/wav");
+        assert_eq!(cached.bytes.len(), 100);
+        assert_eq!(cached.duration_ms, 100);
+
+        disk.write(&key("b"), &audio(100));
+        disk.write(&key("c"), &audio(100));
+        let remaining = ["a", "b", "c"]
+            .iter()
+            .filter(|text| disk.read(&key(text)).is_some())
+            .count();
+        assert_eq!(remaining, 2);
+
+        // Entries survive reopening
+        let reopened = DiskCache::open(dir.clone(), 250, None).unwrap();
+        assert!(reopened.read(&key("c")).is_some());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_purge_clears_both_tiers() {
+        let dir = std::env::temp_dir().join(format!("kokoro-disk-cache-{}", uuid::Uuid::new_v4()));
+        let disk = DiskCache::open(dir.clone(), 1_000, None).unwrap();
+        disk.write(&key("a"), &audio(100));
+        disk.write(&key("b"), &audio(100));
+
+        let cache = AudioCache::new(1_000, Some(disk));
+        assert!(cache.get(&key("a")).await.is_some());
+        assert_eq!(cache.stats().entries, 1);
+        assert_eq!(cache.stats().disk_bytes, Some(216));
+
+        assert_eq!(cache.purge(), (1, 2));
+        assert_eq!(cache.stats().entries, 0);
+        assert_eq!(cache.stats().disk_bytes, Some(0));
+        assert!(cache.get(&key("b")).await.is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_phrases_skips_blanks_and_comments() {
+        let path = std::env::temp_dir().join(format!("kokoro-phrases-{}", uuid::Uuid::new_v4()));
+        fs::write(&path, "# menu\nPress one for sales.\n\n  Goodbye.  \n").unwrap();
+        let phrases = load_phrases(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(phrases, vec!["Press one for sales.", "Goodbye."]);
+    }
+
+    #[test]
+    fn test_disk_cache_expires_entries() {
+        let dir = std::env::temp_dir().join(format!("kokoro-disk-cache-{}", uuid::Uuid::new_v4()));
+        let disk = DiskCache::open(dir.clone(), 1_000, Some(Duration::ZERO)).unwrap();
+
+        disk.write(&key("a"), &audio(10));
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(disk.read(&key("a")).is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,745 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::sync::LazyLock;
+
+/// Longest chapter title kept; longer first lines are cut at a word boundary
+const MAX_TITLE_CHARS: usize = 80;
+/// Largest single EPUB document unpacked; a chapter's markup is far smaller
+const MAX_EPUB_ENTRY_BYTES: u64 = 16 * 1024 * 1024;
+/// Most markup unpacked from one EPUB, so an archive that compresses extremely well is refused
+/// rather than inflated
+const MAX_EPUB_TOTAL_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Document formats a long-document request may use
+pub const DOCUMENT_FORMATS: [&str; 3] = ["text", "markdown", "epub"];
+
+/// How the input of a long-document request is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    /// Plain text with chapters separated by a line containing only `---`
+    Text,
+    /// Markdown split at its chapter headings
+    Markdown,
+    /// An EPUB book, one chapter per spine document
+    Epub,
+}
+
+impl DocumentFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "text" | "txt" => Some(Self::Text),
+            "markdown" | "md" => Some(Self::Markdown),
+            "epub" => Some(Self::Epub),
+            _ => None,
+        }
+    }
+}
+
+/// One chapter of a long document, as plain text ready for synthesis
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chapter {
+    pub title: String,
+    /// Text read out for the chapter, starting with its heading when it has one
+    pub text: String,
+}
+
+/// Split plain text into chapters at lines containing only `---` (or a form feed).
+///
+/// Each chapter is titled after its first line.
+pub fn split_text(text: &str) -> Vec<Chapter> {
+    let mut chapters = Vec::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        if line.trim() == "---" || line.contains('\u{c}') {
+            push_chapter(&mut chapters, None, &current);
+            current.clear();
+        } else {
+            current.push_str(line);
+            current.push('\n');
+        }
+    }
+    push_chapter(&mut chapters, None, &current);
+    chapters
+}
+
+/// Split Markdown into chapters at its chapter headings and reduce each to plain text.
+///
+/// Chapters start at the shallowest heading level used more than once, so a single `# Title`
+/// above `## Chapter` headings becomes a short opening chapter of its own. Front matter and
+/// fenced code blocks are not read out.
+pub fn split_markdown(markdown: &str) -> Vec<Chapter> {
+    let body = strip_front_matter(markdown);
+    let lines = markdown_lines(body);
+
+    let mut counts = [0usize; 7];
+    for line in &lines {
+        if let MarkdownLine::Heading(level, _) = line {
+            counts[*level] += 1;
+        }
+    }
+    let chapter_level = (1..=6)
+        .find(|&level| counts[level] > 1)
+        .or_else(|| (1..=6).find(|&level| counts[level] > 0));
+
+    let mut chapters = Vec::new();
+    let mut title = None;
+    let mut current = String::new();
+    for line in lines {
+        match line {
+            MarkdownLine::Heading(level, heading) if Some(level) <= chapter_level => {
+                push_chapter(&mut chapters, title.take(), &current);
+                current = format!("{}\n", sentence(&heading));
+                title = Some(heading);
+            }
+            MarkdownLine::Heading(_, heading) => {
+                current.push_str(&sentence(&heading));
+                current.push('\n');
+            }
+            MarkdownLine::Text(text) => {
+                current.push_str(&text);
+                current.push('\n');
+            }
+        }
+    }
+    push_chapter(&mut chapters, title, &current);
+    chapters
+}
+
+/// Read the chapters of an EPUB in spine order; documents without text (covers, blank pages)
+/// are skipped
+pub fn read_epub(bytes: &[u8]) -> Result<Vec<Chapter>> {
+    let mut archive = EpubArchive::new(bytes, MAX_EPUB_ENTRY_BYTES, MAX_EPUB_TOTAL_BYTES)?;
+
+    let container = archive.read("META-INF/container.xml")?;
+    let package_path = FULL_PATH
+        .captures(&container)
+        .map(|captures| captures[1].to_string())
+        .context("META-INF/container.xml does not name a package document")?;
+    let package = archive.read(&package_path)?;
+    let base = package_path
+        .rsplit_once('/')
+        .map_or("", |(dir, _)| dir)
+        .to_string();
+
+    let manifest: HashMap<String, String> = MANIFEST_ITEM
+        .find_iter(&package)
+        .filter_map(|item| {
+            let attributes = attributes(item.as_str());
+            Some((
+                attributes.get("id")?.clone(),
+                attributes.get("href")?.clone(),
+            ))
+        })
+        .collect();
+
+    let mut chapters = Vec::new();
+    for itemref in SPINE_ITEM.find_iter(&package) {
+        let attributes = attributes(itemref.as_str());
+        if attributes
+            .get("linear")
+            .is_some_and(|linear| linear == "no")
+        {
+            continue;
+        }
+        let Some(href) = attributes
+            .get("idref")
+            .and_then(|idref| manifest.get(idref))
+        else {
+            continue;
+        };
+        let path = resolve_path(&base, &percent_decode(href));
+        let document = archive.read(&path)?;
+        let (title, text) = xhtml_to_text(&document);
+        push_chapter(&mut chapters, title, &text);
+    }
+
+    if chapters.is_empty() {
+        anyhow::bail!("EPUB contains no readable text");
+    }
+    Ok(chapters)
+}
+
+/// The main text of a web page as one chapter, titled by its first heading or else the page
+/// title.
+///
+/// The text is taken from the page's longest `<article>`, or else its `<main>`, or else the
+/// whole page, leaving out navigation, sidebars, footers, forms, and figures.
+pub fn read_article(html: &str) -> Result<Chapter> {
+    let page = NON_CONTENT.replace_all(html, "");
+    let article = ARTICLE
+        .captures_iter(&page)
+        .filter_map(|captures| captures.get(1))
+        .map(|article| article.as_str())
+        .max_by_key(|article| HTML_TAG.replace_all(article, "").trim().len());
+    let content = match article {
+        Some(article) => BOILERPLATE.replace_all(article, "").into_owned(),
+        None => {
+            // Outside an article, a header is the site's banner rather than the story's title
+            let region = MAIN
+                .captures(&page)
+                .and_then(|captures| captures.get(1))
+                .map_or(page.as_ref(), |main| main.as_str());
+            let region = PAGE_HEADER.replace_all(region, "");
+            BOILERPLATE.replace_all(&region, "").into_owned()
+        }
+    };
+
+    let (heading, text) = xhtml_to_text(&content);
+    let title = heading.or_else(|| {
+        DOCUMENT_TITLE
+            .captures(html)
+            .map(|captures| collapse_whitespace(&decode_entities(&captures[1])))
+    });
+    let mut chapters = Vec::new();
+    push_chapter(&mut chapters, title, &text);
+    chapters.pop().context("page contains no readable text")
+}
+
+/// Add a chapter unless it has no text, titling it after its first line when no title is given
+fn push_chapter(chapters: &mut Vec<Chapter>, title: Option<String>, text: &str) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+    let title = title
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| text.lines().next().unwrap_or_default().to_string());
+    chapters.push(Chapter {
+        title: truncate_title(title.trim()),
+        text: text.to_string(),
+    });
+}
+
+fn truncate_title(title: &str) -> String {
+    if title.chars().count() <= MAX_TITLE_CHARS {
+        return title.to_string();
+    }
+    let cut: String = title.chars().take(MAX_TITLE_CHARS).collect();
+    match cut.rsplit_once(' ') {
+        Some((head, _)) if !head.is_empty() => head.to_string(),
+        _ => cut,
+    }
+}
+
+/// A heading as a sentence, so the engine pauses after it instead of running into the text
+fn sentence(heading: &str) -> String {
+    let heading = heading.trim();
+    if heading.ends_with(['.', '!', '?', ':', ';']) {
+        heading.to_string()
+    } else {
+        format!("{}.", heading)
+    }
+}
+
+enum MarkdownLine {
+    Heading(usize, String),
+    Text(String),
+}
+
+/// Drop a leading `---` ... `---` YAML block
+fn strip_front_matter(markdown: &str) -> &str {
+    split_front_matter(markdown).1
+}
+
+/// Split a leading `---` ... `---` YAML block from the document body; the block is `None`
+/// when the document has none
+pub fn split_front_matter(document: &str) -> (Option<&str>, &str) {
+    let Some(rest) = document
+        .strip_prefix("---\n")
+        .or_else(|| document.strip_prefix("---\r\n"))
+    else {
+        return (None, document);
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    (None, document)
+}
+
+/// Markdown reduced to headings and plain text lines
+fn markdown_lines(markdown: &str) -> Vec<MarkdownLine> {
+    let mut lines = Vec::new();
+    let mut fence: Option<&str> = None;
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            fence = Some("```");
+            continue;
+        }
+        if trimmed.starts_with("~~~") {
+            fence = Some("~~~");
+            continue;
+        }
+        if THEMATIC_BREAK.is_match(trimmed) {
+            lines.push(MarkdownLine::Text(String::new()));
+            continue;
+        }
+        if let Some(captures) = ATX_HEADING.captures(trimmed) {
+            let level = captures[1].len();
+            let text = markdown_inline(captures[2].trim_end_matches('#').trim());
+            lines.push(MarkdownLine::Heading(level, text));
+            continue;
+        }
+        let text = LIST_MARKER.replace(trimmed, "");
+        lines.push(MarkdownLine::Text(markdown_inline(&text)));
+    }
+    lines
+}
+
+/// Inline Markdown reduced to the words it displays
+fn markdown_inline(text: &str) -> String {
+    let text = IMAGE.replace_all(text, "$1");
+    let text = LINK.replace_all(&text, "$1");
+    let text = HTML_TAG.replace_all(&text, "");
+    let mut text = text.into_owned();
+    for emphasis in EMPHASIS.iter() {
+        text = emphasis.replace_all(&text, "$1").into_owned();
+    }
+    decode_entities(&text)
+}
+
+/// Headings and paragraphs of an XHTML document as plain text, with the first heading (or the
+/// document title) as the chapter title
+fn xhtml_to_text(document: &str) -> (Option<String>, String) {
+    let content = NON_CONTENT.replace_all(document, "");
+    let mut title = None;
+    let text = HTML_HEADING.replace_all(&content, |captures: &regex::Captures| {
+        let heading =
+            collapse_whitespace(&decode_entities(&HTML_TAG.replace_all(&captures[1], "")));
+        if heading.is_empty() {
+            return String::from("\n\n");
+        }
+        let text = format!("\n\n{}\n\n", sentence(&heading));
+        title.get_or_insert(heading);
+        text
+    });
+    let text = BLOCK_END.replace_all(&text, "\n\n");
+    let text = decode_entities(&HTML_TAG.replace_all(&text, ""));
+
+    let paragraphs: Vec<String> = text
+        .split("\n\n")
+        .map(collapse_whitespace)
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect();
+    let title = title.or_else(|| {
+        DOCUMENT_TITLE
+            .captures(document)
+            .map(|captures| collapse_whitespace(&decode_entities(&captures[1])))
+            .filter(|title| !title.is_empty())
+    });
+    (title, paragraphs.join("\n\n"))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Decode the XML entities and numeric character references that appear in book text
+fn decode_entities(text: &str) -> String {
+    ENTITY
+        .replace_all(text, |captures: &regex::Captures| {
+            let entity = &captures[1];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                "mdash" => Some('\u{2014}'),
+                "ndash" => Some('\u{2013}'),
+                "hellip" => Some('\u{2026}'),
+                "lsquo" => Some('\u{2018}'),
+                "rsquo" => Some('\u{2019}'),
+                "ldquo" => Some('\u{201c}'),
+                "rdquo" => Some('\u{201d}'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            decoded.map_or_else(|| captures[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+fn attributes(tag: &str) -> HashMap<String, String> {
+    ATTRIBUTE
+        .captures_iter(tag)
+        .map(|captures| {
+            let value = captures.get(2).or_else(|| captures.get(3));
+            (
+                captures[1].to_string(),
+                value.map_or("", |value| value.as_str()).to_string(),
+            )
+        })
+        .collect()
+}
+
+/// Path of `href` inside the archive, relative to the package document's directory
+fn resolve_path(base: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or_default();
+    let mut parts: Vec<&str> = base.split('/').filter(|part| !part.is_empty()).collect();
+    for part in href.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// EPUB archive whose documents are unpacked within a per-entry and a total byte limit
+struct EpubArchive<'a> {
+    archive: zip::ZipArchive<Cursor<&'a [u8]>>,
+    entry_limit: u64,
+    /// Bytes left to unpack before the total limit is reached
+    remaining: u64,
+}
+
+impl<'a> EpubArchive<'a> {
+    fn new(bytes: &'a [u8], entry_limit: u64, total_limit: u64) -> Result<Self> {
+        Ok(Self {
+            archive: zip::ZipArchive::new(Cursor::new(bytes)).context("not an EPUB archive")?,
+            entry_limit,
+            remaining: total_limit,
+        })
+    }
+
+    fn read(&mut self, path: &str) -> Result<String> {
+        let entry = self
+            .archive
+            .by_name(path)
+            .with_context(|| format!("EPUB is missing {}", path))?;
+        let limit = self.entry_limit.min(self.remaining);
+        let too_large = || {
+            if limit < self.entry_limit {
+                anyhow::anyhow!("EPUB unpacks to more than the document limit")
+            } else {
+                anyhow::anyhow!("{} is larger than {} bytes", path, self.entry_limit)
+            }
+        };
+        if entry.size() > limit {
+            return Err(too_large());
+        }
+        // The declared size can be forged, so the read itself is capped too
+        let mut bytes = Vec::new();
+        entry
+            .take(limit + 1)
+            .read_to_end(&mut bytes)
+            .with_context(|| format!("Failed to unpack {}", path))?;
+        if bytes.len() as u64 > limit {
+            return Err(too_large());
+        }
+        self.remaining -= bytes.len() as u64;
+        String::from_utf8(bytes).with_context(|| format!("{} is not UTF-8 text", path))
+    }
+}
+
+static FULL_PATH: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"full-path\s*=\s*["']([^"']+)["']"#).expect("valid regex"));
+static MANIFEST_ITEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(?:\w+:)?item\s[^>]*>").expect("valid regex"));
+static SPINE_ITEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(?:\w+:)?itemref\s[^>]*>").expect("valid regex"));
+static ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"([\w:-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("valid regex")
+});
+static NON_CONTENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)<head[\s>].*?</head>|<script[\s>].*?</script>|<style[\s>].*?</style>|<!--.*?-->",
+    )
+    .expect("valid regex")
+});
+static BOILERPLATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)<nav[\s>].*?</nav\s*>|<aside[\s>].*?</aside\s*>|<footer[\s>].*?</footer\s*>|<form[\s>].*?</form\s*>|<figure[\s>].*?</figure\s*>|<noscript[\s>].*?</noscript\s*>|<svg[\s>].*?</svg\s*>|<button[\s>].*?</button\s*>",
+    )
+    .expect("valid regex")
+});
+static PAGE_HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<header[\s>].*?</header\s*>").expect("valid regex"));
+static ARTICLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<article(?:\s[^>]*)?>(.*?)</article\s*>").expect("valid regex")
+});
+static MAIN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<main(?:\s[^>]*)?>(.*?)</main\s*>").expect("valid regex"));
+static DOCUMENT_TITLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").expect("valid regex"));
+static HTML_HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<h[1-6][^>]*>(.*?)</h[1-6]\s*>").expect("valid regex"));
+static BLOCK_END: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)</(?:p|div|section|blockquote|li|tr)\s*>|<br\s*/?>").expect("valid regex")
+});
+static HTML_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<[^>]*>").expect("valid regex"));
+static ENTITY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(#?[a-zA-Z0-9]+);").expect("valid regex"));
+static ATX_HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(#{1,6})\s+(.*)$").expect("valid regex"));
+static THEMATIC_BREAK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:(?:\*\s*){3,}|(?:-\s*){3,}|(?:_\s*){3,})$").expect("valid regex")
+});
+static LIST_MARKER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:>\s*)*(?:[-*+]\s+|\d+[.)]\s+)?").expect("valid regex"));
+static IMAGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"!\[([^\]]*)\]\([^)]*\)").expect("valid regex"));
+static LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]]*)\](?:\([^)]*\)|\[[^\]]*\])").expect("valid regex"));
+static EMPHASIS: LazyLock<[Regex; 6]> = LazyLock::new(|| {
+    [
+        r"\*\*(.+?)\*\*",
+        r"__(.+?)__",
+        r"\*([^*\s][^*]*?)\*",
+        r"\b_([^_\s][^_]*?)_\b",
+        r"~~(.+?)~~",
+        r"`([^`]+)`",
+    ]
+    .map(|pattern| Regex::new(pattern).expect("valid regex"))
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    #[test]
+    fn test_split_text() {
+        let chapters =
+            split_text("Chapter One\nIt was a dark night.\n---\n\nChapter Two\nDawn came.\n---\n");
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].title, "Chapter One");
+        assert_eq!(chapters[0].text, "Chapter One\nIt was a dark night.");
+        assert_eq!(chapters[1].title, "Chapter Two");
+
+        let single = split_text("Just one passage.");
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].title, "Just one passage.");
+        assert!(split_text("\n---\n").is_empty());
+    }
+
+    #[test]
+    fn test_split_markdown() {
+        let chapters = split_markdown(
+            "---\ntitle: Book\n---\n# The Book\n\n## First\n\nSome **bold** and [linked](http://x) text.\n\n```\ncode()\n```\n### Aside\n- item one\n\n## Second!\n\n> Quoted `code`.\n",
+        );
+        assert_eq!(chapters.len(), 3);
+        assert_eq!(chapters[0].title, "The Book");
+        assert_eq!(chapters[0].text, "The Book.");
+        assert_eq!(chapters[1].title, "First");
+        assert_eq!(
+            chapters[1].text,
+            "First.\n\nSome bold and linked text.\n\nAside.\nitem one"
+        );
+        assert_eq!(chapters[2].title, "Second!");
+        assert_eq!(chapters[2].text, "Second!\n\nQuoted code.");
+
+        // Without headings the whole document is one chapter
+        let plain = split_markdown("Just a note with snake_case_names.");
+        assert_eq!(plain.len(), 1);
+        assert_eq!(plain[0].text, "Just a note with snake_case_names.");
+    }
+
+    #[test]
+    fn test_split_front_matter() {
+        assert_eq!(
+            split_front_matter("---\nvoice: bf_emma\n---\nBody\n"),
+            (Some("voice: bf_emma\n"), "Body\n")
+        );
+        assert_eq!(split_front_matter("---\r\n---\r\nBody"), (Some(""), "Body"));
+        // An unclosed block is part of the body
+        assert_eq!(
+            split_front_matter("---\nvoice: bf_emma\n"),
+            (None, "---\nvoice: bf_emma\n")
+        );
+        assert_eq!(split_front_matter("Body"), (None, "Body"));
+    }
+
+    #[test]
+    fn test_read_article() {
+        let page = r#"<html><head><title>Tides Explained | Coast Blog</title></head><body>
+            <header><a href="/">Coast Blog</a></header>
+            <nav><ul><li>Home</li><li>About</li></ul></nav>
+            <article>
+                <header><h1>Why the tide turns</h1><p>By Ana, 3 min read</p></header>
+                <p>The moon pulls the sea.</p>
+                <figure><img src="moon.jpg"/><figcaption>The moon</figcaption></figure>
+                <p>Twice a day &amp; night, the water rises.</p>
+                <aside>Subscribe for more</aside>
+            </article>
+            <article><p>Related: waves</p></article>
+            <footer>Copyright</footer>
+        </body></html>"#;
+        let chapter = read_article(page).unwrap();
+        assert_eq!(chapter.title, "Why the tide turns");
+        assert_eq!(
+            chapter.text,
+            "Why the tide turns.\n\nBy Ana, 3 min read\n\nThe moon pulls the sea.\n\nTwice a day & night, the water rises."
+        );
+
+        let plain = read_article(
+            "<title>Notes</title><header>Site</header><main><p>Only this.</p></main><footer>x</footer>",
+        )
+        .unwrap();
+        assert_eq!(plain.title, "Notes");
+        assert_eq!(plain.text, "Only this.");
+        assert!(read_article("<html><body><nav>Menu</nav></body></html>").is_err());
+    }
+
+    #[test]
+    fn test_truncate_title() {
+        let long = "word ".repeat(30);
+        let title = truncate_title(long.trim());
+        assert!(title.chars().count() <= MAX_TITLE_CHARS);
+        assert!(title.ends_with("word"));
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(
+            decode_entities("Tom &amp; Jerry&#8217;s &#x201C;show&#x201D; &unknown;"),
+            "Tom & Jerry\u{2019}s \u{201c}show\u{201d} &unknown;"
+        );
+    }
+
+    #[test]
+    fn test_resolve_path() {
+        assert_eq!(
+            resolve_path("OEBPS", "text/ch1.xhtml"),
+            "OEBPS/text/ch1.xhtml"
+        );
+        assert_eq!(
+            resolve_path("OEBPS/text", "../ch1.xhtml#start"),
+            "OEBPS/ch1.xhtml"
+        );
+        assert_eq!(resolve_path("", "ch1.xhtml"), "ch1.xhtml");
+        assert_eq!(percent_decode("chapter%201.xhtml"), "chapter 1.xhtml");
+    }
+
+    fn epub(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_read_epub() {
+        let book = epub(&[
+            ("mimetype", "application/epub+zip"),
+            (
+                "META-INF/container.xml",
+                r#"<container><rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles></container>"#,
+            ),
+            (
+                "OEBPS/content.opf",
+                r#"<package><manifest>
+                    <item id="cover" href="cover.xhtml" media-type="application/xhtml+xml"/>
+                    <item href="text/chapter%201.xhtml" id="c1" media-type="application/xhtml+xml"/>
+                    <item id='c2' href='text/c2.xhtml' media-type='application/xhtml+xml'/>
+                    <item id="notes" href="notes.xhtml" media-type="application/xhtml+xml"/>
+                </manifest><spine>
+                    <itemref idref="cover"/><itemref idref="c1"/><itemref idref="c2"/>
+                    <itemref idref="notes" linear="no"/>
+                </spine></package>"#,
+            ),
+            (
+                "OEBPS/cover.xhtml",
+                r#"<html><head><title>Cover</title></head><body><img src="cover.jpg"/></body></html>"#,
+            ),
+            (
+                "OEBPS/text/chapter 1.xhtml",
+                r#"<html><head><title>Ignored</title><style>p { color: red }</style></head><body>
+                    <h1 class="chapter">Chapter <em>One</em></h1>
+                    <p>It was a   dark &amp; stormy night.</p><p>Rain fell.<br/>Hard.</p>
+                </body></html>"#,
+            ),
+            (
+                "OEBPS/text/c2.xhtml",
+                r#"<html><head><title>Epilogue</title></head><body><p>The end.</p></body></html>"#,
+            ),
+            (
+                "OEBPS/notes.xhtml",
+                "<html><body><p>Notes</p></body></html>",
+            ),
+        ]);
+
+        let chapters = read_epub(&book).unwrap();
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].title, "Chapter One");
+        assert_eq!(
+            chapters[0].text,
+            "Chapter One.\n\nIt was a dark & stormy night.\n\nRain fell.\n\nHard."
+        );
+        assert_eq!(chapters[1].title, "Epilogue");
+        assert_eq!(chapters[1].text, "The end.");
+
+        assert!(read_epub(b"not a zip").is_err());
+        assert!(read_epub(&epub(&[("mimetype", "application/epub+zip")])).is_err());
+    }
+
+    #[test]
+    fn test_epub_unpack_limits() {
+        let text = "word ".repeat(40);
+        let book = epub(&[("a.xhtml", text.as_str()), ("b.xhtml", text.as_str())]);
+
+        let mut archive = EpubArchive::new(&book, 200, 1_000).unwrap();
+        assert_eq!(archive.read("a.xhtml").unwrap(), text);
+        let mut archive = EpubArchive::new(&book, 100, 1_000).unwrap();
+        let error = archive.read("a.xhtml").unwrap_err().to_string();
+        assert!(error.contains("larger than 100 bytes"), "{error}");
+
+        // Rereading the same document counts against the total
+        let mut archive = EpubArchive::new(&book, 200, 500).unwrap();
+        assert!(archive.read("a.xhtml").is_ok());
+        assert!(archive.read("a.xhtml").is_ok());
+        let error = archive.read("b.xhtml").unwrap_err().to_string();
+        assert!(error.contains("more than the document limit"), "{error}");
+    }
+}
@@ -0,0 +1,149 @@
+use crate::presets::silence_samples;
+
+/// Peak level of the noise, about -50 dBFS (-63 dBFS RMS): inaudible under speech, but loud
+/// enough to survive 16-bit and mu-law encoding as something other than silence
+const NOISE_PEAK: f32 = 0.003;
+/// Samples quieter than this, about -100 dBFS, count as digital silence
+const SILENCE_THRESHOLD: f32 = 1e-5;
+/// Shortest run of silence that is filled, so brief dips inside speech are left alone
+const MIN_RUN_MS: u32 = 10;
+
+/// Low-level noise for padding telephony audio, so VoIP stacks that clamp or drop "dead air"
+/// keep the stream open.
+///
+/// White noise is low-passed with a one-pole filter, giving a soft rumble rather than hiss.
+/// The generator is seeded the same way every time, so a request always renders to the same
+/// bytes and stays cacheable.
+#[derive(Debug, Clone)]
+pub struct ComfortNoise {
+    state: u32,
+    low: f32,
+}
+
+impl Default for ComfortNoise {
+    fn default() -> Self {
+        Self {
+            state: 0x9E37_79B9,
+            low: 0.0,
+        }
+    }
+}
+
+impl ComfortNoise {
+    /// Next noise sample, within ±[`NOISE_PEAK`]
+    pub fn next_sample(&mut self) -> f32 {
+        // xorshift32
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        let white = self.state as f32 / u32::MAX as f32 * 2.0 - 1.0;
+        self.low += 0.25 * (white - self.low);
+        self.low * NOISE_PEAK
+    }
+}
+
+/// Replace every run of digital silence at least [`MIN_RUN_MS`] long with comfort noise.
+///
+/// This covers leading silence, trailing silence, and sentence gaps alike, wherever in the
+/// pipeline they were inserted.
+pub fn fill_silence(samples: &mut [f32], sample_rate: u32) {
+    SilenceFiller::new(sample_rate).fill(samples);
+}
+
+/// Fills silence in audio that arrives in blocks, such as a stream, with one noise generator,
+/// so the noise carries on from block to block instead of starting over in each
+#[derive(Debug, Clone)]
+pub struct SilenceFiller {
+    noise: ComfortNoise,
+    min_run: usize,
+    /// Silent samples at the end of the last block, which a run at the start of the next one
+    /// continues
+    trailing: usize,
+}
+
+impl SilenceFiller {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            noise: ComfortNoise::default(),
+            min_run: silence_samples(MIN_RUN_MS, sample_rate).max(1),
+            trailing: 0,
+        }
+    }
+
+    /// Fill the next block's runs of silence
+    pub fn fill(&mut self, samples: &mut [f32]) {
+        let mut carried = std::mem::take(&mut self.trailing);
+        let mut start = 0;
+        while start < samples.len() {
+            if samples[start].abs() >= SILENCE_THRESHOLD {
+                carried = 0;
+                start += 1;
+                continue;
+            }
+            let end = samples[start..]
+                .iter()
+                .position(|sample| sample.abs() >= SILENCE_THRESHOLD)
+                .map_or(samples.len(), |offset| start + offset);
+            let run = carried + end - start;
+            if end == samples.len() {
+                self.trailing = run;
+            }
+            if run >= self.min_run {
+                for sample in &mut samples[start..end] {
+                    *sample = self.noise.next_sample();
+                }
+            }
+            carried = 0;
+            start = end;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_silence_pads_only_long_runs() {
+        let mut samples = vec![0.0f32; 2_400];
+        samples.extend([0.5, 0.0, -0.5]);
+        samples.extend(vec![0.0; 480]);
+        fill_silence(&mut samples, 24_000);
+
+        // The 100 ms lead and 20 ms tail are filled, the single zero inside speech is not
+        assert!(samples[..2_400].iter().any(|sample| *sample != 0.0));
+        assert!(samples[..2_400]
+            .iter()
+            .all(|sample| sample.abs() <= NOISE_PEAK));
+        assert_eq!(&samples[2_400..2_403], &[0.5, 0.0, -0.5]);
+        assert!(samples[2_403..].iter().any(|sample| *sample != 0.0));
+
+        // The same input always gets the same noise
+        let mut again = vec![0.0f32; 2_400];
+        fill_silence(&mut again, 24_000);
+        assert_eq!(&again[..], &samples[..2_400]);
+    }
+
+    #[test]
+    fn test_filler_continues_across_blocks() {
+        let mut whole = vec![0.0f32; 4_800];
+        fill_silence(&mut whole, 24_000);
+
+        let mut filler = SilenceFiller::new(24_000);
+        let mut first = vec![0.0f32; 2_400];
+        let mut second = vec![0.0f32; 2_400];
+        filler.fill(&mut first);
+        filler.fill(&mut second);
+        assert_ne!(first, second);
+        assert_eq!([first, second].concat(), whole);
+
+        // A run split across blocks is long enough once both halves are counted
+        let mut filler = SilenceFiller::new(24_000);
+        let mut end = vec![0.5f32, 0.0, 0.0, 0.0];
+        let mut start = vec![0.0f32; 238];
+        filler.fill(&mut end);
+        filler.fill(&mut start);
+        assert_eq!(&end[1..], &[0.0, 0.0, 0.0]);
+        assert!(start.iter().any(|sample| *sample != 0.0));
+    }
+}
@@ -0,0 +1,63 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn mac(key: &[u8], data: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac
+}
+
+/// HMAC-SHA256 of `data` under `key`
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    mac(key, data).finalize().into_bytes().into()
+}
+
+/// Whether `signature` is the hex HMAC-SHA256 of `data` under `key`, compared in constant time
+pub fn verify_hmac_sha256(key: &[u8], data: &[u8], signature: &str) -> bool {
+    unhex(signature).is_some_and(|tag| mac(key, data).verify_slice(&tag).is_ok())
+}
+
+/// Lowercase hex of `bytes`
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Bytes of a hex string in either case, or `None` if it is not hex
+fn unhex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|at| u8::from_str_radix(hex.get(at..at + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test case 2
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            hex(&mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_verify_hmac_sha256() {
+        let signature = "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+        let data = b"what do ya want for nothing?";
+        assert!(verify_hmac_sha256(b"Jefe", data, signature));
+        assert!(verify_hmac_sha256(b"Jefe", data, &signature.to_uppercase()));
+        assert!(!verify_hmac_sha256(b"Jeff", data, signature));
+        assert!(!verify_hmac_sha256(b"Jefe", data, &signature[..62]));
+        assert!(!verify_hmac_sha256(b"Jefe", data, "not hex"));
+        assert!(!verify_hmac_sha256(b"Jefe", data, "é"));
+    }
+}
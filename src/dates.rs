@@ -0,0 +1,454 @@
+use crate::{
+    config::{DateOrder, TimeFormat},
+    language::Language,
+    numbers,
+};
+
+const EN_MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const ES_MONTHS: [&str; 12] = [
+    "enero",
+    "febrero",
+    "marzo",
+    "abril",
+    "mayo",
+    "junio",
+    "julio",
+    "agosto",
+    "septiembre",
+    "octubre",
+    "noviembre",
+    "diciembre",
+];
+const FR_MONTHS: [&str; 12] = [
+    "janvier",
+    "février",
+    "mars",
+    "avril",
+    "mai",
+    "juin",
+    "juillet",
+    "août",
+    "septembre",
+    "octobre",
+    "novembre",
+    "décembre",
+];
+const IT_MONTHS: [&str; 12] = [
+    "gennaio",
+    "febbraio",
+    "marzo",
+    "aprile",
+    "maggio",
+    "giugno",
+    "luglio",
+    "agosto",
+    "settembre",
+    "ottobre",
+    "novembre",
+    "dicembre",
+];
+const PT_MONTHS: [&str; 12] = [
+    "janeiro",
+    "fevereiro",
+    "março",
+    "abril",
+    "maio",
+    "junho",
+    "julho",
+    "agosto",
+    "setembro",
+    "outubro",
+    "novembro",
+    "dezembro",
+];
+
+/// A calendar date, checked to exist
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub year: u64,
+    pub month: u64,
+    pub day: u64,
+}
+
+impl Date {
+    pub fn new(year: u64, month: u64, day: u64) -> Option<Self> {
+        let days = match month {
+            2 if year.is_multiple_of(4)
+                && (!year.is_multiple_of(100) || year.is_multiple_of(400)) =>
+            {
+                29
+            }
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return None,
+        };
+        (1..=days)
+            .contains(&day)
+            .then_some(Self { year, month, day })
+    }
+
+    /// A date written as two numbers and a year (`03/05/2024`), taking the first number as the
+    /// day or the month according to `order`, unless only the other reading is a real date
+    pub fn from_parts(first: u64, second: u64, year: u64, order: DateOrder) -> Option<Self> {
+        let (month_first, day_first) = (
+            Self::new(year, first, second),
+            Self::new(year, second, first),
+        );
+        match order {
+            DateOrder::Dmy => day_first.or(month_first),
+            DateOrder::Mdy | DateOrder::Ymd => month_first.or(day_first),
+        }
+    }
+}
+
+/// A time of day, in 24-hour form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Time {
+    pub hour: u64,
+    pub minute: u64,
+    pub second: Option<u64>,
+    /// The time was written with AM or PM, or past noon in 24-hour form, so which half of the
+    /// day it falls in is known
+    pub meridiem_known: bool,
+}
+
+/// `date` read in `language`. English follows `order`: "March fifth, twenty twenty-four",
+/// "the fifth of March, twenty twenty-four", or "twenty twenty-four, March fifth"; other
+/// languages read dates the one way their speakers do.
+pub fn date(date: Date, language: Language, order: DateOrder) -> Option<String> {
+    let month = date.month as usize - 1;
+    let year = numbers::year(date.year, language)?;
+    Some(match language {
+        Language::AmericanEnglish | Language::BritishEnglish => {
+            let day = numbers::ordinal(date.day, language, false)?;
+            match order {
+                DateOrder::Mdy => format!("{} {}, {}", EN_MONTHS[month], day, year),
+                DateOrder::Dmy => format!("the {} of {}, {}", day, EN_MONTHS[month], year),
+                DateOrder::Ymd => format!("{}, {} {}", year, EN_MONTHS[month], day),
+            }
+        }
+        Language::Spanish => format!(
+            "{} de {} de {}",
+            first_or_cardinal(date.day, language, "primero")?,
+            ES_MONTHS[month],
+            year
+        ),
+        Language::French => format!(
+            "{} {} {}",
+            first_or_cardinal(date.day, language, "premier")?,
+            FR_MONTHS[month],
+            year
+        ),
+        Language::Italian => format!(
+            "{} {} {}",
+            first_or_cardinal(date.day, language, "primo")?,
+            IT_MONTHS[month],
+            year
+        ),
+        Language::Portuguese => format!(
+            "{} de {} de {}",
+            first_or_cardinal(date.day, language, "primeiro")?,
+            PT_MONTHS[month],
+            year
+        ),
+        Language::Japanese | Language::Chinese => format!(
+            "{}年{}月{}日",
+            year,
+            numbers::cardinal(date.month, language)?,
+            numbers::cardinal(date.day, language)?
+        ),
+        Language::Hindi => return None,
+    })
+}
+
+/// The first of the month is read as an ordinal in Romance languages, other days as numbers
+fn first_or_cardinal(day: u64, language: Language, first: &str) -> Option<String> {
+    match day {
+        1 => Some(first.to_string()),
+        _ => numbers::cardinal(day, language),
+    }
+}
+
+/// `time` read in `language` on a 12- or 24-hour clock: "two thirty PM" or "fourteen thirty"
+pub fn time(time: Time, language: Language, format: TimeFormat) -> Option<String> {
+    let twelve_hour = format == TimeFormat::TwelveHour;
+    let hour = match (twelve_hour, time.hour % 12) {
+        (true, 0) => 12,
+        (true, hour) => hour,
+        (false, _) => time.hour,
+    };
+    let pm = time.hour >= 12;
+    // The part of the day is only said when it is known and the clock needs it
+    let part_of_day = twelve_hour && time.meridiem_known;
+    let minute = time.minute;
+
+    let mut words = match language {
+        Language::AmericanEnglish | Language::BritishEnglish => {
+            let hour = numbers::cardinal(hour, language)?;
+            let mut words = match (minute, twelve_hour) {
+                (0, true) if part_of_day => hour,
+                (0, true) => format!("{} o'clock", hour),
+                (0, false) => format!("{} hundred", hour),
+                (1..=9, _) => format!("{} oh {}", hour, numbers::cardinal(minute, language)?),
+                _ => format!("{} {}", hour, numbers::cardinal(minute, language)?),
+            };
+            if part_of_day {
+                words.push_str(if pm { " PM" } else { " AM" });
+            }
+            words
+        }
+        Language::Spanish | Language::Italian | Language::Portuguese => {
+            // Hours are feminine: "la una", "las veintiuna", "duas e meia"
+            let hour_words = numbers::cardinal_for_noun(hour, language, true)?;
+            let mut words = match (minute, language) {
+                (0, Language::Spanish) if twelve_hour => format!("{} en punto", hour_words),
+                (0, Language::Spanish) => format!("{} horas", hour_words),
+                (0, Language::Portuguese) if hour == 1 => format!("{} hora", hour_words),
+                (0, Language::Portuguese) => format!("{} horas", hour_words),
+                (0, _) => hour_words,
+                (_, Language::Spanish) => {
+                    format!("{} y {}", hour_words, numbers::cardinal(minute, language)?)
+                }
+                _ => format!("{} e {}", hour_words, numbers::cardinal(minute, language)?),
+            };
+            if part_of_day {
+                let period = match (language, time.hour) {
+                    (Language::Spanish, 0..=11) => "de la mañana",
+                    (Language::Spanish, 12..=19) => "de la tarde",
+                    (Language::Spanish, _) => "de la noche",
+                    (Language::Italian, 0..=11) => "di mattina",
+                    (Language::Italian, 12..=17) => "del pomeriggio",
+                    (Language::Italian, _) => "di sera",
+                    (_, 0..=11) => "da manhã",
+                    (_, 12..=18) => "da tarde",
+                    _ => "da noite",
+                };
+                words.push(' ');
+                words.push_str(period);
+            }
+            words
+        }
+        Language::French => {
+            let hour_words = numbers::cardinal_for_noun(hour, language, true)?;
+            let heures = if hour < 2 { "heure" } else { "heures" };
+            let mut words = match minute {
+                0 => format!("{} {}", hour_words, heures),
+                _ => format!(
+                    "{} {} {}",
+                    hour_words,
+                    heures,
+                    numbers::cardinal(minute, language)?
+                ),
+            };
+            if part_of_day {
+                words.push_str(match time.hour {
+                    0..=11 => " du matin",
+                    12..=17 => " de l'après-midi",
+                    _ => " du soir",
+                });
+            }
+            words
+        }
+        Language::Japanese | Language::Chinese => {
+            let japanese = language == Language::Japanese;
+            let mut words = String::new();
+            if part_of_day {
+                words.push_str(match (japanese, pm) {
+                    (true, false) => "午前",
+                    (true, true) => "午後",
+                    (false, false) => "上午",
+                    (false, true) => "下午",
+                });
+            }
+            // Two o'clock is 两点 in Chinese
+            match (japanese, hour) {
+                (false, 2) => words.push('两'),
+                _ => words.push_str(&numbers::cardinal(hour, language)?),
+            }
+            words.push_str(if japanese { "時" } else { "点" });
+            if minute > 0 {
+                if !japanese && minute < 10 {
+                    words.push('零');
+                }
+                words.push_str(&numbers::cardinal(minute, language)?);
+                words.push('分');
+            }
+            words
+        }
+        Language::Hindi => return None,
+    };
+
+    if let Some(second) = time.second.filter(|&second| second > 0) {
+        let seconds = numbers::cardinal(second, language)?;
+        let plural = second != 1;
+        match language {
+            Language::AmericanEnglish | Language::BritishEnglish => words.push_str(&format!(
+                " and {} second{}",
+                seconds,
+                if plural { "s" } else { "" }
+            )),
+            Language::Spanish => words.push_str(&format!(
+                " y {} segundo{}",
+                seconds,
+                if plural { "s" } else { "" }
+            )),
+            Language::French => words.push_str(&format!(
+                " et {} seconde{}",
+                seconds,
+                if plural { "s" } else { "" }
+            )),
+            Language::Italian => words.push_str(&format!(
+                " e {} {}",
+                seconds,
+                if plural { "secondi" } else { "secondo" }
+            )),
+            Language::Portuguese => words.push_str(&format!(
+                " e {} segundo{}",
+                seconds,
+                if plural { "s" } else { "" }
+            )),
+            Language::Japanese | Language::Chinese => {
+                words.push_str(&seconds);
+                words.push('秒');
+            }
+            Language::Hindi => {}
+        }
+    }
+    Some(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn march_fifth() -> Date {
+        Date::new(2024, 3, 5).unwrap()
+    }
+
+    #[test]
+    fn test_date_parts() {
+        assert_eq!(Date::new(2023, 2, 29), None);
+        assert!(Date::new(2024, 2, 29).is_some());
+        assert_eq!(
+            Date::from_parts(3, 5, 2024, DateOrder::Mdy),
+            Some(march_fifth())
+        );
+        assert_eq!(
+            Date::from_parts(5, 3, 2024, DateOrder::Dmy),
+            Some(march_fifth())
+        );
+        // 25 cannot be a month, so the day comes first whatever the order
+        assert_eq!(
+            Date::from_parts(25, 12, 2024, DateOrder::Mdy),
+            Date::new(2024, 12, 25)
+        );
+    }
+
+    #[test]
+    fn test_date_words() {
+        let en = Language::AmericanEnglish;
+        assert_eq!(
+            date(march_fifth(), en, DateOrder::Mdy).unwrap(),
+            "March fifth, twenty twenty-four"
+        );
+        assert_eq!(
+            date(march_fifth(), en, DateOrder::Dmy).unwrap(),
+            "the fifth of March, twenty twenty-four"
+        );
+        assert_eq!(
+            date(march_fifth(), en, DateOrder::Ymd).unwrap(),
+            "twenty twenty-four, March fifth"
+        );
+        assert_eq!(
+            date(march_fifth(), Language::Spanish, DateOrder::Mdy).unwrap(),
+            "cinco de marzo de dos mil veinticuatro"
+        );
+        assert_eq!(
+            date(
+                Date::new(2024, 5, 1).unwrap(),
+                Language::French,
+                DateOrder::Mdy
+            )
+            .unwrap(),
+            "premier mai deux mille vingt-quatre"
+        );
+        assert_eq!(
+            date(march_fifth(), Language::Chinese, DateOrder::Mdy).unwrap(),
+            "二〇二四年三月五日"
+        );
+    }
+
+    #[test]
+    fn test_time_words() {
+        let en = Language::AmericanEnglish;
+        let at = |hour, minute, meridiem_known| Time {
+            hour,
+            minute,
+            second: None,
+            meridiem_known,
+        };
+        assert_eq!(
+            time(at(14, 30, true), en, TimeFormat::TwelveHour).unwrap(),
+            "two thirty PM"
+        );
+        assert_eq!(
+            time(at(9, 5, false), en, TimeFormat::TwelveHour).unwrap(),
+            "nine oh five"
+        );
+        assert_eq!(
+            time(at(9, 0, false), en, TimeFormat::TwelveHour).unwrap(),
+            "nine o'clock"
+        );
+        assert_eq!(
+            time(at(14, 30, true), en, TimeFormat::TwentyFourHour).unwrap(),
+            "fourteen thirty"
+        );
+        assert_eq!(
+            time(at(14, 0, true), en, TimeFormat::TwentyFourHour).unwrap(),
+            "fourteen hundred"
+        );
+        assert_eq!(
+            time(at(13, 0, true), Language::Spanish, TimeFormat::TwelveHour).unwrap(),
+            "una en punto de la tarde"
+        );
+        assert_eq!(
+            time(
+                at(21, 15, true),
+                Language::French,
+                TimeFormat::TwentyFourHour
+            )
+            .unwrap(),
+            "vingt et une heures quinze"
+        );
+        assert_eq!(
+            time(at(14, 5, true), Language::Chinese, TimeFormat::TwelveHour).unwrap(),
+            "下午两点零五分"
+        );
+        assert_eq!(
+            time(
+                Time {
+                    second: Some(15),
+                    ..at(14, 30, true)
+                },
+                en,
+                TimeFormat::TwentyFourHour
+            )
+            .unwrap(),
+            "fourteen thirty and fifteen seconds"
+        );
+    }
+}
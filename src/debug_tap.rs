@@ -0,0 +1,232 @@
+use crate::{
+    api::{AppState, SpeechParams},
+    cache::CachedAudio,
+    error::{ApiResult, AppError},
+    redact,
+};
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+    Json,
+};
+use bytes::Bytes;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Most entries allocated up front; a larger tap grows as it fills
+const PREALLOCATED_ENTRIES: usize = 64;
+
+/// Recent syntheses kept for diagnosing reports of bad audio, oldest dropped first.
+///
+/// Complete clips are captured whether synthesized or served from the cache. Streamed responses
+/// are not, since their audio is never held in one piece.
+pub struct DebugTap {
+    entries: Mutex<VecDeque<TapEntry>>,
+    capacity: usize,
+}
+
+/// What was asked for and what came out, without the audio
+#[derive(Debug, Clone, Serialize)]
+pub struct TapSummary {
+    pub request_id: String,
+    /// Unix seconds
+    pub created_at: u64,
+    /// Request text, or its length and hash when text redaction is enabled
+    pub input: String,
+    pub voice: String,
+    /// The requested voice was unknown and `voice` is the fallback
+    pub voice_substituted: bool,
+    pub format: String,
+    pub speed: f32,
+    pub sample_rate: Option<u32>,
+    pub initial_silence: Option<usize>,
+    pub trailing_silence: usize,
+    /// Filters and compressor applied, in order
+    pub effects: String,
+    pub bit_depth: u16,
+    /// The clip was served from the audio cache
+    pub cached: bool,
+    /// Length of the audio, unknown for a clip served from the cache
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    pub bytes: usize,
+}
+
+struct TapEntry {
+    summary: TapSummary,
+    content_type: &'static str,
+    audio: Bytes,
+}
+
+/// Response body for GET /admin/debug/requests
+#[derive(Debug, Serialize)]
+pub struct TapListResponse {
+    pub object: &'static str,
+    /// Newest first
+    pub data: Vec<TapSummary>,
+}
+
+/// Response body for DELETE /admin/debug/requests
+#[derive(Debug, Serialize)]
+pub struct ClearResponse {
+    pub cleared: usize,
+}
+
+impl DebugTap {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(capacity.min(PREALLOCATED_ENTRIES))),
+            capacity,
+        }
+    }
+
+    /// Capture a returned clip; `duration_ms` is `None` when it came from the cache
+    pub fn record(
+        &self,
+        request_id: &str,
+        params: &SpeechParams,
+        audio: &CachedAudio,
+        duration_ms: Option<u64>,
+    ) {
+        let entry = TapEntry {
+            summary: TapSummary {
+                request_id: request_id.to_string(),
+                created_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_secs()),
+                input: redact::text(&params.input).to_string(),
+                voice: params.voice.clone(),
+                voice_substituted: params.voice_substituted,
+                format: params.format.clone(),
+                speed: params.speed,
+                sample_rate: params.sample_rate,
+                initial_silence: params.initial_silence,
+                trailing_silence: params.trailing_silence,
+                effects: params.effects.describe(),
+                bit_depth: params.bit_depth.bits(),
+                cached: duration_ms.is_none(),
+                duration_ms,
+                bytes: audio.bytes.len(),
+            },
+            content_type: audio.content_type,
+            audio: audio.bytes.clone(),
+        };
+
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    pub fn list(&self) -> Vec<TapSummary> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries
+            .iter()
+            .rev()
+            .map(|entry| entry.summary.clone())
+            .collect()
+    }
+
+    fn audio(&self, request_id: &str) -> Option<(&'static str, Bytes)> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries
+            .iter()
+            .rev()
+            .find(|entry| entry.summary.request_id == request_id)
+            .map(|entry| (entry.content_type, entry.audio.clone()))
+    }
+
+    pub fn clear(&self) -> usize {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let cleared = entries.len();
+        entries.clear();
+        cleared
+    }
+}
+
+fn tap(state: &AppState) -> ApiResult<&DebugTap> {
+    state
+        .debug_tap
+        .as_deref()
+        .ok_or_else(|| AppError::invalid_request("The debug tap is disabled"))
+}
+
+pub async fn list_handler(State(state): State<AppState>) -> ApiResult<Json<TapListResponse>> {
+    Ok(Json(TapListResponse {
+        object: "list",
+        data: tap(&state)?.list(),
+    }))
+}
+
+/// Audio of a retained synthesis, exactly as it was returned
+pub async fn audio_handler(
+    State(state): State<AppState>,
+    Path(request_id): Path<String>,
+) -> ApiResult<impl IntoResponse> {
+    let (content_type, audio) = tap(&state)?
+        .audio(&request_id)
+        .ok_or_else(|| AppError::capture_not_found(&request_id))?;
+    Ok(([(header::CONTENT_TYPE, content_type)], audio))
+}
+
+pub async fn clear_handler(State(state): State<AppState>) -> ApiResult<Json<ClearResponse>> {
+    Ok(Json(ClearResponse {
+        cleared: tap(&state)?.clear(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(input: &str) -> SpeechParams {
+        SpeechParams {
+            input: input.to_string(),
+            voice: "af_heart".to_string(),
+            format: "wav".to_string(),
+            speed: 1.0,
+            ..Default::default()
+        }
+    }
+
+    fn audio(bytes: &'static [u8]) -> CachedAudio {
+        CachedAudio {
+            content_type: "audio/wav",
+            bytes: Bytes::from_static(bytes),
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_tap_keeps_newest_entries() {
+        let tap = DebugTap::new(2);
+        tap.record("req-1", &params("One."), &audio(b"one"), Some(100));
+        tap.record("req-2", &params("Two."), &audio(b"two"), Some(200));
+        tap.record("req-3", &params("Three."), &audio(b"three"), None);
+
+        let list = tap.list();
+        assert_eq!(
+            list.iter()
+                .map(|entry| entry.request_id.as_str())
+                .collect::<Vec<_>>(),
+            ["req-3", "req-2"]
+        );
+        assert_eq!(list[0].input, "Three.");
+        assert_eq!(list[0].bytes, 5);
+        assert!(list[0].cached);
+        assert_eq!(list[1].duration_ms, Some(200));
+        assert!(!list[1].cached);
+
+        assert!(tap.audio("req-1").is_none());
+        let (content_type, bytes) = tap.audio("req-2").unwrap();
+        assert_eq!(content_type, "audio/wav");
+        assert_eq!(bytes, Bytes::from_static(b"two"));
+
+        assert_eq!(tap.clear(), 2);
+        assert!(tap.list().is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use audiopus::{coder::Encoder, Application, Bitrate, Channels, SampleRate};
+use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+
+/// Discord voice connections expect 48 kHz stereo Opus
+const DISCORD_SAMPLE_RATE: u32 = 48_000;
+const CHANNELS: usize = 2;
+/// 20 ms per Opus frame, matching Discord's packet interval
+const FRAME_SAMPLES: usize = DISCORD_SAMPLE_RATE as usize / 50;
+/// Discord's default voice channel bitrate
+const BITRATE: i32 = 64_000;
+/// Upper bound for one encoded packet, as recommended by libopus
+const MAX_PACKET_BYTES: usize = 4000;
+const STREAM_SERIAL: u32 = 0x4b4f_4b4f;
+
+/// Encode mono samples as Ogg Opus at 48 kHz stereo with one 20 ms frame per packet
+pub fn encode_discord_opus(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
+    let stereo = upsample_to_stereo(samples, sample_rate);
+
+    let mut encoder = Encoder::new(SampleRate::Hz48000, Channels::Stereo, Application::Audio)
+        .context("Failed to create Opus encoder")?;
+    encoder
+        .set_bitrate(Bitrate::BitsPerSecond(BITRATE))
+        .context("Failed to set Opus bitrate")?;
+    let pre_skip = encoder
+        .lookahead()
+        .context("Failed to read Opus lookahead")? as u16;
+
+    let mut writer = PacketWriter::new(Vec::new());
+    writer
+        .write_packet(
+            opus_head(pre_skip, sample_rate),
+            STREAM_SERIAL,
+            PacketWriteEndInfo::EndPage,
+            0,
+        )
+        .context("Failed to write OpusHead")?;
+    writer
+        .write_packet(opus_tags(), STREAM_SERIAL, PacketWriteEndInfo::EndPage, 0)
+        .context("Failed to write OpusTags")?;
+
+    let frame_len = FRAME_SAMPLES * CHANNELS;
+    let total_samples = (stereo.len() / CHANNELS) as u64;
+    let frame_count = stereo.len().div_ceil(frame_len).max(1);
+    let mut frame = vec![0.0f32; frame_len];
+    let mut packet = vec![0u8; MAX_PACKET_BYTES];
+
+    for index in 0..frame_count {
+        // The last frame is zero-padded; its granule position trims the padding on decode
+        let start = index * frame_len;
+        let end = (start + frame_len).min(stereo.len());
+        frame.fill(0.0);
+        frame[..end - start].copy_from_slice(&stereo[start..end]);
+
+        let len = encoder
+            .encode_float(&frame, &mut packet)
+            .context("Failed to encode Opus frame")?;
+        let is_last = index + 1 == frame_count;
+        let granule = if is_last {
+            u64::from(pre_skip) + total_samples
+        } else {
+            u64::from(pre_skip) + ((index + 1) * FRAME_SAMPLES) as u64
+        };
+        let end_info = if is_last {
+            PacketWriteEndInfo::EndStream
+        } else {
+            PacketWriteEndInfo::NormalPacket
+        };
+        writer
+            .write_packet(
+                packet[..len].to_vec().into_boxed_slice(),
+                STREAM_SERIAL,
+                end_info,
+                granule,
+            )
+            .context("Failed to write Opus packet")?;
+    }
+
+    Ok(writer.into_inner())
+}
+
+/// Linearly resample mono audio to 48 kHz and duplicate it into interleaved stereo
+fn upsample_to_stereo(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    if samples.is_empty() || sample_rate == 0 {
+        return Vec::new();
+    }
+
+    let step = f64::from(sample_rate) / f64::from(DISCORD_SAMPLE_RATE);
+    let out_len = (samples.len() as f64 / step).round() as usize;
+    let mut out = Vec::with_capacity(out_len * CHANNELS);
+    for i in 0..out_len {
+        let position = i as f64 * step;
+        let index = position as usize;
+        let frac = (position - index as f64) as f32;
+        let current = samples[index.min(samples.len() - 1)];
+        let next = samples[(index + 1).min(samples.len() - 1)];
+        let value = current + (next - current) * frac;
+        out.extend([value; CHANNELS]);
+    }
+    out
+}
+
+/// Identification header from RFC 7845 section 5.1
+fn opus_head(pre_skip: u16, input_sample_rate: u32) -> Box<[u8]> {
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(b"OpusHead");
+    head.push(1); // version
+    head.push(CHANNELS as u8);
+    head.extend_from_slice(&pre_skip.to_le_bytes());
+    head.extend_from_slice(&input_sample_rate.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    head.push(0); // channel mapping family: mono/stereo
+    head.into_boxed_slice()
+}
+
+/// Comment header from RFC 7845 section 5.2, with no user comments
+fn opus_tags() -> Box<[u8]> {
+    const VENDOR: &[u8] = b"kokoro-openai-server";
+
+    let mut tags = Vec::with_capacity(8 + 4 + VENDOR.len() + 4);
+    tags.extend_from_slice(b"OpusTags");
+    tags.extend_from_slice(&(VENDOR.len() as u32).to_le_bytes());
+    tags.extend_from_slice(VENDOR);
+    tags.extend_from_slice(&0u32.to_le_bytes());
+    tags.into_boxed_slice()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upsample_to_stereo() {
+        let stereo = upsample_to_stereo(&[0.0, 1.0, -1.0], 24_000);
+        assert_eq!(
+            stereo,
+            vec![0.0, 0.0, 0.5, 0.5, 1.0, 1.0, 0.0, 0.0, -1.0, -1.0, -1.0, -1.0]
+        );
+        assert!(upsample_to_stereo(&[], 24_000).is_empty());
+    }
+
+    #[test]
+    fn test_encode_discord_opus_is_ogg() {
+        let ogg = encode_discord_opus(&vec![0.0; 24_000], 24_000).unwrap();
+        assert_eq!(&ogg[..4], b"OggS");
+        assert!(ogg.windows(8).any(|window| window == b"OpusHead"));
+        assert!(ogg.windows(8).any(|window| window == b"OpusTags"));
+    }
+
+    #[test]
+    fn test_opus_head() {
+        let head = opus_head(312, 24_000);
+        assert_eq!(head.len(), 19);
+        assert_eq!(&head[..8], b"OpusHead");
+        assert_eq!(head[9], 2);
+        assert_eq!(u16::from_le_bytes([head[10], head[11]]), 312);
+        assert_eq!(
+            u32::from_le_bytes([head[12], head[13], head[14], head[15]]),
+            24_000
+        );
+    }
+}
@@ -0,0 +1,436 @@
+use anyhow::{Context, Result};
+use reqwest::{header, StatusCode};
+use sha2::{Digest, Sha256};
+use std::fs::{File, TryLockError};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
+
+const PROGRESS_STEPS: u64 = 10;
+const PROGRESS_BYTES_WITHOUT_LENGTH: u64 = 16 * 1024 * 1024;
+const DOWNLOAD_ATTEMPTS: u32 = 5;
+const RETRY_INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const RETRY_MAX_BACKOFF: Duration = Duration::from_secs(30);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Longest stall between body chunks before the attempt is abandoned and resumed
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Download `url` to `target`, resuming from a previous `.part` file when the server supports ranges.
+///
+/// The body is streamed to disk. Failed attempts are retried with exponential backoff and pick up
+/// where the partial file left off; HTTP client errors, local I/O errors and checksum mismatches
+/// fail immediately.
+/// Proxies are taken from `HTTPS_PROXY`, `HTTP_PROXY`, `ALL_PROXY` and `NO_PROXY`.
+///
+/// Returns the SHA256 hex digest of the completed file. When `expected_sha256` is provided and does
+/// not match, the partial file is discarded and an error is returned.
+pub async fn download_file(
+    url: &str,
+    target: &Path,
+    expected_sha256: Option<&str>,
+) -> Result<String> {
+    let client = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+        .build()
+        .context("Failed to build HTTP client")?;
+
+    let mut backoff = RETRY_INITIAL_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        let error = match download_attempt(&client, url, target, expected_sha256).await {
+            Ok(digest) => return Ok(digest),
+            Err(e) => e,
+        };
+        if attempt >= DOWNLOAD_ATTEMPTS || !is_retryable(&error) {
+            return Err(error);
+        }
+        warn!(
+            url = %url,
+            attempt,
+            retry_in_secs = backoff.as_secs(),
+            error = %format!("{error:#}"),
+            "Download attempt failed"
+        );
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(RETRY_MAX_BACKOFF);
+        attempt += 1;
+    }
+}
+
+/// Candidate URLs for `url`: each mirror base joined with the file name, then `url` itself
+pub fn mirror_urls(url: &str, mirrors: &[String]) -> Vec<String> {
+    let file_name = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|parsed| {
+            parsed
+                .path_segments()
+                .and_then(|mut segments| segments.next_back().map(str::to_string))
+        })
+        .filter(|name| !name.is_empty());
+
+    let mut urls: Vec<String> = match file_name {
+        Some(name) => mirrors
+            .iter()
+            .map(|mirror| format!("{}/{}", mirror.trim_end_matches('/'), name))
+            .collect(),
+        None => Vec::new(),
+    };
+    urls.push(url.to_string());
+    urls
+}
+
+/// Download from the first of `urls` that succeeds, in order.
+///
+/// Each URL gets the full retry budget of [`download_file`]; a partial file left by one is resumed
+/// by the next, and the checksum guards against mirrors serving different bytes.
+pub async fn download_from_any(
+    urls: &[String],
+    target: &Path,
+    expected_sha256: Option<&str>,
+) -> Result<String> {
+    let mut last_error = None;
+    for url in urls {
+        match download_file(url, target, expected_sha256).await {
+            Ok(digest) => return Ok(digest),
+            Err(e) => {
+                if urls.len() > 1 {
+                    warn!(url = %url, error = %format!("{e:#}"), "Download source failed, trying the next");
+                }
+                last_error = Some(e);
+            }
+        }
+    }
+    Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No download URLs configured")))
+}
+
+/// Network and server errors are worth another attempt; client errors and local failures are not
+fn is_retryable(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<reqwest::Error>()
+        .is_some_and(|e| !e.status().is_some_and(|status| status.is_client_error()))
+}
+
+async fn download_attempt(
+    client: &reqwest::Client,
+    url: &str,
+    target: &Path,
+    expected_sha256: Option<&str>,
+) -> Result<String> {
+    let partial = partial_path(target);
+    let (mut hasher, mut resume_from) = if fs::try_exists(&partial).await.unwrap_or(false) {
+        hash_partial(partial.clone()).await?
+    } else {
+        (Sha256::new(), 0)
+    };
+
+    let mut response = send_download_request(client, url, resume_from).await?;
+
+    if resume_from > 0 && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        warn!(url = %url, "Server rejected resume range, restarting download");
+        resume_from = 0;
+        hasher = Sha256::new();
+        response = send_download_request(client, url, 0).await?;
+    }
+
+    let response = response
+        .error_for_status()
+        .with_context(|| format!("Download failed for {url}"))?;
+
+    let resumed = resume_from > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
+    if resume_from > 0 && !resumed {
+        info!(url = %url, "Server does not support resume, restarting download");
+        resume_from = 0;
+        hasher = Sha256::new();
+    }
+
+    let mut file = if resumed {
+        info!(url = %url, resume_from, "Resuming partial download");
+        OpenOptions::new().append(true).open(&partial).await
+    } else {
+        fs::File::create(&partial).await
+    }
+    .with_context(|| format!("Failed to open {}", partial.display()))?;
+
+    let total = response.content_length().map(|len| len + resume_from);
+    let mut progress = DownloadProgress::new(url, total, resume_from);
+    let mut response = response;
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .with_context(|| format!("Failed to read response body from {url}"))?
+    {
+        file.write_all(&chunk)
+            .await
+            .with_context(|| format!("Failed to write {}", partial.display()))?;
+        hasher.update(&chunk);
+        progress.advance(chunk.len() as u64);
+    }
+
+    file.sync_all()
+        .await
+        .with_context(|| format!("Failed to flush {}", partial.display()))?;
+    drop(file);
+
+    let digest = format!("{:x}", hasher.finalize());
+    if let Some(expected) = expected_sha256 {
+        if !expected.eq_ignore_ascii_case(&digest) {
+            let _ = fs::remove_file(&partial).await;
+            anyhow::bail!("Checksum mismatch for {url}: expected SHA256 {expected}, got {digest}");
+        }
+    }
+
+    fs::rename(&partial, target).await.with_context(|| {
+        format!(
+            "Failed to move {} into place at {}",
+            partial.display(),
+            target.display()
+        )
+    })?;
+
+    info!(
+        url = %url,
+        path = %target.display(),
+        bytes = progress.downloaded,
+        sha256 = %digest,
+        "Download complete"
+    );
+
+    Ok(digest)
+}
+
+/// Take an exclusive advisory lock on `<target>.lock`, waiting while another process holds it.
+///
+/// Replicas sharing a cache volume use this so only one of them downloads or extracts `target`;
+/// the others wait and then find the finished file. The lock is released when the returned file
+/// is dropped, or by the OS if the process dies.
+pub async fn lock_download(target: &Path) -> Result<File> {
+    let path = sibling_path(target, ".lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .await
+        .with_context(|| format!("Failed to open lock file {}", path.display()))?
+        .into_std()
+        .await;
+
+    match file.try_lock() {
+        Ok(()) => return Ok(file),
+        Err(TryLockError::WouldBlock) => {}
+        Err(TryLockError::Error(e)) => {
+            return Err(e).with_context(|| format!("Failed to lock {}", path.display()))
+        }
+    }
+
+    info!(path = %path.display(), "Waiting for another process to finish downloading");
+    tokio::task::spawn_blocking(move || file.lock().map(|()| file))
+        .await
+        .context("Lock task panicked")?
+        .with_context(|| format!("Failed to lock {}", path.display()))
+}
+
+/// Compute the SHA256 hex digest of a file on disk.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hash_file_into(path, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+async fn send_download_request(
+    client: &reqwest::Client,
+    url: &str,
+    resume_from: u64,
+) -> Result<reqwest::Response> {
+    let mut request = client.get(url);
+    if resume_from > 0 {
+        request = request.header(header::RANGE, format!("bytes={resume_from}-"));
+    }
+
+    request
+        .send()
+        .await
+        .with_context(|| format!("Failed to request {url}"))
+}
+
+/// Hash a partial download off the async runtime, returning the hasher and the bytes it covers
+async fn hash_partial(path: PathBuf) -> Result<(Sha256, u64)> {
+    tokio::task::spawn_blocking(move || {
+        let mut hasher = Sha256::new();
+        let len = hash_file_into(&path, &mut hasher)?;
+        Ok((hasher, len))
+    })
+    .await
+    .context("Hashing task panicked")?
+}
+
+fn hash_file_into(path: &Path, hasher: &mut Sha256) -> Result<u64> {
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut total = 0u64;
+
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        total += read as u64;
+    }
+
+    Ok(total)
+}
+
+fn partial_path(target: &Path) -> PathBuf {
+    sibling_path(target, ".part")
+}
+
+fn sibling_path(target: &Path, suffix: &str) -> PathBuf {
+    let mut name = target
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    name.push(suffix);
+    target.with_file_name(name)
+}
+
+struct DownloadProgress<'a> {
+    url: &'a str,
+    total: Option<u64>,
+    downloaded: u64,
+    next_report: u64,
+}
+
+impl<'a> DownloadProgress<'a> {
+    fn new(url: &'a str, total: Option<u64>, downloaded: u64) -> Self {
+        let mut progress = Self {
+            url,
+            total,
+            downloaded,
+            next_report: 0,
+        };
+        progress.next_report = progress.next_threshold();
+        progress
+    }
+
+    fn step(&self) -> u64 {
+        match self.total {
+            Some(total) if total > 0 => (total / PROGRESS_STEPS).max(1),
+            _ => PROGRESS_BYTES_WITHOUT_LENGTH,
+        }
+    }
+
+    fn next_threshold(&self) -> u64 {
+        let step = self.step();
+        (self.downloaded / step + 1) * step
+    }
+
+    fn advance(&mut self, bytes: u64) {
+        self.downloaded += bytes;
+        if self.downloaded < self.next_report {
+            return;
+        }
+
+        match self.total {
+            Some(total) if total > 0 => info!(
+                url = %self.url,
+                downloaded = self.downloaded,
+                total,
+                "Download progress: {}%",
+                self.downloaded * 100 / total
+            ),
+            _ => info!(url = %self.url, downloaded = self.downloaded, "Download progress"),
+        }
+        self.next_report = self.next_threshold();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_path_appends_suffix() {
+        let partial = partial_path(Path::new("/tmp/models/kokoro.onnx"));
+        assert_eq!(partial, PathBuf::from("/tmp/models/kokoro.onnx.part"));
+    }
+
+    #[tokio::test]
+    async fn test_lock_download_waits_for_holder() {
+        let target = std::env::temp_dir().join(format!("kokoro-lock-{}", uuid::Uuid::new_v4()));
+        let held = lock_download(&target).await.unwrap();
+
+        let waiter = tokio::spawn({
+            let target = target.clone();
+            async move { lock_download(&target).await.map(drop) }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(!waiter.is_finished());
+
+        drop(held);
+        waiter.await.unwrap().unwrap();
+        let _ = std::fs::remove_file(sibling_path(&target, ".lock"));
+    }
+
+    #[test]
+    fn test_sha256_file() {
+        let path = std::env::temp_dir().join(format!("kokoro-sha256-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"abc").unwrap();
+        let digest = sha256_file(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(
+            digest,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_mirror_urls() {
+        let mirrors = vec![
+            "https://artifacts.internal/kokoro/".to_string(),
+            "http://10.0.0.5:8080".to_string(),
+        ];
+        assert_eq!(
+            mirror_urls(
+                "https://huggingface.co/org/repo/resolve/main/onnx/model.onnx",
+                &mirrors
+            ),
+            [
+                "https://artifacts.internal/kokoro/model.onnx",
+                "http://10.0.0.5:8080/model.onnx",
+                "https://huggingface.co/org/repo/resolve/main/onnx/model.onnx",
+            ]
+        );
+        assert_eq!(
+            mirror_urls("https://example.com/voices.bin", &[]),
+            ["https://example.com/voices.bin"]
+        );
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(!is_retryable(&anyhow::anyhow!(
+            "Checksum mismatch for http://example.invalid"
+        )));
+        let io = std::io::Error::other("disk full");
+        assert!(!is_retryable(
+            &anyhow::Error::new(io).context("Failed to write")
+        ));
+    }
+
+    #[test]
+    fn test_progress_thresholds_with_known_total() {
+        let mut progress = DownloadProgress::new("http://example.invalid", Some(100), 0);
+        assert_eq!(progress.next_report, 10);
+        progress.advance(25);
+        assert_eq!(progress.next_report, 30);
+    }
+}
@@ -0,0 +1,479 @@
+/// Dynamic range compressor applied to synthesized speech.
+///
+/// A feed-forward design with a peak envelope follower: gain above the
+/// threshold is reduced by `ratio`, then `makeup_db` brings the level back up
+/// so quiet syllables stay audible on small speakers and in noisy rooms.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Compressor {
+    pub threshold_db: f32,
+    pub ratio: f32,
+    pub attack_ms: f32,
+    pub release_ms: f32,
+    pub makeup_db: f32,
+}
+
+/// Compressor presets selectable by name, from lightest to heaviest
+pub const COMPRESSOR_PRESETS: [&str; 3] = ["gentle", "speech", "phone"];
+
+impl Compressor {
+    pub fn preset(name: &str) -> Option<Self> {
+        let (threshold_db, ratio, attack_ms, release_ms, makeup_db) =
+            match name.to_lowercase().as_str() {
+                "gentle" => (-18.0, 2.0, 10.0, 150.0, 3.0),
+                "speech" => (-24.0, 3.0, 5.0, 100.0, 6.0),
+                "phone" => (-30.0, 6.0, 2.0, 80.0, 10.0),
+                _ => return None,
+            };
+        Some(Self {
+            threshold_db,
+            ratio,
+            attack_ms,
+            release_ms,
+            makeup_db,
+        })
+    }
+
+    /// Compress `samples` in place, continuing from the `envelope` level the previous block left;
+    /// a new signal starts from 0
+    fn process(&self, samples: &mut [f32], sample_rate: u32, envelope: &mut f32) {
+        let rate = sample_rate.max(1) as f32;
+        let attack = smoothing_coefficient(self.attack_ms, rate);
+        let release = smoothing_coefficient(self.release_ms, rate);
+        let slope = 1.0 - 1.0 / self.ratio;
+
+        for sample in samples {
+            let level = sample.abs();
+            let coefficient = if level > *envelope { attack } else { release };
+            *envelope = coefficient * *envelope + (1.0 - coefficient) * level;
+
+            let envelope_db = 20.0 * envelope.max(1e-6).log10();
+            let reduction_db = (envelope_db - self.threshold_db).max(0.0) * slope;
+            let gain = db_to_gain(self.makeup_db - reduction_db);
+            *sample = (*sample * gain).clamp(-1.0, 1.0);
+        }
+    }
+}
+
+/// One-pole smoothing coefficient reaching ~63% of a step after `ms`
+fn smoothing_coefficient(ms: f32, rate: f32) -> f32 {
+    if ms <= 0.0 {
+        return 0.0;
+    }
+    (-1.0 / (ms / 1000.0 * rate)).exp()
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Filter presets selectable in a request's `effects` array
+pub const FILTER_PRESETS: [&str; 3] = ["highpass", "presence", "telephone"];
+
+/// Fixed EQ presets built from biquad sections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    /// 80 Hz high-pass that removes rumble and DC
+    HighPass,
+    /// +4 dB bell at 3 kHz that brings speech forward
+    Presence,
+    /// 300 Hz to 3.4 kHz band-pass that sounds like a phone line
+    Telephone,
+}
+
+impl Filter {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "highpass" => Some(Self::HighPass),
+            "presence" => Some(Self::Presence),
+            "telephone" => Some(Self::Telephone),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::HighPass => "highpass",
+            Self::Presence => "presence",
+            Self::Telephone => "telephone",
+        }
+    }
+
+    /// Biquad sections at rest, applied in order
+    fn sections(self, sample_rate: u32) -> Vec<Biquad> {
+        let rate = sample_rate.max(1) as f32;
+        match self {
+            Self::HighPass => vec![Biquad::high_pass(80.0, BUTTERWORTH_Q, rate)],
+            Self::Presence => vec![Biquad::peaking(3_000.0, 1.0, 4.0, rate)],
+            Self::Telephone => vec![
+                Biquad::high_pass(300.0, BUTTERWORTH_Q, rate),
+                Biquad::low_pass(3_400.0, BUTTERWORTH_Q, rate),
+            ],
+        }
+    }
+}
+
+const BUTTERWORTH_Q: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+/// Second-order IIR section with coefficients from the RBJ audio EQ cookbook
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    fn high_pass(frequency: f32, q: f32, rate: f32) -> Self {
+        let (cos, alpha) = Self::angle(frequency, q, rate);
+        Self::normalized(
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    fn low_pass(frequency: f32, q: f32, rate: f32) -> Self {
+        let (cos, alpha) = Self::angle(frequency, q, rate);
+        Self::normalized(
+            [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    fn peaking(frequency: f32, q: f32, gain_db: f32, rate: f32) -> Self {
+        let (cos, alpha) = Self::angle(frequency, q, rate);
+        let amplitude = 10f32.powf(gain_db / 40.0);
+        Self::normalized(
+            [1.0 + alpha * amplitude, -2.0 * cos, 1.0 - alpha * amplitude],
+            [1.0 + alpha / amplitude, -2.0 * cos, 1.0 - alpha / amplitude],
+        )
+    }
+
+    /// Cosine of the normalized frequency and the bandwidth term, clamped below Nyquist
+    fn angle(frequency: f32, q: f32, rate: f32) -> (f32, f32) {
+        let omega = std::f32::consts::TAU * frequency.min(rate * 0.45) / rate;
+        (omega.cos(), omega.sin() / (2.0 * q))
+    }
+
+    fn normalized(b: [f32; 3], a: [f32; 3]) -> Self {
+        Self {
+            b0: b[0] / a[0],
+            b1: b[1] / a[0],
+            b2: b[2] / a[0],
+            a1: a[1] / a[0],
+            a2: a[2] / a[0],
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    /// Transposed direct form II
+    fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples {
+            let input = *sample;
+            let output = self.b0 * input + self.z1;
+            self.z1 = self.b1 * input - self.a1 * output + self.z2;
+            self.z2 = self.b2 * input - self.a2 * output;
+            *sample = output.clamp(-1.0, 1.0);
+        }
+    }
+}
+
+/// Length of the crossfaded read window of [`PitchShifter`]: long enough to span a few pitch
+/// periods of speech, short enough that the added delay goes unnoticed
+const PITCH_WINDOW_MS: f32 = 30.0;
+
+/// Shifts the pitch by a number of semitones without changing the length of the audio.
+///
+/// Two read taps sweep through a short delay line at the shifted rate, each faded in and out
+/// so one is always mid-window while the other wraps around. This is a cheap time-domain
+/// shifter: it moves formants along with the pitch and delays the audio by about half a
+/// window, which is fine for the few semitones the delivery styles use. The end of each block
+/// stays in the delay line, so the taps at the start of the next block read the audio before it
+/// instead of silence.
+#[derive(Debug, Clone)]
+struct PitchShifter {
+    window: f32,
+    step: f32,
+    phase: f32,
+    /// Trailing input of the previous blocks, as far back as a tap reads
+    history: Vec<f32>,
+}
+
+impl PitchShifter {
+    fn new(semitones: f32, sample_rate: u32) -> Self {
+        let window = (PITCH_WINDOW_MS / 1000.0 * sample_rate as f32).max(2.0);
+        let ratio = 2f32.powf(semitones / 12.0);
+        Self {
+            window,
+            // The delay changes by 1 - ratio samples per sample, so reads advance at `ratio`
+            step: (1.0 - ratio) / window,
+            phase: 0.0,
+            history: Vec::new(),
+        }
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        let offset = self.history.len();
+        let mut input = std::mem::take(&mut self.history);
+        input.extend_from_slice(samples);
+        for (index, sample) in samples.iter_mut().enumerate() {
+            let mut output = 0.0;
+            for tap_offset in [0.0, 0.5] {
+                let tap = (self.phase + tap_offset).rem_euclid(1.0);
+                let position = (offset + index) as f32 - tap * self.window;
+                // sin² windows half a cycle apart sum to one
+                let gain = (std::f32::consts::PI * tap).sin().powi(2);
+                output += gain * read_interpolated(&input, position);
+            }
+            *sample = output.clamp(-1.0, 1.0);
+            self.phase = (self.phase + self.step).rem_euclid(1.0);
+        }
+
+        let keep = (self.window.ceil() as usize + 1).min(input.len());
+        input.drain(..input.len() - keep);
+        self.history = input;
+    }
+}
+
+/// Linearly interpolated sample at a fractional `position`, silent before the start
+fn read_interpolated(samples: &[f32], position: f32) -> f32 {
+    if position < 0.0 {
+        return 0.0;
+    }
+    let index = position as usize;
+    let fraction = position - index as f32;
+    let current = samples.get(index).copied().unwrap_or(0.0);
+    let next = samples.get(index + 1).copied().unwrap_or(current);
+    current + (next - current) * fraction
+}
+
+/// Post-processing applied to synthesized samples before resampling and encoding
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Effects {
+    /// Pitch shift in semitones, applied before the filters
+    pub pitch_semitones: Option<f32>,
+    /// Filters from the `effects` array, applied in order before compression
+    pub filters: Vec<Filter>,
+    /// Name of the compressor preset, kept for cache keys and logs
+    pub compressor: Option<(String, Compressor)>,
+}
+
+impl Effects {
+    pub fn apply(&self, samples: &mut [f32], sample_rate: u32) {
+        self.start(sample_rate).process(samples);
+    }
+
+    /// Chain state at rest, for running the effects over a stream one block at a time
+    pub fn start(&self, sample_rate: u32) -> EffectsState {
+        EffectsState {
+            sample_rate,
+            pitch: self
+                .pitch_semitones
+                .map(|semitones| PitchShifter::new(semitones, sample_rate)),
+            sections: self
+                .filters
+                .iter()
+                .flat_map(|filter| filter.sections(sample_rate))
+                .collect(),
+            compressor: self
+                .compressor
+                .as_ref()
+                .map(|(_, compressor)| (*compressor, 0.0)),
+        }
+    }
+
+    /// Stable description of the chain, distinguishing cached clips with different effects
+    pub fn describe(&self) -> String {
+        self.pitch_semitones
+            .iter()
+            .map(|semitones| format!("pitch={}", semitones))
+            .chain(self.filters.iter().map(|filter| filter.name().to_string()))
+            .chain(
+                self.compressor
+                    .iter()
+                    .map(|(name, _)| format!("compressor={}", name)),
+            )
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// An [`Effects`] chain partway through a stream.
+///
+/// Filter memory, the compressor envelope and the pitch shifter's delay line carry over from one
+/// block to the next, so a stream processed chunk by chunk sounds like the whole clip processed at
+/// once, without clicks or gain jumps where the chunks meet. Blocks must be passed in order.
+#[derive(Debug, Clone)]
+pub struct EffectsState {
+    sample_rate: u32,
+    pitch: Option<PitchShifter>,
+    sections: Vec<Biquad>,
+    /// Compressor and its envelope level
+    compressor: Option<(Compressor, f32)>,
+}
+
+impl EffectsState {
+    /// Run the next block of the stream through the chain in place
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if let Some(pitch) = &mut self.pitch {
+            pitch.process(samples);
+        }
+        for section in &mut self.sections {
+            section.process(samples);
+        }
+        if let Some((compressor, envelope)) = &mut self.compressor {
+            compressor.process(samples, self.sample_rate, envelope);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples
+            .iter()
+            .fold(0.0, |peak, sample| peak.max(sample.abs()))
+    }
+
+    #[test]
+    fn test_compressor_presets() {
+        for name in COMPRESSOR_PRESETS {
+            let compressor = Compressor::preset(name).unwrap();
+            assert!(compressor.ratio > 1.0);
+        }
+        assert_eq!(Compressor::preset("SPEECH"), Compressor::preset("speech"));
+        assert!(Compressor::preset("brickwall").is_none());
+    }
+
+    #[test]
+    fn test_compressor_narrows_dynamic_range() {
+        let compressor = Compressor::preset("speech").unwrap();
+        let mut loud = vec![0.9f32; 24_000];
+        let mut quiet = vec![0.01f32; 24_000];
+        compressor.process(&mut loud, 24_000, &mut 0.0);
+        compressor.process(&mut quiet, 24_000, &mut 0.0);
+
+        // Loud passages come down, quiet ones get makeup gain
+        assert!(peak(&loud[12_000..]) < 0.9);
+        assert!(peak(&quiet[12_000..]) > 0.01);
+        assert!(peak(&loud) <= 1.0);
+
+        let mut silence = vec![0.0f32; 100];
+        compressor.process(&mut silence, 24_000, &mut 0.0);
+        assert!(silence.iter().all(|&sample| sample == 0.0));
+    }
+
+    /// RMS level of a sine at `frequency` after `filter`, skipping the settling time
+    fn filtered_level(filter: Filter, frequency: f32) -> f32 {
+        let mut samples: Vec<f32> = (0..24_000)
+            .map(|i| 0.5 * (std::f32::consts::TAU * frequency * i as f32 / 24_000.0).sin())
+            .collect();
+        for mut section in filter.sections(24_000) {
+            section.process(&mut samples);
+        }
+        let tail = &samples[4_800..];
+        (tail.iter().map(|sample| sample * sample).sum::<f32>() / tail.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_filters() {
+        let unfiltered = 0.5 / std::f32::consts::SQRT_2;
+
+        assert!(filtered_level(Filter::HighPass, 30.0) < unfiltered * 0.2);
+        assert!(filtered_level(Filter::HighPass, 1_000.0) > unfiltered * 0.95);
+
+        assert!(filtered_level(Filter::Presence, 3_000.0) > unfiltered * 1.4);
+        assert!(filtered_level(Filter::Presence, 200.0) < unfiltered * 1.05);
+
+        assert!(filtered_level(Filter::Telephone, 100.0) < unfiltered * 0.2);
+        assert!(filtered_level(Filter::Telephone, 1_000.0) > unfiltered * 0.9);
+        assert!(filtered_level(Filter::Telephone, 8_000.0) < unfiltered * 0.2);
+
+        for name in FILTER_PRESETS {
+            assert_eq!(Filter::from_name(name).unwrap().name(), name);
+        }
+        assert!(Filter::from_name("reverb").is_none());
+    }
+
+    #[test]
+    fn test_effects_describe() {
+        assert_eq!(Effects::default().describe(), "");
+
+        let effects = Effects {
+            pitch_semitones: None,
+            filters: vec![Filter::HighPass, Filter::Presence],
+            compressor: Some(("phone".to_string(), Compressor::preset("phone").unwrap())),
+        };
+        assert_eq!(effects.describe(), "highpass,presence,compressor=phone");
+
+        let shifted = Effects {
+            pitch_semitones: Some(-1.5),
+            ..Effects::default()
+        };
+        assert_eq!(shifted.describe(), "pitch=-1.5");
+    }
+
+    #[test]
+    fn test_effects_state_continues_across_blocks() {
+        let effects = Effects {
+            pitch_semitones: Some(3.0),
+            filters: vec![Filter::Telephone, Filter::Presence],
+            compressor: Some(("speech".to_string(), Compressor::preset("speech").unwrap())),
+        };
+        let signal: Vec<f32> = (0..24_000)
+            .map(|i| 0.6 * (std::f32::consts::TAU * 440.0 * i as f32 / 24_000.0).sin())
+            .collect();
+        let mut whole = signal.clone();
+        effects.apply(&mut whole, 24_000);
+
+        let mut state = effects.start(24_000);
+        let mut blocks = Vec::new();
+        for block in signal.chunks(1_000) {
+            let mut block = block.to_vec();
+            state.process(&mut block);
+            blocks.extend(block);
+        }
+
+        assert_eq!(blocks.len(), whole.len());
+        let largest_difference = whole
+            .iter()
+            .zip(&blocks)
+            .fold(0.0f32, |largest, (a, b)| largest.max((a - b).abs()));
+        assert!(largest_difference < 1e-3, "{}", largest_difference);
+    }
+
+    /// Zero crossings per second of `samples`, which tracks the frequency of a sine
+    fn crossing_rate(samples: &[f32]) -> f32 {
+        let crossings = samples
+            .windows(2)
+            .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+            .count();
+        crossings as f32 / 2.0 / (samples.len() as f32 / 24_000.0)
+    }
+
+    #[test]
+    fn test_pitch_shift_keeps_length() {
+        let sine = |frequency: f32| -> Vec<f32> {
+            (0..24_000)
+                .map(|i| 0.5 * (std::f32::consts::TAU * frequency * i as f32 / 24_000.0).sin())
+                .collect()
+        };
+        let mut up = sine(200.0);
+        PitchShifter::new(12.0, 24_000).process(&mut up);
+        assert_eq!(up.len(), 24_000);
+        let frequency = crossing_rate(&up[2_400..]);
+        assert!((frequency - 400.0).abs() < 20.0, "{}", frequency);
+
+        let mut down = sine(200.0);
+        PitchShifter::new(-2.0, 24_000).process(&mut down);
+        let frequency = crossing_rate(&down[2_400..]);
+        assert!((frequency - 178.2).abs() < 10.0, "{}", frequency);
+        assert!(peak(&down) <= 1.0);
+    }
+}
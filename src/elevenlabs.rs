@@ -0,0 +1,187 @@
+use crate::{
+    api::{
+        synthesize_clip, validate_speech_request, AppState, SpeechRequest, VOICE_SUBSTITUTED_HEADER,
+    },
+    error::{ApiResult, AppError},
+    extract::ApiJson,
+    validation::{VoiceVendor, ELEVENLABS_VOICE_ALIASES},
+};
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::debug;
+use uuid::Uuid;
+
+/// Names ElevenLabs lists its premade voices under, by voice ID
+const PREMADE_VOICE_NAMES: [(&str, &str); 16] = [
+    ("21m00Tcm4TlvDq8ikWAM", "Rachel"),
+    ("EXAVITQu4vr4xnSDxMaL", "Sarah"),
+    ("AZnzlk1XvdvUeBnXmlld", "Domi"),
+    ("MF3mGyEYCl7XYWbV9V6O", "Elli"),
+    ("piTKgcLEGmPE4e6mEKli", "Nicole"),
+    ("pNInz6obpgDQGcFmaJgB", "Adam"),
+    ("ErXwobaYiR019PkySvjV", "Antoni"),
+    ("TxGEqnHWrfWFTfGW9XjX", "Josh"),
+    ("VR6AewLTigWG4xSOukaG", "Arnold"),
+    ("yoZ06aMxZJJ28mfd3POQ", "Sam"),
+    ("TX3LPaxmHKxFdv7VOQHJ", "Liam"),
+    ("cjVigY5qzO86Huf0OWal", "Eric"),
+    ("JBFqnCBsd6RMkjVDRZzb", "George"),
+    ("onwK4e9ZLuTAKqWW03F9", "Daniel"),
+    ("pFZP5JQG7iQjIQuC4Bku", "Lily"),
+    ("Xb7hH8MSUJpSbSDYk0k2", "Alice"),
+];
+
+/// Output format used by ElevenLabs when `output_format` is omitted
+const DEFAULT_OUTPUT_FORMAT: &str = "mp3_44100_128";
+
+/// Request body for POST /v1/text-to-speech/{voice_id}
+#[derive(Debug, Deserialize)]
+pub struct TextToSpeechRequest {
+    pub text: String,
+    /// Accepted for compatibility; every model ID maps to Kokoro
+    #[serde(default)]
+    pub model_id: Option<String>,
+    #[serde(default)]
+    pub voice_settings: Option<VoiceSettings>,
+}
+
+/// Only `speed` affects Kokoro; the other settings are accepted and ignored
+#[derive(Debug, Default, Deserialize)]
+pub struct VoiceSettings {
+    #[serde(default)]
+    pub speed: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TextToSpeechQuery {
+    #[serde(default)]
+    pub output_format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ElevenLabsVoice {
+    voice_id: String,
+    name: String,
+    category: &'static str,
+    labels: BTreeMap<&'static str, String>,
+    preview_url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct VoicesResponse {
+    voices: Vec<ElevenLabsVoice>,
+}
+
+/// Map an ElevenLabs `output_format` such as `mp3_44100_128` onto a response format
+fn response_format(output_format: &str) -> ApiResult<&'static str> {
+    match output_format {
+        format if format.starts_with("mp3_") => Ok("mp3"),
+        format if format.starts_with("opus_") => Ok("opus"),
+        // Kokoro renders at 24 kHz, so raw PCM is only offered at that rate
+        "pcm_24000" => Ok("pcm"),
+        other => Err(AppError::invalid_request(format!(
+            "output_format '{}' not supported. Supported formats: mp3_*, opus_*, pcm_24000",
+            other
+        ))),
+    }
+}
+
+/// ElevenLabs-compatible speech synthesis
+///
+/// Also serves `/stream`, which returns the complete clip in one response.
+pub async fn text_to_speech_handler(
+    State(state): State<AppState>,
+    Path(voice_id): Path<String>,
+    Query(query): Query<TextToSpeechQuery>,
+    ApiJson(req): ApiJson<TextToSpeechRequest>,
+) -> ApiResult<Response> {
+    let request_id = Uuid::new_v4().to_string();
+    let output_format = query
+        .output_format
+        .as_deref()
+        .unwrap_or(DEFAULT_OUTPUT_FORMAT);
+
+    debug!(
+        request_id = %request_id,
+        voice_id = %voice_id,
+        model_id = ?req.model_id,
+        output_format = %output_format,
+        "Received ElevenLabs speech request"
+    );
+
+    let params = validate_speech_request(
+        &state,
+        SpeechRequest {
+            model: "kokoro".to_string(),
+            input: req.text,
+            voice: Some(VoiceVendor::ElevenLabs.kokoro_voice(&voice_id).to_string()),
+            response_format: Some(response_format(output_format)?.to_string()),
+            speed: req.voice_settings.and_then(|settings| settings.speed),
+            ..Default::default()
+        },
+    )?;
+    let (audio, cache_status) = synthesize_clip(&state, &params, &request_id).await?;
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, audio.content_type)
+        .header("X-Request-Id", request_id);
+    if let Some(cache_status) = cache_status {
+        response = response.header("X-Cache", cache_status);
+    }
+    if params.voice_substituted {
+        response = response.header(VOICE_SUBSTITUTED_HEADER, &params.voice);
+    }
+
+    response
+        .body(Body::from(audio.bytes))
+        .map_err(|_| AppError::Internal)
+}
+
+/// ElevenLabs-compatible voice list: the premade aliases followed by every permitted Kokoro voice
+pub async fn list_voices_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let voices = state.backend.voices();
+    let aliases = ELEVENLABS_VOICE_ALIASES
+        .iter()
+        .zip(PREMADE_VOICE_NAMES)
+        .filter(|((_, kokoro), _)| voices.iter().any(|voice| voice.id == *kokoro))
+        .map(|((id, voice), (_, name))| ElevenLabsVoice {
+            voice_id: id.to_string(),
+            name: name.to_string(),
+            category: "premade",
+            labels: BTreeMap::from([("kokoro_voice", voice.to_string())]),
+            preview_url: None,
+        });
+    let kokoro = voices.iter().map(|voice| ElevenLabsVoice {
+        voice_id: voice.id.clone(),
+        name: voice.name.clone(),
+        category: "premade",
+        labels: BTreeMap::from([("kokoro_voice", voice.id.clone())]),
+        preview_url: voice.preview_url.clone(),
+    });
+
+    Json(VoicesResponse {
+        voices: aliases.chain(kokoro).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_format() {
+        assert_eq!(response_format("mp3_44100_128").unwrap(), "mp3");
+        assert_eq!(response_format("opus_48000_64").unwrap(), "opus");
+        assert_eq!(response_format("pcm_24000").unwrap(), "pcm");
+        assert!(response_format("pcm_16000").is_err());
+        assert!(response_format("ulaw_8000").is_err());
+    }
+}
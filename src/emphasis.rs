@@ -0,0 +1,209 @@
+use crate::{
+    backend::{AudioData, KokoroBackend},
+    language::Language,
+    presets::silence_samples,
+    streaming::{trim_to_zero_crossings, ZERO_CROSSING_WINDOW_MS},
+};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// How strongly a fragment of text is stressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emphasis {
+    None,
+    /// `*word*`
+    Moderate,
+    /// `**word**`
+    Strong,
+}
+
+impl Emphasis {
+    /// Speed multiplier and gain the fragment is read with
+    fn adjustment(self) -> (f32, f32) {
+        match self {
+            Emphasis::None => (1.0, 1.0),
+            Emphasis::Moderate => (0.92, 1.12),
+            Emphasis::Strong => (0.85, 1.25),
+        }
+    }
+}
+
+/// Run of text read with one emphasis
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fragment {
+    pub text: String,
+    pub emphasis: Emphasis,
+}
+
+/// Split `text` at `*word*` and `**word**` markers into fragments, without the markers.
+///
+/// A marker opens only before a non-space and closes only after one, so `2 * 3` is left alone;
+/// an opening marker with no partner is dropped. Fragments with nothing to pronounce, such as
+/// the period after `*word*.`, join their neighbour so the engine is never asked to read
+/// punctuation alone.
+pub fn fragments(text: &str) -> Vec<Fragment> {
+    let mut raw: Vec<(String, Emphasis)> = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('*') {
+        let run = rest[start..].chars().take_while(|&c| c == '*').count();
+        let marker = if run >= 2 { "**" } else { "*" };
+        let after = &rest[start + run..];
+        plain.push_str(&rest[..start]);
+        if after.starts_with(char::is_whitespace) || after.is_empty() {
+            plain.push_str(&rest[start..start + run]);
+            rest = after;
+            continue;
+        }
+        let close = after
+            .match_indices(marker)
+            .map(|(index, _)| index)
+            .find(|&index| index > 0 && !after[..index].ends_with(char::is_whitespace));
+        match close {
+            Some(close) => {
+                raw.push((std::mem::take(&mut plain), Emphasis::None));
+                let emphasis = if marker == "**" {
+                    Emphasis::Strong
+                } else {
+                    Emphasis::Moderate
+                };
+                raw.push((after[..close].replace('*', ""), emphasis));
+                rest = after[close + marker.len()..].trim_start_matches('*');
+            }
+            None => rest = after,
+        }
+    }
+    plain.push_str(rest);
+    raw.push((plain, Emphasis::None));
+
+    let mut fragments: Vec<Fragment> = Vec::new();
+    let mut leading = String::new();
+    for (text, emphasis) in raw {
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        if !text.chars().any(char::is_alphanumeric) {
+            match fragments.last_mut() {
+                Some(last) => last.text.push_str(text),
+                None => leading.push_str(text),
+            }
+            continue;
+        }
+        fragments.push(Fragment {
+            text: format!("{}{}", std::mem::take(&mut leading), text),
+            emphasis,
+        });
+    }
+    fragments
+}
+
+/// Synthesize `text`, reading emphasized fragments slower and louder. Text with no emphasis is
+/// synthesized in one call, as it would be without markup.
+pub async fn synthesize(
+    backend: &Arc<KokoroBackend>,
+    text: &str,
+    voice: &str,
+    language: Language,
+    speed: f32,
+    initial_silence: Option<usize>,
+) -> Result<AudioData> {
+    let fragments = fragments(text);
+    if fragments
+        .iter()
+        .all(|fragment| fragment.emphasis == Emphasis::None)
+    {
+        let plain = match fragments.as_slice() {
+            [] => text.to_string(),
+            _ => fragments
+                .iter()
+                .map(|fragment| fragment.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        return backend
+            .synthesize(&plain, voice, language, speed, initial_silence)
+            .await;
+    }
+
+    let mut joined = AudioData {
+        samples: Vec::new(),
+        sample_rate: backend.sample_rate(),
+    };
+    for (index, fragment) in fragments.iter().enumerate() {
+        let (rate, gain) = fragment.emphasis.adjustment();
+        let mut audio = backend
+            .synthesize(
+                &fragment.text,
+                voice,
+                language,
+                (speed * rate).clamp(0.25, 4.0),
+                initial_silence.filter(|_| index == 0),
+            )
+            .await?;
+        if gain != 1.0 {
+            for sample in &mut audio.samples {
+                *sample = (*sample * gain).clamp(-1.0, 1.0);
+            }
+        }
+        let window = silence_samples(ZERO_CROSSING_WINDOW_MS, audio.sample_rate);
+        trim_to_zero_crossings(&mut audio.samples, window);
+        joined.sample_rate = audio.sample_rate;
+        joined.samples.extend_from_slice(&audio.samples);
+    }
+    Ok(joined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fragment(text: &str, emphasis: Emphasis) -> Fragment {
+        Fragment {
+            text: text.to_string(),
+            emphasis,
+        }
+    }
+
+    #[test]
+    fn test_fragments_split_at_emphasis() {
+        assert_eq!(
+            fragments("I *really* mean it, **never** again."),
+            vec![
+                fragment("I", Emphasis::None),
+                fragment("really", Emphasis::Moderate),
+                fragment("mean it,", Emphasis::None),
+                fragment("never", Emphasis::Strong),
+                fragment("again.", Emphasis::None),
+            ]
+        );
+        // Punctuation joins the emphasized word before it
+        assert_eq!(
+            fragments("Stop *now*!"),
+            vec![
+                fragment("Stop", Emphasis::None),
+                fragment("now!", Emphasis::Moderate),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fragments_leave_stray_asterisks() {
+        assert_eq!(
+            fragments("2 * 3 is six"),
+            vec![fragment("2 * 3 is six", Emphasis::None)]
+        );
+        assert_eq!(
+            fragments("An *unclosed marker"),
+            vec![fragment("An unclosed marker", Emphasis::None)]
+        );
+        assert_eq!(
+            fragments("**Note:** read on"),
+            vec![
+                fragment("Note:", Emphasis::Strong),
+                fragment("read on", Emphasis::None),
+            ]
+        );
+        assert!(fragments("**").is_empty());
+    }
+}
@@ -5,14 +5,15 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use tracing::error;
+use utoipa::ToSchema;
 
 /// OpenAI-compatible error response
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct OpenAIError {
     pub error: ErrorDetails,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ErrorDetails {
     pub message: String,
     #[serde(rename = "type")]
@@ -31,15 +32,67 @@ pub enum AppError {
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
 
+    #[error("Invalid JSON body: {message}")]
+    InvalidJson {
+        message: String,
+        param: Option<String>,
+    },
+
+    #[error("Input exceeds {0} characters")]
+    InputTooLong(usize),
+
+    /// The estimated size of a non-streamed response is over the configured limit
+    #[error("Estimated response of {estimated} bytes exceeds the {limit} byte limit")]
+    OutputTooLarge { estimated: u64, limit: u64 },
+
+    /// Synthesis was stopped because the audio ran past the request's duration cap
+    #[error("Audio exceeded the {0} second duration limit")]
+    OutputLimitExceeded(u32),
+
+    #[error("Invalid speed: {0}")]
+    InvalidSpeed(String),
+
     #[error("Model not found: {0}")]
     ModelNotFound(String),
 
+    /// Retrieval of a model ID that does not exist, as opposed to a request naming one
+    #[error("Model does not exist: {0}")]
+    ModelDoesNotExist(String),
+
     #[error("Voice not found: {0}")]
     VoiceNotFound(String),
 
+    #[error("Preset not found: {0}")]
+    PresetNotFound(String),
+
+    #[error("API key not found: {0}")]
+    KeyNotFound(String),
+
+    #[error("Job not found: {0}")]
+    JobNotFound(String),
+
+    /// A request ID the debug tap no longer holds, or never captured
+    #[error("Capture not found: {0}")]
+    CaptureNotFound(String),
+
     #[error("Invalid response format: {0}")]
     InvalidResponseFormat(String),
 
+    #[error("Invalid profile: {0}")]
+    InvalidProfile(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
+    #[error("Overloaded: {0}")]
+    Overloaded(String),
+
+    #[error("Timeout: {0}")]
+    Timeout(String),
+
     #[error("Backend error: {0}")]
     Backend(String),
 
@@ -52,41 +105,179 @@ impl AppError {
         Self::InvalidRequest(msg.into())
     }
 
+    pub fn invalid_json(message: impl Into<String>, param: Option<String>) -> Self {
+        Self::InvalidJson {
+            message: message.into(),
+            param,
+        }
+    }
+
     pub fn unsupported_format(format: impl Into<String>) -> Self {
         Self::InvalidResponseFormat(format.into())
     }
 
+    pub fn unsupported_profile(profile: impl Into<String>) -> Self {
+        Self::InvalidProfile(profile.into())
+    }
+
     pub fn voice_not_found(voice: impl Into<String>) -> Self {
         Self::VoiceNotFound(voice.into())
     }
 
+    pub fn preset_not_found(preset: impl Into<String>) -> Self {
+        Self::PresetNotFound(preset.into())
+    }
+
     pub fn model_not_found(model: impl Into<String>) -> Self {
         Self::ModelNotFound(model.into())
     }
+
+    pub fn model_does_not_exist(model: impl Into<String>) -> Self {
+        Self::ModelDoesNotExist(model.into())
+    }
+
+    pub fn key_not_found(id: impl Into<String>) -> Self {
+        Self::KeyNotFound(id.into())
+    }
+
+    pub fn job_not_found(id: impl Into<String>) -> Self {
+        Self::JobNotFound(id.into())
+    }
+
+    pub fn capture_not_found(id: impl Into<String>) -> Self {
+        Self::CaptureNotFound(id.into())
+    }
+
+    pub fn conflict(msg: impl Into<String>) -> Self {
+        Self::Conflict(msg.into())
+    }
+
+    pub fn service_unavailable(msg: impl Into<String>) -> Self {
+        Self::ServiceUnavailable(msg.into())
+    }
+
+    pub fn overloaded(msg: impl Into<String>) -> Self {
+        Self::Overloaded(msg.into())
+    }
+
+    pub fn timeout(msg: impl Into<String>) -> Self {
+        Self::Timeout(msg.into())
+    }
+
+    /// Stable machine-readable code reported in the `code` field of the error body.
+    ///
+    /// Clients branch on these, so existing values must not change.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Unauthorized => "invalid_api_key",
+            AppError::InvalidRequest(_) => "invalid_request",
+            AppError::InvalidJson { .. } => "invalid_json",
+            AppError::InputTooLong(_) => "input_too_long",
+            AppError::OutputTooLarge { .. } => "output_too_large",
+            AppError::OutputLimitExceeded(_) => "output_limit_exceeded",
+            AppError::InvalidSpeed(_) => "invalid_speed",
+            AppError::ModelNotFound(_) | AppError::ModelDoesNotExist(_) => "model_not_found",
+            AppError::VoiceNotFound(_) => "voice_not_found",
+            AppError::PresetNotFound(_) => "preset_not_found",
+            AppError::KeyNotFound(_) => "key_not_found",
+            AppError::JobNotFound(_) => "job_not_found",
+            AppError::CaptureNotFound(_) => "capture_not_found",
+            AppError::InvalidResponseFormat(_) => "unsupported_response_format",
+            AppError::InvalidProfile(_) => "unsupported_profile",
+            AppError::Conflict(_) => "conflict",
+            AppError::ServiceUnavailable(_) => "backend_unavailable",
+            AppError::Overloaded(_) => "queue_full",
+            AppError::Timeout(_) => "synthesis_timeout",
+            AppError::Backend(_) => "synthesis_failed",
+            AppError::Internal => "internal_error",
+        }
+    }
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status, error_type, message, param, code) = match &self {
+        let (status, error_type, message, param) = match &self {
             AppError::Unauthorized => (
                 StatusCode::UNAUTHORIZED,
                 "authentication_error",
                 self.to_string(),
                 None,
-                None,
             ),
             AppError::InvalidRequest(msg) => (
                 StatusCode::BAD_REQUEST,
                 "invalid_request_error",
                 msg.clone(),
                 None,
-                None,
+            ),
+            AppError::InvalidJson { message, param } => (
+                StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                message.clone(),
+                param.clone(),
+            ),
+            AppError::InputTooLong(max_chars) => (
+                StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                format!(
+                    "Input text exceeds maximum length of {} characters",
+                    max_chars
+                ),
+                Some("input".to_string()),
+            ),
+            AppError::OutputTooLarge { estimated, limit } => (
+                StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                format!(
+                    "Estimated audio of {} bytes exceeds the {} byte response limit; \
+                     set \"stream\": true or split the input into shorter requests",
+                    estimated, limit
+                ),
+                Some("input".to_string()),
+            ),
+            AppError::OutputLimitExceeded(limit) => (
+                StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                format!(
+                    "Audio exceeded the {} second duration limit; \
+                     split the input into shorter requests",
+                    limit
+                ),
+                Some("input".to_string()),
+            ),
+            AppError::InvalidSpeed(msg) => (
+                StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                msg.clone(),
+                Some("speed".to_string()),
             ),
             AppError::ModelNotFound(model) => (
                 StatusCode::BAD_REQUEST,
                 "invalid_request_error",
                 format!("Model '{}' not found", model),
                 Some("model".to_string()),
+            ),
+            AppError::ModelDoesNotExist(model) => (
+                StatusCode::NOT_FOUND,
+                "invalid_request_error",
+                format!("The model '{}' does not exist", model),
+                Some("model".to_string()),
+            ),
+            AppError::KeyNotFound(id) => (
+                StatusCode::NOT_FOUND,
+                "invalid_request_error",
+                format!("API key '{}' not found", id),
+                None,
+            ),
+            AppError::JobNotFound(id) => (
+                StatusCode::NOT_FOUND,
+                "invalid_request_error",
+                format!("Job '{}' not found", id),
+                None,
+            ),
+            AppError::CaptureNotFound(id) => (
+                StatusCode::NOT_FOUND,
+                "invalid_request_error",
+                format!("No captured synthesis for request '{}'", id),
                 None,
             ),
             AppError::VoiceNotFound(voice) => (
@@ -94,7 +285,12 @@ impl IntoResponse for AppError {
                 "invalid_request_error",
                 format!("Voice '{}' not found", voice),
                 Some("voice".to_string()),
-                None,
+            ),
+            AppError::PresetNotFound(preset) => (
+                StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                format!("Preset '{}' not found", preset),
+                Some("preset".to_string()),
             ),
             AppError::InvalidResponseFormat(format) => (
                 StatusCode::BAD_REQUEST,
@@ -104,16 +300,42 @@ impl IntoResponse for AppError {
                     format
                 ),
                 Some("response_format".to_string()),
+            ),
+            AppError::InvalidProfile(profile) => (
+                StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                format!(
+                    "Profile '{}' not supported. Supported profiles: discord",
+                    profile
+                ),
+                Some("profile".to_string()),
+            ),
+            AppError::Conflict(msg) => (
+                StatusCode::CONFLICT,
+                "invalid_request_error",
+                msg.clone(),
                 None,
             ),
+            AppError::ServiceUnavailable(msg) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "api_error",
+                msg.clone(),
+                None,
+            ),
+            AppError::Overloaded(msg) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "api_error",
+                msg.clone(),
+                None,
+            ),
+            AppError::Timeout(msg) => (StatusCode::GATEWAY_TIMEOUT, "api_error", msg.clone(), None),
             AppError::Backend(msg) => {
-                error!("Backend error: {}", msg);
+                error!("Backend error: {}", crate::redact::message(msg));
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     "api_error",
                     "Backend processing error".to_string(),
                     None,
-                    None,
                 )
             }
             AppError::Internal => {
@@ -123,7 +345,6 @@ impl IntoResponse for AppError {
                     "api_error",
                     "Internal server error".to_string(),
                     None,
-                    None,
                 )
             }
         };
@@ -133,7 +354,7 @@ impl IntoResponse for AppError {
                 message,
                 error_type: error_type.to_string(),
                 param,
-                code,
+                code: Some(self.code().to_string()),
             },
         });
 
@@ -143,3 +364,77 @@ impl IntoResponse for AppError {
 
 /// Type alias for API results
 pub type ApiResult<T> = Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes_and_status() {
+        let cases = [
+            (
+                AppError::InputTooLong(10),
+                StatusCode::BAD_REQUEST,
+                "input_too_long",
+            ),
+            (
+                AppError::OutputTooLarge {
+                    estimated: 2,
+                    limit: 1,
+                },
+                StatusCode::BAD_REQUEST,
+                "output_too_large",
+            ),
+            (
+                AppError::OutputLimitExceeded(60),
+                StatusCode::BAD_REQUEST,
+                "output_limit_exceeded",
+            ),
+            (
+                AppError::voice_not_found("x"),
+                StatusCode::BAD_REQUEST,
+                "voice_not_found",
+            ),
+            (
+                AppError::preset_not_found("x"),
+                StatusCode::BAD_REQUEST,
+                "preset_not_found",
+            ),
+            (
+                AppError::model_does_not_exist("x"),
+                StatusCode::NOT_FOUND,
+                "model_not_found",
+            ),
+            (
+                AppError::key_not_found("x"),
+                StatusCode::NOT_FOUND,
+                "key_not_found",
+            ),
+            (
+                AppError::job_not_found("x"),
+                StatusCode::NOT_FOUND,
+                "job_not_found",
+            ),
+            (
+                AppError::capture_not_found("x"),
+                StatusCode::NOT_FOUND,
+                "capture_not_found",
+            ),
+            (
+                AppError::overloaded("busy"),
+                StatusCode::SERVICE_UNAVAILABLE,
+                "queue_full",
+            ),
+            (
+                AppError::timeout("slow"),
+                StatusCode::GATEWAY_TIMEOUT,
+                "synthesis_timeout",
+            ),
+        ];
+
+        for (error, status, code) in cases {
+            assert_eq!(error.code(), code);
+            assert_eq!(error.into_response().status(), status);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::sync::{PoisonError, RwLock};
+
+/// Characters spoken per second at speed 1.0, roughly Kokoro's pace for English
+const DEFAULT_CHARS_PER_SECOND: f64 = 15.0;
+/// Weight of each new observation in a voice's running rate
+const SMOOTHING: f64 = 0.2;
+/// Shorter clips are dominated by the model's edge padding and would skew the rate
+const MIN_OBSERVED_CHARS: usize = 20;
+
+/// Speaking-rate estimator used to predict audio length before synthesis.
+///
+/// Every voice starts at a fixed characters-per-second rate; each completed
+/// synthesis nudges that voice's rate towards what the model actually produced,
+/// so estimates track the voices a deployment uses most.
+#[derive(Default)]
+pub struct DurationEstimator {
+    /// Characters per second at speed 1.0, keyed by Kokoro voice ID
+    rates: RwLock<HashMap<String, f64>>,
+}
+
+impl DurationEstimator {
+    /// Record that `text` at `speed` produced `audio_secs` of speech
+    pub fn observe(&self, voice: &str, text: &str, speed: f32, audio_secs: f64) {
+        let characters = text.trim().chars().count();
+        if characters < MIN_OBSERVED_CHARS || audio_secs <= 0.0 || speed <= 0.0 {
+            return;
+        }
+        let observed = characters as f64 / (audio_secs * f64::from(speed));
+
+        let mut rates = self.rates.write().unwrap_or_else(PoisonError::into_inner);
+        rates
+            .entry(voice.to_string())
+            .and_modify(|rate| *rate += SMOOTHING * (observed - *rate))
+            .or_insert(observed);
+    }
+
+    /// Characters per second at speed 1.0 for `voice`
+    pub fn chars_per_second(&self, voice: &str) -> f64 {
+        self.rates
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(voice)
+            .copied()
+            .unwrap_or(DEFAULT_CHARS_PER_SECOND)
+    }
+
+    /// Estimated speaking time of `text` in milliseconds
+    pub fn estimate_ms(&self, voice: &str, text: &str, speed: f32) -> u64 {
+        let characters = text.trim().chars().count() as f64;
+        let rate = self.chars_per_second(voice) * f64::from(speed);
+        (characters / rate * 1000.0).round() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_uses_default_rate() {
+        let estimator = DurationEstimator::default();
+        let text = "a".repeat(30);
+        assert_eq!(estimator.estimate_ms("af_heart", &text, 1.0), 2_000);
+        assert_eq!(estimator.estimate_ms("af_heart", &text, 2.0), 1_000);
+        assert_eq!(estimator.estimate_ms("af_heart", "", 1.0), 0);
+    }
+
+    #[test]
+    fn test_observations_adjust_one_voice() {
+        let estimator = DurationEstimator::default();
+        let text = "a".repeat(60);
+
+        // First observation sets the rate: 60 chars in 3 s at speed 2.0 is 10 chars/s at 1.0
+        estimator.observe("bm_george", &text, 2.0, 3.0);
+        assert_eq!(estimator.chars_per_second("bm_george"), 10.0);
+        assert_eq!(
+            estimator.chars_per_second("af_heart"),
+            DEFAULT_CHARS_PER_SECOND
+        );
+
+        // Later ones move it part of the way
+        estimator.observe("bm_george", &text, 1.0, 3.0);
+        assert_eq!(estimator.chars_per_second("bm_george"), 12.0);
+
+        // Short clips are ignored
+        estimator.observe("bm_george", "Hi.", 1.0, 1.0);
+        assert_eq!(estimator.chars_per_second("bm_george"), 12.0);
+    }
+}
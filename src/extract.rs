@@ -0,0 +1,459 @@
+use crate::{chapters::split_markdown, error::AppError};
+use axum::{
+    body::Bytes,
+    extract::{
+        multipart::MultipartError, rejection::JsonRejection, FromRequest, FromRequestParts,
+        Multipart, OptionalFromRequest, Query, Request,
+    },
+    http::{header, request::Parts, Uri},
+    Json,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// JSON body extractor that rejects with an OpenAI-formatted error instead of axum's plain text
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApiJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = <Json<T> as FromRequest<S>>::from_request(req, state)
+            .await
+            .map_err(json_rejection_to_error)?;
+        Ok(Self(value))
+    }
+}
+
+impl<T, S> OptionalFromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        let value = <Json<T> as OptionalFromRequest<S>>::from_request(req, state)
+            .await
+            .map_err(json_rejection_to_error)?;
+        Ok(value.map(|Json(value)| Self(value)))
+    }
+}
+
+/// Request bodies that may also be sent as `multipart/form-data` or as plain text
+pub trait FormFields {
+    /// Fields whose form or query values are JSON (numbers, booleans, lists, objects) rather
+    /// than text
+    const JSON_FIELDS: &'static [&'static str];
+}
+
+/// Body extractor that takes JSON, `multipart/form-data` for `curl -F` uploads, or a plain-text
+/// body for `curl --data-binary @notes.txt`.
+///
+/// Form fields, or the query string's fields for a plain-text body, become the fields of the
+/// JSON object: those in [`FormFields::JSON_FIELDS`] are parsed as JSON and the rest are kept
+/// as text. A `file` part or a `text/plain` or `text/markdown` body is read as a document into
+/// `input`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApiBody<T>(pub T);
+
+impl<T, S> FromRequest<S> for ApiBody<T>
+where
+    T: DeserializeOwned + FormFields,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let content_type = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let object = if content_type.starts_with("multipart/form-data") {
+            let multipart = <Multipart as FromRequest<S>>::from_request(req, state)
+                .await
+                .map_err(|rejection| AppError::invalid_request(rejection.body_text()))?;
+            form_object(multipart, T::JSON_FIELDS).await?
+        } else if content_type.starts_with("text/plain")
+            || content_type.starts_with("text/markdown")
+        {
+            text_object(req, state, T::JSON_FIELDS).await?
+        } else {
+            let ApiJson(value) = <ApiJson<T> as FromRequest<S>>::from_request(req, state).await?;
+            return Ok(Self(value));
+        };
+
+        from_object(object).map(Self)
+    }
+}
+
+/// Query string extractor for requests made by following a URL, such as `<audio src>`; fields
+/// are typed as for [`ApiBody`] forms
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApiQuery<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for ApiQuery<T>
+where
+    T: DeserializeOwned + FormFields,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let object = query_object(&parts.uri, T::JSON_FIELDS)?;
+        from_object(object).map(Self)
+    }
+}
+
+fn from_object<T: DeserializeOwned>(object: Map<String, Value>) -> Result<T, AppError> {
+    serde_json::from_value(Value::Object(object)).map_err(|e| {
+        let message = e.to_string();
+        let param = json_error_param(&message);
+        AppError::invalid_json(crate::redact::message(&message), param)
+    })
+}
+
+/// Collect form fields into a JSON object, reading a `file` part into `input`
+async fn form_object(
+    mut multipart: Multipart,
+    json_fields: &[&str],
+) -> Result<Map<String, Value>, AppError> {
+    let mut object = Map::new();
+    while let Some(field) = multipart.next_field().await.map_err(form_error)? {
+        let name = field.name().unwrap_or_default().to_string();
+        if name == "file" {
+            let markdown = is_markdown(field.file_name(), field.content_type());
+            let bytes = field.bytes().await.map_err(form_error)?;
+            insert_document(&mut object, &bytes, markdown)?;
+        } else {
+            let text = field.text().await.map_err(form_error)?;
+            insert_field(&mut object, name, text, json_fields)?;
+        }
+    }
+    Ok(object)
+}
+
+/// Collect the query string's fields into a JSON object, reading the body into `input`
+async fn text_object<S: Send + Sync>(
+    req: Request,
+    state: &S,
+    json_fields: &[&str],
+) -> Result<Map<String, Value>, AppError> {
+    let mut object = query_object(req.uri(), json_fields)?;
+    let markdown = is_markdown(
+        None,
+        req.headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok()),
+    );
+    let body = Bytes::from_request(req, state)
+        .await
+        .map_err(|rejection| AppError::invalid_request(rejection.body_text()))?;
+    insert_document(&mut object, &body, markdown)?;
+    Ok(object)
+}
+
+/// Collect the query string's fields into a JSON object
+fn query_object(uri: &Uri, json_fields: &[&str]) -> Result<Map<String, Value>, AppError> {
+    let Query(fields) = Query::<Vec<(String, String)>>::try_from_uri(uri)
+        .map_err(|rejection| AppError::invalid_request(rejection.body_text()))?;
+    let mut object = Map::new();
+    for (name, text) in fields {
+        insert_field(&mut object, name, text, json_fields)?;
+    }
+    Ok(object)
+}
+
+fn insert_field(
+    object: &mut Map<String, Value>,
+    name: String,
+    text: String,
+    json_fields: &[&str],
+) -> Result<(), AppError> {
+    let value = if json_fields.contains(&name.as_str()) {
+        serde_json::from_str(&text)
+            .map_err(|e| AppError::invalid_json(format!("{}: {}", name, e), Some(name.clone())))?
+    } else {
+        Value::String(text)
+    };
+    if object.contains_key(&name) {
+        return Err(if name == "input" {
+            duplicate_input()
+        } else {
+            AppError::invalid_request(format!("Field '{}' given more than once", name))
+        });
+    }
+    object.insert(name, value);
+    Ok(())
+}
+
+/// Read an uploaded document into `input`
+fn insert_document(
+    object: &mut Map<String, Value>,
+    document: &[u8],
+    markdown: bool,
+) -> Result<(), AppError> {
+    let text = std::str::from_utf8(document)
+        .map_err(|_| AppError::invalid_request("Documents must be UTF-8 text"))?;
+    if object.contains_key("input") {
+        return Err(duplicate_input());
+    }
+    object.insert(
+        "input".to_string(),
+        Value::String(document_text(text, markdown)),
+    );
+    Ok(())
+}
+
+fn duplicate_input() -> AppError {
+    AppError::invalid_request("Send the text either as input or as a document, not both")
+}
+
+fn form_error(e: MultipartError) -> AppError {
+    AppError::invalid_request(format!("Failed to read form: {}", e.body_text()))
+}
+
+/// Whether an uploaded document is Markdown, by its file name or content type
+fn is_markdown(file_name: Option<&str>, content_type: Option<&str>) -> bool {
+    let extension = file_name
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, extension)| extension.to_ascii_lowercase());
+    matches!(extension.as_deref(), Some("md" | "markdown"))
+        || content_type.is_some_and(|content_type| content_type.starts_with("text/markdown"))
+}
+
+/// Text read out for an uploaded document; Markdown is reduced to plain text the way
+/// audiobooks and the watch folder read it
+fn document_text(document: &str, markdown: bool) -> String {
+    if !markdown {
+        return document.to_string();
+    }
+    split_markdown(document)
+        .iter()
+        .map(|chapter| chapter.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn json_rejection_to_error(rejection: JsonRejection) -> AppError {
+    let message = match &rejection {
+        JsonRejection::JsonDataError(_) | JsonRejection::JsonSyntaxError(_) => {
+            rejection.body_text()
+        }
+        JsonRejection::MissingJsonContentType(_) => {
+            "Request body must be JSON with `Content-Type: application/json`".to_string()
+        }
+        _ => format!("Failed to read request body: {}", rejection.body_text()),
+    };
+    let param = match &rejection {
+        JsonRejection::JsonDataError(_) => json_error_param(&message),
+        _ => None,
+    };
+
+    AppError::invalid_json(crate::redact::message(&message), param)
+}
+
+/// Pull the offending field out of a deserialization message such as
+/// `...target type: speed: invalid type: ...` or `...target type: missing field `voice` ...`
+fn json_error_param(message: &str) -> Option<String> {
+    let detail = message
+        .split_once("target type: ")
+        .map_or(message, |(_, detail)| detail);
+
+    if let Some(rest) = detail.strip_prefix("missing field `") {
+        return rest.split_once('`').map(|(field, _)| field.to_string());
+    }
+    if let Some(rest) = detail.strip_prefix("unknown field `") {
+        return rest.split_once('`').map(|(field, _)| field.to_string());
+    }
+
+    let (path, _) = detail.split_once(": ")?;
+    let is_path = !path.is_empty()
+        && path
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '[' | ']'));
+    is_path.then(|| path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Body {
+        #[allow(dead_code)]
+        voice: String,
+        #[allow(dead_code)]
+        speed: Option<f32>,
+    }
+
+    fn rejection_param(body: &str) -> Option<String> {
+        let rejection = Json::<Body>::from_bytes(body.as_bytes()).unwrap_err();
+        match json_rejection_to_error(rejection) {
+            AppError::InvalidJson { param, .. } => param,
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_rejection_reports_offending_field() {
+        assert_eq!(
+            rejection_param(r#"{"voice":"af_alloy","speed":"fast"}"#),
+            Some("speed".to_string())
+        );
+        assert_eq!(
+            rejection_param(r#"{"speed":1.0}"#),
+            Some("voice".to_string())
+        );
+        assert_eq!(rejection_param(r#"{"voice":"#), None);
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Upload {
+        input: String,
+        voice: String,
+        speed: Option<f32>,
+    }
+
+    impl FormFields for Upload {
+        const JSON_FIELDS: &'static [&'static str] = &["speed"];
+    }
+
+    async fn upload(parts: &[(&str, Option<&str>, &str)]) -> Result<Upload, AppError> {
+        let mut body = String::new();
+        for (name, file_name, value) in parts {
+            body.push_str("--boundary\r\n");
+            match file_name {
+                Some(file_name) => body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\r\n",
+                    name, file_name
+                )),
+                None => body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{}\"\r\n\r\n",
+                    name
+                )),
+            }
+            body.push_str(value);
+            body.push_str("\r\n");
+        }
+        body.push_str("--boundary--\r\n");
+        let request = Request::builder()
+            .method("POST")
+            .header(
+                header::CONTENT_TYPE,
+                "multipart/form-data; boundary=boundary",
+            )
+            .body(axum::body::Body::from(body))
+            .unwrap();
+        ApiBody::<Upload>::from_request(request, &())
+            .await
+            .map(|ApiBody(upload)| upload)
+    }
+
+    #[tokio::test]
+    async fn test_form_upload() {
+        let form = upload(&[
+            ("voice", None, "af_heart"),
+            ("speed", None, "1.5"),
+            ("input", None, "Hello there."),
+        ])
+        .await
+        .unwrap();
+        assert_eq!(form.input, "Hello there.");
+        assert_eq!(form.voice, "af_heart");
+        assert_eq!(form.speed, Some(1.5));
+
+        let form = upload(&[
+            ("file", Some("notes.md"), "# Notes\n\nSome **bold** text."),
+            ("voice", None, "af_heart"),
+        ])
+        .await
+        .unwrap();
+        assert!(form.input.contains("Some bold text."));
+        assert!(!form.input.contains("**"));
+        let form = upload(&[
+            ("file", Some("notes.txt"), "Some **bold** text."),
+            ("voice", None, "af_heart"),
+        ])
+        .await
+        .unwrap();
+        assert_eq!(form.input, "Some **bold** text.");
+
+        let both = upload(&[
+            ("input", None, "Hello"),
+            ("file", Some("notes.txt"), "Hello"),
+            ("voice", None, "af_heart"),
+        ]);
+        assert!(matches!(both.await, Err(AppError::InvalidRequest(_))));
+        let speed = upload(&[
+            ("input", None, "Hi"),
+            ("voice", None, "af_heart"),
+            ("speed", None, "fast"),
+        ]);
+        assert!(matches!(
+            speed.await,
+            Err(AppError::InvalidJson { param: Some(param), .. }) if param == "speed"
+        ));
+        let missing = upload(&[("input", None, "Hi")]);
+        assert!(matches!(
+            missing.await,
+            Err(AppError::InvalidJson { param: Some(param), .. }) if param == "voice"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_text_body() {
+        let text = |content_type: &str, query: &str, body: &str| {
+            let request = Request::builder()
+                .method("POST")
+                .uri(format!("/v1/audio/speech?{}", query))
+                .header(header::CONTENT_TYPE, content_type)
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap();
+            ApiBody::<Upload>::from_request(request, &())
+        };
+        let ApiBody(upload) = text("text/plain", "voice=af_heart&speed=0.9", "Hello *there*.")
+            .await
+            .unwrap();
+        assert_eq!(upload.input, "Hello *there*.");
+        assert_eq!(upload.voice, "af_heart");
+        assert_eq!(upload.speed, Some(0.9));
+        let ApiBody(upload) = text("text/markdown", "voice=af_heart", "Hello *there*.")
+            .await
+            .unwrap();
+        assert_eq!(upload.input, "Hello there.");
+        assert!(matches!(
+            text("text/plain", "voice=af_heart&input=Hi", "Hello").await,
+            Err(AppError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_json_error_param() {
+        assert_eq!(
+            json_error_param(
+                "Failed to deserialize the JSON body into the target type: speed: invalid type: string \"fast\", expected f32 at line 1 column 40"
+            ),
+            Some("speed".to_string())
+        );
+        assert_eq!(
+            json_error_param(
+                "Failed to deserialize the JSON body into the target type: missing field `voice` at line 1 column 30"
+            ),
+            Some("voice".to_string())
+        );
+        assert_eq!(
+            json_error_param(
+                "Failed to deserialize the JSON body into the target type: invalid type: integer `1`, expected struct SpeechRequest at line 1 column 1"
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,368 @@
+use crate::{
+    api::{presented_api_key, AppState},
+    error::{ApiResult, AppError},
+    extract::ApiJson,
+    jobs::{result_chapters, Job},
+    signed_url::{self, SignedUrl},
+};
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, Uri},
+    response::{IntoResponse, Response},
+    Json,
+};
+use reqwest::Url;
+use serde::Deserialize;
+use tracing::warn;
+
+/// Longest feed ID accepted in an audiobook request
+const MAX_FEED_ID_CHARS: usize = 64;
+
+/// Whether `feed` can name a feed: letters, digits, `-`, and `_`
+pub fn valid_feed_id(feed: &str) -> bool {
+    !feed.is_empty()
+        && feed.len() <= MAX_FEED_ID_CHARS
+        && feed
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// One chapter of a completed job, as a podcast episode
+#[derive(Debug)]
+struct Episode {
+    guid: String,
+    title: String,
+    /// Unix timestamp in seconds
+    published: u64,
+    url: String,
+    length: u64,
+    content_type: &'static str,
+    duration_secs: u64,
+}
+
+/// Podcast RSS feed of the completed audiobook jobs published in a feed
+#[utoipa::path(
+    get,
+    path = "/feeds/{feed}",
+    tag = "jobs",
+    params(("feed" = String, Path, description = "Feed ID followed by `.xml`, e.g. `news.xml`")),
+    responses(
+        (status = 200, description = "RSS 2.0 feed with one item per chapter", content_type = "application/rss+xml"),
+        (status = 400, description = "Malformed feed name", body = crate::error::OpenAIError),
+        (status = 401, description = "Missing or invalid API key", body = crate::error::OpenAIError)
+    )
+)]
+pub async fn feed_handler(
+    State(state): State<AppState>,
+    Path(file): Path<String>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> ApiResult<Response> {
+    let feed = file
+        .strip_suffix(".xml")
+        .filter(|feed| valid_feed_id(feed))
+        .ok_or_else(feed_name_error)?
+        .to_string();
+
+    // Podcast apps cannot send headers, so links are signed with the key the feed was fetched
+    // with, or that signed the feed's own URL
+    let secret = if state.api_keys.is_required() {
+        let secrets = state.api_keys.secrets();
+        presented_api_key(&headers, &uri)
+            .map(str::to_string)
+            .or_else(|| {
+                uri.query()
+                    .and_then(|query| signed_url::verify(&secrets, uri.path(), query))
+                    .map(str::to_string)
+            })
+    } else {
+        None
+    };
+    let base = base_url(&headers, state.trust_forwarded_headers);
+
+    let jobs = state.jobs.in_feed(&feed);
+    let store = state.jobs.clone();
+    let episodes = tokio::task::spawn_blocking(move || {
+        jobs.iter()
+            .flat_map(|job| {
+                let link = Link {
+                    base: &base,
+                    secret: secret.as_deref(),
+                    expires_at: store.expires_at(job),
+                };
+                episodes(job, &store.result_path(&job.id), &link)
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")],
+        render_feed(&feed, &episodes),
+    )
+        .into_response())
+}
+
+fn feed_name_error() -> AppError {
+    AppError::invalid_request(
+        "Feed URLs are /feeds/{feed}.xml, with a feed ID of letters, digits, '-', and '_'",
+    )
+}
+
+/// Request body for POST /feeds/{feed}/sign
+#[derive(Debug, Default, Deserialize)]
+pub struct SignFeedUrlRequest {
+    /// Seconds until the URL expires
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+/// Create a feed URL signed with the caller's API key, for podcast apps that cannot send an
+/// Authorization header
+#[utoipa::path(
+    post,
+    path = "/feeds/{feed}/sign",
+    tag = "jobs",
+    params(("feed" = String, Path, description = "Feed ID, without `.xml`")),
+    request_body(
+        content = Object,
+        description = "Optional `expires_in` seconds (default 86400, at most 2592000)"
+    ),
+    responses(
+        (status = 200, description = "Signed feed URL", body = SignedUrl),
+        (status = 400, description = "Malformed feed ID or expiry", body = crate::error::OpenAIError),
+        (status = 401, description = "Missing or invalid API key", body = crate::error::OpenAIError)
+    )
+)]
+pub async fn sign_feed_url_handler(
+    State(state): State<AppState>,
+    Path(feed): Path<String>,
+    headers: HeaderMap,
+    uri: Uri,
+    req: Option<ApiJson<SignFeedUrlRequest>>,
+) -> ApiResult<Json<SignedUrl>> {
+    if !valid_feed_id(&feed) {
+        return Err(feed_name_error());
+    }
+    let req = req.map_or_else(SignFeedUrlRequest::default, |ApiJson(req)| req);
+    let expires_at = signed_url::expires_at(req.expires_in)?;
+    let mut url = Url::parse(&format!(
+        "{}/feeds/{}.xml",
+        base_url(&headers, state.trust_forwarded_headers),
+        feed
+    ))
+    .map_err(|_| AppError::invalid_request("Host header is not a valid host"))?;
+
+    let key = presented_api_key(&headers, &uri).filter(|_| state.api_keys.is_required());
+    let expires_at = key.map(|key| {
+        signed_url::sign(&mut url, key, expires_at);
+        expires_at
+    });
+
+    Ok(Json(SignedUrl {
+        url: url.into(),
+        expires_at,
+    }))
+}
+
+/// Scheme and host clients reached the server at. `X-Forwarded-Proto` and `X-Forwarded-Host`
+/// are only believed from a trusted reverse proxy, since anyone can send them.
+pub fn base_url(headers: &HeaderMap, trust_forwarded: bool) -> String {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    let forwarded = |name: &str| header(name).filter(|_| trust_forwarded);
+    let scheme = forwarded("x-forwarded-proto").unwrap_or("http");
+    let host = forwarded("x-forwarded-host")
+        .or_else(|| header("host"))
+        .unwrap_or("localhost");
+    format!("{}://{}", scheme, host)
+}
+
+/// How episode links are built
+struct Link<'a> {
+    base: &'a str,
+    /// Key enclosure URLs are signed with, when API keys are required
+    secret: Option<&'a str>,
+    /// When signed enclosure URLs stop working
+    expires_at: u64,
+}
+
+impl Link<'_> {
+    fn chapter(&self, job: &Job, index: usize) -> String {
+        let url = format!("{}/v1/audio/jobs/{}/chapters/{}", self.base, job.id, index);
+        match (self.secret, Url::parse(&url)) {
+            (Some(secret), Ok(mut url)) => {
+                signed_url::sign(&mut url, secret, self.expires_at);
+                url.into()
+            }
+            _ => url,
+        }
+    }
+}
+
+/// Episodes for each chapter of `job`; a result that can no longer be read has none
+fn episodes(job: &Job, result_path: &std::path::Path, link: &Link) -> Vec<Episode> {
+    let files = match result_chapters(result_path) {
+        Ok(files) => files,
+        Err(e) => {
+            warn!(job_id = %job.id, error = %e, "Failed to read job result for feed");
+            return Vec::new();
+        }
+    };
+    let finished = job.finished_at.unwrap_or(job.created_at);
+    job.chapters
+        .iter()
+        .zip(files)
+        .map(|(chapter, (name, length))| {
+            let format = name.rsplit_once('.').map_or("", |(_, extension)| extension);
+            Episode {
+                guid: format!("{}/{}", job.id, chapter.index),
+                title: chapter.title.clone(),
+                // Chapters finish together; a second apart keeps them in reading order in apps
+                // that sort by date
+                published: finished + chapter.index as u64,
+                url: link.chapter(job, chapter.index),
+                length,
+                content_type: crate::cache::content_type_for_format(format)
+                    .unwrap_or("application/octet-stream"),
+                duration_secs: chapter.duration_ms.unwrap_or(0).div_ceil(1000),
+            }
+        })
+        .collect()
+}
+
+fn render_feed(feed: &str, episodes: &[Episode]) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <rss version=\"2.0\" xmlns:itunes=\"http://www.itunes.com/dtds/podcast-1.0.dtd\">\n\
+         <channel>\n",
+    );
+    xml.push_str(&format!("<title>{}</title>\n", escape(feed)));
+    xml.push_str("<description>Audio synthesized by kokoro-openai-server</description>\n");
+    for episode in episodes {
+        xml.push_str(&format!(
+            "<item>\n\
+             <title>{}</title>\n\
+             <guid isPermaLink=\"false\">{}</guid>\n\
+             <pubDate>{}</pubDate>\n\
+             <enclosure url=\"{}\" length=\"{}\" type=\"{}\"/>\n\
+             <itunes:duration>{}</itunes:duration>\n\
+             </item>\n",
+            escape(&episode.title),
+            escape(&episode.guid),
+            rfc2822(episode.published),
+            escape(&episode.url),
+            episode.length,
+            episode.content_type,
+            episode.duration_secs
+        ));
+    }
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A Unix timestamp as an RFC 2822 date, as RSS `pubDate` wants it
+fn rfc2822(unix_secs: u64) -> String {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let days = unix_secs / 86_400;
+    let secs = unix_secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} +0000",
+        DAYS[(days % 7) as usize],
+        day,
+        MONTHS[month as usize - 1],
+        year,
+        secs / 3_600,
+        secs % 3_600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_feed_id() {
+        assert!(valid_feed_id("morning-news_2"));
+        assert!(!valid_feed_id(""));
+        assert!(!valid_feed_id("../secrets"));
+        assert!(!valid_feed_id(&"a".repeat(65)));
+    }
+
+    #[test]
+    fn test_rfc2822() {
+        assert_eq!(rfc2822(0), "Thu, 01 Jan 1970 00:00:00 +0000");
+        assert_eq!(rfc2822(951_782_400), "Tue, 29 Feb 2000 00:00:00 +0000");
+        assert_eq!(rfc2822(1_709_649_000), "Tue, 05 Mar 2024 14:30:00 +0000");
+    }
+
+    #[test]
+    fn test_base_url() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, "tts.local:8000".parse().unwrap());
+        assert_eq!(base_url(&headers, false), "http://tts.local:8000");
+        headers.insert("x-forwarded-proto", "https".parse().unwrap());
+        headers.insert("x-forwarded-host", "tts.example.com".parse().unwrap());
+        assert_eq!(base_url(&headers, false), "http://tts.local:8000");
+        assert_eq!(base_url(&headers, true), "https://tts.example.com");
+    }
+
+    #[test]
+    fn test_render_feed() {
+        let xml = render_feed(
+            "news",
+            &[Episode {
+                guid: "job_1/0".to_string(),
+                title: "Tides & Moons".to_string(),
+                published: 0,
+                url: "http://tts.local/v1/audio/jobs/job_1/chapters/0?expires=1&signature=ab"
+                    .to_string(),
+                length: 1234,
+                content_type: "audio/mpeg",
+                duration_secs: 61,
+            }],
+        );
+        assert!(xml.contains("<title>news</title>"));
+        assert!(xml.contains("<title>Tides &amp; Moons</title>"));
+        assert!(xml.contains(
+            "<enclosure url=\"http://tts.local/v1/audio/jobs/job_1/chapters/0?expires=1&amp;signature=ab\" length=\"1234\" type=\"audio/mpeg\"/>"
+        ));
+        assert!(xml.contains("<pubDate>Thu, 01 Jan 1970 00:00:00 +0000</pubDate>"));
+        assert!(xml.contains("<itunes:duration>61</itunes:duration>"));
+    }
+}
@@ -0,0 +1,143 @@
+use anyhow::{Context, Result};
+use reqwest::{header, redirect, Url};
+use std::time::Duration;
+
+/// Longest a page fetch may take, from connecting to the last byte
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_REDIRECTS: usize = 5;
+
+/// Web pages the server may fetch to read aloud
+#[derive(Debug, Clone)]
+pub struct FetchPolicy {
+    /// Host names, or `*.example.com` for a domain and its subdomains
+    allow: Vec<String>,
+    max_bytes: usize,
+    client: reqwest::Client,
+}
+
+/// Body of a fetched page
+#[derive(Debug)]
+pub struct Page {
+    pub body: String,
+    /// Served as HTML rather than plain text
+    pub html: bool,
+}
+
+impl FetchPolicy {
+    pub fn new(allow: &[String], max_bytes: usize) -> Result<Self> {
+        let allow: Vec<String> = allow.iter().map(|host| host.to_lowercase()).collect();
+        // A redirect must not lead off the allow-list
+        let redirect_allow = allow.clone();
+        let client = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .redirect(redirect::Policy::custom(move |attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS {
+                    attempt.error("too many redirects")
+                } else if host_allowed(&redirect_allow, attempt.url()) {
+                    attempt.follow()
+                } else {
+                    attempt.error("redirected to a host outside KOKORO_FETCH_ALLOW")
+                }
+            }))
+            .user_agent(concat!("kokoro-openai-server/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .context("Failed to build HTTP client")?;
+        Ok(Self {
+            allow,
+            max_bytes,
+            client,
+        })
+    }
+
+    /// Fetch `url`, which must be http(s) on an allowed host, failing for bodies over the size
+    /// limit and for anything but HTML or plain text
+    pub async fn fetch(&self, url: &str) -> Result<Page> {
+        let url = Url::parse(url).context("not a valid URL")?;
+        if !matches!(url.scheme(), "http" | "https") {
+            anyhow::bail!("only http and https URLs can be fetched");
+        }
+        if !host_allowed(&self.allow, &url) {
+            anyhow::bail!(
+                "host {} is not in KOKORO_FETCH_ALLOW",
+                url.host_str().unwrap_or_default()
+            );
+        }
+
+        let mut response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)?;
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_lowercase();
+        let html = content_type.contains("html");
+        if !html && !content_type.starts_with("text/plain") {
+            anyhow::bail!("unsupported content type '{}'", content_type);
+        }
+        if response
+            .content_length()
+            .is_some_and(|length| length > self.max_bytes as u64)
+        {
+            anyhow::bail!("page is larger than {} bytes", self.max_bytes);
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > self.max_bytes {
+                anyhow::bail!("page is larger than {} bytes", self.max_bytes);
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(Page {
+            body: String::from_utf8_lossy(&body).into_owned(),
+            html,
+        })
+    }
+}
+
+fn host_allowed(allow: &[String], url: &Url) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.to_lowercase();
+    allow.iter().any(|entry| match entry.strip_prefix("*.") {
+        Some(domain) => {
+            host == domain
+                || host
+                    .strip_suffix(domain)
+                    .is_some_and(|subdomain| subdomain.ends_with('.'))
+        }
+        None => host == *entry,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_allowed() {
+        let allow = ["blog.example.com", "*.news.org"].map(String::from);
+        let allowed = |url: &str| host_allowed(&allow, &Url::parse(url).unwrap());
+        assert!(allowed("https://blog.example.com/post"));
+        assert!(allowed("https://BLOG.example.com/post"));
+        assert!(!allowed("https://example.com/"));
+        assert!(allowed("https://news.org/a"));
+        assert!(allowed("http://www.news.org/a"));
+        assert!(!allowed("http://fakenews.org/a"));
+        assert!(!allowed("http://127.0.0.1/"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_rejects_urls_outside_policy() {
+        let policy = FetchPolicy::new(&["example.com".to_string()], 1024).unwrap();
+        assert!(policy.fetch("not a url").await.is_err());
+        assert!(policy.fetch("ftp://example.com/page").await.is_err());
+        assert!(policy.fetch("http://169.254.169.254/").await.is_err());
+    }
+}
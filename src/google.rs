@@ -0,0 +1,216 @@
+use crate::{
+    api::{
+        synthesize_clip, validate_speech_request, voice_language, AppState, SpeechRequest,
+        VOICE_SUBSTITUTED_HEADER,
+    },
+    error::{ApiResult, AppError},
+    extract::ApiJson,
+    pcm::{pcm16_to_wav, resample_pcm16},
+    ssml,
+    validation::VoiceVendor,
+};
+use axum::{
+    extract::State,
+    http::HeaderValue,
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error};
+use uuid::Uuid;
+
+/// Request body for POST /v1/text:synthesize
+#[derive(Debug, Deserialize)]
+pub struct SynthesizeRequest {
+    pub input: SynthesisInput,
+    #[serde(default)]
+    pub voice: VoiceSelectionParams,
+    #[serde(rename = "audioConfig")]
+    pub audio_config: AudioConfig,
+}
+
+/// Exactly one of `text` or `ssml`
+#[derive(Debug, Deserialize)]
+pub struct SynthesisInput {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub ssml: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct VoiceSelectionParams {
+    /// Accepted for compatibility; the voice alone selects the language
+    #[serde(rename = "languageCode", default)]
+    pub language_code: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Chooses the default voice when `name` is omitted
+    #[serde(rename = "ssmlGender", default)]
+    pub ssml_gender: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AudioConfig {
+    #[serde(rename = "audioEncoding")]
+    pub audio_encoding: String,
+    #[serde(rename = "speakingRate", default)]
+    pub speaking_rate: Option<f32>,
+    /// Only changes `LINEAR16` and `PCM` output; encoded formats stay at Kokoro's rate
+    #[serde(rename = "sampleRateHertz", default)]
+    pub sample_rate_hertz: Option<u32>,
+}
+
+/// Response body for POST /v1/text:synthesize
+#[derive(Debug, Serialize)]
+pub struct SynthesizeResponse {
+    /// Base64-encoded audio
+    #[serde(rename = "audioContent")]
+    pub audio_content: String,
+}
+
+/// How a Google `audioEncoding` is produced
+#[derive(Debug, PartialEq)]
+enum AudioEncoding {
+    /// Encoded by the shared speech path
+    Encoded(&'static str),
+    /// 16-bit mono PCM at the requested rate, with a WAV header when `wav` is set
+    Pcm { wav: bool },
+}
+
+/// Map a Google voice name to a Kokoro voice; Kokoro IDs and OpenAI aliases pass through
+fn kokoro_voice(voice: &VoiceSelectionParams) -> &str {
+    match voice.name.as_deref() {
+        Some(name) => VoiceVendor::Google.kokoro_voice(name),
+        None if voice.ssml_gender.as_deref() == Some("MALE") => "am_adam",
+        None => "af_alloy",
+    }
+}
+
+fn audio_encoding(encoding: &str) -> ApiResult<AudioEncoding> {
+    match encoding {
+        // Google's LINEAR16 output includes a WAV header
+        "LINEAR16" => Ok(AudioEncoding::Pcm { wav: true }),
+        "PCM" => Ok(AudioEncoding::Pcm { wav: false }),
+        "MP3" => Ok(AudioEncoding::Encoded("mp3")),
+        "OGG_OPUS" => Ok(AudioEncoding::Encoded("opus")),
+        other => Err(AppError::invalid_request(format!(
+            "audioEncoding '{}' not supported. Supported encodings: LINEAR16, PCM, MP3, OGG_OPUS",
+            other
+        ))),
+    }
+}
+
+/// Google-compatible speech synthesis returning base64 audio in JSON
+pub async fn synthesize_handler(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<SynthesizeRequest>,
+) -> ApiResult<Response> {
+    let request_id = Uuid::new_v4().to_string();
+
+    debug!(
+        request_id = %request_id,
+        voice_name = ?req.voice.name,
+        language_code = ?req.voice.language_code,
+        audio_encoding = %req.audio_config.audio_encoding,
+        "Received Google speech request"
+    );
+
+    let encoding = audio_encoding(&req.audio_config.audio_encoding)?;
+    let native_rate = state.backend.sample_rate();
+    let sample_rate = req.audio_config.sample_rate_hertz.unwrap_or(native_rate);
+    if matches!(encoding, AudioEncoding::Pcm { .. }) && !(8_000..=48_000).contains(&sample_rate) {
+        return Err(AppError::invalid_request(format!(
+            "sampleRateHertz {} not supported. Supported rates: 8000 to 48000",
+            sample_rate
+        )));
+    }
+    let voice = kokoro_voice(&req.voice).to_string();
+    let input = match (req.input.text, req.input.ssml) {
+        (Some(text), None) => text,
+        (None, Some(markup)) => ssml::to_plain_text(&markup, voice_language(&state, &voice)),
+        _ => {
+            return Err(AppError::invalid_request(
+                "input must set exactly one of text or ssml",
+            ))
+        }
+    };
+
+    let response_format = match encoding {
+        AudioEncoding::Encoded(response_format) => response_format,
+        AudioEncoding::Pcm { .. } => "pcm",
+    };
+    let params = validate_speech_request(
+        &state,
+        SpeechRequest {
+            model: "kokoro".to_string(),
+            input,
+            voice: Some(voice),
+            response_format: Some(response_format.to_string()),
+            speed: req.audio_config.speaking_rate,
+            ..Default::default()
+        },
+    )?;
+    let (audio, _) = synthesize_clip(&state, &params, &request_id).await?;
+
+    let bytes = match encoding {
+        AudioEncoding::Encoded(_) => audio.bytes.to_vec(),
+        AudioEncoding::Pcm { wav } => {
+            let pcm = resample_pcm16(&audio.bytes, native_rate, sample_rate);
+            if wav {
+                pcm16_to_wav(&pcm, sample_rate).map_err(|e| {
+                    error!(error = %e, "Failed to encode WAV");
+                    AppError::Internal
+                })?
+            } else {
+                pcm
+            }
+        }
+    };
+
+    let mut response = Json(SynthesizeResponse {
+        audio_content: BASE64.encode(bytes),
+    })
+    .into_response();
+    if params.voice_substituted {
+        if let Ok(voice) = HeaderValue::from_str(&params.voice) {
+            response
+                .headers_mut()
+                .insert(VOICE_SUBSTITUTED_HEADER, voice);
+        }
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_voice_follows_gender() {
+        let male = VoiceSelectionParams {
+            ssml_gender: Some("MALE".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(kokoro_voice(&male), "am_adam");
+        assert_eq!(kokoro_voice(&VoiceSelectionParams::default()), "af_alloy");
+    }
+
+    #[test]
+    fn test_request_schema() {
+        let req: SynthesizeRequest = serde_json::from_str(
+            r#"{"input":{"ssml":"<speak>Hi</speak>"},"voice":{"languageCode":"en-GB","name":"en-GB-Neural2-B"},"audioConfig":{"audioEncoding":"LINEAR16","speakingRate":1.25,"sampleRateHertz":16000}}"#,
+        )
+        .unwrap();
+        assert_eq!(req.input.ssml.as_deref(), Some("<speak>Hi</speak>"));
+        assert_eq!(kokoro_voice(&req.voice), "bm_george");
+        assert_eq!(req.audio_config.speaking_rate, Some(1.25));
+        assert_eq!(req.audio_config.sample_rate_hertz, Some(16_000));
+        assert_eq!(
+            audio_encoding(&req.audio_config.audio_encoding).unwrap(),
+            AudioEncoding::Pcm { wav: true }
+        );
+        assert!(audio_encoding("MULAW").is_err());
+    }
+}
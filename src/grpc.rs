@@ -0,0 +1,289 @@
+use crate::{
+    api::{synthesize_clip, validate_speech_request, AppState, SpeechRequest},
+    error::AppError,
+    keys::ApiKeys,
+    pcm::BitDepth,
+    streaming::{spawn_pcm_chunks, StreamOptions},
+};
+use anyhow::{Context, Result};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
+use tonic::{metadata::MetadataValue, Code, Request, Response, Status};
+use tracing::{debug, error, warn};
+use uuid::Uuid;
+
+pub mod proto {
+    tonic::include_proto!("kokoro.v1");
+}
+
+use proto::{
+    speech_server::{Speech, SpeechServer},
+    AudioChunk, SynthesizeRequest, SynthesizeResponse,
+};
+
+/// Metadata key carrying the same stable error code as the REST `code` field
+const ERROR_CODE_METADATA: &str = "x-error-code";
+/// Metadata key naming the fallback voice, like the REST `X-Voice-Substituted` header
+const VOICE_SUBSTITUTED_METADATA: &str = "x-voice-substituted";
+
+struct SpeechService {
+    state: AppState,
+}
+
+/// Map a protobuf request onto the REST request so both transports share validation.
+///
+/// Proto3 has no field presence for strings, so empty values take the REST defaults.
+fn speech_request(req: SynthesizeRequest) -> SpeechRequest {
+    fn or_default(value: String, default: &str) -> String {
+        if value.is_empty() {
+            default.to_string()
+        } else {
+            value
+        }
+    }
+
+    SpeechRequest {
+        model: or_default(req.model, "kokoro"),
+        input: req.input,
+        voice: Some(or_default(req.voice, "af_alloy")),
+        response_format: Some(or_default(req.response_format, "wav")),
+        speed: req.speed,
+        initial_silence: req.initial_silence.map(|samples| samples as usize),
+        ..Default::default()
+    }
+}
+
+fn status_from_error(err: AppError) -> Status {
+    let code = match &err {
+        AppError::Unauthorized => Code::Unauthenticated,
+        AppError::InvalidRequest(_)
+        | AppError::InvalidJson { .. }
+        | AppError::InputTooLong(_)
+        | AppError::OutputTooLarge { .. }
+        | AppError::InvalidSpeed(_)
+        | AppError::ModelNotFound(_)
+        | AppError::VoiceNotFound(_)
+        | AppError::PresetNotFound(_)
+        | AppError::InvalidResponseFormat(_)
+        | AppError::InvalidProfile(_) => Code::InvalidArgument,
+        AppError::ModelDoesNotExist(_)
+        | AppError::KeyNotFound(_)
+        | AppError::JobNotFound(_)
+        | AppError::CaptureNotFound(_) => Code::NotFound,
+        AppError::Conflict(_) => Code::Aborted,
+        AppError::ServiceUnavailable(_) => Code::Unavailable,
+        AppError::Overloaded(_) | AppError::OutputLimitExceeded(_) => Code::ResourceExhausted,
+        AppError::Timeout(_) => Code::DeadlineExceeded,
+        AppError::Backend(_) | AppError::Internal => Code::Internal,
+    };
+    let message = match &err {
+        AppError::Backend(msg) => {
+            error!("Backend error: {}", crate::redact::message(msg));
+            "Backend processing error".to_string()
+        }
+        _ => err.to_string(),
+    };
+
+    let mut status = Status::new(code, message);
+    status
+        .metadata_mut()
+        .insert(ERROR_CODE_METADATA, MetadataValue::from_static(err.code()));
+    status
+}
+
+#[tonic::async_trait]
+impl Speech for SpeechService {
+    async fn synthesize(
+        &self,
+        request: Request<SynthesizeRequest>,
+    ) -> Result<Response<SynthesizeResponse>, Status> {
+        let request_id = Uuid::new_v4().to_string();
+        let params = validate_speech_request(&self.state, speech_request(request.into_inner()))
+            .map_err(status_from_error)?;
+
+        debug!(request_id = %request_id, voice = %params.voice, "Received gRPC speech request");
+
+        let (audio, _) = synthesize_clip(&self.state, &params, &request_id)
+            .await
+            .map_err(status_from_error)?;
+
+        let mut response = Response::new(SynthesizeResponse {
+            audio: audio.bytes.to_vec(),
+            content_type: audio.content_type.to_string(),
+        });
+        mark_substituted_voice(
+            &mut response,
+            params.voice_substituted.then_some(params.voice.as_str()),
+        );
+        Ok(response)
+    }
+
+    type SynthesizeStreamStream = Pin<Box<dyn Stream<Item = Result<AudioChunk, Status>> + Send>>;
+
+    async fn synthesize_stream(
+        &self,
+        request: Request<SynthesizeRequest>,
+    ) -> Result<Response<Self::SynthesizeStreamStream>, Status> {
+        let request_id = Uuid::new_v4().to_string();
+        let mut req = speech_request(request.into_inner());
+        // Streamed chunks are always raw PCM; the requested format is not used
+        req.response_format = Some("pcm".to_string());
+        let params = validate_speech_request(&self.state, req).map_err(status_from_error)?;
+
+        debug!(request_id = %request_id, voice = %params.voice, "Received gRPC streaming speech request");
+
+        let backend = self.state.backend_for(params.quality).clone();
+        let sample_rate = params.sample_rate.unwrap_or_else(|| backend.sample_rate());
+        let substituted_voice = params.voice_substituted.then(|| params.voice.clone());
+        let rx = spawn_pcm_chunks(
+            backend.clone(),
+            params.input,
+            params.voice,
+            params.speed,
+            params.initial_silence,
+            request_id,
+            StreamOptions {
+                parallelism: backend.worker_limit(),
+                retry_policy: self.state.chunk_retry,
+                trailers: false,
+                trailing_silence: params.trailing_silence,
+                sample_rate: params.sample_rate,
+                effects: params.effects.clone(),
+                // Stream chunks are documented as 16-bit PCM
+                bit_depth: BitDepth::Int16,
+                adaptive_chunks: self.state.adaptive_chunks,
+                chunk_rules: params.chunk_rules.clone(),
+                language: params.language,
+                speed_curve: params.speed_curve.clone(),
+                emphasis: params.emphasis,
+                sentence_gap_ms: params.sentence_gap_ms,
+                comfort_noise: params.comfort_noise,
+                max_duration_s: params.max_duration_s,
+            },
+        );
+
+        let mut index = 0u32;
+        let stream = ReceiverStream::new(rx).filter_map(move |chunk| match chunk {
+            // Skipped chunks arrive empty and carry no audio
+            Ok(pcm) if pcm.is_empty() => None,
+            Ok(pcm) => {
+                let chunk = AudioChunk {
+                    pcm: pcm.to_vec(),
+                    sample_rate,
+                    index,
+                };
+                index += 1;
+                Some(Ok(chunk))
+            }
+            Err(e) => {
+                // A stream stopped at its duration cap carries the error code like a refused request
+                let message = e.to_string();
+                Some(Err(
+                    match e.into_inner().map(|inner| inner.downcast::<AppError>()) {
+                        Some(Ok(err)) => status_from_error(*err),
+                        _ => Status::internal(message),
+                    },
+                ))
+            }
+        });
+
+        let mut response: Response<Self::SynthesizeStreamStream> = Response::new(Box::pin(stream));
+        mark_substituted_voice(&mut response, substituted_voice.as_deref());
+        Ok(response)
+    }
+}
+
+/// Name the fallback voice in the response metadata when it replaced the requested one
+fn mark_substituted_voice<T>(response: &mut Response<T>, voice: Option<&str>) {
+    if let Some(Ok(voice)) = voice.map(MetadataValue::try_from) {
+        response
+            .metadata_mut()
+            .insert(VOICE_SUBSTITUTED_METADATA, voice);
+    }
+}
+
+/// Check the bearer token with the same rules as the HTTP auth middleware
+fn check_api_key(api_keys: &ApiKeys, request: Request<()>) -> Result<Request<()>, Status> {
+    if !api_keys.is_required() {
+        return Ok(request);
+    }
+
+    let provided_key = request
+        .metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match provided_key {
+        Some(key) if api_keys.verify(key) => Ok(request),
+        _ => {
+            warn!("Missing or invalid gRPC authorization metadata");
+            Err(status_from_error(AppError::Unauthorized))
+        }
+    }
+}
+
+/// Serve the gRPC speech service until `shutdown` resolves
+pub async fn serve(
+    state: AppState,
+    addr: SocketAddr,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    let api_keys = state.api_keys.clone();
+    let service = SpeechServer::with_interceptor(SpeechService { state }, move |request| {
+        check_api_key(&api_keys, request)
+    });
+
+    tonic::transport::Server::builder()
+        .add_service(service)
+        .serve_with_shutdown(addr, shutdown)
+        .await
+        .context("gRPC server error")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speech_request_defaults() {
+        let req = speech_request(SynthesizeRequest {
+            input: "Hello".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(req.model, "kokoro");
+        assert_eq!(req.voice.as_deref(), Some("af_alloy"));
+        assert_eq!(req.response_format.as_deref(), Some("wav"));
+        assert_eq!(req.speed, None);
+        assert_eq!(req.initial_silence, None);
+    }
+
+    #[test]
+    fn test_status_from_error() {
+        let status = status_from_error(AppError::voice_not_found("x"));
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert_eq!(
+            status.metadata().get(ERROR_CODE_METADATA).unwrap(),
+            "voice_not_found"
+        );
+
+        let status = status_from_error(AppError::overloaded("busy"));
+        assert_eq!(status.code(), Code::ResourceExhausted);
+    }
+
+    #[test]
+    fn test_check_api_key() {
+        let mut request = Request::new(());
+        request
+            .metadata_mut()
+            .insert("authorization", MetadataValue::from_static("Bearer secret"));
+        let api_keys = ApiKeys::new(Some("secret".to_string()));
+        assert!(check_api_key(&api_keys, request).is_ok());
+
+        let status = check_api_key(&api_keys, Request::new(())).unwrap_err();
+        assert_eq!(status.code(), Code::Unauthenticated);
+        assert!(check_api_key(&ApiKeys::new(None), Request::new(())).is_ok());
+    }
+}
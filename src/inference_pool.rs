@@ -0,0 +1,125 @@
+use anyhow::{Context, Result};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use tracing::{debug, warn};
+
+type Task = Box<dyn FnOnce() + Send>;
+
+/// Dedicated threads that run inference, apart from Tokio's blocking pool so model calls do not
+/// compete with file I/O for threads.
+///
+/// Threads are started on demand up to the requested size and live for the rest of the process.
+/// With `cores` set, thread `i` is pinned to `cores[i % cores.len()]`; this pins the thread
+/// driving each inference call, while ONNX Runtime's intra-op threads are scheduled by the
+/// session.
+pub struct InferencePool {
+    sender: mpsc::Sender<Task>,
+    receiver: Arc<Mutex<mpsc::Receiver<Task>>>,
+    cores: Vec<usize>,
+    /// Threads started so far
+    threads: Mutex<usize>,
+}
+
+impl InferencePool {
+    pub fn new(threads: usize, cores: Vec<usize>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let pool = Self {
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            cores,
+            threads: Mutex::new(0),
+        };
+        pool.ensure_threads(threads);
+        pool
+    }
+
+    /// Threads currently running
+    pub fn threads(&self) -> usize {
+        *self.threads.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Start threads until at least `threads` are running
+    pub fn ensure_threads(&self, threads: usize) {
+        let mut started = self.threads.lock().unwrap_or_else(PoisonError::into_inner);
+        while *started < threads {
+            let index = *started;
+            let core = (!self.cores.is_empty()).then(|| self.cores[index % self.cores.len()]);
+            let receiver = self.receiver.clone();
+            let spawned = std::thread::Builder::new()
+                .name(format!("kokoro-inference-{}", index))
+                .spawn(move || {
+                    if let Some(core) = core {
+                        pin_to_core(index, core);
+                    }
+                    loop {
+                        // The lock is only held while waiting, never while a task runs
+                        let task = receiver
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .recv();
+                        match task {
+                            Ok(task) => task(),
+                            Err(_) => return,
+                        }
+                    }
+                });
+            if let Err(e) = spawned {
+                warn!(error = %e, "Failed to start inference thread");
+                return;
+            }
+            *started += 1;
+        }
+    }
+
+    /// Run `task` on a pool thread and wait for its result
+    pub async fn run<T: Send + 'static>(
+        &self,
+        task: impl FnOnce() -> T + Send + 'static,
+    ) -> Result<T> {
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        self.sender
+            .send(Box::new(move || {
+                let _ = sender.send(catch_unwind(AssertUnwindSafe(task)));
+            }))
+            .ok()
+            .context("Inference pool has stopped")?;
+        match receiver.await.context("Inference pool has stopped")? {
+            Ok(result) => Ok(result),
+            Err(_) => anyhow::bail!("Inference task panicked"),
+        }
+    }
+}
+
+fn pin_to_core(thread: usize, core: usize) {
+    if core_affinity::set_for_current(core_affinity::CoreId { id: core }) {
+        debug!(thread, core, "Pinned inference thread");
+    } else {
+        warn!(
+            thread,
+            core, "Could not pin inference thread; it will float across cores"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_runs_tasks_on_named_threads() {
+        let pool = InferencePool::new(2, Vec::new());
+        assert_eq!(pool.threads(), 2);
+        let name = pool
+            .run(|| std::thread::current().name().map(str::to_string))
+            .await
+            .unwrap();
+        assert!(name.unwrap().starts_with("kokoro-inference-"));
+
+        assert!(pool.run(|| panic!("engine crashed")).await.is_err());
+        assert_eq!(pool.run(|| 2 + 2).await.unwrap(), 4);
+
+        pool.ensure_threads(3);
+        pool.ensure_threads(1);
+        assert_eq!(pool.threads(), 3);
+    }
+}
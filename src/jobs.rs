@@ -0,0 +1,1211 @@
+use crate::{
+    api::AppState,
+    cache::content_type_for_format,
+    error::{ApiResult, AppError},
+};
+use anyhow::Context;
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncReadExt;
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// File in the jobs directory that jobs are recorded in
+const DATABASE_FILE: &str = "jobs.sqlite3";
+
+/// Extension of finished results in the jobs directory
+const RESULT_EXTENSION: &str = "zip";
+
+/// Read size when sending a result file
+const RESULT_READ_BYTES: usize = 64 * 1024;
+
+/// How often finished jobs past the retention period are looked for
+const EXPIRY_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "queued" => Some(Self::Queued),
+            "running" => Some(Self::Running),
+            "completed" => Some(Self::Completed),
+            "failed" => Some(Self::Failed),
+            "cancelled" | "canceled" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+
+    /// The job completed, failed, or was cancelled, and will not change again
+    pub fn is_finished(self) -> bool {
+        !matches!(self, Self::Queued | Self::Running)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Response body for GET /v1/audio/jobs
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobList {
+    pub object: &'static str,
+    /// Most recently created first
+    pub data: Vec<Job>,
+}
+
+/// Query parameters for GET /v1/audio/jobs
+#[derive(Debug, Deserialize)]
+pub struct ListJobsQuery {
+    #[serde(default)]
+    status: Option<String>,
+}
+
+/// Response body for GET /v1/audio/jobs/{job_id}
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Job {
+    pub id: String,
+    pub object: String,
+    /// What the job produces, e.g. "audiobook"
+    pub kind: String,
+    /// Podcast feed the job's chapters are published in once it completes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed: Option<String>,
+    pub status: JobStatus,
+    /// Unix timestamp in seconds
+    pub created_at: u64,
+    /// Unix timestamp in seconds, once the job completed, failed, or was cancelled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+    pub progress: JobProgress,
+    /// Per-chapter status of an audiobook job
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chapters: Vec<ChapterProgress>,
+    /// Where to download the result once the job has completed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+pub struct JobProgress {
+    pub completed: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChapterProgress {
+    pub index: usize,
+    pub title: String,
+    pub status: JobStatus,
+    /// Length of the chapter's audio once synthesized
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+}
+
+struct JobEntry {
+    job: Job,
+    /// Set to `true` when the job is cancelled
+    cancel: watch::Sender<bool>,
+}
+
+/// Jobs in memory, and the database they are recorded in
+struct Jobs {
+    entries: HashMap<String, JobEntry>,
+    /// Runner input of the jobs that were queued when the server started, until they resume
+    resumable: HashMap<String, String>,
+    /// Record runner input, so queued jobs survive a restart; off while redacting request text
+    keep_input: bool,
+    writer: Writer,
+}
+
+impl Jobs {
+    fn insert(&mut self, job: Job, spec: &str) {
+        match serde_json::to_string(&job) {
+            Ok(json) => self.writer.send(Write::Insert {
+                id: job.id.clone(),
+                job: json,
+                spec: self.keep_input.then(|| spec.to_string()),
+            }),
+            Err(e) => warn!(job_id = %job.id, error = %e, "Failed to record job"),
+        }
+        self.entries.insert(
+            job.id.clone(),
+            JobEntry {
+                job,
+                cancel: watch::Sender::new(false),
+            },
+        );
+    }
+
+    /// Record a job's current state; a finished job no longer needs its runner input
+    fn save(&self, id: &str) {
+        let Some(entry) = self.entries.get(id) else {
+            return;
+        };
+        match serde_json::to_string(&entry.job) {
+            Ok(json) => self.writer.send(Write::Update {
+                id: id.to_string(),
+                job: json,
+                finished: entry.job.status.is_finished(),
+            }),
+            Err(e) => warn!(job_id = %id, error = %e, "Failed to record job"),
+        }
+    }
+
+    fn remove(&mut self, id: &str) {
+        self.entries.remove(id);
+        self.resumable.remove(id);
+        self.writer.send(Write::Delete { id: id.to_string() });
+    }
+}
+
+/// A change to the job database
+enum Write {
+    Insert {
+        id: String,
+        job: String,
+        spec: Option<String>,
+    },
+    Update {
+        id: String,
+        job: String,
+        finished: bool,
+    },
+    Delete {
+        id: String,
+    },
+}
+
+impl Write {
+    fn apply(&self, db: &Connection) -> rusqlite::Result<usize> {
+        match self {
+            Self::Insert { id, job, spec } => db.execute(
+                "INSERT INTO jobs (id, job, spec) VALUES (?1, ?2, ?3)",
+                params![id, job, spec],
+            ),
+            Self::Update {
+                id,
+                job,
+                finished: true,
+            } => db.execute(
+                "UPDATE jobs SET job = ?2, spec = NULL WHERE id = ?1",
+                params![id, job],
+            ),
+            Self::Update { id, job, .. } => {
+                db.execute("UPDATE jobs SET job = ?2 WHERE id = ?1", params![id, job])
+            }
+            Self::Delete { id } => db.execute("DELETE FROM jobs WHERE id = ?1", params![id]),
+        }
+    }
+
+    fn id(&self) -> &str {
+        match self {
+            Self::Insert { id, .. } | Self::Update { id, .. } | Self::Delete { id } => id,
+        }
+    }
+}
+
+/// Applies writes to the job database in order on a thread of its own, so recording a job
+/// never blocks an async worker on disk
+struct Writer {
+    sender: Option<mpsc::Sender<Write>>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Writer {
+    fn start(db: Connection) -> anyhow::Result<Self> {
+        let (sender, receiver) = mpsc::channel::<Write>();
+        let thread = std::thread::Builder::new()
+            .name("job-database".to_string())
+            .spawn(move || {
+                for write in receiver {
+                    if let Err(e) = write.apply(&db) {
+                        warn!(job_id = %write.id(), error = %e, "Failed to record job");
+                    }
+                }
+            })
+            .context("Failed to start job database writer")?;
+        Ok(Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        })
+    }
+
+    fn send(&self, write: Write) {
+        if let Some(sender) = &self.sender {
+            if sender.send(write).is_err() {
+                warn!("Job database writer has stopped");
+            }
+        }
+    }
+}
+
+impl Drop for Writer {
+    /// Finish the writes already made, so the database is current once the store is gone
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Place in the job runner's queue, taken when a job is submitted so that jobs run in the
+/// order they were submitted. Dropping it leaves the queue.
+pub struct RunTicket {
+    queue: Arc<watch::Sender<BTreeSet<u64>>>,
+    number: u64,
+}
+
+impl RunTicket {
+    /// Wait until every job queued before this one has finished or left the queue; the job
+    /// runs while the ticket is held
+    pub async fn turn(&self) -> anyhow::Result<()> {
+        self.queue
+            .subscribe()
+            .wait_for(|queue| queue.first() == Some(&self.number))
+            .await
+            .map(drop)
+            .context("Job runner is closed")
+    }
+}
+
+impl Drop for RunTicket {
+    fn drop(&mut self) {
+        self.queue.send_modify(|queue| {
+            queue.remove(&self.number);
+        });
+    }
+}
+
+/// Background jobs and their results, kept for a retention period after they finish.
+///
+/// Jobs are recorded in a SQLite database in `dir`, along with what their runner needs to start
+/// them, so queued jobs run and finished jobs stay available after a restart. Results are
+/// written to files in `dir` so long documents are not held in memory. Jobs run one at a time
+/// and synthesize one chunk at a time, leaving the other inference workers to interactive
+/// requests.
+pub struct JobStore {
+    jobs: Mutex<Jobs>,
+    dir: PathBuf,
+    retention: Duration,
+    /// Tickets of the jobs waiting for the runner or running; the first one runs
+    runner: Arc<watch::Sender<BTreeSet<u64>>>,
+    next_ticket: AtomicU64,
+}
+
+impl JobStore {
+    /// Open the job database in `dir`, creating it if needed. Without `keep_input`, the text
+    /// of queued jobs is not written to disk, and jobs unfinished at a restart fail.
+    ///
+    /// Jobs that were running when the server stopped are queued again from the start, since
+    /// their partial results are discarded.
+    pub fn open(dir: PathBuf, retention: Duration, keep_input: bool) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join(DATABASE_FILE);
+        let db = Connection::open(&path)
+            .with_context(|| format!("Failed to open job database {}", path.display()))?;
+        Self::with_database(dir, retention, keep_input, db)
+    }
+
+    fn with_database(
+        dir: PathBuf,
+        retention: Duration,
+        keep_input: bool,
+        db: Connection,
+    ) -> anyhow::Result<Self> {
+        db.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA synchronous = NORMAL;
+             CREATE TABLE IF NOT EXISTS jobs (
+                 id TEXT PRIMARY KEY,
+                 job TEXT NOT NULL,
+                 spec TEXT
+             );",
+        )
+        .context("Failed to set up job database")?;
+
+        let rows: Vec<(String, String, Option<String>)> = db
+            .prepare("SELECT id, job, spec FROM jobs")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<_>>()
+            .context("Failed to read job database")?;
+        let mut jobs = Jobs {
+            entries: HashMap::new(),
+            resumable: HashMap::new(),
+            keep_input,
+            writer: Writer::start(db)?,
+        };
+        for (id, json, spec) in rows {
+            match serde_json::from_str::<Job>(&json) {
+                Ok(mut job) => {
+                    let unfinished = !job.status.is_finished();
+                    let mut changed = job.status == JobStatus::Running;
+                    if changed {
+                        restart(&mut job);
+                    }
+                    match spec.filter(|_| unfinished) {
+                        Some(spec) => {
+                            jobs.resumable.insert(id.clone(), spec);
+                        }
+                        None if unfinished => {
+                            changed = true;
+                            job.status = JobStatus::Failed;
+                            job.finished_at = Some(unix_now());
+                            job.error = Some(
+                                "Interrupted by a restart; job input is not kept while request \
+                                 text is redacted"
+                                    .to_string(),
+                            );
+                        }
+                        None => {}
+                    }
+                    jobs.entries.insert(
+                        id.clone(),
+                        JobEntry {
+                            job,
+                            cancel: watch::Sender::new(false),
+                        },
+                    );
+                    if changed {
+                        jobs.save(&id);
+                    }
+                }
+                Err(e) => {
+                    warn!(job_id = %id, error = %e, "Discarding unreadable job record");
+                    jobs.remove(&id);
+                }
+            }
+        }
+
+        let store = Self {
+            jobs: Mutex::new(jobs),
+            dir,
+            retention,
+            runner: Arc::new(watch::Sender::new(BTreeSet::new())),
+            next_ticket: AtomicU64::new(0),
+        };
+        store.expire();
+        Ok(store)
+    }
+
+    /// Delete partial results, and results of jobs the database does not know, e.g. because
+    /// they expired while the server was stopped
+    pub fn remove_leftovers(&self) {
+        let completed: HashSet<PathBuf> = self
+            .jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entries
+            .values()
+            .filter(|entry| entry.job.status == JobStatus::Completed)
+            .map(|entry| self.result_path(&entry.job.id))
+            .collect();
+        if let Ok(entries) = std::fs::read_dir(&self.dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let leftover = path.extension().is_some_and(|ext| {
+                    ext == "part" || (ext == RESULT_EXTENSION && !completed.contains(&path))
+                });
+                if leftover && path.is_file() {
+                    let _ = std::fs::remove_file(&path);
+                }
+            }
+        }
+    }
+
+    pub fn dir(&self) -> &std::path::Path {
+        &self.dir
+    }
+
+    /// Register a queued job with one entry per chapter title. `spec` is what the job's
+    /// runner needs to start it again after a restart.
+    pub fn create(
+        &self,
+        kind: &str,
+        chapters: Vec<String>,
+        feed: Option<String>,
+        spec: &str,
+    ) -> Job {
+        self.expire();
+
+        let job = Job {
+            id: format!("job_{}", Uuid::new_v4().simple()),
+            object: "audio.job".to_string(),
+            kind: kind.to_string(),
+            feed,
+            status: JobStatus::Queued,
+            created_at: unix_now(),
+            finished_at: None,
+            progress: JobProgress {
+                completed: 0,
+                total: chapters.len(),
+            },
+            chapters: chapters
+                .into_iter()
+                .enumerate()
+                .map(|(index, title)| ChapterProgress {
+                    index,
+                    title,
+                    status: JobStatus::Queued,
+                    duration_ms: None,
+                })
+                .collect(),
+            result_url: None,
+            error: None,
+        };
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(job.clone(), spec);
+        job
+    }
+
+    pub fn get(&self, id: &str) -> Option<Job> {
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entries
+            .get(id)
+            .map(|entry| entry.job.clone())
+    }
+
+    /// Queued jobs of `kind` that were waiting when the server started, with their runner
+    /// input, oldest first. Each is handed out once, for resuming it at startup.
+    pub fn queued(&self, kind: &str) -> Vec<(String, String)> {
+        let mut jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+        let ids: Vec<String> = jobs
+            .resumable
+            .keys()
+            .filter(|id| {
+                jobs.entries.get(*id).is_some_and(|entry| {
+                    entry.job.kind == kind && entry.job.status == JobStatus::Queued
+                })
+            })
+            .cloned()
+            .collect();
+        let mut queued: Vec<(u64, String, String)> = ids
+            .into_iter()
+            .filter_map(|id| {
+                let spec = jobs.resumable.remove(&id)?;
+                Some((jobs.entries.get(&id)?.job.created_at, id, spec))
+            })
+            .collect();
+        queued.sort();
+        queued.into_iter().map(|(_, id, spec)| (id, spec)).collect()
+    }
+
+    /// Known jobs, optionally only those with `status`, most recently created first
+    pub fn list(&self, status: Option<JobStatus>) -> Vec<Job> {
+        self.expire();
+
+        let mut jobs: Vec<Job> = self
+            .jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entries
+            .values()
+            .map(|entry| &entry.job)
+            .filter(|job| status.is_none_or(|status| job.status == status))
+            .cloned()
+            .collect();
+        jobs.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        jobs
+    }
+
+    /// Completed jobs published in `feed`, most recently finished first
+    pub fn in_feed(&self, feed: &str) -> Vec<Job> {
+        self.expire();
+
+        let mut jobs: Vec<Job> = self
+            .jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entries
+            .values()
+            .map(|entry| &entry.job)
+            .filter(|job| job.status == JobStatus::Completed && job.feed.as_deref() == Some(feed))
+            .cloned()
+            .collect();
+        jobs.sort_by_key(|job| std::cmp::Reverse(job.finished_at));
+        jobs
+    }
+
+    /// Join the job runner's queue; jobs run in the order they joined
+    pub fn enqueue(&self) -> RunTicket {
+        let mut number = 0;
+        self.runner.send_modify(|queue| {
+            number = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+            queue.insert(number);
+        });
+        RunTicket {
+            queue: self.runner.clone(),
+            number,
+        }
+    }
+
+    /// Join the job runner's queue and wait for a turn
+    pub async fn run_slot(&self) -> anyhow::Result<RunTicket> {
+        let ticket = self.enqueue();
+        ticket.turn().await?;
+        Ok(ticket)
+    }
+
+    /// Update a job that has not finished; a cancelled job's runner may still report progress
+    /// before it stops
+    pub fn update(&self, id: &str, update: impl FnOnce(&mut Job)) {
+        let mut jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(entry) = jobs
+            .entries
+            .get_mut(id)
+            .filter(|entry| !entry.job.status.is_finished())
+        {
+            update(&mut entry.job);
+            jobs.save(id);
+        }
+    }
+
+    /// Resolves once the job is cancelled; `None` for an unknown job
+    pub fn cancellation(&self, id: &str) -> Option<watch::Receiver<bool>> {
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entries
+            .get(id)
+            .map(|entry| entry.cancel.subscribe())
+    }
+
+    /// Cancel a queued or running job, signalling its runner to stop. Returns `None` for an
+    /// unknown job and `Err` with the job when it had already finished.
+    pub fn cancel(&self, id: &str) -> Option<Result<Job, Job>> {
+        let mut jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+        let entry = jobs.entries.get_mut(id)?;
+        if entry.job.status.is_finished() {
+            return Some(Err(entry.job.clone()));
+        }
+        entry.job.status = JobStatus::Cancelled;
+        for chapter in &mut entry.job.chapters {
+            if !chapter.status.is_finished() {
+                chapter.status = JobStatus::Cancelled;
+            }
+        }
+        entry.job.finished_at = Some(unix_now());
+        entry.cancel.send_replace(true);
+        let job = entry.job.clone();
+        jobs.save(id);
+        Some(Ok(job))
+    }
+
+    /// Mark the job completed; its result must already be at `result_path(id)`
+    pub fn complete(&self, id: &str) {
+        self.finish(id, |job| {
+            job.status = JobStatus::Completed;
+            job.result_url = Some(format!("/v1/audio/jobs/{}/content", job.id));
+        });
+    }
+
+    pub fn fail(&self, id: &str, error: String) {
+        self.finish(id, |job| {
+            job.status = JobStatus::Failed;
+            job.error = Some(error);
+        });
+    }
+
+    fn finish(&self, id: &str, update: impl FnOnce(&mut Job)) {
+        let mut jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(entry) = jobs
+            .entries
+            .get_mut(id)
+            .filter(|entry| !entry.job.status.is_finished())
+        {
+            update(&mut entry.job);
+            entry.job.finished_at = Some(unix_now());
+            jobs.save(id);
+        }
+    }
+
+    /// Unix time a finished job and its result expire
+    pub fn expires_at(&self, job: &Job) -> u64 {
+        job.finished_at.unwrap_or(job.created_at) + self.retention.as_secs()
+    }
+
+    /// File a job's result is written to
+    pub fn result_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", id, RESULT_EXTENSION))
+    }
+
+    /// Expire finished jobs in the background, so their results are deleted on time even
+    /// while no one creates or lists jobs
+    pub fn start_expiry_sweep(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(EXPIRY_SWEEP_INTERVAL);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let store = self.clone();
+                let _ = tokio::task::spawn_blocking(move || store.expire()).await;
+            }
+        });
+    }
+
+    /// Forget jobs that finished longer than the retention period ago and delete their results
+    fn expire(&self) {
+        let oldest = unix_now().saturating_sub(self.retention.as_secs());
+        let expired: Vec<String> = {
+            let mut jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+            let expired: Vec<String> = jobs
+                .entries
+                .iter()
+                .filter(|(_, entry)| {
+                    entry
+                        .job
+                        .finished_at
+                        .is_some_and(|finished| finished <= oldest)
+                })
+                .map(|(id, _)| id.clone())
+                .collect();
+            for id in &expired {
+                jobs.remove(id);
+            }
+            expired
+        };
+        for id in expired {
+            debug!(job_id = %id, "Job expired");
+            let path = self.result_path(&id);
+            if let Err(e) = std::fs::remove_file(&path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!(path = %path.display(), error = %e, "Failed to delete job result");
+                }
+            }
+        }
+    }
+}
+
+/// Put a job interrupted by a restart back in the queue, to run again from the first chapter
+fn restart(job: &mut Job) {
+    job.status = JobStatus::Queued;
+    job.progress.completed = 0;
+    for chapter in &mut job.chapters {
+        chapter.status = JobStatus::Queued;
+        chapter.duration_ms = None;
+    }
+}
+
+/// File name and size of each chapter in a job's result archive, in reading order
+pub fn result_chapters(path: &std::path::Path) -> anyhow::Result<Vec<(String, u64)>> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+    (0..archive.len())
+        .map(|index| {
+            let entry = archive.by_index(index)?;
+            Ok((entry.name().to_string(), entry.size()))
+        })
+        .collect()
+}
+
+/// File name and audio of one chapter in a job's result archive
+fn read_result_chapter(
+    path: &std::path::Path,
+    index: usize,
+) -> anyhow::Result<Option<(String, Vec<u8>)>> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+    if index >= archive.len() {
+        return Ok(None);
+    }
+    let mut entry = archive.by_index(index)?;
+    let mut audio = Vec::with_capacity(entry.size() as usize);
+    std::io::Read::read_to_end(&mut entry, &mut audio)?;
+    Ok(Some((entry.name().to_string(), audio)))
+}
+
+/// Jobs directory used when `KOKORO_JOBS_DIR` is not set
+pub fn default_jobs_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("kokoro-openai-server")
+        .join("jobs")
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Report a job's status and progress
+#[utoipa::path(
+    get,
+    path = "/v1/audio/jobs/{job_id}",
+    tag = "jobs",
+    params(("job_id" = String, Path, description = "ID returned when the job was created")),
+    responses(
+        (status = 200, description = "Job status and progress", body = Job),
+        (status = 401, description = "Missing or invalid API key", body = crate::error::OpenAIError),
+        (status = 404, description = "Unknown or expired job", body = crate::error::OpenAIError)
+    )
+)]
+pub async fn get_job_handler(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> ApiResult<Json<Job>> {
+    state
+        .jobs
+        .get(&job_id)
+        .map(Json)
+        .ok_or_else(|| AppError::job_not_found(job_id))
+}
+
+/// List jobs, optionally filtered by status
+#[utoipa::path(
+    get,
+    path = "/v1/audio/jobs",
+    tag = "jobs",
+    params(("status" = Option<String>, Query, description = "Only jobs with this status: queued, running, completed, failed, or cancelled")),
+    responses(
+        (status = 200, description = "Jobs, most recently created first", body = JobList),
+        (status = 400, description = "Unknown status", body = crate::error::OpenAIError),
+        (status = 401, description = "Missing or invalid API key", body = crate::error::OpenAIError)
+    )
+)]
+pub async fn list_jobs_handler(
+    State(state): State<AppState>,
+    Query(query): Query<ListJobsQuery>,
+) -> ApiResult<Json<JobList>> {
+    let status = match query.status.as_deref() {
+        Some(name) => Some(JobStatus::from_name(name).ok_or_else(|| {
+            AppError::invalid_request(format!(
+                "status '{}' not supported. Supported values: queued, running, completed, failed, cancelled",
+                name
+            ))
+        })?),
+        None => None,
+    };
+    Ok(Json(JobList {
+        object: "list",
+        data: state.jobs.list(status),
+    }))
+}
+
+/// Cancel a queued or running job; synthesis stops and any partial result is deleted
+#[utoipa::path(
+    delete,
+    path = "/v1/audio/jobs/{job_id}",
+    tag = "jobs",
+    params(("job_id" = String, Path, description = "ID returned when the job was created")),
+    responses(
+        (status = 200, description = "The cancelled job", body = Job),
+        (status = 401, description = "Missing or invalid API key", body = crate::error::OpenAIError),
+        (status = 404, description = "Unknown or expired job", body = crate::error::OpenAIError),
+        (status = 409, description = "Job has already finished", body = crate::error::OpenAIError)
+    )
+)]
+pub async fn cancel_job_handler(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> ApiResult<Json<Job>> {
+    match state.jobs.cancel(&job_id) {
+        Some(Ok(job)) => {
+            info!(job_id = %job_id, "Job cancelled");
+            Ok(Json(job))
+        }
+        Some(Err(job)) => Err(AppError::conflict(format!(
+            "Job '{}' has already {}",
+            job_id,
+            match job.status {
+                JobStatus::Cancelled => "been cancelled",
+                status => status.name(),
+            }
+        ))),
+        None => Err(AppError::job_not_found(job_id)),
+    }
+}
+
+/// Download the result of a completed job
+#[utoipa::path(
+    get,
+    path = "/v1/audio/jobs/{job_id}/content",
+    tag = "jobs",
+    params(("job_id" = String, Path, description = "ID returned when the job was created")),
+    responses(
+        (status = 200, description = "Zip archive of the job's audio", content_type = "application/zip"),
+        (status = 401, description = "Missing or invalid API key", body = crate::error::OpenAIError),
+        (status = 404, description = "Unknown or expired job", body = crate::error::OpenAIError),
+        (status = 409, description = "Job has not completed", body = crate::error::OpenAIError)
+    )
+)]
+pub async fn job_content_handler(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> ApiResult<Response> {
+    let job = state
+        .jobs
+        .get(&job_id)
+        .ok_or_else(|| AppError::job_not_found(&job_id))?;
+    match job.status {
+        JobStatus::Completed => {}
+        JobStatus::Failed => {
+            return Err(AppError::conflict(format!("Job '{}' failed", job_id)));
+        }
+        JobStatus::Cancelled => {
+            return Err(AppError::conflict(format!(
+                "Job '{}' was cancelled",
+                job_id
+            )));
+        }
+        JobStatus::Queued | JobStatus::Running => {
+            return Err(AppError::conflict(format!(
+                "Job '{}' has not completed yet",
+                job_id
+            )));
+        }
+    }
+
+    let path = state.jobs.result_path(&job_id);
+    let mut file = tokio::fs::File::open(&path).await.map_err(|e| {
+        warn!(path = %path.display(), error = %e, "Failed to open job result");
+        AppError::Internal
+    })?;
+    let length = file.metadata().await.map_err(|_| AppError::Internal)?.len();
+
+    let body = async_stream::stream! {
+        let mut buffer = vec![0u8; RESULT_READ_BYTES];
+        loop {
+            match file.read(&mut buffer).await {
+                Ok(0) => break,
+                Ok(read) => yield Ok(Bytes::copy_from_slice(&buffer[..read])),
+                Err(e) => {
+                    yield Err(e);
+                    break;
+                }
+            }
+        }
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_LENGTH, length.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.{}\"", job_id, RESULT_EXTENSION),
+            ),
+        ],
+        Body::from_stream(body),
+    )
+        .into_response())
+}
+
+/// Download one chapter of a completed job, as podcast feeds link to it
+#[utoipa::path(
+    get,
+    path = "/v1/audio/jobs/{job_id}/chapters/{index}",
+    tag = "jobs",
+    params(
+        ("job_id" = String, Path, description = "ID returned when the job was created"),
+        ("index" = usize, Path, description = "Chapter index, from 0")
+    ),
+    responses(
+        (status = 200, description = "The chapter's audio file", content_type = "audio/mpeg"),
+        (status = 401, description = "Missing or invalid API key", body = crate::error::OpenAIError),
+        (status = 404, description = "Unknown or expired job, or no such chapter", body = crate::error::OpenAIError),
+        (status = 409, description = "Job has not completed", body = crate::error::OpenAIError)
+    )
+)]
+pub async fn job_chapter_handler(
+    State(state): State<AppState>,
+    Path((job_id, index)): Path<(String, usize)>,
+) -> ApiResult<Response> {
+    let job = state
+        .jobs
+        .get(&job_id)
+        .ok_or_else(|| AppError::job_not_found(&job_id))?;
+    if job.status != JobStatus::Completed {
+        return Err(AppError::conflict(format!(
+            "Job '{}' has not completed",
+            job_id
+        )));
+    }
+
+    let path = state.jobs.result_path(&job_id);
+    let chapter = tokio::task::spawn_blocking(move || read_result_chapter(&path, index))
+        .await
+        .map_err(|_| AppError::Internal)?
+        .map_err(|e| {
+            warn!(job_id = %job_id, error = %e, "Failed to read job result");
+            AppError::Internal
+        })?;
+    let Some((name, audio)) = chapter else {
+        return Err(AppError::job_not_found(format!(
+            "{}/chapters/{}",
+            job_id, index
+        )));
+    };
+
+    let format = name.rsplit_once('.').map_or("", |(_, extension)| extension);
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                content_type_for_format(format)
+                    .unwrap_or("application/octet-stream")
+                    .to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{}\"", name.replace('"', "'")),
+            ),
+        ],
+        audio,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RETENTION: Duration = Duration::from_secs(60 * 60);
+
+    fn store() -> JobStore {
+        JobStore::with_database(
+            std::env::temp_dir().join(format!("kokoro-jobs-{}", Uuid::new_v4())),
+            RETENTION,
+            true,
+            Connection::open_in_memory().unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_job_lifecycle() {
+        let store = store();
+        let job = store.create(
+            "audiobook",
+            vec!["One".to_string(), "Two".to_string()],
+            Some("news".to_string()),
+            "{}",
+        );
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(job.progress.total, 2);
+        assert!(job.id.starts_with("job_"));
+
+        store.update(&job.id, |job| {
+            job.status = JobStatus::Running;
+            job.chapters[0].status = JobStatus::Completed;
+            job.chapters[0].duration_ms = Some(1_500);
+            job.progress.completed = 1;
+        });
+        let running = store.get(&job.id).unwrap();
+        assert_eq!(running.status, JobStatus::Running);
+        assert_eq!(running.chapters[0].duration_ms, Some(1_500));
+        assert!(running.result_url.is_none());
+
+        store.complete(&job.id);
+        let completed = store.get(&job.id).unwrap();
+        assert_eq!(completed.status, JobStatus::Completed);
+        assert!(completed.finished_at.is_some());
+        assert_eq!(
+            completed.result_url.as_deref(),
+            Some(format!("/v1/audio/jobs/{}/content", job.id).as_str())
+        );
+
+        let failed = store.create("audiobook", vec![], Some("news".to_string()), "{}");
+        store.fail(&failed.id, "Backend error".to_string());
+        assert_eq!(
+            store.get(&failed.id).unwrap().error.as_deref(),
+            Some("Backend error")
+        );
+        assert!(store.get("job_missing").is_none());
+
+        let feed = store.in_feed("news");
+        assert_eq!(feed.len(), 1);
+        assert_eq!(feed[0].id, job.id);
+        assert!(store.in_feed("other").is_empty());
+    }
+
+    #[test]
+    fn test_cancel_and_list() {
+        let store = store();
+        let first = store.create("audiobook", vec!["One".to_string()], None, "{}");
+        let second = store.create(
+            "audiobook",
+            vec!["One".to_string(), "Two".to_string()],
+            None,
+            "{}",
+        );
+        store.update(&second.id, |job| {
+            job.status = JobStatus::Running;
+            job.chapters[0].status = JobStatus::Completed;
+        });
+        let mut cancelled = store.cancellation(&second.id).unwrap();
+        assert!(!*cancelled.borrow_and_update());
+
+        let job = store.cancel(&second.id).unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Cancelled);
+        assert_eq!(job.chapters[0].status, JobStatus::Completed);
+        assert_eq!(job.chapters[1].status, JobStatus::Cancelled);
+        assert!(job.finished_at.is_some());
+        assert!(*cancelled.borrow_and_update());
+
+        // The runner's late reports do not undo the cancellation
+        store.update(&second.id, |job| job.progress.completed = 2);
+        store.complete(&second.id);
+        let job = store.get(&second.id).unwrap();
+        assert_eq!(job.status, JobStatus::Cancelled);
+        assert_eq!(job.progress.completed, 0);
+        assert!(store.cancel(&second.id).unwrap().is_err());
+        assert!(store.cancel("job_missing").is_none());
+
+        assert_eq!(store.list(None).len(), 2);
+        let queued = store.list(Some(JobStatus::Queued));
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].id, first.id);
+        assert!(store.list(Some(JobStatus::Running)).is_empty());
+        assert_eq!(JobStatus::from_name("Canceled"), Some(JobStatus::Cancelled));
+        assert_eq!(JobStatus::from_name("done"), None);
+    }
+
+    #[test]
+    fn test_leftover_results_removed() {
+        let dir = std::env::temp_dir().join(format!("kokoro-jobs-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("job_old.zip"), b"zip").unwrap();
+        std::fs::write(dir.join("job_old.zip.part"), b"zip").unwrap();
+        std::fs::write(dir.join("notes.txt"), b"keep").unwrap();
+
+        let store = JobStore::open(dir.clone(), RETENTION, true).unwrap();
+        store.remove_leftovers();
+        assert_eq!(store.result_path("job_new"), dir.join("job_new.zip"));
+        assert!(!dir.join("job_old.zip").exists());
+        assert!(!dir.join("job_old.zip.part").exists());
+        assert!(dir.join("notes.txt").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_jobs_survive_restart() {
+        let dir = std::env::temp_dir().join(format!("kokoro-jobs-{}", Uuid::new_v4()));
+        let chapters = || vec!["One".to_string(), "Two".to_string()];
+        let (queued, running, completed) = {
+            let store = JobStore::open(dir.clone(), RETENTION, true).unwrap();
+            let queued = store.create("audiobook", chapters(), None, "queued spec");
+            let running = store.create("audiobook", chapters(), None, "running spec");
+            store.update(&running.id, |job| {
+                job.status = JobStatus::Running;
+                job.chapters[0].status = JobStatus::Completed;
+                job.chapters[0].duration_ms = Some(900);
+                job.progress.completed = 1;
+            });
+            let completed = store.create("audiobook", chapters(), Some("news".to_string()), "");
+            store.complete(&completed.id);
+            std::fs::write(store.result_path(&completed.id), b"zip").unwrap();
+            (queued.id, running.id, completed.id)
+        };
+
+        let store = JobStore::open(dir.clone(), RETENTION, true).unwrap();
+        store.remove_leftovers();
+        assert_eq!(store.get(&queued).unwrap().status, JobStatus::Queued);
+        let restarted = store.get(&running).unwrap();
+        assert_eq!(restarted.status, JobStatus::Queued);
+        assert_eq!(restarted.progress.completed, 0);
+        assert_eq!(restarted.chapters[0].status, JobStatus::Queued);
+        assert_eq!(restarted.chapters[0].duration_ms, None);
+        assert_eq!(store.in_feed("news")[0].id, completed);
+        assert!(store.result_path(&completed).exists());
+
+        let mut specs = store.queued("audiobook");
+        specs.sort();
+        let mut expected = vec![
+            (queued, "queued spec".to_string()),
+            (running, "running spec".to_string()),
+        ];
+        expected.sort();
+        assert_eq!(specs, expected);
+        assert!(store.queued("other").is_empty());
+        drop(store);
+
+        // Finished jobs past the retention period are forgotten at startup
+        let store = JobStore::open(dir.clone(), Duration::ZERO, true).unwrap();
+        assert!(store.get(&completed).is_none());
+        assert!(!store.result_path(&completed).exists());
+        assert_eq!(store.queued("audiobook").len(), 2);
+        assert!(store.queued("audiobook").is_empty());
+        drop(store);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_redacted_input_not_kept() {
+        let dir = std::env::temp_dir().join(format!("kokoro-jobs-{}", Uuid::new_v4()));
+        let job = {
+            let store = JobStore::open(dir.clone(), RETENTION, false).unwrap();
+            store.create("audiobook", vec!["One".to_string()], None, "secret text")
+        };
+        let db = Connection::open(dir.join(DATABASE_FILE)).unwrap();
+        let spec: Option<String> = db
+            .query_row("SELECT spec FROM jobs WHERE id = ?1", [&job.id], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(spec, None);
+        drop(db);
+
+        // Without its input the job cannot resume, so it fails instead of waiting forever
+        let store = JobStore::open(dir.clone(), RETENTION, false).unwrap();
+        assert!(store.queued("audiobook").is_empty());
+        let failed = store.get(&job.id).unwrap();
+        assert_eq!(failed.status, JobStatus::Failed);
+        assert!(failed.error.is_some());
+        drop(store);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_jobs_run_in_queue_order() {
+        let store = store();
+        let first = store.enqueue();
+        let second = store.enqueue();
+        let third = store.enqueue();
+        first.turn().await.unwrap();
+        // A ticket that leaves the queue before its turn does not hold up the rest
+        drop(second);
+        let waiting = tokio::time::timeout(Duration::from_millis(50), third.turn()).await;
+        assert!(waiting.is_err());
+        drop(first);
+        third.turn().await.unwrap();
+    }
+}
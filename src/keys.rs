@@ -0,0 +1,146 @@
+use crate::api::constant_time_eq;
+use serde::Serialize;
+use std::sync::{PoisonError, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+/// API keys accepted on the public port.
+///
+/// Holds the static `API_KEY` plus keys issued through the admin API. Issued
+/// keys live in memory only and are gone after a restart. Authentication is
+/// required as soon as any key exists.
+pub struct ApiKeys {
+    configured: Option<String>,
+    issued: RwLock<Vec<IssuedKey>>,
+}
+
+struct IssuedKey {
+    summary: KeySummary,
+    secret: String,
+}
+
+/// Issued key as listed by the admin API; the secret is only shown when the key is created
+#[derive(Debug, Clone, Serialize)]
+pub struct KeySummary {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Unix timestamp in seconds
+    pub created_at: u64,
+}
+
+/// Response body for a newly issued key
+#[derive(Debug, Serialize)]
+pub struct NewKey {
+    #[serde(flatten)]
+    pub summary: KeySummary,
+    pub key: String,
+}
+
+impl ApiKeys {
+    pub fn new(configured: Option<String>) -> Self {
+        Self {
+            configured,
+            issued: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Whether requests must present a key
+    pub fn is_required(&self) -> bool {
+        self.configured.is_some()
+            || !self
+                .issued
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .is_empty()
+    }
+
+    /// Check a presented key against every accepted key in constant time
+    pub fn verify(&self, provided: &str) -> bool {
+        self.secrets().iter().fold(false, |found, secret| {
+            found | constant_time_eq(provided, secret)
+        })
+    }
+
+    /// Every accepted key, for schemes such as SigV4 that derive a signature from the secret
+    pub fn secrets(&self) -> Vec<String> {
+        let issued = self.issued.read().unwrap_or_else(PoisonError::into_inner);
+        self.configured
+            .iter()
+            .cloned()
+            .chain(issued.iter().map(|key| key.secret.clone()))
+            .collect()
+    }
+
+    pub fn issue(&self, name: Option<String>) -> NewKey {
+        let summary = KeySummary {
+            id: Uuid::new_v4().simple().to_string()[..12].to_string(),
+            name,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+        };
+        let secret = format!("sk-kokoro-{}", Uuid::new_v4().simple());
+
+        self.issued
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(IssuedKey {
+                summary: summary.clone(),
+                secret: secret.clone(),
+            });
+        NewKey {
+            summary,
+            key: secret,
+        }
+    }
+
+    /// Revoke an issued key; returns `false` if no key has that ID
+    pub fn revoke(&self, id: &str) -> bool {
+        let mut issued = self.issued.write().unwrap_or_else(PoisonError::into_inner);
+        let before = issued.len();
+        issued.retain(|key| key.summary.id != id);
+        issued.len() != before
+    }
+
+    pub fn list(&self) -> Vec<KeySummary> {
+        self.issued
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|key| key.summary.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_and_revoke() {
+        let keys = ApiKeys::new(None);
+        assert!(!keys.is_required());
+
+        let issued = keys.issue(Some("ci".to_string()));
+        assert!(keys.is_required());
+        assert!(keys.verify(&issued.key));
+        assert!(!keys.verify("sk-kokoro-wrong"));
+        assert_eq!(keys.list().len(), 1);
+        assert_eq!(keys.list()[0].name.as_deref(), Some("ci"));
+
+        assert!(keys.revoke(&issued.summary.id));
+        assert!(!keys.revoke(&issued.summary.id));
+        assert!(!keys.verify(&issued.key));
+        assert!(!keys.is_required());
+    }
+
+    #[test]
+    fn test_configured_key_stays_valid() {
+        let keys = ApiKeys::new(Some("secret".to_string()));
+        let issued = keys.issue(None);
+        assert!(keys.verify("secret"));
+        assert!(keys.verify(&issued.key));
+        assert_eq!(keys.secrets().len(), 2);
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt;
+
+/// Languages Kokoro has voices for, identified by the first letter of a voice ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Language {
+    #[default]
+    AmericanEnglish,
+    BritishEnglish,
+    Spanish,
+    French,
+    Hindi,
+    Italian,
+    Japanese,
+    Portuguese,
+    Chinese,
+}
+
+/// Every language, in voice prefix order
+pub const LANGUAGES: [Language; 9] = [
+    Language::AmericanEnglish,
+    Language::BritishEnglish,
+    Language::Spanish,
+    Language::French,
+    Language::Hindi,
+    Language::Italian,
+    Language::Japanese,
+    Language::Portuguese,
+    Language::Chinese,
+];
+
+/// Codes accepted in a request's `language` field
+pub const LANGUAGE_CODES: &[&str] = &[
+    "en-us", "en-gb", "es", "fr-fr", "hi", "it", "ja", "pt-br", "zh",
+];
+
+impl Language {
+    /// Language of a Kokoro voice ID such as `ef_dora`; `None` for IDs outside the naming scheme
+    pub fn of_voice(voice: &str) -> Option<Self> {
+        let (prefix, _) = voice.split_once('_')?;
+        if prefix.chars().count() != 2 {
+            return None;
+        }
+        Self::of_voice_letter(prefix.chars().next()?)
+    }
+
+    /// Language of the voices whose IDs start with `letter` (`a` for `af_heart`, `am_adam`, ...)
+    pub fn of_voice_letter(letter: char) -> Option<Self> {
+        match letter {
+            'a' => Some(Self::AmericanEnglish),
+            'b' => Some(Self::BritishEnglish),
+            'e' => Some(Self::Spanish),
+            'f' => Some(Self::French),
+            'h' => Some(Self::Hindi),
+            'i' => Some(Self::Italian),
+            'j' => Some(Self::Japanese),
+            'p' => Some(Self::Portuguese),
+            'z' => Some(Self::Chinese),
+            _ => None,
+        }
+    }
+
+    /// Parse a language code, case-insensitively and accepting common aliases (`en`, `pt`, `cmn`)
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim().to_lowercase().replace('_', "-").as_str() {
+            "en" | "en-us" => Some(Self::AmericanEnglish),
+            "en-gb" => Some(Self::BritishEnglish),
+            "es" | "es-es" | "es-mx" => Some(Self::Spanish),
+            "fr" | "fr-fr" => Some(Self::French),
+            "hi" | "hi-in" => Some(Self::Hindi),
+            "it" | "it-it" => Some(Self::Italian),
+            "ja" | "ja-jp" => Some(Self::Japanese),
+            "pt" | "pt-br" => Some(Self::Portuguese),
+            "zh" | "zh-cn" | "cmn" => Some(Self::Chinese),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::AmericanEnglish => "en-us",
+            Self::BritishEnglish => "en-gb",
+            Self::Spanish => "es",
+            Self::French => "fr-fr",
+            Self::Hindi => "hi",
+            Self::Italian => "it",
+            Self::Japanese => "ja",
+            Self::Portuguese => "pt-br",
+            Self::Chinese => "zh",
+        }
+    }
+
+    /// eSpeak-ng voice the language is phonemized with
+    pub fn espeak_code(self) -> &'static str {
+        match self {
+            Self::Chinese => "cmn",
+            language => language.code(),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_of_voice() {
+        assert_eq!(
+            Language::of_voice("af_heart"),
+            Some(Language::AmericanEnglish)
+        );
+        assert_eq!(Language::of_voice("ef_dora"), Some(Language::Spanish));
+        assert_eq!(Language::of_voice("zm_yunxi"), Some(Language::Chinese));
+        assert_eq!(Language::of_voice("custom"), None);
+        assert_eq!(Language::of_voice("xx_voice"), None);
+        assert_eq!(Language::of_voice("abc_voice"), None);
+        assert_eq!(Language::of_voice_letter('j'), Some(Language::Japanese));
+    }
+
+    #[test]
+    fn test_language_codes_round_trip() {
+        for code in LANGUAGE_CODES {
+            assert_eq!(Language::from_code(code).unwrap().code(), *code);
+        }
+        assert_eq!(Language::from_code("EN_gb"), Some(Language::BritishEnglish));
+        assert_eq!(Language::from_code("cmn"), Some(Language::Chinese));
+        assert_eq!(Language::from_code("de"), None);
+        assert_eq!(Language::Chinese.espeak_code(), "cmn");
+        assert_eq!(Language::Spanish.espeak_code(), "es");
+    }
+}
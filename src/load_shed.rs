@@ -0,0 +1,427 @@
+use crate::{
+    api::AppState,
+    backend::KokoroBackend,
+    error::{ApiResult, AppError},
+    metrics::MetricsSnapshot,
+};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// How often utilization and queue wait are sampled
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Weight of the newest sample in the smoothed readings
+const SMOOTHING: f32 = 0.3;
+
+/// Shedding stops once every reading falls below this fraction of its threshold, so the
+/// server does not flap around a limit
+const RECOVERY_RATIO: f32 = 0.9;
+
+/// Limits above which batch-priority work is refused; `None` leaves a signal unchecked
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ShedThresholds {
+    pub cpu_percent: Option<f32>,
+    pub gpu_percent: Option<f32>,
+    pub queue_wait_ms: Option<f32>,
+}
+
+/// Smoothed pressure readings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Pressure {
+    pub cpu_percent: f32,
+    /// Absent when no GPU threshold is set or `nvidia-smi` cannot be queried
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gpu_percent: Option<f32>,
+    /// Mean time syntheses waited for a worker over the last sample
+    pub queue_wait_ms: f32,
+}
+
+/// Load shedding state reported by the admin stats endpoint
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ShedStatus {
+    pub shedding: bool,
+    #[serde(flatten)]
+    pub pressure: Pressure,
+}
+
+/// Refuses batch-priority requests (batch speech, audiobook jobs, and the watch folder) while
+/// the host is under pressure, leaving headroom for interactive speech requests
+pub struct LoadShedder {
+    thresholds: ShedThresholds,
+    /// `f32` bits of the readings, written by the sampler task
+    cpu_percent: AtomicU32,
+    gpu_percent: AtomicU32,
+    queue_wait_ms: AtomicU32,
+    has_gpu: AtomicBool,
+    shedding: AtomicBool,
+}
+
+impl LoadShedder {
+    pub fn new(thresholds: ShedThresholds) -> Self {
+        Self {
+            thresholds,
+            cpu_percent: AtomicU32::new(0),
+            gpu_percent: AtomicU32::new(0),
+            queue_wait_ms: AtomicU32::new(0),
+            has_gpu: AtomicBool::new(false),
+            shedding: AtomicBool::new(false),
+        }
+    }
+
+    pub fn is_shedding(&self) -> bool {
+        self.shedding.load(Ordering::Acquire)
+    }
+
+    pub fn status(&self) -> ShedStatus {
+        ShedStatus {
+            shedding: self.is_shedding(),
+            pressure: self.pressure(),
+        }
+    }
+
+    fn pressure(&self) -> Pressure {
+        let load = |value: &AtomicU32| f32::from_bits(value.load(Ordering::Relaxed));
+        Pressure {
+            cpu_percent: load(&self.cpu_percent),
+            gpu_percent: self
+                .has_gpu
+                .load(Ordering::Relaxed)
+                .then(|| load(&self.gpu_percent)),
+            queue_wait_ms: load(&self.queue_wait_ms),
+        }
+    }
+
+    fn store(&self, pressure: Pressure) {
+        self.cpu_percent
+            .store(pressure.cpu_percent.to_bits(), Ordering::Relaxed);
+        self.has_gpu
+            .store(pressure.gpu_percent.is_some(), Ordering::Relaxed);
+        self.gpu_percent.store(
+            pressure.gpu_percent.unwrap_or(0.0).to_bits(),
+            Ordering::Relaxed,
+        );
+        self.queue_wait_ms
+            .store(pressure.queue_wait_ms.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Sample pressure in the background for as long as the process runs, with queue wait
+    /// taken across the pools of every model in `backends`
+    pub fn start(self: Arc<Self>, backends: Vec<Arc<KokoroBackend>>) {
+        let ShedThresholds {
+            cpu_percent,
+            gpu_percent,
+            queue_wait_ms,
+        } = self.thresholds;
+        let describe = |limit: Option<f32>, unit: &str| {
+            limit.map_or_else(|| "off".to_string(), |limit| format!("{}{}", limit, unit))
+        };
+        info!(
+            "  Load shedding: CPU {}, GPU {}, queue wait {}",
+            describe(cpu_percent, "%"),
+            describe(gpu_percent, "%"),
+            describe(queue_wait_ms, " ms")
+        );
+        tokio::spawn(self.run(backends));
+    }
+
+    async fn run(self: Arc<Self>, backends: Vec<Arc<KokoroBackend>>) {
+        let snapshots = || -> Vec<MetricsSnapshot> {
+            backends
+                .iter()
+                .map(|backend| backend.metrics().snapshot(backend.sample_rate()))
+                .collect()
+        };
+        let mut ticker = tokio::time::interval(SAMPLE_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut cpu = read_cpu_times().await;
+        let mut metrics = snapshots();
+        let mut gpu_enabled = self.thresholds.gpu_percent.is_some();
+        let mut pressure = Pressure::default();
+        loop {
+            ticker.tick().await;
+
+            let cpu_now = read_cpu_times().await;
+            if let (Some(previous), Some(current)) = (cpu, cpu_now) {
+                pressure.cpu_percent = smooth(pressure.cpu_percent, cpu_busy(previous, current));
+            }
+            cpu = cpu_now;
+
+            let metrics_now = snapshots();
+            let wait = mean_queue_wait_ms(&metrics, &metrics_now);
+            pressure.queue_wait_ms = match wait {
+                Some(wait) => smooth(pressure.queue_wait_ms, wait),
+                // Nothing finished; a standing queue keeps the last reading
+                None if backends.iter().any(|backend| backend.queued() > 0) => {
+                    pressure.queue_wait_ms
+                }
+                None => smooth(pressure.queue_wait_ms, 0.0),
+            };
+            metrics = metrics_now;
+
+            if gpu_enabled {
+                match read_gpu_utilization().await {
+                    Some(busy) => {
+                        pressure.gpu_percent =
+                            Some(smooth(pressure.gpu_percent.unwrap_or(busy), busy))
+                    }
+                    None => {
+                        warn!("Could not read GPU utilization from nvidia-smi; ignoring the GPU threshold");
+                        gpu_enabled = false;
+                        pressure.gpu_percent = None;
+                    }
+                }
+            }
+
+            self.store(pressure);
+            let was_shedding = self.is_shedding();
+            let shedding = should_shed(&self.thresholds, &pressure, was_shedding);
+            if shedding != was_shedding {
+                self.shedding.store(shedding, Ordering::Release);
+                if shedding {
+                    warn!(
+                        cpu_percent = pressure.cpu_percent,
+                        gpu_percent = pressure.gpu_percent,
+                        queue_wait_ms = pressure.queue_wait_ms,
+                        "Load shedding started; refusing batch-priority requests"
+                    );
+                } else {
+                    info!("Load shedding stopped");
+                }
+            }
+        }
+    }
+}
+
+/// Reject a batch-priority request while the server is shedding load
+pub fn admit_batch(state: &AppState) -> ApiResult<()> {
+    match &state.load_shedder {
+        Some(shedder) if shedder.is_shedding() => Err(AppError::overloaded(
+            "Server is under load and not accepting batch requests; retry later",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Whether a threshold is exceeded; while already shedding, whether any reading is still
+/// above the recovery level
+fn should_shed(thresholds: &ShedThresholds, pressure: &Pressure, shedding: bool) -> bool {
+    let ratio = if shedding { RECOVERY_RATIO } else { 1.0 };
+    let over = |limit: Option<f32>, value: Option<f32>| match (limit, value) {
+        (Some(limit), Some(value)) => value >= limit * ratio,
+        _ => false,
+    };
+    over(thresholds.cpu_percent, Some(pressure.cpu_percent))
+        || over(thresholds.gpu_percent, pressure.gpu_percent)
+        || over(thresholds.queue_wait_ms, Some(pressure.queue_wait_ms))
+}
+
+fn smooth(previous: f32, sample: f32) -> f32 {
+    previous + SMOOTHING * (sample - previous)
+}
+
+/// Busy and total jiffies across all CPUs
+type CpuTimes = (u64, u64);
+
+/// Aggregate CPU times from `/proc/stat`; `None` on platforms without it
+async fn read_cpu_times() -> Option<CpuTimes> {
+    let stat = tokio::fs::read_to_string("/proc/stat").await.ok()?;
+    parse_cpu_times(&stat)
+}
+
+fn parse_cpu_times(stat: &str) -> Option<CpuTimes> {
+    let fields = stat
+        .lines()
+        .find_map(|line| line.strip_prefix("cpu "))?
+        .split_whitespace()
+        .map(|field| field.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    if fields.len() < 4 {
+        return None;
+    }
+    // user nice system idle iowait irq softirq steal; guest time is already in user
+    let total = fields.iter().take(8).sum::<u64>();
+    let idle = fields[3] + fields.get(4).copied().unwrap_or(0);
+    Some((total - idle, total))
+}
+
+/// Percentage of CPU time spent busy between two readings
+fn cpu_busy(previous: CpuTimes, current: CpuTimes) -> f32 {
+    let busy = current.0.saturating_sub(previous.0);
+    let total = current.1.saturating_sub(previous.1);
+    if total == 0 {
+        return 0.0;
+    }
+    (busy as f64 / total as f64 * 100.0) as f32
+}
+
+/// Mean queue wait of the syntheses completed between two sets of pool snapshots
+fn mean_queue_wait_ms(previous: &[MetricsSnapshot], current: &[MetricsSnapshot]) -> Option<f32> {
+    let syntheses = |snapshots: &[MetricsSnapshot]| -> u64 {
+        snapshots
+            .iter()
+            .map(|metrics| metrics.syntheses_total)
+            .sum()
+    };
+    let queue_wait = |snapshots: &[MetricsSnapshot]| -> f64 {
+        snapshots
+            .iter()
+            .map(|metrics| metrics.queue_wait_seconds_total)
+            .sum()
+    };
+    let completed = syntheses(current).saturating_sub(syntheses(previous));
+    if completed == 0 {
+        return None;
+    }
+    let waited = (queue_wait(current) - queue_wait(previous)).max(0.0);
+    Some((waited * 1000.0 / completed as f64) as f32)
+}
+
+/// Busiest GPU's utilization as reported by `nvidia-smi`
+async fn read_gpu_utilization() -> Option<f32> {
+    let output = tokio::process::Command::new("nvidia-smi")
+        .args([
+            "--query-gpu=utilization.gpu",
+            "--format=csv,noheader,nounits",
+        ])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_gpu_utilization(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Utilization and memory of one GPU, as reported by `nvidia-smi`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GpuStats {
+    pub index: u32,
+    pub utilization_percent: f32,
+    pub memory_used_mb: u64,
+    pub memory_total_mb: u64,
+}
+
+/// Current readings for GPU `index`; `None` when `nvidia-smi` cannot be queried
+pub async fn read_gpu_stats(index: u32) -> Option<GpuStats> {
+    let output = tokio::process::Command::new("nvidia-smi")
+        .args([
+            "--query-gpu=index,utilization.gpu,memory.used,memory.total",
+            "--format=csv,noheader,nounits",
+            &format!("--id={}", index),
+        ])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_gpu_stats(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_gpu_stats(output: &str) -> Option<GpuStats> {
+    let mut fields = output.lines().next()?.split(',').map(str::trim);
+    Some(GpuStats {
+        index: fields.next()?.parse().ok()?,
+        utilization_percent: fields.next()?.parse().ok()?,
+        memory_used_mb: fields.next()?.parse().ok()?,
+        memory_total_mb: fields.next()?.parse().ok()?,
+    })
+}
+
+fn parse_gpu_utilization(output: &str) -> Option<f32> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().parse::<f32>().ok())
+        .reduce(f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_busy() {
+        let before = parse_cpu_times("cpu  100 0 100 700 100 0 0 0 0 0\ncpu0 1 2 3 4\n").unwrap();
+        let after = parse_cpu_times("cpu  400 0 200 1000 100 0 0 0 0 0\n").unwrap();
+        assert_eq!(before, (200, 1000));
+        assert!((cpu_busy(before, after) - 57.14).abs() < 0.01);
+        assert_eq!(cpu_busy(after, after), 0.0);
+        assert!(parse_cpu_times("intr 1 2 3\n").is_none());
+    }
+
+    #[test]
+    fn test_mean_queue_wait() {
+        let snapshot = |syntheses_total, queue_wait_seconds_total| MetricsSnapshot {
+            syntheses_total,
+            synthesis_failures_total: 0,
+            queue_wait_seconds_total,
+            synthesis_seconds_total: 0.0,
+            audio_samples_total: 0,
+            audio_seconds_total: 0.0,
+        };
+        assert_eq!(
+            mean_queue_wait_ms(&[snapshot(10, 1.0)], &[snapshot(14, 2.0)]),
+            Some(250.0)
+        );
+        assert_eq!(
+            mean_queue_wait_ms(&[snapshot(10, 1.0)], &[snapshot(10, 1.0)]),
+            None
+        );
+        // Waits in both pools count toward one mean
+        assert_eq!(
+            mean_queue_wait_ms(
+                &[snapshot(10, 1.0), snapshot(5, 0.0)],
+                &[snapshot(12, 1.5), snapshot(7, 0.5)]
+            ),
+            Some(250.0)
+        );
+    }
+
+    #[test]
+    fn test_parse_gpu_utilization() {
+        assert_eq!(parse_gpu_utilization("12\n87\n"), Some(87.0));
+        assert_eq!(parse_gpu_utilization("[N/A]\n"), None);
+        assert_eq!(
+            parse_gpu_stats("1, 37, 1024, 24576\n"),
+            Some(GpuStats {
+                index: 1,
+                utilization_percent: 37.0,
+                memory_used_mb: 1024,
+                memory_total_mb: 24576,
+            })
+        );
+        assert_eq!(parse_gpu_stats("1, [N/A], 1024, 24576\n"), None);
+    }
+
+    #[test]
+    fn test_should_shed_with_hysteresis() {
+        let thresholds = ShedThresholds {
+            cpu_percent: Some(90.0),
+            queue_wait_ms: Some(500.0),
+            ..Default::default()
+        };
+        let pressure = |cpu_percent, queue_wait_ms| Pressure {
+            cpu_percent,
+            gpu_percent: Some(100.0),
+            queue_wait_ms,
+        };
+
+        assert!(!should_shed(&thresholds, &pressure(85.0, 100.0), false));
+        assert!(should_shed(&thresholds, &pressure(95.0, 100.0), false));
+        assert!(should_shed(&thresholds, &pressure(50.0, 600.0), false));
+        // Stays on until readings drop below 90% of the limits
+        assert!(should_shed(&thresholds, &pressure(85.0, 100.0), true));
+        assert!(!should_shed(&thresholds, &pressure(80.0, 100.0), true));
+        // The GPU reading is ignored without a GPU threshold
+        assert!(!should_shed(
+            &ShedThresholds::default(),
+            &pressure(100.0, 1e6),
+            false
+        ));
+    }
+}
@@ -1,44 +1,121 @@
 use anyhow::{Context, Result};
+use std::future::Future;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
 use tracing::{info, warn};
 
+mod admin;
 mod api;
+mod audiobook;
+mod azure;
 mod backend;
+mod batch;
+mod bench;
+mod cache;
+mod chapters;
+mod comfort_noise;
 mod config;
+mod crypto;
+mod dates;
+mod debug_tap;
+mod discord;
+mod download;
+mod effects;
+mod elevenlabs;
+mod emphasis;
 mod error;
+mod estimate;
+mod extract;
+mod feeds;
+mod fetch;
+mod google;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod icecast;
+mod inference_pool;
+mod jobs;
+mod keys;
+mod language;
+mod load_shed;
+mod metrics;
+#[cfg(feature = "mock-backend")]
+mod mock;
+mod model_assets;
+mod mqtt;
+mod normalize;
+mod numbers;
+mod pcm;
+mod phonemes;
+mod pipe;
+mod plan;
+mod polly;
+mod presets;
+mod punctuation;
+mod redact;
 mod runtime_assets;
+mod signed_url;
+mod speed_curve;
+mod spell;
+mod ssml;
 mod streaming;
+mod style;
+mod tags;
+mod twilio;
+mod units;
 mod validation;
+mod verify;
+mod version;
+mod voice_blend;
+mod voice_pack;
+mod watch_folder;
 
 use config::Config;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Parse configuration
+    let config = Config::from_env_and_args().context("Failed to parse configuration")?;
+
     // Initialize tracing
     let filter = std::env::var("RUST_LOG")
         .unwrap_or_else(|_| "kokoro_openai_server=info,axum=info".to_string());
 
-    tracing_subscriber::fmt()
+    let subscriber = tracing_subscriber::fmt()
         .with_env_filter(filter)
         .with_target(true)
         .with_thread_ids(false)
         .with_file(false)
-        .with_line_number(false)
-        .init();
+        .with_line_number(false);
+    // Stdout carries the audio or report of a subcommand
+    if config.command.is_some() {
+        subscriber.with_writer(std::io::stderr).init();
+    } else {
+        subscriber.init();
+    }
 
     info!(
         "Starting Kokoro OpenAI Server v{}",
         env!("CARGO_PKG_VERSION")
     );
 
-    // Parse configuration
-    let config = Config::from_env_and_args().context("Failed to parse configuration")?;
-
     info!("Configuration loaded:");
     info!("  Host: {}:{}", config.host, config.port);
     info!("  Workers: {}", config.workers);
     info!("  Max input chars: {}", config.max_input_chars);
+    if config.max_response_mb > 0 {
+        info!("  Max response size: {} MB", config.max_response_mb);
+    }
+    if let Some(max_duration_s) = config.max_duration_s {
+        info!("  Max audio duration: {} s", max_duration_s);
+    }
+    if config.redact_text {
+        redact::enable();
+        info!("  Text redaction: enabled");
+    }
     info!("  Acceleration: {:?}", config.acceleration);
 
     if config.api_key.is_some() {
@@ -47,23 +124,359 @@ async fn main() -> Result<()> {
         warn!("  Authentication: disabled (set API_KEY to enable)");
     }
 
-    runtime_assets::ensure_runtime_assets()
+    let voice_aliases = match &config.voice_aliases_file {
+        Some(path) => {
+            info!("  Voice aliases: {}", path.display());
+            validation::VoiceAliases::load(path).context("Failed to load voice aliases")?
+        }
+        None => validation::VoiceAliases::default(),
+    };
+    let voice_aliases = Arc::new(voice_aliases);
+    let presets = match &config.presets_file {
+        Some(path) => {
+            info!("  Presets: {}", path.display());
+            presets::Presets::load(path).context("Failed to load presets")?
+        }
+        None => presets::Presets::default(),
+    };
+    let presets = Arc::new(presets);
+
+    // The mock backend needs neither espeak-ng data nor model files
+    let model_files = if config.backend == config::BackendKind::Mock {
+        model_assets::resolve_model_files(&config).await?
+    } else {
+        runtime_assets::ensure_runtime_assets(
+            config.offline,
+            &config.download_mirrors,
+            config.espeak_languages().as_deref(),
+        )
         .await
         .context("Failed to prepare runtime assets")?;
 
+        model_assets::ensure_model_files(&config)
+            .await
+            .context("Failed to prepare model files")?
+    };
+
+    // Execution providers are set once for the process and shared by the full and draft models
+    if config.backend == config::BackendKind::Kokoro {
+        backend::init_onnx_runtime(&config);
+    } else {
+        warn!("Mock backend enabled; requests return generated tones instead of speech");
+    }
+
     // Initialize backend
-    let backend = backend::KokoroBackend::new(&config)
+    let backend = backend::KokoroBackend::new(&config, &model_files)
         .await
         .context("Failed to initialize Kokoro backend")?;
 
     info!("Backend initialized successfully");
 
+    let backend = Arc::new(backend);
+
+    // The draft model shares the voices of the full one
+    let draft_backend = match &config.draft_model_path {
+        Some(path) => {
+            if config.backend != config::BackendKind::Mock && !path.is_file() {
+                anyhow::bail!("Draft model not found at {}", path.display());
+            }
+            let files = model_assets::ModelFiles {
+                model_path: path.clone(),
+                voices_path: model_files.voices_path.clone(),
+            };
+            info!(
+                "  Draft model: {} ({}, {} workers)",
+                path.display(),
+                model_assets::model_quantization(&files, ""),
+                config.draft_workers
+            );
+            // Its pool is sized on its own, with one inference thread per worker
+            let draft_config = config::Config {
+                workers: config.draft_workers,
+                inference_threads: None,
+                ..config.clone()
+            };
+            let draft = backend::KokoroBackend::new(&draft_config, &files)
+                .await
+                .context("Failed to initialize draft model")?;
+            Some(Arc::new(draft))
+        }
+        None => None,
+    };
+
+    if let Some(voice) = &config.fallback_voice {
+        validation::validate_voice(voice, &backend.voices(), &voice_aliases)
+            .context("Invalid fallback voice")?;
+        info!("  Fallback voice: {}", voice);
+    }
+
+    for voice in presets.voice_ids() {
+        if !backend
+            .voices()
+            .iter()
+            .any(|available| available.id == voice)
+        {
+            warn!("  Preset for unknown voice '{}' will never apply", voice);
+        }
+    }
+    for (name, preset) in presets.named_presets() {
+        if let Some(voice) = &preset.voice {
+            validation::validate_voice(voice, &backend.voices(), &voice_aliases)
+                .with_context(|| format!("Invalid voice in preset '{}'", name))?;
+        }
+    }
+
+    if config.warmup {
+        let available = backend.voices();
+        let voices = config
+            .warmup_voices
+            .iter()
+            .map(|voice| validation::validate_voice(voice, &available, &voice_aliases))
+            .collect::<Result<Vec<_>, _>>()
+            .context("Invalid warm-up voice")?;
+        info!("  Warm-up: {} voice(s) per engine", voices.len());
+        backend.start_warmup(voices);
+    }
+
+    if let Some(secs) = config.idle_unload_secs {
+        info!("  Idle unload: after {}s without requests", secs);
+        backend.start_idle_unloader(Duration::from_secs(secs));
+        if let Some(draft) = &draft_backend {
+            draft.start_idle_unloader(Duration::from_secs(secs));
+        }
+    }
+
     // Build router
-    let app = api::create_router(
-        Arc::new(backend),
-        config.api_key.clone(),
-        config.max_input_chars,
-    );
+    let chunk_retry = streaming::ChunkRetryPolicy {
+        retries: config.stream_chunk_retries,
+        backoff: Duration::from_millis(config.stream_chunk_retry_backoff_ms),
+        skip_failed: config.stream_skip_failed_chunks,
+        stall_timeout: config.stream_stall_timeout_secs.map(Duration::from_secs),
+    };
+    let disk_cache = if config.disk_cache {
+        let dir = match &config.disk_cache_dir {
+            Some(dir) => dir.clone(),
+            None => cache::default_disk_cache_dir()?,
+        };
+        info!(
+            "  Disk cache: {} ({} MB)",
+            dir.display(),
+            config.disk_cache_max_mb
+        );
+        Some(
+            cache::DiskCache::open(
+                dir,
+                config.disk_cache_max_mb * 1024 * 1024,
+                config.disk_cache_ttl_secs.map(Duration::from_secs),
+            )
+            .context("Failed to open disk audio cache")?,
+        )
+    } else {
+        None
+    };
+    let cache = (config.cache_max_mb > 0 || disk_cache.is_some()).then(|| {
+        info!("  Audio cache: {} MB in memory", config.cache_max_mb);
+        Arc::new(cache::AudioCache::new(
+            config.cache_max_mb * 1024 * 1024,
+            disk_cache,
+        ))
+    });
+    let prewarm = if let (Some(path), Some(_)) = (&config.cache_prewarm_file, &cache) {
+        let phrases = cache::load_phrases(path)?;
+        let available = backend.voices();
+        let voices = config
+            .cache_prewarm_voices
+            .iter()
+            .map(|voice| validation::validate_voice(voice, &available, &voice_aliases))
+            .collect::<Result<Vec<_>, _>>()
+            .context("Invalid cache pre-warm voice")?;
+        let formats = config
+            .cache_prewarm_formats
+            .iter()
+            .map(|format| validation::validate_response_format(format))
+            .collect::<Result<Vec<_>, _>>()
+            .context("Invalid cache pre-warm format")?;
+        info!(
+            "  Cache pre-warm: {} phrase(s) x {} voice(s) x {} format(s)",
+            phrases.len(),
+            voices.len(),
+            formats.len()
+        );
+        Some((phrases, voices, formats))
+    } else {
+        None
+    };
+
+    let icecast_target = config
+        .icecast_url
+        .as_deref()
+        .map(icecast::IcecastTarget::parse)
+        .transpose()?;
+    let radio = icecast_target
+        .is_some()
+        .then(|| Arc::new(icecast::RadioQueue::new(config.icecast_queue_size)));
+
+    let jobs_dir = config
+        .jobs_dir
+        .clone()
+        .unwrap_or_else(jobs::default_jobs_dir);
+    info!("  Jobs directory: {}", jobs_dir.display());
+
+    let shed_thresholds = load_shed::ShedThresholds {
+        cpu_percent: config.shed_cpu_percent,
+        gpu_percent: config.shed_gpu_percent,
+        queue_wait_ms: config.shed_queue_wait_ms.map(|ms| ms as f32),
+    };
+
+    let fetch = if config.fetch_allow.is_empty() {
+        None
+    } else {
+        info!("  URL input: {}", config.fetch_allow.join(", "));
+        Some(Arc::new(fetch::FetchPolicy::new(
+            &config.fetch_allow,
+            config.fetch_max_mb.saturating_mul(1024 * 1024),
+        )?))
+    };
+
+    let state = api::AppState {
+        backend,
+        draft_backend: draft_backend.clone(),
+        api_keys: Arc::new(keys::ApiKeys::new(config.api_key.clone())),
+        max_input_chars: config.max_input_chars,
+        max_document_chars: config.max_document_chars,
+        max_response_bytes: (config.max_response_mb > 0)
+            .then(|| config.max_response_mb.saturating_mul(1024 * 1024)),
+        max_duration_s: config.max_duration_s,
+        model_ids: config::accepted_model_ids(&config.model_aliases, draft_backend.is_some())
+            .into(),
+        voice_aliases,
+        fallback_voice: config.fallback_voice.clone(),
+        presets,
+        model_url: config.model_url.clone(),
+        chunk_retry,
+        adaptive_chunks: config.stream_adaptive_chunks,
+        sentence_gap_ms: config.sentence_gap_ms,
+        comfort_noise: config.comfort_noise,
+        chunk_profiles: Arc::new(streaming::ChunkProfiles::new(
+            streaming::ChunkRules {
+                terminators: config.chunk_terminators.chars().collect(),
+                soft_breaks: config.chunk_soft_breaks.chars().collect(),
+                break_words: config
+                    .chunk_break_words
+                    .iter()
+                    .map(|word| word.trim().to_lowercase())
+                    .filter(|word| !word.is_empty())
+                    .collect(),
+                min_words: config.chunk_min_words,
+                split_words: config.chunk_split_words,
+                spaceless: false,
+            },
+            config.chunk_profiles,
+        )),
+        fold_punctuation: config.fold_punctuation,
+        normalize_rules: config
+            .text_normalization
+            .then_some(normalize::NormalizeRules {
+                measurement_system: config.measurement_system,
+                date_order: config.date_order,
+                time_format: config.time_format,
+                // Requests that know what their numbers stand for pick their own style
+                number_style: normalize::NumberStyle::default(),
+            }),
+        cache,
+        radio: radio.clone(),
+        jobs: Arc::new(jobs::JobStore::open(
+            jobs_dir,
+            Duration::from_secs(config.job_retention_hours.saturating_mul(60 * 60)),
+            !config.redact_text,
+        )?),
+        fetch,
+        debug_tap: (config.debug_tap > 0)
+            .then(|| Arc::new(debug_tap::DebugTap::new(config.debug_tap))),
+        load_shedder: shed_thresholds
+            .ne(&load_shed::ShedThresholds::default())
+            .then(|| Arc::new(load_shed::LoadShedder::new(shed_thresholds))),
+        trust_forwarded_headers: config.trust_forwarded_headers,
+    };
+
+    match &config.command {
+        Some(config::Command::Pipe(args)) => return pipe::run(state, args).await,
+        Some(config::Command::Verify(args)) => return verify::run(state, args).await,
+        Some(config::Command::Bench(args)) => return bench::run(state, args).await,
+        None => {}
+    }
+    state.jobs.remove_leftovers();
+    state.jobs.clone().start_expiry_sweep();
+    audiobook::resume_jobs(&state);
+    if let Some((phrases, voices, formats)) = prewarm {
+        api::start_cache_prewarm(state.clone(), phrases, voices, formats);
+    }
+
+    if let Some(shedder) = &state.load_shedder {
+        shedder.clone().start(state.backends().cloned().collect());
+    }
+
+    if let (Some(target), Some(queue)) = (icecast_target, radio) {
+        info!("  Icecast source: {}", target.display());
+        icecast::start(state.clone(), queue, target);
+    }
+
+    if let Some(url) = &config.mqtt_url {
+        mqtt::start(
+            state.clone(),
+            mqtt::MqttSettings {
+                url: url.clone(),
+                client_id: config.mqtt_client_id.clone(),
+                topic: config.mqtt_topic.clone(),
+                response_topic: config.mqtt_response_topic.clone(),
+            },
+        )
+        .context("Failed to start MQTT client")?;
+    }
+
+    if let Some(dir) = &config.watch_dir {
+        watch_folder::start(
+            state.clone(),
+            dir.clone(),
+            Duration::from_secs(config.watch_interval_secs),
+        );
+    }
+
+    // One signal handler fans out to every listener
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        let _ = shutdown_tx.send(true);
+    });
+    let mut background_listeners = Vec::new();
+
+    #[cfg(feature = "grpc")]
+    if let Some(grpc_port) = config.grpc_port {
+        let grpc_addr: SocketAddr = format!("{}:{}", config.host, grpc_port)
+            .parse()
+            .context("Invalid host:grpc_port combination")?;
+        info!("gRPC listening on {}", grpc_addr);
+        let grpc_state = state.clone();
+        let shutdown = shutdown_requested(&shutdown_rx);
+        background_listeners.push(tokio::spawn(async move {
+            if let Err(e) = grpc::serve(grpc_state, grpc_addr, shutdown).await {
+                tracing::error!("gRPC server error: {:#}", e);
+            }
+        }));
+    }
+    #[cfg(not(feature = "grpc"))]
+    if config.grpc_port.is_some() {
+        warn!(
+            "KOKORO_GRPC_PORT is set but this build does not include the `grpc` feature; ignoring"
+        );
+    }
+
+    if config.admin_port.is_some() || config.admin_socket.is_some() {
+        background_listeners.extend(serve_admin(&config, state.clone(), &shutdown_rx).await?);
+    }
+
+    let backends: Vec<_> = state.backends().cloned().collect();
+    let app = api::create_router(state);
 
     // Create socket address
     let addr: SocketAddr = format!("{}:{}", config.host, config.port)
@@ -75,15 +488,141 @@ async fn main() -> Result<()> {
     // Start server with graceful shutdown
     let listener = tokio::net::TcpListener::bind(addr).await?;
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .context("Server error")?;
+    let server =
+        axum::serve(listener, app).with_graceful_shutdown(shutdown_requested(&shutdown_rx));
+    let drained = async {
+        server.await.context("Server error")?;
+        for listener in background_listeners {
+            let _ = listener.await;
+        }
+        Ok::<_, anyhow::Error>(())
+    };
 
-    info!("Server shutdown complete");
+    tokio::select! {
+        result = drained => {
+            result?;
+            info!("Server shutdown complete");
+        }
+        () = drain_deadline(
+            shutdown_rx,
+            backends,
+            Duration::from_secs(config.shutdown_drain_secs),
+        ) => {}
+    }
     Ok(())
 }
 
+/// Resolves once shutdown has been requested
+fn shutdown_requested(shutdown: &watch::Receiver<bool>) -> impl Future<Output = ()> {
+    let mut shutdown = shutdown.clone();
+    async move {
+        let _ = shutdown.wait_for(|requested| *requested).await;
+    }
+}
+
+/// How often the drain progress is logged
+const DRAIN_LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Wait out the drain window once shutdown starts, logging work still in flight.
+///
+/// Listeners stop accepting connections as soon as the signal arrives; this
+/// only bounds how long open requests and streams may take to finish.
+async fn drain_deadline(
+    shutdown: watch::Receiver<bool>,
+    backends: Vec<Arc<backend::KokoroBackend>>,
+    window: Duration,
+) {
+    // Summed over the full and draft models, which have separate worker pools
+    let load = || {
+        backends
+            .iter()
+            .fold((0, 0), |(in_flight, queued), backend| {
+                (in_flight + backend.in_flight(), queued + backend.queued())
+            })
+    };
+
+    shutdown_requested(&shutdown).await;
+    let (in_flight, queued) = load();
+    info!(
+        "Draining for up to {}s: {} synthesis(es) in flight, {} queued",
+        window.as_secs(),
+        in_flight,
+        queued
+    );
+
+    let deadline = Instant::now() + window;
+    let mut progress =
+        tokio::time::interval_at(Instant::now() + DRAIN_LOG_INTERVAL, DRAIN_LOG_INTERVAL);
+    loop {
+        tokio::select! {
+            () = tokio::time::sleep_until(deadline) => break,
+            _ = progress.tick() => {
+                let (in_flight, queued) = load();
+                info!("Still draining: {} synthesis(es) in flight, {} queued", in_flight, queued);
+            }
+        }
+    }
+
+    let (in_flight, queued) = load();
+    warn!(
+        "Drain window of {}s elapsed with {} synthesis(es) in flight and {} queued; exiting",
+        window.as_secs(),
+        in_flight,
+        queued
+    );
+}
+
+/// Bind the admin listener and serve it in the background
+async fn serve_admin(
+    config: &Config,
+    state: api::AppState,
+    shutdown: &watch::Receiver<bool>,
+) -> Result<Option<JoinHandle<()>>> {
+    let app = admin::create_admin_router(state, config.admin_token.clone());
+    let shutdown = shutdown_requested(shutdown);
+
+    if let Some(admin_port) = config.admin_port {
+        let addr: SocketAddr = format!("{}:{}", config.host, admin_port)
+            .parse()
+            .context("Invalid host:admin_port combination")?;
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .context("Failed to bind admin listener")?;
+        info!("Admin API listening on http://{}", addr);
+        return Ok(Some(tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown)
+                .await
+            {
+                tracing::error!("Admin server error: {:#}", e);
+            }
+        })));
+    }
+
+    #[cfg(unix)]
+    if let Some(path) = &config.admin_socket {
+        // A socket left behind by an unclean exit would make the bind fail
+        if path.exists() {
+            std::fs::remove_file(path).with_context(|| {
+                format!("Failed to remove stale admin socket {}", path.display())
+            })?;
+        }
+        let listener = tokio::net::UnixListener::bind(path)
+            .with_context(|| format!("Failed to bind admin socket {}", path.display()))?;
+        info!("Admin API listening on unix:{}", path.display());
+        return Ok(Some(tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown)
+                .await
+            {
+                tracing::error!("Admin server error: {:#}", e);
+            }
+        })));
+    }
+
+    Ok(None)
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
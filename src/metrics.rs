@@ -0,0 +1,155 @@
+use serde::Serialize;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Per-call timings from one `synthesize` call
+#[derive(Debug, Clone, Copy)]
+pub struct SynthesisTimings {
+    /// Time spent waiting for a free inference worker
+    pub queue_wait: Duration,
+    /// Time inside kokoros, covering phonemization (G2P) and ONNX inference
+    pub synthesis: Duration,
+    /// Number of audio samples produced
+    pub samples: u64,
+}
+
+/// Point-in-time copy of `BackendMetrics`
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MetricsSnapshot {
+    pub syntheses_total: u64,
+    pub synthesis_failures_total: u64,
+    pub queue_wait_seconds_total: f64,
+    pub synthesis_seconds_total: f64,
+    pub audio_samples_total: u64,
+    pub audio_seconds_total: f64,
+}
+
+/// Cumulative backend counters rendered at `/metrics`
+#[derive(Debug, Default)]
+pub struct BackendMetrics {
+    syntheses_total: AtomicU64,
+    synthesis_failures_total: AtomicU64,
+    queue_wait_micros_total: AtomicU64,
+    synthesis_micros_total: AtomicU64,
+    samples_total: AtomicU64,
+}
+
+impl BackendMetrics {
+    pub fn record_success(&self, timings: &SynthesisTimings) {
+        self.syntheses_total.fetch_add(1, Ordering::Relaxed);
+        self.queue_wait_micros_total
+            .fetch_add(timings.queue_wait.as_micros() as u64, Ordering::Relaxed);
+        self.synthesis_micros_total
+            .fetch_add(timings.synthesis.as_micros() as u64, Ordering::Relaxed);
+        self.samples_total
+            .fetch_add(timings.samples, Ordering::Relaxed);
+    }
+
+    pub fn record_failure(&self) {
+        self.synthesis_failures_total
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Current counter values, as reported by the admin stats endpoint
+    pub fn snapshot(&self, sample_rate: u32) -> MetricsSnapshot {
+        let samples = self.samples_total.load(Ordering::Relaxed);
+        MetricsSnapshot {
+            syntheses_total: self.syntheses_total.load(Ordering::Relaxed),
+            synthesis_failures_total: self.synthesis_failures_total.load(Ordering::Relaxed),
+            queue_wait_seconds_total: micros_to_secs(
+                self.queue_wait_micros_total.load(Ordering::Relaxed),
+            ),
+            synthesis_seconds_total: micros_to_secs(
+                self.synthesis_micros_total.load(Ordering::Relaxed),
+            ),
+            audio_samples_total: samples,
+            audio_seconds_total: samples as f64 / f64::from(sample_rate.max(1)),
+        }
+    }
+
+    /// Render counters in the Prometheus text exposition format
+    pub fn render_prometheus(&self, sample_rate: u32) -> String {
+        let snapshot = self.snapshot(sample_rate);
+        let metrics = [
+            (
+                "kokoro_syntheses_total",
+                "counter",
+                "Successful synthesis calls",
+                snapshot.syntheses_total as f64,
+            ),
+            (
+                "kokoro_synthesis_failures_total",
+                "counter",
+                "Failed synthesis calls",
+                snapshot.synthesis_failures_total as f64,
+            ),
+            (
+                "kokoro_queue_wait_seconds_total",
+                "counter",
+                "Time spent waiting for an inference worker",
+                snapshot.queue_wait_seconds_total,
+            ),
+            (
+                "kokoro_synthesis_seconds_total",
+                "counter",
+                "Time spent in phonemization and model inference",
+                snapshot.synthesis_seconds_total,
+            ),
+            (
+                "kokoro_audio_samples_total",
+                "counter",
+                "Audio samples generated",
+                snapshot.audio_samples_total as f64,
+            ),
+            (
+                "kokoro_audio_seconds_total",
+                "counter",
+                "Seconds of audio generated",
+                snapshot.audio_seconds_total,
+            ),
+        ];
+
+        let mut out = String::new();
+        for (name, kind, help, value) in metrics {
+            write_metric(&mut out, name, kind, help, value);
+        }
+        out
+    }
+}
+
+/// Append one metric with its HELP and TYPE lines in Prometheus text format
+pub fn write_metric(out: &mut String, name: &str, kind: &str, help: &str, value: f64) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    let _ = writeln!(out, "{name} {value}");
+}
+
+fn micros_to_secs(micros: u64) -> f64 {
+    micros as f64 / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus_accumulates() {
+        let metrics = BackendMetrics::default();
+        let timings = SynthesisTimings {
+            queue_wait: Duration::from_millis(500),
+            synthesis: Duration::from_secs(2),
+            samples: 24_000,
+        };
+        metrics.record_success(&timings);
+        metrics.record_success(&timings);
+        metrics.record_failure();
+
+        let text = metrics.render_prometheus(24_000);
+        assert!(text.contains("kokoro_syntheses_total 2\n"));
+        assert!(text.contains("kokoro_synthesis_failures_total 1\n"));
+        assert!(text.contains("kokoro_queue_wait_seconds_total 1\n"));
+        assert!(text.contains("kokoro_synthesis_seconds_total 4\n"));
+        assert!(text.contains("kokoro_audio_seconds_total 2\n"));
+    }
+}
@@ -0,0 +1,116 @@
+use crate::language::Language;
+use std::f32::consts::TAU;
+
+/// Characters spoken per second at speed 1.0, roughly Kokoro's pace for English
+const CHARS_PER_SECOND: f32 = 15.0;
+/// Shortest clip returned, so even a single character produces audible output
+const MIN_SECONDS: f32 = 0.25;
+const AMPLITUDE: f32 = 0.2;
+/// Fade applied at both ends so chunked streams do not click between clips
+const FADE_SECONDS: f32 = 0.01;
+/// Text that leaves a test build's engine stuck, so stall handling can be exercised
+#[cfg(test)]
+pub const HANG_MARKER: &str = "hang forever";
+
+/// Stand-in engine that returns a sine tone instead of running ONNX Runtime.
+///
+/// The clip length follows the text length and speed the way real speech
+/// would, and each voice gets its own pitch so voice selection is audible.
+pub struct MockEngine {
+    sample_rate: u32,
+}
+
+impl MockEngine {
+    pub fn new(sample_rate: u32) -> Self {
+        Self { sample_rate }
+    }
+
+    pub fn synthesize(
+        &self,
+        text: &str,
+        voice: &str,
+        speed: f32,
+        initial_silence: Option<usize>,
+    ) -> Vec<f32> {
+        #[cfg(test)]
+        if text.contains(HANG_MARKER) {
+            std::thread::sleep(std::time::Duration::from_secs(600));
+        }
+        let rate = self.sample_rate as f32;
+        let seconds =
+            (text.trim().chars().count() as f32 / (CHARS_PER_SECOND * speed)).max(MIN_SECONDS);
+        let len = (seconds * rate) as usize;
+        let fade = ((FADE_SECONDS * rate) as usize).max(1);
+        let frequency = voice_frequency(voice);
+
+        let mut samples = vec![0.0; initial_silence.unwrap_or(0)];
+        samples.extend((0..len).map(|i| {
+            let envelope = (i.min(len - 1 - i) as f32 / fade as f32).min(1.0);
+            AMPLITUDE * envelope * (TAU * frequency * i as f32 / rate).sin()
+        }));
+        samples
+    }
+}
+
+/// Stand-in phonemes: the eSpeak-ng language flag, such as `(es)`, then the lowercased letters
+/// and spaces of `text`, with their code points as token IDs
+pub fn phonemize(text: &str, language: Language) -> (String, Vec<i64>) {
+    let letters: String = text
+        .chars()
+        .filter(|c| c.is_alphabetic() || *c == ' ')
+        .flat_map(char::to_lowercase)
+        .collect();
+    let phonemes = format!("({}) {letters}", language.espeak_code());
+    let tokens = phonemes.chars().map(|c| i64::from(u32::from(c))).collect();
+    (phonemes, tokens)
+}
+
+/// Pitch between 160 and 400 Hz derived from the voice name
+fn voice_frequency(voice: &str) -> f32 {
+    let hash = voice.bytes().fold(0u32, |hash, byte| {
+        hash.wrapping_mul(31).wrapping_add(u32::from(byte))
+    });
+    160.0 + (hash % 240) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration_follows_text_and_speed() {
+        let engine = MockEngine::new(24_000);
+        let text = "a".repeat(150);
+
+        assert_eq!(
+            engine.synthesize(&text, "af_heart", 1.0, None).len(),
+            240_000
+        );
+        assert_eq!(
+            engine.synthesize(&text, "af_heart", 2.0, None).len(),
+            120_000
+        );
+        assert_eq!(engine.synthesize("", "af_heart", 1.0, None).len(), 6_000);
+
+        let padded = engine.synthesize(&text, "af_heart", 1.0, Some(100));
+        assert_eq!(padded.len(), 240_100);
+        assert!(padded[..100].iter().all(|&sample| sample == 0.0));
+        assert!(padded.iter().all(|sample| sample.abs() <= AMPLITUDE));
+    }
+
+    #[test]
+    fn test_phonemize() {
+        let (phonemes, tokens) = phonemize("Hi, Bob!", Language::AmericanEnglish);
+        assert_eq!(phonemes, "(en-us) hi bob");
+        assert_eq!(tokens.len(), 14);
+        assert_eq!(tokens[8], 'h' as i64);
+        assert!(phonemize("Hola", Language::Chinese).0.starts_with("(cmn)"));
+    }
+
+    #[test]
+    fn test_voice_frequency() {
+        assert_eq!(voice_frequency("af_heart"), voice_frequency("af_heart"));
+        assert_ne!(voice_frequency("af_heart"), voice_frequency("am_adam"));
+        assert!((160.0..400.0).contains(&voice_frequency("bm_george")));
+    }
+}
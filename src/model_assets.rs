@@ -0,0 +1,282 @@
+use crate::config::Config;
+use crate::download::{download_from_any, lock_download, mirror_urls, sha256_file};
+use crate::voice_pack;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tokio::fs;
+use tracing::info;
+
+/// Resolved locations of the Kokoro model and voice pack on disk
+#[derive(Debug, Clone)]
+pub struct ModelFiles {
+    pub model_path: PathBuf,
+    pub voices_path: PathBuf,
+}
+
+impl ModelFiles {
+    /// Model at `model_path` with `voices.json` expected alongside it
+    pub fn from_model_path(model_path: PathBuf) -> Self {
+        let voices_path = model_path
+            .parent()
+            .unwrap_or(&model_path)
+            .join("voices.json");
+        Self {
+            model_path,
+            voices_path,
+        }
+    }
+
+    /// Identity of the files' current contents, from their sizes and modification times. Cache
+    /// keys carry it so clips from another model or voice pack are never served.
+    pub async fn fingerprint(&self) -> String {
+        let mut parts = Vec::with_capacity(2);
+        for path in [&self.model_path, &self.voices_path] {
+            parts.push(match fs::metadata(path).await {
+                Ok(metadata) => {
+                    let modified = metadata
+                        .modified()
+                        .ok()
+                        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                        .map_or(0, |since| since.as_nanos());
+                    format!("{}@{}", metadata.len(), modified)
+                }
+                Err(_) => "-".to_string(),
+            });
+        }
+        parts.join("/")
+    }
+}
+
+/// File name the model is saved under when it is downloaded from `KOKORO_MODEL_URL`
+const DOWNLOADED_MODEL_FILE: &str = "kokoro.onnx";
+
+/// Weight precision of the model, inferred from its file name.
+///
+/// Follows the naming in onnx-community/Kokoro-82M-v1.0-ONNX (`model_fp16.onnx`,
+/// `model_quantized.onnx`, `model_q4f16.onnx`, ...). A downloaded model is named after its
+/// source URL; names without a recognized suffix are assumed to be full precision.
+pub fn model_quantization(files: &ModelFiles, model_url: &str) -> &'static str {
+    let local_name = files
+        .model_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let name = if local_name == DOWNLOADED_MODEL_FILE {
+        model_url.rsplit('/').next().unwrap_or_default()
+    } else {
+        local_name
+    };
+
+    let stem = name.split(['?', '#']).next().unwrap_or_default();
+    let stem = stem.strip_suffix(".onnx").unwrap_or(stem);
+    match stem.rsplit_once('_').map(|(_, suffix)| suffix) {
+        Some("fp16") => "fp16",
+        Some("quantized") => "int8",
+        Some("q8f16") => "q8f16",
+        Some("uint8") => "uint8",
+        Some("uint8f16") => "uint8f16",
+        Some("q4") => "q4",
+        Some("q4f16") => "q4f16",
+        _ => "fp32",
+    }
+}
+
+struct ModelAsset<'a> {
+    label: &'static str,
+    path: &'a Path,
+    url: &'a str,
+    /// Mirror base URLs tried before `url`
+    mirrors: &'a [String],
+    sha256: Option<&'a str>,
+}
+
+/// Make sure the model and voices files exist locally, downloading them when allowed.
+pub async fn ensure_model_files(config: &Config) -> Result<ModelFiles> {
+    let files = resolve_model_files(config).await?;
+
+    ensure_asset(
+        ModelAsset {
+            label: "model",
+            path: &files.model_path,
+            url: &config.model_url,
+            mirrors: &config.download_mirrors,
+            sha256: config.model_sha256.as_deref(),
+        },
+        config.offline,
+    )
+    .await?;
+
+    ensure_asset(
+        ModelAsset {
+            label: "voices",
+            path: &files.voices_path,
+            url: &config.voices_url,
+            mirrors: &config.download_mirrors,
+            sha256: config.voices_sha256.as_deref(),
+        },
+        config.offline,
+    )
+    .await?;
+
+    // Reading the pack means unzipping it, so it stays off the runtime
+    let (voices_path, voices_url) = (files.voices_path.clone(), config.voices_url.clone());
+    tokio::task::spawn_blocking(move || check_voice_pack(&voices_path, &voices_url))
+        .await
+        .context("Voice pack check panicked")??;
+
+    Ok(files)
+}
+
+/// Fail with instructions for replacing the voices file when it is not a usable voice pack.
+///
+/// kokoros panics deep inside model loading on a bad voice pack, so this runs first.
+fn check_voice_pack(path: &Path, voices_url: &str) -> Result<()> {
+    let voices = voice_pack::voice_names(path).map_err(|e| {
+        anyhow::anyhow!(
+            "voices file at {} is not a valid Kokoro voice pack: {:#}. Delete it to download it again from {} (KOKORO_VOICES_URL), or replace it with the voices-v1.0.bin release saved as voices.json next to the model",
+            path.display(),
+            e,
+            voices_url
+        )
+    })?;
+    info!(path = %path.display(), count = voices.len(), "Voice pack validated");
+    Ok(())
+}
+
+/// Where the model and voices files live, without checking or downloading them
+pub async fn resolve_model_files(config: &Config) -> Result<ModelFiles> {
+    if let Some(ref path) = config.model_path {
+        return Ok(ModelFiles::from_model_path(path.clone()));
+    }
+
+    let cache_dir = dirs::cache_dir()
+        .context("Failed to determine cache directory")?
+        .join("kokoro-openai-server")
+        .join("models");
+    fs::create_dir_all(&cache_dir)
+        .await
+        .context("Failed to create model cache directory")?;
+
+    Ok(ModelFiles {
+        model_path: cache_dir.join(DOWNLOADED_MODEL_FILE),
+        voices_path: cache_dir.join("voices.json"),
+    })
+}
+
+/// Whether the file is already in place; fails if it does not match the pinned SHA256
+async fn existing_asset_is_valid(asset: &ModelAsset<'_>) -> Result<bool> {
+    let is_file = fs::metadata(asset.path)
+        .await
+        .is_ok_and(|metadata| metadata.is_file());
+    if !is_file {
+        return Ok(false);
+    }
+
+    if let Some(expected) = asset.sha256 {
+        let path = asset.path.to_path_buf();
+        let actual = tokio::task::spawn_blocking(move || sha256_file(&path))
+            .await
+            .context("Hashing task panicked")??;
+        if !expected.eq_ignore_ascii_case(&actual) {
+            anyhow::bail!(
+                "{} file at {} does not match the configured SHA256 (expected {}, got {}); delete it to re-download",
+                asset.label,
+                asset.path.display(),
+                expected,
+                actual
+            );
+        }
+    }
+    info!(path = %asset.path.display(), "Using existing {} file", asset.label);
+    Ok(true)
+}
+
+async fn ensure_asset(asset: ModelAsset<'_>, offline: bool) -> Result<()> {
+    if existing_asset_is_valid(&asset).await? {
+        return Ok(());
+    }
+
+    if offline {
+        anyhow::bail!(
+            "{} file not found at {} and downloads are disabled (KOKORO_OFFLINE). Download it from {} or set KOKORO_MODEL_PATH to a model file with voices.json next to it",
+            asset.label,
+            asset.path.display(),
+            asset.url
+        );
+    }
+
+    if let Some(parent) = asset.path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    // Another process sharing the cache may already be downloading this file
+    let _lock = lock_download(asset.path).await?;
+    if existing_asset_is_valid(&asset).await? {
+        return Ok(());
+    }
+
+    info!(url = %asset.url, path = %asset.path.display(), "Downloading {} file", asset.label);
+    download_from_any(
+        &mirror_urls(asset.url, asset.mirrors),
+        asset.path,
+        asset.sha256,
+    )
+    .await
+    .with_context(|| format!("Failed to download {} file", asset.label))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_model_quantization() {
+        let downloaded = ModelFiles::from_model_path(PathBuf::from("/cache/kokoro.onnx"));
+        assert_eq!(
+            model_quantization(
+                &downloaded,
+                "https://huggingface.co/onnx-community/Kokoro-82M-v1.0-ONNX/resolve/main/onnx/model.onnx"
+            ),
+            "fp32"
+        );
+        assert_eq!(
+            model_quantization(
+                &downloaded,
+                "https://example.com/onnx/model_q8f16.onnx?download=true"
+            ),
+            "q8f16"
+        );
+
+        let local = ModelFiles::from_model_path(PathBuf::from("/models/model_quantized.onnx"));
+        assert_eq!(
+            model_quantization(&local, "https://example.com/model_fp16.onnx"),
+            "int8"
+        );
+        let local = ModelFiles::from_model_path(PathBuf::from("/models/my_model.onnx"));
+        assert_eq!(model_quantization(&local, ""), "fp32");
+    }
+
+    #[tokio::test]
+    async fn test_fingerprint_follows_files() {
+        let dir = std::env::temp_dir().join(format!("kokoro-files-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let files = ModelFiles::from_model_path(dir.join("kokoro.onnx"));
+        assert_eq!(files.fingerprint().await, "-/-");
+
+        fs::write(&files.model_path, b"model").unwrap();
+        fs::write(&files.voices_path, b"voices").unwrap();
+        let loaded = files.fingerprint().await;
+        assert!(loaded.starts_with("5@"), "{loaded}");
+        assert_eq!(files.fingerprint().await, loaded);
+
+        fs::write(&files.voices_path, b"other voices").unwrap();
+        assert_ne!(files.fingerprint().await, loaded);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,689 @@
+use crate::{
+    config::{DateOrder, MeasurementSystem, TimeFormat},
+    dates::{self, Date, Time},
+    language::Language,
+    numbers,
+    units::{Currency, Noun, Scale, Unit},
+};
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+/// A date written year first: `2024-03-05`, `2024/3/5`
+static YEAR_FIRST_DATE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?P<year>[0-9]{4})[-/](?P<month>[0-9]{1,2})[-/](?P<day>[0-9]{1,2})")
+        .expect("valid regex")
+});
+
+/// A date written as two numbers and a year: `3/5/2024`, `05.03.2024`, `3/5/24`
+static NUMERIC_DATE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?P<first>[0-9]{1,2})(?P<separator>[/.])(?P<second>[0-9]{1,2})[/.](?P<year>[0-9]{4}|[0-9]{2})",
+    )
+    .expect("valid regex")
+});
+
+/// A clock time with optional seconds and AM/PM: `14:30`, `9:05 am`, `12:00:15 P.M.`
+static TIME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?P<hour>[0-9]{1,2}):(?P<minute>[0-9]{2})(?::(?P<second>[0-9]{2}))?(?:[ \u{a0}]?(?P<meridiem>[AaPp](?:\.[Mm]\.|[Mm](?-u:\b))))?",
+    )
+    .expect("valid regex")
+});
+
+/// Money written with the currency first: `$5.50`, `€3.2M`, `USD 12`
+static CURRENCY_BEFORE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?P<sign>-)?(?P<currency>[$€£¥₹]|(?-u:\b)(?:USD|EUR|GBP|JPY|CNY|RMB|INR)[ \u{a0}]?)(?P<amount>[0-9]+(?:[.,][0-9]+)*)(?:[ \u{a0}]?(?P<scale>(?:[KkMB]|bn|mn|thousand|million|billion)(?-u:\b)))?",
+    )
+    .expect("valid regex")
+});
+
+/// Money written with the currency last, as much of Europe does: `5,50 €`, `100 EUR`
+static CURRENCY_AFTER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?P<sign>-)?(?P<amount>[0-9]+(?:[.,][0-9]+)*)(?:[ \u{a0}]?(?P<scale>(?:[KkMB]|bn|mn|thousand|million|billion)(?-u:\b)))?[ \u{a0}\u{202f}]?(?P<currency>[€£¥₹]|(?:USD|EUR|GBP|JPY|CNY|RMB|INR)(?-u:\b))",
+    )
+    .expect("valid regex")
+});
+
+/// A measurement: `15km/h`, `20°C`, `5 kg`, `50%`. A unit followed by `/` (`m/s`) is not
+/// one of these and is left alone.
+static UNIT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?P<sign>-)?(?P<amount>[0-9]+(?:[.,][0-9]+)*)[ \u{a0}\u{202f}]?(?P<unit>(?:km/h|kph|mph|km|cm|mm|mi|ft|kg|mg|lbs|lb|oz|ml|mL|m|g|l|L|t)(?-u:\b)|°[CF](?-u:\b)|°|%)(?P<per>/)?",
+    )
+    .expect("valid regex")
+});
+
+/// A number with an optional leading minus, digit groups joined by `.` or `,`, and a suffix
+/// written straight after it ("1st", "2e", "3.º")
+static NUMBER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?P<sign>-)?(?P<number>[0-9]+(?:[.,][0-9]+)*)(?P<suffix>\.?[ºª]|\p{Latin}+)?")
+        .expect("valid regex")
+});
+
+/// How request text is rewritten before synthesis
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalizeRules {
+    /// System ambiguous units such as `t` are read in
+    pub measurement_system: MeasurementSystem,
+    /// Order the numbers of a date like `03/05/2024` are taken in, and English dates are read in
+    pub date_order: DateOrder,
+    /// Clock times are read on
+    pub time_format: TimeFormat,
+    /// How whole numbers outside dates, times, money, and measurements are read
+    pub number_style: NumberStyle,
+}
+
+/// Number styles a request may ask for
+pub const NUMBER_STYLES: [&str; 4] = ["cardinal", "ordinal", "year", "digits"];
+
+/// How a whole number such as `2024` is read, when the client knows what it stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberStyle {
+    /// "two thousand twenty-four"; numbers with a leading zero are still read digit by digit
+    #[default]
+    Cardinal,
+    /// "two thousand twenty-fourth"
+    Ordinal,
+    /// "twenty twenty-four"
+    Year,
+    /// "two zero two four"
+    Digits,
+}
+
+impl NumberStyle {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "cardinal" => Some(Self::Cardinal),
+            "ordinal" => Some(Self::Ordinal),
+            "year" => Some(Self::Year),
+            "digits" => Some(Self::Digits),
+            _ => None,
+        }
+    }
+}
+
+/// Rewrite dates, times, money, measurements, and numbers in `text` as words in `language`, so "123" is read
+/// "ciento veintitrés" by a Spanish voice rather than digit by digit or in English.
+///
+/// Numbers written against letters ("B12") are left as they are.
+pub fn normalize(text: &str, language: Language, rules: &NormalizeRules) -> String {
+    if !numbers::supported(language) {
+        return text.to_string();
+    }
+    let text = replace(text, &YEAR_FIRST_DATE_REGEX, language, |caps| {
+        let date = Date::new(
+            caps["year"].parse().ok()?,
+            caps["month"].parse().ok()?,
+            caps["day"].parse().ok()?,
+        )?;
+        dates::date(date, language, rules.date_order)
+    });
+    let text = replace(&text, &NUMERIC_DATE_REGEX, language, |caps| {
+        numeric_date(caps, language, rules.date_order)
+    });
+    let text = replace(&text, &TIME_REGEX, language, |caps| {
+        time(caps, language, rules.time_format)
+    });
+    let text = replace(&text, &CURRENCY_BEFORE_REGEX, language, |caps| {
+        money(caps, language)
+    });
+    let text = replace(&text, &CURRENCY_AFTER_REGEX, language, |caps| {
+        money(caps, language)
+    });
+    let text = replace(&text, &UNIT_REGEX, language, |caps| {
+        measurement(caps, language, rules.measurement_system)
+    });
+    replace(&text, &NUMBER_REGEX, language, |caps| {
+        number(caps, language, rules.number_style)
+    })
+}
+
+/// Replace each match of `regex` that stands apart from the letters and digits around it with
+/// the words `speak` gives for it, leaving the matches it returns `None` for as written.
+///
+/// A leading `sign` group is read as a minus sign when it does not follow a word.
+fn replace(
+    text: &str,
+    regex: &Regex,
+    language: Language,
+    speak: impl Fn(&Captures) -> Option<String>,
+) -> String {
+    // Japanese and Chinese write counters straight after numbers ("3個"), so only Latin
+    // letters and digits attach to one there
+    let attached = |c: char| match language {
+        Language::Japanese | Language::Chinese => c.is_ascii_alphanumeric(),
+        _ => c.is_alphanumeric(),
+    };
+    let mut normalized = String::with_capacity(text.len());
+    let mut last = 0;
+    for caps in regex.captures_iter(text) {
+        let whole = caps.get_match();
+        let before = text[..whole.start()].chars().next_back();
+        let after = text[whole.end()..].chars().next();
+        let sign = caps.name("sign");
+        if (sign.is_none() && before.is_some_and(attached)) || after.is_some_and(attached) {
+            continue;
+        }
+        // A hyphen after a word joins it to the number ("COVID-19") rather than negating it
+        let start = match sign {
+            Some(sign) if !before.is_none_or(|c| c.is_whitespace() || c == '(') => sign.end(),
+            _ => whole.start(),
+        };
+        let Some(words) = speak(&caps) else {
+            continue;
+        };
+        normalized.push_str(&text[last..start]);
+        if start == whole.start() && sign.is_some() {
+            normalized.push_str(numbers::minus(language).unwrap_or_default());
+        }
+        normalized.push_str(&words);
+        last = whole.end();
+    }
+    normalized.push_str(&text[last..]);
+    normalized
+}
+
+/// Words for a date like `3/5/2024`. Dotted dates (`05.03.2024`) are always day first, as
+/// they are written where dots are used, and need a four-digit year to tell them from other
+/// dotted numbers; two-digit years are taken to be within 50 years of 2000.
+fn numeric_date(caps: &Captures, language: Language, order: DateOrder) -> Option<String> {
+    let (order, year) = match (&caps["separator"], caps["year"].len()) {
+        (".", 4) => (DateOrder::Dmy, caps["year"].parse().ok()?),
+        (".", _) => return None,
+        (_, 4) => (order, caps["year"].parse().ok()?),
+        _ => match caps["year"].parse::<u64>().ok()? {
+            year @ 0..=49 => (order, 2000 + year),
+            year => (order, 1900 + year),
+        },
+    };
+    let date = Date::from_parts(
+        caps["first"].parse().ok()?,
+        caps["second"].parse().ok()?,
+        year,
+        order,
+    )?;
+    dates::date(date, language, order)
+}
+
+/// Words for a clock time, or `None` when it is not a time of day ("25:00")
+fn time(caps: &Captures, language: Language, format: TimeFormat) -> Option<String> {
+    let hour: u64 = caps["hour"].parse().ok()?;
+    let minute: u64 = caps["minute"].parse().ok()?;
+    let second = match caps.name("second") {
+        Some(second) => Some(second.as_str().parse().ok().filter(|&s: &u64| s < 60)?),
+        None => None,
+    };
+    if minute >= 60 {
+        return None;
+    }
+    let hour = match caps.name("meridiem") {
+        Some(meridiem) => {
+            if !(1..=12).contains(&hour) {
+                return None;
+            }
+            let pm = meridiem.as_str().starts_with(['P', 'p']);
+            hour % 12 + if pm { 12 } else { 0 }
+        }
+        None if hour < 24 => hour,
+        None => return None,
+    };
+    let time = Time {
+        hour,
+        minute,
+        second,
+        meridiem_known: caps.name("meridiem").is_some() || hour == 0 || hour >= 13,
+    };
+    dates::time(time, language, format)
+}
+
+/// Words for an amount of money: "five dollars and fifty cents", "tres millones de euros"
+fn money(caps: &Captures, language: Language) -> Option<String> {
+    let currency = Currency::from_symbol(&caps["currency"], language)?;
+    let (major, minor) = currency.names(language)?;
+    let amount = Amount::parse(&caps["amount"], language)?;
+
+    if let Some(scale) = caps.name("scale") {
+        let scale = Scale::from_suffix(scale.as_str())?;
+        let mut scale_name = scale.name(language)?;
+        // A thousand agrees with the currency: "doscientas mil libras"
+        scale_name.feminine = scale == Scale::Thousand && major.feminine;
+        // "mil dólares", not "un mil dólares"
+        let bare = amount.fraction.is_none()
+            && amount.integer == 1
+            && !is_english(language)
+            && !is_spaceless(language)
+            && (scale == Scale::Thousand
+                || (scale == Scale::Billion && language == Language::Spanish));
+        let scaled = if bare {
+            scale_name.one.to_string()
+        } else {
+            quantity(&amount, &scale_name, language)?
+        };
+        return Some(if is_spaceless(language) {
+            format!("{}{}", scaled, major.one)
+        } else if scale == Scale::Thousand || is_english(language) {
+            format!("{} {}", scaled, major.many)
+        } else {
+            format!("{} {}{}", scaled, of(language, major.many), major.many)
+        });
+    }
+
+    match (amount.fraction, minor) {
+        (Some(fraction), Some(minor)) if fraction.len() == 2 => {
+            let cents = Amount {
+                integer: fraction.parse().ok()?,
+                fraction: None,
+            };
+            let whole = Amount {
+                integer: amount.integer,
+                fraction: None,
+            };
+            Some(match (whole.integer, cents.integer) {
+                (_, 0) => quantity(&whole, &major, language)?,
+                (0, _) => quantity(&cents, &minor, language)?,
+                _ => {
+                    let and = match language {
+                        Language::Spanish => " con ",
+                        Language::French => " et ",
+                        Language::Italian | Language::Portuguese => " e ",
+                        Language::Japanese | Language::Chinese => "",
+                        _ => " and ",
+                    };
+                    format!(
+                        "{}{}{}",
+                        quantity(&whole, &major, language)?,
+                        and,
+                        quantity(&cents, &minor, language)?
+                    )
+                }
+            })
+        }
+        _ => quantity(&amount, &major, language),
+    }
+}
+
+/// Words for a measurement: "fifteen kilometers per hour", "摂氏二十度"
+fn measurement(caps: &Captures, language: Language, system: MeasurementSystem) -> Option<String> {
+    if caps.name("per").is_some() {
+        return None;
+    }
+    let unit = Unit::from_symbol(&caps["unit"], system)?;
+    quantity(
+        &Amount::parse(&caps["amount"], language)?,
+        &unit.name(language)?,
+        language,
+    )
+}
+
+/// A number read with a noun after it
+struct Amount<'a> {
+    integer: u64,
+    /// Digits after the decimal separator
+    fraction: Option<&'a str>,
+}
+
+impl<'a> Amount<'a> {
+    fn parse(number: &'a str, language: Language) -> Option<Self> {
+        let (integer, fraction) = match parse(number, language)? {
+            Parsed::Integer(integer) => (integer, None),
+            Parsed::Decimal(integer, fraction) => (integer, Some(fraction)),
+        };
+        Some(Self {
+            integer: integer
+                .replace(group_separator(language), "")
+                .parse()
+                .ok()?,
+            fraction,
+        })
+    }
+
+    /// Whether the noun takes its singular form; French uses it for everything under two
+    fn singular(&self, language: Language) -> bool {
+        match language {
+            Language::French => self.integer < 2,
+            _ => self.integer == 1 && self.fraction.is_none(),
+        }
+    }
+}
+
+/// `amount` followed by `noun` in the right number: "one dollar", "dos libras", "百分之五十"
+fn quantity(amount: &Amount, noun: &Noun, language: Language) -> Option<String> {
+    let number = match amount.fraction {
+        Some(fraction) => numbers::decimal(amount.integer, fraction, language)?,
+        None => numbers::cardinal_for_noun(amount.integer, language, noun.feminine)?,
+    };
+    let name = if amount.singular(language) {
+        noun.one
+    } else {
+        noun.many
+    };
+    Some(if is_spaceless(language) {
+        format!("{}{}{}", noun.prefix, number, name)
+    } else if name.is_empty() {
+        number
+    } else if number
+        .rsplit(' ')
+        .next()
+        .is_some_and(numbers::is_large_scale)
+    {
+        // "dos millones de kilómetros"
+        format!("{} {}{}", number, of(language, name), name)
+    } else {
+        format!("{} {}", number, name)
+    })
+}
+
+/// The "of" Romance languages put between millions and what they count
+fn of(language: Language, name: &str) -> &'static str {
+    match language {
+        Language::Spanish | Language::Portuguese => "de ",
+        Language::Italian => "di ",
+        Language::French if name.starts_with(['a', 'e', 'é', 'i', 'o', 'u', 'h']) => "d'",
+        Language::French => "de ",
+        _ => "",
+    }
+}
+
+fn is_english(language: Language) -> bool {
+    matches!(
+        language,
+        Language::AmericanEnglish | Language::BritishEnglish
+    )
+}
+
+fn is_spaceless(language: Language) -> bool {
+    matches!(language, Language::Japanese | Language::Chinese)
+}
+
+/// Words for one matched number, or `None` to leave it as written
+fn number(caps: &Captures, language: Language, style: NumberStyle) -> Option<String> {
+    let number = &caps["number"];
+    if let Some(suffix) = caps.name("suffix") {
+        if !number.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let feminine = ordinal_suffix(suffix.as_str(), language)?;
+        return numbers::ordinal(number.parse().ok()?, language, feminine);
+    }
+    Some(match parse(number, language) {
+        Some(Parsed::Integer(digits)) => styled_integer(digits, language, style)?,
+        Some(Parsed::Decimal(integer, fraction)) => numbers::decimal(
+            integer
+                .replace(group_separator(language), "")
+                .parse()
+                .ok()?,
+            fraction,
+            language,
+        )?,
+        // Not a number in this language's notation, such as "1,2,3": read each run of digits
+        None => {
+            let mut words = String::new();
+            let mut run = String::new();
+            for c in number.chars() {
+                if c.is_ascii_digit() {
+                    run.push(c);
+                } else {
+                    words.push_str(&integer(&run, language)?);
+                    words.push(c);
+                    run.clear();
+                }
+            }
+            words.push_str(&integer(&run, language)?);
+            words
+        }
+    })
+}
+
+enum Parsed<'a> {
+    /// Digits, possibly in thousands groups
+    Integer(&'a str),
+    /// Integer part, possibly in thousands groups, and the digits after the decimal separator
+    Decimal(&'a str, &'a str),
+}
+
+/// Read a number written with the separators of `language`: `1,234.5` in English and
+/// `1.234,5` in Spanish, French, Italian, and Portuguese. A single `.` is taken as a decimal
+/// point in any language, as numbers copied from English text often are.
+fn parse(number: &str, language: Language) -> Option<Parsed<'_>> {
+    let group = group_separator(language);
+    let decimal = if group == '.' { ',' } else { '.' };
+    let separators = number.matches([',', '.']).count();
+    if separators == 0 {
+        return Some(Parsed::Integer(number));
+    }
+
+    let grouped = |digits: &str| {
+        let mut groups = digits.split(group);
+        !digits.contains(decimal)
+            && groups
+                .next()
+                .is_some_and(|first| (1..=3).contains(&first.len()))
+            && groups.all(|rest| rest.len() == 3)
+    };
+    if grouped(number) {
+        return Some(Parsed::Integer(number));
+    }
+    let (integer, fraction) = number.rsplit_once([',', '.'])?;
+    let point = number[integer.len()..].chars().next()?;
+    if (point == decimal && grouped(integer)) || (point == '.' && separators == 1) {
+        return Some(Parsed::Decimal(integer, fraction));
+    }
+    None
+}
+
+fn group_separator(language: Language) -> char {
+    match language {
+        Language::Spanish | Language::French | Language::Italian | Language::Portuguese => '.',
+        _ => ',',
+    }
+}
+
+/// An integer as words; written with a leading zero ("007") it is read digit by digit
+fn integer(digits: &str, language: Language) -> Option<String> {
+    let digits_only = digits.replace(group_separator(language), "");
+    if digits_only.len() > 1 && digits_only.starts_with('0') {
+        return numbers::digits(&digits_only, language);
+    }
+    match digits_only.parse() {
+        Ok(n) if n <= numbers::MAX_CARDINAL => numbers::cardinal(n, language),
+        _ => numbers::digits(&digits_only, language),
+    }
+}
+
+/// An integer read in the requested style, falling back to a cardinal where the style has no
+/// words for it, such as ordinals past those spelled out for the language
+fn styled_integer(digits: &str, language: Language, style: NumberStyle) -> Option<String> {
+    let digits_only = digits.replace(group_separator(language), "");
+    let styled = match (style, digits_only.parse::<u64>()) {
+        (NumberStyle::Cardinal, _) => None,
+        (NumberStyle::Digits, _) => numbers::digits(&digits_only, language),
+        (NumberStyle::Ordinal, Ok(n)) => numbers::ordinal(n, language, false),
+        (NumberStyle::Year, Ok(n)) => numbers::year(n, language),
+        (_, Err(_)) => None,
+    };
+    styled.or_else(|| integer(digits, language))
+}
+
+/// Whether `suffix` marks an ordinal in `language`, and if so whether it is feminine
+fn ordinal_suffix(suffix: &str, language: Language) -> Option<bool> {
+    let suffix = suffix.trim_start_matches('.').to_lowercase();
+    match (language, suffix.as_str()) {
+        (Language::AmericanEnglish | Language::BritishEnglish, "st" | "nd" | "rd" | "th") => {
+            Some(false)
+        }
+        (Language::French, "er" | "e" | "è" | "ème" | "eme") => Some(false),
+        (Language::French, "re" | "ère") => Some(true),
+        (Language::Spanish | Language::Italian | Language::Portuguese, "º") => Some(false),
+        (Language::Spanish | Language::Italian | Language::Portuguese, "ª") => Some(true),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn say(text: &str, language: Language) -> String {
+        normalize(text, language, &NormalizeRules::default())
+    }
+
+    #[test]
+    fn test_normalize_numbers() {
+        let en = Language::AmericanEnglish;
+        assert_eq!(
+            say("Pay 1,250 now, or 3.5 later.", en),
+            "Pay one thousand two hundred fifty now, or three point five later."
+        );
+        assert_eq!(
+            say("It fell to -4 overnight.", en),
+            "It fell to minus four overnight."
+        );
+        assert_eq!(
+            say("The 21st of 007 agents", en),
+            "The twenty-first of zero zero seven agents"
+        );
+        assert_eq!(
+            say("COVID-19 on a B12 route", en),
+            "COVID-nineteen on a B12 route"
+        );
+        assert_eq!(say("Pick 1,2,3.", en), "Pick one,two,three.");
+    }
+
+    #[test]
+    fn test_normalize_number_styles() {
+        let styled = |text: &str, number_style| {
+            let rules = NormalizeRules {
+                number_style,
+                ..NormalizeRules::default()
+            };
+            normalize(text, Language::AmericanEnglish, &rules)
+        };
+        assert_eq!(
+            styled("Since 2024", NumberStyle::Cardinal),
+            "Since two thousand twenty-four"
+        );
+        assert_eq!(
+            styled("Since 2024", NumberStyle::Year),
+            "Since twenty twenty-four"
+        );
+        assert_eq!(
+            styled("Room 2024, 3.5 m", NumberStyle::Digits),
+            "Room two zero two four, three point five meters"
+        );
+        assert_eq!(styled("Rank 3", NumberStyle::Ordinal), "Rank third");
+        assert_eq!(NumberStyle::from_name("Year"), Some(NumberStyle::Year));
+        assert_eq!(NumberStyle::from_name("roman"), None);
+    }
+
+    #[test]
+    fn test_normalize_localized() {
+        assert_eq!(
+            say(
+                "Tengo 123 libros y 1.500 euros, 2,5 kilos.",
+                Language::Spanish
+            ),
+            "Tengo ciento veintitrés libros y mil quinientos euros, dos coma cinco kilos."
+        );
+        assert_eq!(
+            say("1.234,5", Language::Italian),
+            "milleduecentotrentaquattro virgola cinque"
+        );
+        assert_eq!(
+            say("La 1.ª fila y la 3ª planta", Language::Spanish),
+            "La primera fila y la tercera planta"
+        );
+        assert_eq!(
+            say("Le 1er et la 2e place, 71 ans", Language::French),
+            "Le premier et la deuxième place, soixante et onze ans"
+        );
+        assert_eq!(say("3個と15人", Language::Japanese), "三個と十五人");
+        assert_eq!(say("Hindi 123", Language::Hindi), "Hindi 123");
+    }
+
+    #[test]
+    fn test_normalize_money() {
+        let en = Language::AmericanEnglish;
+        assert_eq!(say("$5.50", en), "five dollars and fifty cents");
+        assert_eq!(say("$0.99 or $1", en), "ninety-nine cents or one dollar");
+        assert_eq!(say("USD 3.2M", en), "three point two million dollars");
+        assert_eq!(say("-£20", en), "minus twenty pounds");
+        assert_eq!(
+            say("5,50 € y €1", Language::Spanish),
+            "cinco euros con cincuenta céntimos y un euro"
+        );
+        assert_eq!(say("£21", Language::Spanish), "veintiuna libras");
+        assert_eq!(say("€1K", Language::Spanish), "mil euros");
+        assert_eq!(say("€3M", Language::French), "trois millions d'euros");
+        assert_eq!(say("¥500", Language::Japanese), "五百円");
+        assert_eq!(say("¥500", Language::Chinese), "五百元");
+    }
+
+    #[test]
+    fn test_normalize_units() {
+        let en = Language::AmericanEnglish;
+        assert_eq!(
+            say("15km/h at 20°C", en),
+            "fifteen kilometers per hour at twenty degrees Celsius"
+        );
+        assert_eq!(
+            say("1 mi, 2.5 kg, 3 m/s", en),
+            "one mile, two point five kilograms, three m/s"
+        );
+        assert_eq!(say("50%", Language::BritishEnglish), "fifty per cent");
+        assert_eq!(
+            say("2000000 km", Language::Spanish),
+            "dos millones de kilómetros"
+        );
+        assert_eq!(say("20°C", Language::Japanese), "摂氏二十度");
+        assert_eq!(say("50%", Language::Chinese), "百分之五十");
+
+        let imperial = NormalizeRules {
+            measurement_system: MeasurementSystem::Imperial,
+            ..NormalizeRules::default()
+        };
+        assert_eq!(
+            normalize("It is -5°F out", en, &imperial),
+            "It is minus five degrees Fahrenheit out"
+        );
+        assert_eq!(
+            normalize("a 90° angle", en, &imperial),
+            "a ninety degrees angle"
+        );
+        assert_eq!(say("It is 5° out", en), "It is five degrees out");
+    }
+
+    #[test]
+    fn test_normalize_dates_and_times() {
+        let en = Language::AmericanEnglish;
+        assert_eq!(
+            say("Due 2024-03-05 at 14:30.", en),
+            "Due March fifth, twenty twenty-four at two thirty PM."
+        );
+        assert_eq!(
+            say("Born 3/5/1999, up at 9:05 am", en),
+            "Born March fifth, nineteen ninety-nine, up at nine oh five AM"
+        );
+        assert_eq!(
+            say("El 05.03.2024 a las 21:15", Language::Spanish),
+            "El cinco de marzo de dos mil veinticuatro a las nueve y quince de la noche"
+        );
+        // Not a date or a time of day, so read as numbers
+        assert_eq!(
+            say("13/13/2024", en),
+            "thirteen/thirteen/two thousand twenty-four"
+        );
+        assert_eq!(say("25:00", en), "twenty-five:zero zero");
+
+        let european = NormalizeRules {
+            date_order: DateOrder::Dmy,
+            time_format: TimeFormat::TwentyFourHour,
+            ..NormalizeRules::default()
+        };
+        assert_eq!(
+            normalize("On 3/5/24 at 14:30", en, &european),
+            "On the third of May, twenty twenty-four at fourteen thirty"
+        );
+    }
+}
@@ -0,0 +1,1027 @@
+use crate::language::Language;
+
+/// Largest number read out as a cardinal; longer digit strings are read digit by digit
+pub const MAX_CARDINAL: u64 = 999_999_999_999;
+
+const EN_ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const EN_TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+const ES_ONES: [&str; 30] = [
+    "cero",
+    "uno",
+    "dos",
+    "tres",
+    "cuatro",
+    "cinco",
+    "seis",
+    "siete",
+    "ocho",
+    "nueve",
+    "diez",
+    "once",
+    "doce",
+    "trece",
+    "catorce",
+    "quince",
+    "dieciséis",
+    "diecisiete",
+    "dieciocho",
+    "diecinueve",
+    "veinte",
+    "veintiuno",
+    "veintidós",
+    "veintitrés",
+    "veinticuatro",
+    "veinticinco",
+    "veintiséis",
+    "veintisiete",
+    "veintiocho",
+    "veintinueve",
+];
+const ES_TENS: [&str; 10] = [
+    "",
+    "",
+    "veinte",
+    "treinta",
+    "cuarenta",
+    "cincuenta",
+    "sesenta",
+    "setenta",
+    "ochenta",
+    "noventa",
+];
+const ES_HUNDREDS: [&str; 10] = [
+    "",
+    "ciento",
+    "doscientos",
+    "trescientos",
+    "cuatrocientos",
+    "quinientos",
+    "seiscientos",
+    "setecientos",
+    "ochocientos",
+    "novecientos",
+];
+const ES_ORDINAL_ONES: [&str; 10] = [
+    "", "primero", "segundo", "tercero", "cuarto", "quinto", "sexto", "séptimo", "octavo", "noveno",
+];
+const ES_ORDINAL_TENS: [&str; 10] = [
+    "",
+    "décimo",
+    "vigésimo",
+    "trigésimo",
+    "cuadragésimo",
+    "quincuagésimo",
+    "sexagésimo",
+    "septuagésimo",
+    "octogésimo",
+    "nonagésimo",
+];
+
+const FR_ONES: [&str; 17] = [
+    "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf", "dix", "onze",
+    "douze", "treize", "quatorze", "quinze", "seize",
+];
+const FR_TENS: [&str; 7] = [
+    "",
+    "",
+    "vingt",
+    "trente",
+    "quarante",
+    "cinquante",
+    "soixante",
+];
+
+const IT_ONES: [&str; 20] = [
+    "zero",
+    "uno",
+    "due",
+    "tre",
+    "quattro",
+    "cinque",
+    "sei",
+    "sette",
+    "otto",
+    "nove",
+    "dieci",
+    "undici",
+    "dodici",
+    "tredici",
+    "quattordici",
+    "quindici",
+    "sedici",
+    "diciassette",
+    "diciotto",
+    "diciannove",
+];
+const IT_TENS: [&str; 10] = [
+    "",
+    "",
+    "venti",
+    "trenta",
+    "quaranta",
+    "cinquanta",
+    "sessanta",
+    "settanta",
+    "ottanta",
+    "novanta",
+];
+const IT_ORDINALS: [&str; 11] = [
+    "", "primo", "secondo", "terzo", "quarto", "quinto", "sesto", "settimo", "ottavo", "nono",
+    "decimo",
+];
+
+const PT_ONES: [&str; 20] = [
+    "zero",
+    "um",
+    "dois",
+    "três",
+    "quatro",
+    "cinco",
+    "seis",
+    "sete",
+    "oito",
+    "nove",
+    "dez",
+    "onze",
+    "doze",
+    "treze",
+    "quatorze",
+    "quinze",
+    "dezesseis",
+    "dezessete",
+    "dezoito",
+    "dezenove",
+];
+const PT_TENS: [&str; 10] = [
+    "",
+    "",
+    "vinte",
+    "trinta",
+    "quarenta",
+    "cinquenta",
+    "sessenta",
+    "setenta",
+    "oitenta",
+    "noventa",
+];
+const PT_HUNDREDS: [&str; 10] = [
+    "",
+    "cento",
+    "duzentos",
+    "trezentos",
+    "quatrocentos",
+    "quinhentos",
+    "seiscentos",
+    "setecentos",
+    "oitocentos",
+    "novecentos",
+];
+const PT_ORDINAL_ONES: [&str; 10] = [
+    "", "primeiro", "segundo", "terceiro", "quarto", "quinto", "sexto", "sétimo", "oitavo", "nono",
+];
+const PT_ORDINAL_TENS: [&str; 10] = [
+    "",
+    "décimo",
+    "vigésimo",
+    "trigésimo",
+    "quadragésimo",
+    "quinquagésimo",
+    "sexagésimo",
+    "septuagésimo",
+    "octogésimo",
+    "nonagésimo",
+];
+
+const CJK_DIGITS: [&str; 10] = ["零", "一", "二", "三", "四", "五", "六", "七", "八", "九"];
+
+/// Whether numbers are written out for `language`; Hindi is left to the phonemizer
+pub fn supported(language: Language) -> bool {
+    language != Language::Hindi
+}
+
+/// `n` as words, e.g. 123 as "ciento veintitrés" in Spanish
+pub fn cardinal(n: u64, language: Language) -> Option<String> {
+    if n > MAX_CARDINAL {
+        return None;
+    }
+    match language {
+        Language::AmericanEnglish => Some(english(n, false)),
+        Language::BritishEnglish => Some(english(n, true)),
+        Language::Spanish => Some(spanish(n)),
+        Language::French => Some(french(n)),
+        Language::Italian => Some(italian(n)),
+        Language::Portuguese => Some(portuguese(n)),
+        Language::Japanese => Some(japanese(n)),
+        Language::Chinese => Some(chinese(n)),
+        Language::Hindi => None,
+    }
+}
+
+/// `n` as an ordinal ("first", "primera", "vingt et unième"); `None` where the language has no
+/// written ordinal suffix or the number is past the ordinals spelled out for it
+pub fn ordinal(n: u64, language: Language, feminine: bool) -> Option<String> {
+    if n == 0 {
+        return None;
+    }
+    let ordinal = match language {
+        Language::AmericanEnglish | Language::BritishEnglish => {
+            english_ordinal(&cardinal(n, language)?)
+        }
+        Language::Spanish => compound_ordinal(n, &ES_ORDINAL_ONES, &ES_ORDINAL_TENS, "centésimo")
+            .map(|ordinal| {
+            // 11th to 19th are written as one word: "decimotercero"
+            match ordinal.strip_prefix("décimo ") {
+                Some(rest) => format!("decimo{}", rest),
+                None => ordinal,
+            }
+        })?,
+        Language::French => french_ordinal(n, feminine),
+        Language::Italian => italian_ordinal(n)?,
+        Language::Portuguese => {
+            compound_ordinal(n, &PT_ORDINAL_ONES, &PT_ORDINAL_TENS, "centésimo")?
+        }
+        Language::Hindi | Language::Japanese | Language::Chinese => return None,
+    };
+    Some(match language {
+        Language::Spanish | Language::Italian | Language::Portuguese if feminine => ordinal
+            .split(' ')
+            .map(|word| match word.strip_suffix('o') {
+                Some(stem) => format!("{}a", stem),
+                None => word.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => ordinal,
+    })
+}
+
+/// A decimal number from its integer part and the digits after the separator
+pub fn decimal(integer: u64, fraction: &str, language: Language) -> Option<String> {
+    let whole = cardinal(integer, language)?;
+    let (point, fraction) = match language {
+        Language::AmericanEnglish | Language::BritishEnglish => {
+            ("point", digits(fraction, language)?)
+        }
+        Language::Japanese | Language::Chinese => {
+            return Some(format!("{}点{}", whole, digits(fraction, language)?))
+        }
+        Language::Spanish => ("coma", romance_fraction(fraction, language)?),
+        Language::French => ("virgule", romance_fraction(fraction, language)?),
+        Language::Italian => ("virgola", romance_fraction(fraction, language)?),
+        Language::Portuguese => ("vírgula", romance_fraction(fraction, language)?),
+        Language::Hindi => return None,
+    };
+    Some(format!("{} {} {}", whole, point, fraction))
+}
+
+/// Each digit of `digits` read on its own, as in "zero zero seven"
+pub fn digits(digits: &str, language: Language) -> Option<String> {
+    let words = digits
+        .chars()
+        .map(|c| cardinal(u64::from(c.to_digit(10)?), language))
+        .collect::<Option<Vec<_>>>()?;
+    Some(match language {
+        Language::Japanese | Language::Chinese => words.concat(),
+        _ => words.join(" "),
+    })
+}
+
+/// `n` read as a year: "nineteen oh five" and "twenty twenty-four" in English, digit by digit
+/// in Chinese (二〇二四), and as a plain number elsewhere
+pub fn year(n: u64, language: Language) -> Option<String> {
+    match language {
+        Language::AmericanEnglish | Language::BritishEnglish
+            if (1000..10_000).contains(&n)
+                && !n.is_multiple_of(1000)
+                && !(2000..2010).contains(&n) =>
+        {
+            let (century, rest) = (n / 100, n % 100);
+            let century = english(century, false);
+            Some(match rest {
+                0 => format!("{} hundred", century),
+                1..=9 => format!("{} oh {}", century, EN_ONES[rest as usize]),
+                _ => format!("{} {}", century, english(rest, false)),
+            })
+        }
+        Language::Chinese => Some(digits(&n.to_string(), language)?.replace('零', "〇")),
+        _ => cardinal(n, language),
+    }
+}
+
+/// `n` agreeing with the noun it counts: "un dólar", "veintiuna libras", "duas libras"
+pub fn cardinal_for_noun(n: u64, language: Language, feminine: bool) -> Option<String> {
+    let cardinal = cardinal(n, language)?;
+    let mut words = cardinal.split(' ').map(str::to_string).collect::<Vec<_>>();
+    // Words before "millones" count millions, which are masculine; only the rest agree
+    let start = words
+        .iter()
+        .rposition(|word| is_large_scale(word))
+        .map_or(0, |at| at + 1);
+    let Some(last) = words.len().checked_sub(1).filter(|&last| last >= start) else {
+        return Some(cardinal);
+    };
+    match (language, feminine) {
+        (Language::Spanish, false) => words[last] = spanish_apocope(&words[last]),
+        (Language::Spanish, true) => {
+            for word in &mut words[start..] {
+                if let Some(stem) = word.strip_suffix("ientos") {
+                    *word = format!("{}ientas", stem);
+                }
+            }
+            if let Some(stem) = words[last].strip_suffix("uno") {
+                words[last] = format!("{}una", stem);
+            }
+        }
+        (Language::French, true) if words[last].ends_with("un") => words[last].push('e'),
+        (Language::Italian, _) if n == 1 => {
+            words[last] = if feminine { "una" } else { "un" }.to_string()
+        }
+        (Language::Portuguese, true) => {
+            for word in &mut words[start..] {
+                *word = match word.as_str() {
+                    "um" => "uma".to_string(),
+                    "dois" => "duas".to_string(),
+                    _ => match word.strip_suffix("entos") {
+                        Some(stem) => format!("{}entas", stem),
+                        None => word.clone(),
+                    },
+                };
+            }
+        }
+        _ => {}
+    }
+    Some(words.join(" "))
+}
+
+/// Millions and billions, which take "de" before the noun they count in Romance languages
+pub fn is_large_scale(word: &str) -> bool {
+    matches!(
+        word,
+        "millón"
+            | "millones"
+            | "million"
+            | "millions"
+            | "milliard"
+            | "milliards"
+            | "milione"
+            | "milioni"
+            | "miliardo"
+            | "miliardi"
+            | "milhão"
+            | "milhões"
+            | "bilhão"
+            | "bilhões"
+    )
+}
+
+/// Word read for a leading minus sign
+pub fn minus(language: Language) -> Option<&'static str> {
+    match language {
+        Language::AmericanEnglish | Language::BritishEnglish => Some("minus "),
+        Language::Spanish | Language::Portuguese => Some("menos "),
+        Language::French => Some("moins "),
+        Language::Italian => Some("meno "),
+        Language::Japanese => Some("マイナス"),
+        Language::Chinese => Some("负"),
+        Language::Hindi => None,
+    }
+}
+
+/// Romance languages read a short fraction as a number after its leading zeros ("coma cero
+/// cinco"), and a long one digit by digit
+fn romance_fraction(fraction: &str, language: Language) -> Option<String> {
+    let significant = fraction.trim_start_matches('0');
+    if significant.is_empty() || significant.len() > 3 {
+        return digits(fraction, language);
+    }
+    let mut words = vec![cardinal(0, language)?; fraction.len() - significant.len()];
+    words.push(cardinal(significant.parse().ok()?, language)?);
+    Some(words.join(" "))
+}
+
+/// Split `n` into its billions, millions, thousands, and units
+fn groups(n: u64) -> [u64; 4] {
+    [
+        n / 1_000_000_000,
+        n / 1_000_000 % 1000,
+        n / 1000 % 1000,
+        n % 1000,
+    ]
+}
+
+fn english(n: u64, british: bool) -> String {
+    if n == 0 {
+        return EN_ONES[0].to_string();
+    }
+    let mut words = Vec::new();
+    for (group, scale) in groups(n)
+        .into_iter()
+        .zip(["billion", "million", "thousand", ""])
+    {
+        if group == 0 {
+            continue;
+        }
+        // British English joins a final group under a hundred with "and": "one thousand and five"
+        if british && scale.is_empty() && group < 100 && !words.is_empty() {
+            words.push("and".to_string());
+        }
+        words.push(english_below_thousand(group, british));
+        if !scale.is_empty() {
+            words.push(scale.to_string());
+        }
+    }
+    words.join(" ")
+}
+
+fn english_below_thousand(n: u64, british: bool) -> String {
+    let (hundreds, rest) = (n / 100, n % 100);
+    let tens = match rest {
+        0 => String::new(),
+        1..=19 => EN_ONES[rest as usize].to_string(),
+        _ if rest % 10 == 0 => EN_TENS[rest as usize / 10].to_string(),
+        _ => format!(
+            "{}-{}",
+            EN_TENS[rest as usize / 10],
+            EN_ONES[rest as usize % 10]
+        ),
+    };
+    match (hundreds, rest) {
+        (0, _) => tens,
+        (_, 0) => format!("{} hundred", EN_ONES[hundreds as usize]),
+        _ if british => format!("{} hundred and {}", EN_ONES[hundreds as usize], tens),
+        _ => format!("{} hundred {}", EN_ONES[hundreds as usize], tens),
+    }
+}
+
+fn english_ordinal(cardinal: &str) -> String {
+    let (head, last) = match cardinal.rfind([' ', '-']) {
+        Some(at) => cardinal.split_at(at + 1),
+        None => ("", cardinal),
+    };
+    let last = match last {
+        "one" => "first".to_string(),
+        "two" => "second".to_string(),
+        "three" => "third".to_string(),
+        "five" => "fifth".to_string(),
+        "eight" => "eighth".to_string(),
+        "nine" => "ninth".to_string(),
+        "twelve" => "twelfth".to_string(),
+        _ => match last.strip_suffix('y') {
+            Some(stem) => format!("{}ieth", stem),
+            None => format!("{}th", last),
+        },
+    };
+    format!("{}{}", head, last)
+}
+
+/// Ordinals built from a units word and a tens word, as Spanish and Portuguese write them up
+/// to 100: "vigésimo primero"
+fn compound_ordinal(
+    n: u64,
+    ones: &[&str; 10],
+    tens: &[&str; 10],
+    hundredth: &str,
+) -> Option<String> {
+    match n {
+        1..=9 => Some(ones[n as usize].to_string()),
+        10..=99 if n.is_multiple_of(10) => Some(tens[n as usize / 10].to_string()),
+        10..=99 => Some(format!(
+            "{} {}",
+            tens[n as usize / 10],
+            ones[n as usize % 10]
+        )),
+        100 => Some(hundredth.to_string()),
+        _ => None,
+    }
+}
+
+fn spanish(n: u64) -> String {
+    if n == 0 {
+        return ES_ONES[0].to_string();
+    }
+    // Spanish counts thousands of millions ("mil millones") rather than billions
+    let (millions, rest) = (n / 1_000_000, n % 1_000_000);
+    let mut words = Vec::new();
+    match millions {
+        0 => {}
+        1 => words.push("un millón".to_string()),
+        _ => words.push(format!(
+            "{} millones",
+            spanish_apocope(&spanish_below_million(millions))
+        )),
+    }
+    if rest > 0 {
+        words.push(spanish_below_million(rest));
+    }
+    words.join(" ")
+}
+
+fn spanish_below_million(n: u64) -> String {
+    let (thousands, units) = (n / 1000, n % 1000);
+    let mut words = Vec::new();
+    match thousands {
+        0 => {}
+        1 => words.push("mil".to_string()),
+        _ => words.push(format!(
+            "{} mil",
+            spanish_apocope(&spanish_below_thousand(thousands))
+        )),
+    }
+    if units > 0 {
+        words.push(spanish_below_thousand(units));
+    }
+    words.join(" ")
+}
+
+fn spanish_below_thousand(n: u64) -> String {
+    if n == 100 {
+        return "cien".to_string();
+    }
+    let (hundreds, rest) = (n / 100, n % 100);
+    let tens = match rest {
+        0 => String::new(),
+        1..=29 => ES_ONES[rest as usize].to_string(),
+        _ if rest % 10 == 0 => ES_TENS[rest as usize / 10].to_string(),
+        _ => format!(
+            "{} y {}",
+            ES_TENS[rest as usize / 10],
+            ES_ONES[rest as usize % 10]
+        ),
+    };
+    match (hundreds, rest) {
+        (0, _) => tens,
+        (_, 0) => ES_HUNDREDS[hundreds as usize].to_string(),
+        _ => format!("{} {}", ES_HUNDREDS[hundreds as usize], tens),
+    }
+}
+
+/// "uno" shortens before a noun: "veintiún mil", "treinta y un millones"
+fn spanish_apocope(words: &str) -> String {
+    if let Some(stem) = words.strip_suffix("veintiuno") {
+        format!("{}veintiún", stem)
+    } else if let Some(stem) = words.strip_suffix("uno") {
+        format!("{}un", stem)
+    } else {
+        words.to_string()
+    }
+}
+
+fn french(n: u64) -> String {
+    if n == 0 {
+        return FR_ONES[0].to_string();
+    }
+    let [billions, millions, thousands, units] = groups(n);
+    let mut words = Vec::new();
+    for (group, scale) in [(billions, "milliard"), (millions, "million")] {
+        match group {
+            0 => {}
+            1 => words.push(format!("un {}", scale)),
+            _ => words.push(format!("{} {}s", french_below_thousand(group), scale)),
+        }
+    }
+    match thousands {
+        0 => {}
+        1 => words.push("mille".to_string()),
+        // "mille" takes no plural and stops "vingts" and "cents" from taking one
+        _ => words.push(format!(
+            "{} mille",
+            french_singular(&french_below_thousand(thousands))
+        )),
+    }
+    if units > 0 {
+        words.push(french_below_thousand(units));
+    }
+    words.join(" ")
+}
+
+fn french_below_thousand(n: u64) -> String {
+    let (hundreds, rest) = (n / 100, n % 100);
+    let hundreds = match (hundreds, rest) {
+        (0, _) => String::new(),
+        (1, _) => "cent".to_string(),
+        (_, 0) => format!("{} cents", FR_ONES[hundreds as usize]),
+        _ => format!("{} cent", FR_ONES[hundreds as usize]),
+    };
+    match (hundreds.is_empty(), rest) {
+        (_, 0) => hundreds,
+        (true, _) => french_below_hundred(rest),
+        (false, _) => format!("{} {}", hundreds, french_below_hundred(rest)),
+    }
+}
+
+fn french_below_hundred(n: u64) -> String {
+    match n {
+        0..=16 => FR_ONES[n as usize].to_string(),
+        17..=19 => format!("dix-{}", FR_ONES[n as usize - 10]),
+        20..=69 => match n % 10 {
+            0 => FR_TENS[n as usize / 10].to_string(),
+            1 => format!("{} et un", FR_TENS[n as usize / 10]),
+            ones => format!("{}-{}", FR_TENS[n as usize / 10], FR_ONES[ones as usize]),
+        },
+        71 => "soixante et onze".to_string(),
+        70..=79 => format!("soixante-{}", french_below_hundred(n - 60)),
+        80 => "quatre-vingts".to_string(),
+        _ => format!("quatre-vingt-{}", french_below_hundred(n - 80)),
+    }
+}
+
+/// "quatre-vingts" and "deux cents" drop their plural when another word follows them
+fn french_singular(words: &str) -> &str {
+    if words.ends_with("vingts") || words.ends_with("cents") {
+        &words[..words.len() - 1]
+    } else {
+        words
+    }
+}
+
+fn french_ordinal(n: u64, feminine: bool) -> String {
+    if n == 1 {
+        return if feminine { "première" } else { "premier" }.to_string();
+    }
+    let cardinal = french(n);
+    let stem = french_singular(&cardinal);
+    let stem = if let Some(stem) = stem.strip_suffix("cinq") {
+        format!("{}cinqu", stem)
+    } else if let Some(stem) = stem.strip_suffix("neuf") {
+        format!("{}neuv", stem)
+    } else {
+        stem.strip_suffix('e').unwrap_or(stem).to_string()
+    };
+    format!("{}ième", stem)
+}
+
+fn italian(n: u64) -> String {
+    if n == 0 {
+        return IT_ONES[0].to_string();
+    }
+    let [billions, millions, thousands, units] = groups(n);
+    let mut words = Vec::new();
+    for (group, one, many) in [
+        (billions, "un miliardo", "miliardi"),
+        (millions, "un milione", "milioni"),
+    ] {
+        match group {
+            0 => {}
+            1 => words.push(one.to_string()),
+            _ => words.push(format!("{} {}", italian_below_thousand(group), many)),
+        }
+    }
+    // Thousands and units are written as one word: "duemilatrecento"
+    let mut below_million = match thousands {
+        0 => String::new(),
+        1 => "mille".to_string(),
+        _ => format!("{}mila", italian_below_thousand(thousands)),
+    };
+    if units > 0 {
+        below_million.push_str(&italian_below_thousand(units));
+    }
+    if !below_million.is_empty() {
+        words.push(below_million);
+    }
+    let words = words.join(" ");
+    // A compound ending in "tre" is stressed on it: "ventitré", "centotré"
+    match words.strip_suffix("tre") {
+        Some(stem) if n > 3 && !stem.is_empty() && !stem.ends_with(' ') => format!("{}tré", stem),
+        _ => words,
+    }
+}
+
+fn italian_below_thousand(n: u64) -> String {
+    let (hundreds, rest) = (n / 100, n % 100);
+    let mut words = match hundreds {
+        0 => String::new(),
+        1 => "cento".to_string(),
+        _ => format!("{}cento", IT_ONES[hundreds as usize]),
+    };
+    match rest {
+        0 => {}
+        1..=19 => words.push_str(IT_ONES[rest as usize]),
+        _ => {
+            let (tens, ones) = (IT_TENS[rest as usize / 10], rest % 10);
+            // Tens drop their final vowel before "uno" and "otto": "ventuno", "trentotto"
+            if ones == 1 || ones == 8 {
+                words.push_str(&tens[..tens.len() - 1]);
+            } else {
+                words.push_str(tens);
+            }
+            if ones > 0 {
+                words.push_str(IT_ONES[ones as usize]);
+            }
+        }
+    }
+    words
+}
+
+fn italian_ordinal(n: u64) -> Option<String> {
+    if let Some(ordinal) = IT_ORDINALS.get(n as usize) {
+        return Some(ordinal.to_string());
+    }
+    let cardinal = italian(n);
+    Some(if let Some(stem) = cardinal.strip_suffix("tré") {
+        format!("{}treesimo", stem)
+    } else if cardinal.ends_with("sei") {
+        format!("{}esimo", cardinal)
+    } else {
+        let mut stem = cardinal;
+        stem.pop();
+        format!("{}esimo", stem)
+    })
+}
+
+fn portuguese(n: u64) -> String {
+    if n == 0 {
+        return PT_ONES[0].to_string();
+    }
+    let groups = groups(n);
+    let last = groups.iter().rposition(|&group| group > 0).unwrap_or(0);
+    let mut words: Vec<String> = Vec::new();
+    for (i, (group, one, many)) in [
+        (groups[0], "um bilhão", "bilhões"),
+        (groups[1], "um milhão", "milhões"),
+        (groups[2], "mil", "mil"),
+        (groups[3], "", ""),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if group == 0 {
+            continue;
+        }
+        // "e" joins the last group when it is under a hundred or a round hundred
+        if !words.is_empty() && i == last && (group < 100 || group % 100 == 0) {
+            words.push("e".to_string());
+        }
+        words.push(match (group, many) {
+            (_, "") => portuguese_below_thousand(group),
+            (1, _) => one.to_string(),
+            _ => format!("{} {}", portuguese_below_thousand(group), many),
+        });
+    }
+    words.join(" ")
+}
+
+fn portuguese_below_thousand(n: u64) -> String {
+    if n == 100 {
+        return "cem".to_string();
+    }
+    let (hundreds, rest) = (n / 100, n % 100);
+    let mut words = Vec::new();
+    if hundreds > 0 {
+        words.push(PT_HUNDREDS[hundreds as usize]);
+    }
+    match rest {
+        0 => {}
+        1..=19 => words.push(PT_ONES[rest as usize]),
+        _ => {
+            words.push(PT_TENS[rest as usize / 10]);
+            if rest % 10 > 0 {
+                words.push(PT_ONES[rest as usize % 10]);
+            }
+        }
+    }
+    words.join(" e ")
+}
+
+fn japanese(n: u64) -> String {
+    if n == 0 {
+        return CJK_DIGITS[0].to_string();
+    }
+    let mut words = String::new();
+    for (group, scale) in [
+        (n / 100_000_000, "億"),
+        (n / 10_000 % 10_000, "万"),
+        (n % 10_000, ""),
+    ] {
+        if group == 0 {
+            continue;
+        }
+        for (place, unit) in [(1000, "千"), (100, "百"), (10, "十")] {
+            match group / place % 10 {
+                0 => {}
+                1 => words.push_str(unit),
+                digit => {
+                    words.push_str(CJK_DIGITS[digit as usize]);
+                    words.push_str(unit);
+                }
+            }
+        }
+        if group % 10 > 0 {
+            words.push_str(CJK_DIGITS[group as usize % 10]);
+        }
+        words.push_str(scale);
+    }
+    words
+}
+
+fn chinese(n: u64) -> String {
+    if n == 0 {
+        return CJK_DIGITS[0].to_string();
+    }
+    let mut words = String::new();
+    // A gap in the digits is read once as 零: 一百零五, 一万零五十
+    let mut gap = false;
+    for (group, scale) in [
+        (n / 100_000_000, "亿"),
+        (n / 10_000 % 10_000, "万"),
+        (n % 10_000, ""),
+    ] {
+        if group == 0 {
+            gap |= !words.is_empty();
+            continue;
+        }
+        for (place, unit) in [(1000, "千"), (100, "百"), (10, "十"), (1, "")] {
+            let digit = group / place % 10;
+            if digit == 0 {
+                gap |= !words.is_empty();
+                continue;
+            }
+            if gap {
+                words.push_str(CJK_DIGITS[0]);
+                gap = false;
+            }
+            // A leading ten is read 十, not 一十
+            if !(digit == 1 && place == 10 && words.is_empty()) {
+                words.push_str(CJK_DIGITS[digit as usize]);
+            }
+            words.push_str(unit);
+        }
+        words.push_str(scale);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn say(n: u64, language: Language) -> String {
+        cardinal(n, language).unwrap()
+    }
+
+    #[test]
+    fn test_cardinal_english() {
+        let en = Language::AmericanEnglish;
+        assert_eq!(say(0, en), "zero");
+        assert_eq!(say(42, en), "forty-two");
+        assert_eq!(say(123, en), "one hundred twenty-three");
+        assert_eq!(say(1_005, en), "one thousand five");
+        assert_eq!(say(2_000_310, en), "two million three hundred ten");
+        assert_eq!(
+            say(1_005, Language::BritishEnglish),
+            "one thousand and five"
+        );
+        assert_eq!(
+            say(123, Language::BritishEnglish),
+            "one hundred and twenty-three"
+        );
+        assert_eq!(cardinal(MAX_CARDINAL + 1, en), None);
+    }
+
+    #[test]
+    fn test_cardinal_romance() {
+        let es = Language::Spanish;
+        assert_eq!(say(123, es), "ciento veintitrés");
+        assert_eq!(say(100, es), "cien");
+        assert_eq!(say(21_000, es), "veintiún mil");
+        assert_eq!(say(31_000_001, es), "treinta y un millones uno");
+        assert_eq!(say(1_500, es), "mil quinientos");
+
+        let fr = Language::French;
+        assert_eq!(say(71, fr), "soixante et onze");
+        assert_eq!(say(80, fr), "quatre-vingts");
+        assert_eq!(say(97, fr), "quatre-vingt-dix-sept");
+        assert_eq!(say(200, fr), "deux cents");
+        assert_eq!(say(80_000, fr), "quatre-vingt mille");
+        assert_eq!(say(2_000_000, fr), "deux millions");
+
+        let it = Language::Italian;
+        assert_eq!(say(23, it), "ventitré");
+        assert_eq!(say(38, it), "trentotto");
+        assert_eq!(say(2_345, it), "duemilatrecentoquarantacinque");
+        assert_eq!(say(1_000_000, it), "un milione");
+
+        let pt = Language::Portuguese;
+        assert_eq!(say(123, pt), "cento e vinte e três");
+        assert_eq!(say(1_200, pt), "mil e duzentos");
+        assert_eq!(say(1_234, pt), "mil duzentos e trinta e quatro");
+        assert_eq!(say(2_000_000, pt), "dois milhões");
+    }
+
+    #[test]
+    fn test_cardinal_cjk() {
+        assert_eq!(say(123, Language::Japanese), "百二十三");
+        assert_eq!(say(20_005, Language::Japanese), "二万五");
+        assert_eq!(say(15, Language::Chinese), "十五");
+        assert_eq!(say(105, Language::Chinese), "一百零五");
+        assert_eq!(say(10_050, Language::Chinese), "一万零五十");
+        assert_eq!(cardinal(5, Language::Hindi), None);
+    }
+
+    #[test]
+    fn test_ordinal() {
+        let en = Language::AmericanEnglish;
+        assert_eq!(ordinal(1, en, false).unwrap(), "first");
+        assert_eq!(ordinal(22, en, false).unwrap(), "twenty-second");
+        assert_eq!(ordinal(40, en, false).unwrap(), "fortieth");
+        assert_eq!(ordinal(112, en, false).unwrap(), "one hundred twelfth");
+
+        let es = Language::Spanish;
+        assert_eq!(ordinal(3, es, true).unwrap(), "tercera");
+        assert_eq!(ordinal(13, es, false).unwrap(), "decimotercero");
+        assert_eq!(ordinal(21, es, false).unwrap(), "vigésimo primero");
+        assert_eq!(ordinal(101, es, false), None);
+
+        let fr = Language::French;
+        assert_eq!(ordinal(1, fr, true).unwrap(), "première");
+        assert_eq!(ordinal(5, fr, false).unwrap(), "cinquième");
+        assert_eq!(ordinal(21, fr, false).unwrap(), "vingt et unième");
+        assert_eq!(ordinal(80, fr, false).unwrap(), "quatre-vingtième");
+
+        let it = Language::Italian;
+        assert_eq!(ordinal(11, it, false).unwrap(), "undicesimo");
+        assert_eq!(ordinal(23, it, false).unwrap(), "ventitreesimo");
+        assert_eq!(ordinal(2, it, true).unwrap(), "seconda");
+
+        assert_eq!(
+            ordinal(21, Language::Portuguese, true).unwrap(),
+            "vigésima primeira"
+        );
+        assert_eq!(ordinal(3, Language::Japanese, false), None);
+    }
+
+    #[test]
+    fn test_year() {
+        let en = Language::AmericanEnglish;
+        assert_eq!(year(2024, en).unwrap(), "twenty twenty-four");
+        assert_eq!(year(1905, en).unwrap(), "nineteen oh five");
+        assert_eq!(year(1900, en).unwrap(), "nineteen hundred");
+        assert_eq!(year(2005, en).unwrap(), "two thousand five");
+        assert_eq!(year(2000, en).unwrap(), "two thousand");
+        assert_eq!(year(2024, Language::Chinese).unwrap(), "二〇二四");
+        assert_eq!(
+            year(2024, Language::Spanish).unwrap(),
+            "dos mil veinticuatro"
+        );
+    }
+
+    #[test]
+    fn test_cardinal_for_noun() {
+        let noun = |n, language, feminine| cardinal_for_noun(n, language, feminine).unwrap();
+        assert_eq!(noun(1, Language::Spanish, false), "un");
+        assert_eq!(noun(21, Language::Spanish, true), "veintiuna");
+        assert_eq!(noun(500, Language::Spanish, true), "quinientas");
+        assert_eq!(
+            noun(200_000_000, Language::Spanish, true),
+            "doscientos millones"
+        );
+        assert_eq!(noun(21, Language::French, true), "vingt et une");
+        assert_eq!(noun(1, Language::Italian, true), "una");
+        assert_eq!(noun(202, Language::Portuguese, true), "duzentas e duas");
+        assert_eq!(noun(1, Language::AmericanEnglish, true), "one");
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(
+            decimal(3, "14", Language::AmericanEnglish).unwrap(),
+            "three point one four"
+        );
+        assert_eq!(
+            decimal(3, "14", Language::Spanish).unwrap(),
+            "tres coma catorce"
+        );
+        assert_eq!(
+            decimal(2, "05", Language::French).unwrap(),
+            "deux virgule zéro cinq"
+        );
+        assert_eq!(decimal(3, "14", Language::Chinese).unwrap(), "三点一四");
+        assert_eq!(
+            digits("007", Language::AmericanEnglish).unwrap(),
+            "zero zero seven"
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use anyhow::{Context, Result};
+use hound::{SampleFormat, WavSpec, WavWriter};
+use std::io::Cursor;
+
+/// Sample encoding of WAV output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BitDepth {
+    #[default]
+    Int16,
+    Int24,
+    /// IEEE 754 single precision
+    Float32,
+}
+
+impl BitDepth {
+    /// Depth for a `bit_depth` value; 32 selects float
+    pub fn from_bits(bits: u16) -> Option<Self> {
+        match bits {
+            16 => Some(Self::Int16),
+            24 => Some(Self::Int24),
+            32 => Some(Self::Float32),
+            _ => None,
+        }
+    }
+
+    pub fn bits(self) -> u16 {
+        match self {
+            Self::Int16 => 16,
+            Self::Int24 => 24,
+            Self::Float32 => 32,
+        }
+    }
+
+    pub fn bytes_per_sample(self) -> usize {
+        usize::from(self.bits() / 8)
+    }
+
+    pub fn is_float(self) -> bool {
+        self == Self::Float32
+    }
+}
+
+/// Samples converted per block; wide enough for the compiler to fill AVX2 registers
+const PCM16_BLOCK: usize = 16;
+
+/// Scale a float sample [-1.0, 1.0] to a signed 16-bit value.
+///
+/// Rounds to nearest with halves away from zero, through a truncating cast that the
+/// compiler vectorizes rather than a `roundf` call per sample.
+#[inline(always)]
+pub fn pcm_i16_from_f32(sample: f32) -> i16 {
+    let clamped = sample.clamp(-1.0, 1.0);
+    let scaled = clamped * f32::from(i16::MAX);
+    if clamped <= -1.0 {
+        i16::MIN
+    } else {
+        (scaled + 0.5f32.copysign(scaled)) as i16
+    }
+}
+
+/// Convert float samples [-1.0, 1.0] to little-endian 16-bit PCM
+pub fn samples_to_pcm16(samples: &[f32]) -> Vec<u8> {
+    let mut bytes = vec![0u8; samples.len() * 2];
+    write_pcm16(samples, &mut bytes);
+    bytes
+}
+
+/// Convert float samples into `out`, which must hold two bytes per sample.
+///
+/// Whole blocks are converted into a stack array and copied out together so the
+/// loop body has no bounds checks or length updates to stop vectorization.
+pub fn write_pcm16(samples: &[f32], out: &mut [u8]) {
+    debug_assert_eq!(out.len(), samples.len() * 2);
+    let mut sample_blocks = samples.chunks_exact(PCM16_BLOCK);
+    let mut byte_blocks = out.chunks_exact_mut(PCM16_BLOCK * 2);
+    for (block, bytes) in (&mut sample_blocks).zip(&mut byte_blocks) {
+        let mut converted = [0u8; PCM16_BLOCK * 2];
+        for (pair, &sample) in converted.chunks_exact_mut(2).zip(block) {
+            pair.copy_from_slice(&pcm_i16_from_f32(sample).to_le_bytes());
+        }
+        bytes.copy_from_slice(&converted);
+    }
+    for (pair, &sample) in byte_blocks
+        .into_remainder()
+        .chunks_exact_mut(2)
+        .zip(sample_blocks.remainder())
+    {
+        pair.copy_from_slice(&pcm_i16_from_f32(sample).to_le_bytes());
+    }
+}
+
+/// Scale a float sample [-1.0, 1.0] to a signed 24-bit value
+pub fn pcm_i24_from_f32(sample: f32) -> i32 {
+    const I24_MAX: f32 = 8_388_607.0;
+    let clamped = sample.clamp(-1.0, 1.0);
+    if clamped <= -1.0 {
+        -8_388_608
+    } else {
+        (clamped * I24_MAX).round() as i32
+    }
+}
+
+/// Linearly resample little-endian 16-bit mono PCM
+pub fn resample_pcm16(pcm: &[u8], from: u32, to: u32) -> Vec<u8> {
+    if from == to || from == 0 {
+        return pcm.to_vec();
+    }
+
+    let samples: Vec<f64> = pcm
+        .chunks_exact(2)
+        .map(|bytes| f64::from(i16::from_le_bytes([bytes[0], bytes[1]])))
+        .collect();
+    interpolate(&samples, from, to)
+        .flat_map(|value| (value.round() as i16).to_le_bytes())
+        .collect()
+}
+
+/// Linearly resample float mono samples
+pub fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || from == 0 {
+        return samples.to_vec();
+    }
+
+    let samples: Vec<f64> = samples.iter().copied().map(f64::from).collect();
+    interpolate(&samples, from, to)
+        .map(|value| value as f32)
+        .collect()
+}
+
+fn interpolate(samples: &[f64], from: u32, to: u32) -> impl Iterator<Item = f64> + '_ {
+    let last = samples.last().copied().unwrap_or_default();
+    let step = f64::from(from) / f64::from(to);
+    let out_len = (samples.len() as f64 / step).round() as usize;
+    (0..out_len).map(move |i| {
+        let position = i as f64 * step;
+        let index = position as usize;
+        let frac = position - index as f64;
+        let current = samples.get(index).copied().unwrap_or(last);
+        let next = samples.get(index + 1).copied().unwrap_or(last);
+        current + (next - current) * frac
+    })
+}
+
+/// Wrap little-endian 16-bit mono PCM in a WAV (RIFF) header
+pub fn pcm16_to_wav(pcm: &[u8], sample_rate: u32) -> Result<Vec<u8>> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+
+    let mut cursor = Cursor::new(Vec::new());
+    let mut writer = WavWriter::new(&mut cursor, spec).context("Failed to start WAV")?;
+    for bytes in pcm.chunks_exact(2) {
+        writer
+            .write_sample(i16::from_le_bytes([bytes[0], bytes[1]]))
+            .context("Failed to write WAV sample")?;
+    }
+    writer.finalize().context("Failed to finish WAV")?;
+    Ok(cursor.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_to_pcm16() {
+        let samples = vec![0.0, 0.5, -0.5, 1.0, -1.0];
+        let bytes = samples_to_pcm16(&samples);
+        assert_eq!(bytes.len(), 10); // 5 samples * 2 bytes
+
+        // Check that 0.0 maps to 0
+        assert_eq!(bytes[0..2], [0, 0]);
+
+        // Halves round away from zero
+        assert_eq!(bytes[2..4], 16_384i16.to_le_bytes());
+        assert_eq!(bytes[4..6], (-16_384i16).to_le_bytes());
+
+        // Check that 1.0 maps to i16::MAX
+        let max_val = i16::MAX.to_le_bytes();
+        assert_eq!(bytes[6..8], max_val);
+
+        // Check that -1.0 maps to i16::MIN
+        let min_val = i16::MIN.to_le_bytes();
+        assert_eq!(bytes[8..10], min_val);
+
+        // Whole blocks and the remainder agree with converting one sample at a time
+        let samples: Vec<f32> = (0..PCM16_BLOCK * 3 + 5)
+            .map(|i| (i as f32 * 0.37).sin() * 1.2)
+            .chain([f32::NAN, 2.0, -2.0])
+            .collect();
+        let expected: Vec<u8> = samples
+            .iter()
+            .flat_map(|&sample| {
+                let clamped = sample.clamp(-1.0, 1.0);
+                let value = if clamped <= -1.0 {
+                    i16::MIN
+                } else {
+                    (clamped * f32::from(i16::MAX)).round() as i16
+                };
+                value.to_le_bytes()
+            })
+            .collect();
+        assert_eq!(samples_to_pcm16(&samples), expected);
+    }
+
+    #[test]
+    fn test_bit_depth() {
+        for bits in [16, 24, 32] {
+            assert_eq!(BitDepth::from_bits(bits).unwrap().bits(), bits);
+        }
+        assert!(BitDepth::from_bits(8).is_none());
+        assert_eq!(BitDepth::Int24.bytes_per_sample(), 3);
+        assert!(BitDepth::Float32.is_float());
+
+        assert_eq!(pcm_i24_from_f32(0.0), 0);
+        assert_eq!(pcm_i24_from_f32(1.5), 8_388_607);
+        assert_eq!(pcm_i24_from_f32(-1.0), -8_388_608);
+    }
+
+    #[test]
+    fn test_resample_pcm16() {
+        let pcm: Vec<u8> = [0i16, 300, 600, 900, 1200, 1500]
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect();
+        let resampled = resample_pcm16(&pcm, 24_000, 8_000);
+        assert_eq!(resampled, [0i16, 900].map(i16::to_le_bytes).concat());
+        assert_eq!(resample_pcm16(&pcm, 24_000, 24_000), pcm);
+        assert!(resample_pcm16(&[], 24_000, 8_000).is_empty());
+    }
+
+    #[test]
+    fn test_resample() {
+        let samples = [0.0, 0.5, 1.0];
+        assert_eq!(
+            resample(&samples, 24_000, 48_000),
+            [0.0, 0.25, 0.5, 0.75, 1.0, 1.0]
+        );
+        assert_eq!(resample(&samples, 24_000, 8_000), [0.0]);
+        assert_eq!(resample(&samples, 24_000, 24_000), samples);
+    }
+
+    #[test]
+    fn test_pcm16_to_wav() {
+        let pcm = [100i16, -100].map(i16::to_le_bytes).concat();
+        let wav = pcm16_to_wav(&pcm, 16_000).unwrap();
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(
+            u32::from_le_bytes([wav[24], wav[25], wav[26], wav[27]]),
+            16_000
+        );
+        assert_eq!(&wav[wav.len() - 4..], &pcm[..]);
+    }
+}
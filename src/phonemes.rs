@@ -0,0 +1,128 @@
+use crate::{
+    api::{validate_speech_request, AppState, SpeechRequest},
+    error::{ApiResult, AppError, OpenAIError},
+    extract::ApiBody,
+    streaming::chunk_text,
+};
+use axum::{extract::State, Json};
+use serde::Serialize;
+use tracing::error;
+use utoipa::ToSchema;
+
+/// Response body for POST /v1/audio/phonemes
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PhonemesResponse {
+    pub object: &'static str,
+    /// Resolved Kokoro voice ID
+    pub voice: String,
+    /// Language the input is normalized, chunked, and phonemized in
+    pub language: String,
+    /// Each chunk as the model would receive it, in playback order
+    pub chunks: Vec<ChunkPhonemes>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChunkPhonemes {
+    pub index: usize,
+    pub text: String,
+    /// IPA phonemes eSpeak-ng produced for the chunk
+    pub phonemes: String,
+    /// Model vocabulary IDs of the phonemes
+    pub tokens: Vec<i64>,
+}
+
+/// Phonemize a speech request without running the model
+#[utoipa::path(
+    post,
+    path = "/v1/audio/phonemes",
+    tag = "speech",
+    request_body(content(
+        (SpeechRequest = "application/json"),
+        (SpeechRequest = "multipart/form-data"),
+        (String = "text/plain")
+    )),
+    responses(
+        (status = 200, description = "Phonemes and token IDs of each chunk", body = PhonemesResponse),
+        (status = 400, description = "Invalid request, model, voice, or format", body = OpenAIError),
+        (status = 401, description = "Missing or invalid API key", body = OpenAIError)
+    )
+)]
+pub async fn phonemes_handler(
+    State(state): State<AppState>,
+    ApiBody(req): ApiBody<SpeechRequest>,
+) -> ApiResult<Json<PhonemesResponse>> {
+    let params = validate_speech_request(&state, req)?;
+    let texts = chunk_text(&params.input, &params.chunk_rules);
+    let phonemized = state
+        .backend_for(params.quality)
+        .phonemize(texts.clone(), params.language)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Phonemization failed");
+            AppError::Internal
+        })?;
+
+    Ok(Json(PhonemesResponse {
+        object: "audio.phonemes",
+        voice: params.voice,
+        language: params.language.to_string(),
+        chunks: texts
+            .into_iter()
+            .zip(phonemized)
+            .enumerate()
+            .map(|(index, (text, (phonemes, tokens)))| ChunkPhonemes {
+                index,
+                text,
+                phonemes,
+                tokens,
+            })
+            .collect(),
+    }))
+}
+
+#[cfg(all(test, feature = "mock-backend"))]
+mod tests {
+    use super::*;
+
+    async fn phonemes(request: SpeechRequest) -> PhonemesResponse {
+        let state = AppState::for_tests().await;
+        let Json(response) = phonemes_handler(State(state), ApiBody(request))
+            .await
+            .unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_phonemes_in_voice_language() {
+        let response = phonemes(SpeechRequest {
+            model: "kokoro".to_string(),
+            input: "Hola amigo. Buenos días.".to_string(),
+            voice: Some("ef_dora".to_string()),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(response.voice, "ef_dora");
+        assert_eq!(response.language, "es");
+        assert!(!response.chunks.is_empty());
+        assert_eq!(response.chunks[0].index, 0);
+        for chunk in &response.chunks {
+            assert!(chunk.phonemes.starts_with("(es) "), "{}", chunk.phonemes);
+            assert_eq!(chunk.tokens.len(), chunk.phonemes.chars().count());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_phonemes_in_request_language() {
+        let response = phonemes(SpeechRequest {
+            model: "kokoro".to_string(),
+            input: "Bonjour".to_string(),
+            voice: Some("af_heart".to_string()),
+            language: Some("fr".to_string()),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(response.language, "fr-fr");
+        assert_eq!(response.chunks.len(), 1);
+        assert_eq!(response.chunks[0].phonemes, "(fr-fr) bonjour");
+    }
+}
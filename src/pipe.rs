@@ -0,0 +1,85 @@
+use crate::{
+    api::{synthesize_clip, validate_speech_request, AppState, SpeechRequest},
+    config::PipeArgs,
+    redact,
+};
+use anyhow::{Context, Result};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Formats whose clips can be written back to back as one stream
+const PIPE_FORMATS: [&str; 2] = ["pcm", "mp3"];
+
+/// Speak each line of stdin and write its audio to stdout until stdin closes.
+///
+/// Lines that fail validation or synthesis are logged and skipped; a closed stdout ends the
+/// run quietly so the binary behaves in shell pipelines.
+pub async fn run(state: AppState, args: &PipeArgs) -> Result<()> {
+    let format = args.format.to_lowercase();
+    if !PIPE_FORMATS.contains(&format.as_str()) {
+        anyhow::bail!(
+            "Pipe mode supports only these formats: {}",
+            PIPE_FORMATS.join(", ")
+        );
+    }
+    let request = |input: String| SpeechRequest {
+        model: "kokoro".to_string(),
+        input,
+        voice: args.voice.clone(),
+        response_format: Some(format.clone()),
+        speed: args.speed,
+        preset: args.preset.clone(),
+        ..Default::default()
+    };
+
+    // Check the settings before waiting on input
+    let probe = validate_speech_request(&state, request(".".to_string()))
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    info!(
+        "Pipe mode: {} as {} at {} Hz",
+        probe.voice,
+        probe.format,
+        probe.sample_rate.unwrap_or(state.backend.sample_rate())
+    );
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    while let Some(line) = lines.next_line().await.context("Failed to read stdin")? {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let request_id = Uuid::new_v4().to_string();
+        let audio = match validate_speech_request(&state, request(line.to_string())) {
+            Ok(params) => synthesize_clip(&state, &params, &request_id).await,
+            Err(e) => Err(e),
+        };
+        let audio = match audio {
+            Ok((audio, _)) => audio,
+            Err(e) => {
+                warn!(
+                    request_id = %request_id,
+                    "Skipping line: {}",
+                    redact::message(&e.to_string())
+                );
+                continue;
+            }
+        };
+
+        let written = match stdout.write_all(&audio.bytes).await {
+            Ok(()) => stdout.flush().await,
+            Err(e) => Err(e),
+        };
+        match written {
+            Ok(()) => debug!(request_id = %request_id, bytes = audio.bytes.len(), "Line written"),
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                debug!("Stdout closed; stopping");
+                return Ok(());
+            }
+            Err(e) => return Err(e).context("Failed to write stdout"),
+        }
+    }
+    Ok(())
+}
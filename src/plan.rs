@@ -0,0 +1,165 @@
+use crate::{
+    api::SpeechParams, cache::normalize_text, estimate::DurationEstimator, pcm::BitDepth,
+    speed_curve::chunk_speeds, streaming::chunk_text,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Response body for POST /v1/audio/speech/plan
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SpeechPlan {
+    pub object: &'static str,
+    /// Resolved Kokoro voice ID
+    pub voice: String,
+    pub response_format: String,
+    pub speed: f32,
+    /// Language the input is read and chunked in
+    pub language: String,
+    /// Input as the engine receives it, with whitespace collapsed
+    pub normalized_text: String,
+    /// Chunks a streamed request is synthesized in, in playback order
+    pub chunks: Vec<PlannedChunk>,
+    /// Estimated length of the audio, including leading and trailing silence
+    pub estimated_duration_ms: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PlannedChunk {
+    pub index: usize,
+    pub text: String,
+    pub characters: usize,
+    /// Speed the chunk is read at, which varies with a `speed_curve`
+    pub speed: f32,
+    pub estimated_duration_ms: u64,
+}
+
+/// Plan a validated request without synthesizing it
+pub fn plan(params: &SpeechParams, sample_rate: u32, estimator: &DurationEstimator) -> SpeechPlan {
+    let texts = chunk_text(&params.input, &params.chunk_rules);
+    let speeds = chunk_speeds(params.speed, params.speed_curve.as_ref(), &texts);
+    let chunks: Vec<PlannedChunk> = texts
+        .into_iter()
+        .zip(speeds)
+        .enumerate()
+        .map(|(index, (text, speed))| PlannedChunk {
+            index,
+            characters: text.chars().count(),
+            speed,
+            estimated_duration_ms: estimator.estimate_ms(&params.voice, &text, speed),
+            text,
+        })
+        .collect();
+
+    let silence = params.initial_silence.unwrap_or(0) + params.trailing_silence;
+    let output_rate = params.sample_rate.unwrap_or(sample_rate);
+    let gaps =
+        u64::from(params.sentence_gap_ms.unwrap_or(0)) * chunks.len().saturating_sub(1) as u64;
+    let estimated_duration_ms = chunks
+        .iter()
+        .map(|chunk| chunk.estimated_duration_ms)
+        .sum::<u64>()
+        + samples_to_ms(silence, output_rate)
+        + gaps;
+
+    SpeechPlan {
+        object: "speech.plan",
+        voice: params.voice.clone(),
+        response_format: params.format.clone(),
+        speed: params.speed,
+        language: params.language.to_string(),
+        normalized_text: normalize_text(&params.input),
+        chunks,
+        estimated_duration_ms,
+    }
+}
+
+/// Uncompressed size of `duration_ms` of mono audio, an upper bound for every response format
+pub fn estimated_output_bytes(duration_ms: u64, sample_rate: u32, bit_depth: BitDepth) -> u64 {
+    duration_ms * u64::from(sample_rate) / 1000 * bit_depth.bytes_per_sample() as u64
+}
+
+fn samples_to_ms(samples: usize, sample_rate: u32) -> u64 {
+    if sample_rate == 0 {
+        return 0;
+    }
+    samples as u64 * 1000 / u64::from(sample_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speed_curve::{SpeedCurve, SpeedPoint};
+
+    fn params(input: &str) -> SpeechParams {
+        SpeechParams {
+            input: input.to_string(),
+            voice: "af_heart".to_string(),
+            format: "wav".to_string(),
+            speed: 1.0,
+            initial_silence: Some(2_400),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_chunks_and_duration() {
+        let estimator = DurationEstimator::default();
+        let plan = plan(
+            &params("Hello  world!\nThis is a test."),
+            24_000,
+            &estimator,
+        );
+        assert_eq!(plan.normalized_text, "Hello world! This is a test.");
+        assert_eq!(plan.chunks.len(), 2);
+        assert_eq!(plan.chunks[0].text, "Hello world!");
+        assert_eq!(plan.chunks[1].index, 1);
+        assert_eq!(plan.chunks[1].characters, 15);
+        assert_eq!(plan.chunks[1].estimated_duration_ms, 1_000);
+        // 100 ms of leading silence on top of the speech
+        assert_eq!(plan.estimated_duration_ms, 800 + 1_000 + 100);
+
+        let gapped = SpeechParams {
+            sentence_gap_ms: Some(250),
+            ..params("Hello  world!\nThis is a test.")
+        };
+        assert_eq!(
+            super::plan(&gapped, 24_000, &estimator).estimated_duration_ms,
+            800 + 1_000 + 100 + 250
+        );
+    }
+
+    #[test]
+    fn test_plan_follows_speed_curve() {
+        let estimator = DurationEstimator::default();
+        let mut slowing = params("Hello world!\nThis is a test.");
+        slowing.speed_curve = Some(
+            SpeedCurve::new(vec![
+                SpeedPoint {
+                    at: 0.0,
+                    speed: 1.0,
+                },
+                SpeedPoint {
+                    at: 1.0,
+                    speed: 0.5,
+                },
+            ])
+            .unwrap(),
+        );
+        let plan = plan(&slowing, 24_000, &estimator);
+        assert!(plan.chunks[0].speed > plan.chunks[1].speed);
+        assert!(plan.chunks[1].estimated_duration_ms > 1_000);
+    }
+
+    #[test]
+    fn test_estimated_output_bytes() {
+        assert_eq!(
+            estimated_output_bytes(1_000, 24_000, BitDepth::Int16),
+            48_000
+        );
+        assert_eq!(
+            estimated_output_bytes(60_000, 48_000, BitDepth::Float32),
+            11_520_000
+        );
+        assert_eq!(estimated_output_bytes(0, 24_000, BitDepth::Int24), 0);
+    }
+}
@@ -0,0 +1,412 @@
+use crate::{
+    api::{
+        synthesize_clip, validate_speech_request, voice_language, AppState, SpeechRequest,
+        VOICE_SUBSTITUTED_HEADER,
+    },
+    crypto::{hmac_sha256, verify_hmac_sha256},
+    error::{ApiResult, AppError},
+    extract::ApiJson,
+    pcm::resample_pcm16,
+    ssml,
+    validation::VoiceVendor,
+};
+use axum::{
+    body::{Body, Bytes},
+    extract::State,
+    http::{header, request::Parts, StatusCode},
+    response::Response,
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::debug;
+use uuid::Uuid;
+
+/// Polly's default rate for `pcm` output
+const DEFAULT_PCM_SAMPLE_RATE: u32 = 16_000;
+/// Rates accepted for `pcm`: Polly's two plus Kokoro's native rate
+const PCM_SAMPLE_RATES: [u32; 3] = [8_000, 16_000, 24_000];
+/// Furthest a signed request's `x-amz-date` may be from the server clock, as on AWS
+const MAX_CLOCK_SKEW_SECS: u64 = 15 * 60;
+
+/// Request body for POST /v1/speech, matching Polly's `SynthesizeSpeech`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SynthesizeSpeechRequest {
+    pub text: String,
+    /// `text` or `ssml`
+    #[serde(default)]
+    pub text_type: Option<String>,
+    pub output_format: String,
+    pub voice_id: String,
+    #[serde(default)]
+    pub sample_rate: Option<String>,
+    /// Accepted for compatibility; every engine maps to Kokoro
+    #[serde(default)]
+    pub engine: Option<String>,
+}
+
+/// Map a Polly `OutputFormat` onto a response format and the content type Polly reports
+fn output_format(format: &str) -> ApiResult<(&'static str, &'static str)> {
+    match format {
+        "mp3" => Ok(("mp3", "audio/mpeg")),
+        // Ogg Opus rather than Vorbis; players that read Polly's Ogg output accept either
+        "ogg_vorbis" => Ok(("opus", "audio/ogg")),
+        "pcm" => Ok(("pcm", "audio/pcm")),
+        other => Err(AppError::invalid_request(format!(
+            "OutputFormat '{}' not supported. Supported formats: mp3, ogg_vorbis, pcm",
+            other
+        ))),
+    }
+}
+
+/// Resolve the PCM output rate; `SampleRate` only changes `pcm` output, as with Polly's MP3 and Ogg
+fn pcm_sample_rate(sample_rate: Option<&str>) -> ApiResult<u32> {
+    let Some(sample_rate) = sample_rate else {
+        return Ok(DEFAULT_PCM_SAMPLE_RATE);
+    };
+    sample_rate
+        .parse()
+        .ok()
+        .filter(|rate| PCM_SAMPLE_RATES.contains(rate))
+        .ok_or_else(|| {
+            AppError::invalid_request(format!(
+                "SampleRate '{}' not supported for pcm. Supported rates: 8000, 16000, 24000",
+                sample_rate
+            ))
+        })
+}
+
+/// Polly-compatible speech synthesis
+pub async fn synthesize_speech_handler(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<SynthesizeSpeechRequest>,
+) -> ApiResult<Response> {
+    let request_id = Uuid::new_v4().to_string();
+
+    debug!(
+        request_id = %request_id,
+        voice_id = %req.voice_id,
+        output_format = %req.output_format,
+        engine = ?req.engine,
+        "Received Polly speech request"
+    );
+
+    let (response_format, content_type) = output_format(&req.output_format)?;
+    let pcm_rate = match response_format {
+        "pcm" => Some(pcm_sample_rate(req.sample_rate.as_deref())?),
+        _ => None,
+    };
+    let voice = VoiceVendor::Polly.kokoro_voice(&req.voice_id).to_string();
+    let input = match req.text_type.as_deref() {
+        None | Some("text") => req.text,
+        Some("ssml") => ssml::to_plain_text(&req.text, voice_language(&state, &voice)),
+        Some(other) => {
+            return Err(AppError::invalid_request(format!(
+                "TextType '{}' not supported. Supported types: text, ssml",
+                other
+            )))
+        }
+    };
+    let request_characters = input.chars().count();
+
+    let params = validate_speech_request(
+        &state,
+        SpeechRequest {
+            model: "kokoro".to_string(),
+            input,
+            voice: Some(voice),
+            response_format: Some(response_format.to_string()),
+            ..Default::default()
+        },
+    )?;
+    let (audio, cache_status) = synthesize_clip(&state, &params, &request_id).await?;
+
+    let bytes = match pcm_rate {
+        Some(rate) => Bytes::from(resample_pcm16(
+            &audio.bytes,
+            state.backend.sample_rate(),
+            rate,
+        )),
+        None => audio.bytes,
+    };
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .header("x-amzn-RequestCharacters", request_characters)
+        .header("x-amzn-RequestId", request_id.as_str())
+        .header("X-Request-Id", request_id);
+    if let Some(cache_status) = cache_status {
+        response = response.header("X-Cache", cache_status);
+    }
+    if params.voice_substituted {
+        response = response.header(VOICE_SUBSTITUTED_HEADER, &params.voice);
+    }
+
+    response
+        .body(Body::from(bytes))
+        .map_err(|_| AppError::Internal)
+}
+
+/// Check an AWS Signature Version 4 `Authorization` header against `API_KEY`.
+///
+/// Polly SDKs sign every request, so the API key doubles as the secret access
+/// key; the access key ID, region and service in the credential scope are not
+/// checked. The payload hash is always computed from the body rather than
+/// trusted from `x-amz-content-sha256`. As on AWS, `x-amz-date` must be within
+/// [`MAX_CLOCK_SKEW_SECS`] of the server clock and on the credential scope's
+/// date, so a captured request cannot be replayed later.
+pub fn verify_sigv4(parts: &Parts, body: &[u8], secret: &str) -> bool {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    verify_sigv4_at(parts, body, secret, now)
+}
+
+/// [`verify_sigv4`] with the server clock at `now`, in seconds since the Unix epoch
+fn verify_sigv4_at(parts: &Parts, body: &[u8], secret: &str, now: u64) -> bool {
+    let Some(authorization) = parts
+        .headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+    let Some(fields) = authorization.strip_prefix("AWS4-HMAC-SHA256 ") else {
+        return false;
+    };
+
+    let mut credential = None;
+    let mut signed_headers = None;
+    let mut provided_signature = None;
+    for field in fields.split(',') {
+        match field.trim().split_once('=') {
+            Some(("Credential", value)) => credential = Some(value),
+            Some(("SignedHeaders", value)) => signed_headers = Some(value),
+            Some(("Signature", value)) => provided_signature = Some(value),
+            _ => {}
+        }
+    }
+    let (Some(credential), Some(signed_headers), Some(provided_signature)) =
+        (credential, signed_headers, provided_signature)
+    else {
+        return false;
+    };
+    // Credential is `<access key id>/<date>/<region>/<service>/aws4_request`
+    let Some((_, scope)) = credential.split_once('/') else {
+        return false;
+    };
+    let Some(amz_date) = parts
+        .headers
+        .get("x-amz-date")
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+    if scope.split('/').next() != amz_date.get(..8) {
+        return false;
+    }
+    match amz_date_seconds(amz_date) {
+        Some(signed_at) if signed_at.abs_diff(now) <= MAX_CLOCK_SKEW_SECS => {}
+        _ => return false,
+    }
+
+    let Some(string_to_sign) = string_to_sign(parts, body, signed_headers, scope, amz_date) else {
+        return false;
+    };
+    verify_hmac_sha256(
+        &signing_key(secret, scope),
+        string_to_sign.as_bytes(),
+        provided_signature,
+    )
+}
+
+/// Seconds since the Unix epoch for an `x-amz-date` such as `20261016T120000Z`
+fn amz_date_seconds(amz_date: &str) -> Option<u64> {
+    let digits = |range: std::ops::Range<usize>| -> Option<u64> {
+        let field = amz_date.get(range)?;
+        field
+            .bytes()
+            .all(|byte| byte.is_ascii_digit())
+            .then(|| field.parse().ok())?
+    };
+    if amz_date.len() != 16 || amz_date.get(8..9)? != "T" || !amz_date.ends_with('Z') {
+        return None;
+    }
+    let (year, month, day) = (digits(0..4)?, digits(4..6)?, digits(6..8)?);
+    let (hour, minute, second) = (digits(9..11)?, digits(11..13)?, digits(13..15)?);
+    if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 {
+        return None;
+    }
+    if minute > 59 || second > 60 {
+        return None;
+    }
+
+    // Days since 1970-01-01 in the proleptic Gregorian calendar (Howard Hinnant's algorithm)
+    let (year, month) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146_097 + day_of_era).checked_sub(719_468)?;
+    Some(days * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+/// The SigV4 string to sign for a request, or `None` if a signed header is missing
+fn string_to_sign(
+    parts: &Parts,
+    body: &[u8],
+    signed_headers: &str,
+    scope: &str,
+    amz_date: &str,
+) -> Option<String> {
+    let mut canonical_headers = String::new();
+    for name in signed_headers.split(';') {
+        let values: Vec<String> = parts
+            .headers
+            .get_all(name)
+            .iter()
+            .map(|value| {
+                let value = value.to_str().unwrap_or_default();
+                value.split_whitespace().collect::<Vec<_>>().join(" ")
+            })
+            .collect();
+        if values.is_empty() {
+            return None;
+        }
+        canonical_headers.push_str(&format!("{}:{}\n", name, values.join(",")));
+    }
+
+    let mut query: Vec<&str> = parts
+        .uri
+        .query()
+        .map(|query| query.split('&').filter(|pair| !pair.is_empty()).collect())
+        .unwrap_or_default();
+    query.sort_unstable();
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{:x}",
+        parts.method,
+        parts.uri.path(),
+        query.join("&"),
+        canonical_headers,
+        signed_headers,
+        Sha256::digest(body)
+    );
+    Some(format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+        amz_date,
+        scope,
+        Sha256::digest(canonical_request.as_bytes())
+    ))
+}
+
+/// SigV4 signing key for `scope`, which is `<date>/<region>/<service>/aws4_request`; each part
+/// keys the next HMAC
+fn signing_key(secret: &str, scope: &str) -> Vec<u8> {
+    let mut key = format!("AWS4{}", secret).into_bytes();
+    for part in scope.split('/') {
+        key = hmac_sha256(&key, part.as_bytes()).to_vec();
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::hex;
+    use axum::http::Request;
+
+    #[test]
+    fn test_output_format_and_sample_rate() {
+        assert_eq!(output_format("ogg_vorbis").unwrap(), ("opus", "audio/ogg"));
+        assert!(output_format("json").is_err());
+        assert_eq!(pcm_sample_rate(None).unwrap(), 16_000);
+        assert_eq!(pcm_sample_rate(Some("8000")).unwrap(), 8_000);
+        assert!(pcm_sample_rate(Some("22050")).is_err());
+    }
+
+    /// Request carrying `x-amz-date` and signed for `scope`
+    fn signed_request(body: &[u8], scope: &str, amz_date: &str, secret: &str) -> Parts {
+        let signed_headers = "content-type;host;x-amz-date";
+        let request = |authorization: &str| {
+            let (parts, _) = Request::post("/v1/speech")
+                .header("content-type", "application/json")
+                .header("host", "localhost:8000")
+                .header("x-amz-date", amz_date)
+                .header("authorization", authorization)
+                .body(())
+                .unwrap()
+                .into_parts();
+            parts
+        };
+        let unsigned = request("");
+        let string_to_sign =
+            string_to_sign(&unsigned, body, signed_headers, scope, amz_date).unwrap();
+        let signature = hex(&hmac_sha256(
+            &signing_key(secret, scope),
+            string_to_sign.as_bytes(),
+        ));
+        request(&format!(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/{}, SignedHeaders={}, Signature={}",
+            scope, signed_headers, signature
+        ))
+    }
+
+    /// 2026-10-16T12:00:00Z
+    const SIGNED_AT: u64 = 1_792_152_000;
+
+    #[test]
+    fn test_verify_sigv4() {
+        let body = br#"{"Text":"Hi","OutputFormat":"mp3","VoiceId":"Joanna"}"#;
+        let scope = "20261016/us-east-1/polly/aws4_request";
+        let signed = signed_request(body, scope, "20261016T120000Z", "secret");
+
+        assert!(verify_sigv4_at(&signed, body, "secret", SIGNED_AT));
+        assert!(!verify_sigv4_at(&signed, body, "other-secret", SIGNED_AT));
+        assert!(!verify_sigv4_at(&signed, b"{}", "secret", SIGNED_AT));
+        let (unsigned, _) = Request::post("/v1/speech")
+            .header("x-amz-date", "20261016T120000Z")
+            .body(())
+            .unwrap()
+            .into_parts();
+        assert!(!verify_sigv4_at(&unsigned, body, "secret", SIGNED_AT));
+    }
+
+    #[test]
+    fn test_verify_sigv4_rejects_stale_dates() {
+        let body = br#"{"Text":"Hi","OutputFormat":"mp3","VoiceId":"Joanna"}"#;
+        let scope = "20261016/us-east-1/polly/aws4_request";
+        let signed = signed_request(body, scope, "20261016T120000Z", "secret");
+
+        // Within 15 minutes either way of the server clock
+        assert!(verify_sigv4_at(&signed, body, "secret", SIGNED_AT + 900));
+        assert!(verify_sigv4_at(&signed, body, "secret", SIGNED_AT - 900));
+        // A replay later on, or a request signed too far ahead
+        assert!(!verify_sigv4_at(&signed, body, "secret", SIGNED_AT + 901));
+        assert!(!verify_sigv4_at(&signed, body, "secret", SIGNED_AT - 901));
+    }
+
+    #[test]
+    fn test_verify_sigv4_rejects_mismatched_scope_date() {
+        let body = br#"{"Text":"Hi","OutputFormat":"mp3","VoiceId":"Joanna"}"#;
+        // Correctly signed, but the scope names another day than x-amz-date
+        let scope = "20261015/us-east-1/polly/aws4_request";
+        let signed = signed_request(body, scope, "20261016T120000Z", "secret");
+        assert!(!verify_sigv4_at(&signed, body, "secret", SIGNED_AT));
+    }
+
+    #[test]
+    fn test_amz_date_seconds() {
+        assert_eq!(amz_date_seconds("19700101T000000Z"), Some(0));
+        assert_eq!(amz_date_seconds("20261016T120000Z"), Some(SIGNED_AT));
+        assert_eq!(amz_date_seconds("20240229T235959Z"), Some(1_709_251_199));
+        assert_eq!(amz_date_seconds("2026-10-16T12:00"), None);
+        assert_eq!(amz_date_seconds("20261316T120000Z"), None);
+    }
+}
@@ -0,0 +1,273 @@
+use crate::effects::{Compressor, Filter, COMPRESSOR_PRESETS, FILTER_PRESETS};
+use crate::style::{Style, STYLE_PRESETS};
+use crate::validation::{
+    validate_bit_depth, validate_profile, validate_response_format, validate_speed,
+};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Longest trailing silence a preset may add
+const MAX_TRAILING_SILENCE_MS: u32 = 10_000;
+/// Output sample rates a preset may resample to
+const SAMPLE_RATE_RANGE: std::ops::RangeInclusive<u32> = 8_000..=48_000;
+
+/// Delivery defaults for one voice, applied when the request does not set them
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VoicePreset {
+    /// Speed used when the request omits `speed`
+    pub default_speed: Option<f32>,
+    /// Silence appended after the speech
+    pub trailing_silence_ms: Option<u32>,
+}
+
+impl VoicePreset {
+    /// Trailing silence in samples at `sample_rate`
+    pub fn trailing_silence(&self, sample_rate: u32) -> usize {
+        self.trailing_silence_ms
+            .map_or(0, |ms| silence_samples(ms, sample_rate))
+    }
+}
+
+/// Request settings bundled under a name that clients select with the `preset` field.
+///
+/// Anything the request sets itself takes precedence.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NamedPreset {
+    /// Voice ID or alias
+    pub voice: Option<String>,
+    pub speed: Option<f32>,
+    pub response_format: Option<String>,
+    pub profile: Option<String>,
+    /// Output sample rate; only applies to `pcm` and `wav` output
+    pub sample_rate: Option<u32>,
+    /// Silence appended after the speech; replaces the voice preset's
+    pub trailing_silence_ms: Option<u32>,
+    /// Compressor preset name
+    pub compressor: Option<String>,
+    /// Filter preset names, applied in order
+    pub effects: Option<Vec<String>>,
+    /// Delivery style name
+    pub style: Option<String>,
+    /// WAV sample depth: 16, 24, or 32 (float); only applies to `wav` output
+    pub bit_depth: Option<u16>,
+}
+
+/// Operator presets loaded from `KOKORO_PRESETS_FILE`.
+///
+/// ```toml
+/// [voice.af_heart]
+/// default_speed = 1.1
+/// trailing_silence_ms = 120
+///
+/// [preset.ivr]
+/// voice = "af_heart"
+/// response_format = "pcm"
+/// sample_rate = 8000
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Presets {
+    /// Per-voice defaults keyed by Kokoro voice ID
+    #[serde(default)]
+    voice: BTreeMap<String, VoicePreset>,
+    /// Named presets keyed by the name clients send
+    #[serde(default)]
+    preset: BTreeMap<String, NamedPreset>,
+}
+
+impl Presets {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid presets file {}", path.display()))
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let presets: Self = toml::from_str(text)?;
+        for (id, preset) in &presets.voice {
+            let table = format!("voice.{}", id);
+            check_speed(&table, "default_speed", preset.default_speed)?;
+            check_trailing_silence(&table, preset.trailing_silence_ms)?;
+        }
+        for (name, preset) in &presets.preset {
+            let table = format!("preset.{}", name);
+            check_speed(&table, "speed", preset.speed)?;
+            check_trailing_silence(&table, preset.trailing_silence_ms)?;
+            if let Some(format) = &preset.response_format {
+                validate_response_format(format)
+                    .map_err(|e| anyhow::anyhow!("[{}] response_format: {}", table, e))?;
+            }
+            if let Some(profile) = &preset.profile {
+                validate_profile(profile)
+                    .map_err(|e| anyhow::anyhow!("[{}] profile: {}", table, e))?;
+            }
+            if let Some(compressor) = &preset.compressor {
+                if Compressor::preset(compressor).is_none() {
+                    anyhow::bail!(
+                        "[{}] compressor must be one of: {}",
+                        table,
+                        COMPRESSOR_PRESETS.join(", ")
+                    );
+                }
+            }
+            for effect in preset.effects.iter().flatten() {
+                if Filter::from_name(effect).is_none() {
+                    anyhow::bail!(
+                        "[{}] effects must be among: {}",
+                        table,
+                        FILTER_PRESETS.join(", ")
+                    );
+                }
+            }
+            if let Some(style) = &preset.style {
+                if Style::from_name(style).is_none() {
+                    anyhow::bail!(
+                        "[{}] style must be one of: {}",
+                        table,
+                        STYLE_PRESETS.join(", ")
+                    );
+                }
+            }
+            if let Some(bits) = preset.bit_depth {
+                validate_bit_depth(bits)
+                    .map_err(|e| anyhow::anyhow!("[{}] bit_depth: {}", table, e))?;
+            }
+            if let Some(rate) = preset.sample_rate {
+                if !SAMPLE_RATE_RANGE.contains(&rate) {
+                    anyhow::bail!(
+                        "[{}] sample_rate must be between {} and {}",
+                        table,
+                        SAMPLE_RATE_RANGE.start(),
+                        SAMPLE_RATE_RANGE.end()
+                    );
+                }
+            }
+        }
+        Ok(presets)
+    }
+
+    /// Defaults for a resolved Kokoro voice ID
+    pub fn voice(&self, id: &str) -> Option<&VoicePreset> {
+        self.voice.get(id)
+    }
+
+    /// Voice IDs that have a preset
+    pub fn voice_ids(&self) -> impl Iterator<Item = &str> {
+        self.voice.keys().map(String::as_str)
+    }
+
+    /// Named preset selected by a request's `preset` field
+    pub fn named(&self, name: &str) -> Option<&NamedPreset> {
+        self.preset.get(name)
+    }
+
+    /// Every named preset, for checking their voices once the voice pack is loaded
+    pub fn named_presets(&self) -> impl Iterator<Item = (&str, &NamedPreset)> {
+        self.preset
+            .iter()
+            .map(|(name, preset)| (name.as_str(), preset))
+    }
+}
+
+fn check_speed(table: &str, key: &str, speed: Option<f32>) -> Result<()> {
+    if let Some(speed) = speed {
+        validate_speed(speed).map_err(|e| anyhow::anyhow!("[{}] {}: {}", table, key, e))?;
+    }
+    Ok(())
+}
+
+fn check_trailing_silence(table: &str, trailing_silence_ms: Option<u32>) -> Result<()> {
+    if trailing_silence_ms > Some(MAX_TRAILING_SILENCE_MS) {
+        anyhow::bail!(
+            "[{}] trailing_silence_ms cannot exceed {}",
+            table,
+            MAX_TRAILING_SILENCE_MS
+        );
+    }
+    Ok(())
+}
+
+/// Length of `ms` of silence in samples at `sample_rate`
+pub fn silence_samples(ms: u32, sample_rate: u32) -> usize {
+    (u64::from(ms) * u64::from(sample_rate) / 1000) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_voice_presets() {
+        let presets = Presets::parse(
+            r#"
+            [voice.af_heart]
+            default_speed = 1.1
+            trailing_silence_ms = 120
+
+            [voice.bm_george]
+            trailing_silence_ms = 500
+            "#,
+        )
+        .unwrap();
+
+        let heart = presets.voice("af_heart").unwrap();
+        assert_eq!(heart.default_speed, Some(1.1));
+        assert_eq!(heart.trailing_silence(24_000), 2_880);
+        assert_eq!(presets.voice("bm_george").unwrap().default_speed, None);
+        assert!(presets.voice("af_alloy").is_none());
+        assert_eq!(presets.voice_ids().count(), 2);
+    }
+
+    #[test]
+    fn test_parse_named_presets() {
+        let presets = Presets::parse(
+            r#"
+            [preset.ivr]
+            voice = "af_heart"
+            response_format = "pcm"
+            sample_rate = 8000
+            effects = ["telephone"]
+
+            [preset.narrator]
+            voice = "bm_george"
+            speed = 0.9
+            trailing_silence_ms = 300
+            compressor = "gentle"
+            bit_depth = 24
+            "#,
+        )
+        .unwrap();
+
+        let ivr = presets.named("ivr").unwrap();
+        assert_eq!(ivr.voice.as_deref(), Some("af_heart"));
+        assert_eq!(ivr.response_format.as_deref(), Some("pcm"));
+        assert_eq!(ivr.sample_rate, Some(8000));
+        assert_eq!(ivr.effects, Some(vec!["telephone".to_string()]));
+        let narrator = presets.named("narrator").unwrap();
+        assert_eq!(narrator.speed, Some(0.9));
+        assert_eq!(narrator.compressor.as_deref(), Some("gentle"));
+        assert_eq!(narrator.bit_depth, Some(24));
+        assert!(presets.named("alerts").is_none());
+        assert_eq!(presets.named_presets().count(), 2);
+        assert_eq!(presets.voice_ids().count(), 0);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_presets() {
+        assert!(Presets::parse("[voice.af_heart]\ndefault_speed = 9.0").is_err());
+        assert!(Presets::parse("[voice.af_heart]\ntrailing_silence_ms = 60000").is_err());
+        assert!(Presets::parse("[voice.af_heart]\npitch = 2").is_err());
+        assert!(Presets::parse("[voices.af_heart]").is_err());
+        assert!(Presets::parse("[preset.ivr]\nresponse_format = \"flac\"").is_err());
+        assert!(Presets::parse("[preset.ivr]\nprofile = \"teams\"").is_err());
+        assert!(Presets::parse("[preset.ivr]\nsample_rate = 96000").is_err());
+        assert!(Presets::parse("[preset.ivr]\ncompressor = \"brickwall\"").is_err());
+        assert!(Presets::parse("[preset.ivr]\neffects = [\"reverb\"]").is_err());
+        assert!(Presets::parse("[preset.ivr]\nspeed = 0.0").is_err());
+        assert!(Presets::parse("[preset.ivr]\nbit_depth = 8").is_err());
+    }
+}
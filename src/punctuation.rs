@@ -0,0 +1,84 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// Compose `text` to NFC and fold typographic punctuation to the ASCII the chunker and
+/// phonemizer expect.
+///
+/// Smart quotes become straight quotes, `…` becomes `...`, and non-breaking and other fixed-width
+/// spaces become plain spaces. An em dash, or an en dash that is not between digits, reads as a
+/// pause, so it becomes a comma; a leading dialogue dash is dropped. An en dash in a range such as
+/// `9–5` becomes a hyphen. Soft hyphens are removed.
+pub fn fold(text: &str) -> String {
+    let chars: Vec<char> = text.nfc().collect();
+    let mut folded = String::with_capacity(text.len());
+    let mut skip_spaces = false;
+    for (index, &c) in chars.iter().enumerate() {
+        if skip_spaces && is_space(c) {
+            continue;
+        }
+        skip_spaces = false;
+        match c {
+            '\u{2018}' | '\u{2019}' | '\u{201a}' | '\u{201b}' => folded.push('\''),
+            '\u{201c}' | '\u{201d}' | '\u{201e}' | '\u{201f}' => folded.push('"'),
+            '\u{2026}' => folded.push_str("..."),
+            '\u{2010}' | '\u{2011}' | '\u{2212}' => folded.push('-'),
+            '\u{00ad}' => {}
+            '\u{2013}' if is_range(&chars, index) => folded.push('-'),
+            '\u{2013}' | '\u{2014}' | '\u{2015}' => {
+                let kept = folded.trim_end_matches(' ').len();
+                folded.truncate(kept);
+                match folded.chars().next_back() {
+                    Some(last) if last.is_alphanumeric() || "\"')]".contains(last) => {
+                        folded.push_str(", ")
+                    }
+                    Some(last) if !last.is_whitespace() => folded.push(' '),
+                    _ => {}
+                }
+                skip_spaces = true;
+            }
+            c if is_space(c) => folded.push(' '),
+            c => folded.push(c),
+        }
+    }
+    // A dash at the very end has nothing to pause before
+    if skip_spaces {
+        let kept = folded.trim_end_matches([',', ' ']).len();
+        folded.truncate(kept);
+    }
+    folded
+}
+
+/// Spaces other than line breaks, including non-breaking and fixed-width ones
+fn is_space(c: char) -> bool {
+    matches!(
+        c,
+        ' ' | '\t' | '\u{a0}' | '\u{2000}'..='\u{200a}' | '\u{202f}' | '\u{205f}'
+    )
+}
+
+/// Whether the dash at `index` joins two numbers, as in `1990–1995`
+fn is_range(chars: &[char], index: usize) -> bool {
+    index > 0
+        && chars[index - 1].is_ascii_digit()
+        && chars.get(index + 1).is_some_and(char::is_ascii_digit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_punctuation() {
+        assert_eq!(
+            fold("\u{201c}It\u{2019}s late\u{2026}\u{201d} she said\u{a0}\u{2014} again."),
+            "\"It's late...\" she said, again."
+        );
+        assert_eq!(fold("wait\u{2014}what"), "wait, what");
+        assert_eq!(fold("Open 9\u{2013}5 daily"), "Open 9-5 daily");
+        assert_eq!(fold("\u{2014} Who goes there?"), "Who goes there?");
+        assert_eq!(fold("Line one\n\u{2014}Line two"), "Line one\nLine two");
+        assert_eq!(fold("soft\u{ad}ware"), "software");
+        // Decomposed é is composed
+        assert_eq!(fold("cafe\u{301}"), "caf\u{e9}");
+        assert_eq!(fold("trailing \u{2014}"), "trailing");
+    }
+}
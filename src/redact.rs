@@ -0,0 +1,150 @@
+use crate::validation::is_unspoken_char;
+use axum::http::Uri;
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+
+/// Set once at startup from `KOKORO_REDACT_TEXT`
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Query parameters that carry credentials; their values are never logged
+const SECRET_PARAMS: [&str; 2] = ["key", "signature"];
+
+/// Double-quoted literals, which is how serde and most engine errors quote values
+static QUOTED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#""(?:[^"\\]|\\.)*""#).expect("valid regex"));
+
+/// Keep request text out of logs and error messages for the rest of the process
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Request text for a log field; shown as its length and a short hash when redacting
+pub fn text(text: &str) -> Text<'_> {
+    Text(text)
+}
+
+pub struct Text<'a>(&'a str);
+
+impl fmt::Display for Text<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if enabled() {
+            return f.write_str(&fingerprint(self.0));
+        }
+        // Line breaks would let the text forge log lines of its own
+        for c in self.0.chars() {
+            if matches!(c, '\n' | '\r' | '\t' | '\u{2028}' | '\u{2029}') {
+                f.write_char(' ')?;
+            } else if !is_unspoken_char(c) {
+                f.write_char(c)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error message that may quote request content, with quoted values removed when redacting
+pub fn message(message: &str) -> Cow<'_, str> {
+    if enabled() {
+        scrub(message)
+    } else {
+        Cow::Borrowed(message)
+    }
+}
+
+/// A request URI for logs: credentials in the query are masked, and the other query values,
+/// which can hold request text such as `?input=`, are shown like [`text`]
+pub fn uri(uri: &Uri) -> String {
+    let Some(query) = uri.query() else {
+        return uri.path().to_string();
+    };
+    let params: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if SECRET_PARAMS.contains(&name) => format!("{}=[redacted]", name),
+            Some((name, value)) => format!("{}={}", name, text(value)),
+            None => pair.to_string(),
+        })
+        .collect();
+    format!("{}?{}", uri.path(), params.join("&"))
+}
+
+/// Length and hash prefix of `text`; equal text gives equal fingerprints, so log lines about
+/// the same input can still be correlated
+fn fingerprint(text: &str) -> String {
+    let digest = Sha256::digest(text.as_bytes());
+    format!(
+        "[redacted {} chars #{:02x}{:02x}{:02x}{:02x}]",
+        text.chars().count(),
+        digest[0],
+        digest[1],
+        digest[2],
+        digest[3]
+    )
+}
+
+fn scrub(message: &str) -> Cow<'_, str> {
+    QUOTED.replace_all(message, "\"[redacted]\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint() {
+        let fingerprint = fingerprint("My card number is 4111");
+        assert!(fingerprint.starts_with("[redacted 22 chars #"));
+        assert!(!fingerprint.contains("4111"));
+        assert_eq!(fingerprint, super::fingerprint("My card number is 4111"));
+        assert_ne!(fingerprint, super::fingerprint("My card number is 4112"));
+    }
+
+    #[test]
+    fn test_text_keeps_to_one_line() {
+        assert_eq!(
+            text("done\n2026-01-01 INFO forged\u{202e}").to_string(),
+            "done 2026-01-01 INFO forged"
+        );
+    }
+
+    #[test]
+    fn test_uri_masks_credentials() {
+        let uri: Uri = "/v1/text:synthesize?alt=json&key=secret".parse().unwrap();
+        assert_eq!(
+            super::uri(&uri),
+            "/v1/text:synthesize?alt=json&key=[redacted]"
+        );
+        let uri: Uri = "/v1/audio/speech?voice=af_heart&expires=1&signature=abc"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            super::uri(&uri),
+            "/v1/audio/speech?voice=af_heart&expires=1&signature=[redacted]"
+        );
+        assert_eq!(super::uri(&"/health".parse().unwrap()), "/health");
+    }
+
+    #[test]
+    fn test_scrub() {
+        assert_eq!(
+            scrub(r#"speed: invalid type: string "call me at 555", expected f32"#),
+            r#"speed: invalid type: string "[redacted]", expected f32"#
+        );
+        assert_eq!(
+            scrub(r#"bad "quoted \"inner\" text" and "more""#),
+            r#"bad "[redacted]" and "[redacted]""#
+        );
+        assert_eq!(
+            scrub("missing field `voice` at line 1"),
+            "missing field `voice` at line 1"
+        );
+    }
+}
@@ -1,19 +1,78 @@
+use crate::config::is_sha256_hex;
+use crate::download::{download_from_any, lock_download, mirror_urls, sha256_file};
+use crate::language::Language;
 use anyhow::{Context, Result};
 use flate2::read::GzDecoder;
+use std::collections::BTreeSet;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use tar::Archive;
-use tracing::info;
+use tracing::{info, warn};
 
 const PIPER_PHONEMIZE_TAG: &str = "2023.11.14-4";
 const PIPER_URL_ENV: &str = "KOKORO_PIPER_PHONEMIZE_URL";
 const ESPEAK_HOME_ENV: &str = "PIPER_ESPEAKNG_DATA_DIRECTORY";
+const PIPER_SHA256_ENV: &str = "KOKORO_PIPER_PHONEMIZE_SHA256";
+/// SHA256 of each `PIPER_PHONEMIZE_TAG` release archive, by file name; update with the tag
+const PIPER_ARCHIVE_SHA256: &[(&str, &str)] = &[];
+/// Lists the dictionaries a language subset kept, next to `share`; absent when all were
+const DICTIONARIES_MARKER: &str = "espeak-dictionaries";
 
-pub async fn ensure_runtime_assets() -> Result<()> {
-    ensure_espeak_data_directory().await
+/// espeak-ng-data packed by build.rs from `KOKORO_EMBED_ESPEAK_DATA_DIRECTORY`
+#[cfg(feature = "embedded-espeak-data")]
+static EMBEDDED_ESPEAK_DATA: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/espeak-ng-data.tar.gz"));
+
+/// Where the piper-phonemize archive is read from
+#[derive(Debug, PartialEq)]
+enum ArchiveSource {
+    Url(String),
+    /// Local archive, for air-gapped hosts
+    File(PathBuf),
+}
+
+impl ArchiveSource {
+    /// `KOKORO_PIPER_PHONEMIZE_URL` accepts an http(s) URL, a `file://` URL, or a plain path
+    fn parse(value: &str) -> Self {
+        if let Some(path) = value.strip_prefix("file://") {
+            Self::File(PathBuf::from(path))
+        } else if value.contains("://") {
+            Self::Url(value.to_string())
+        } else {
+            Self::File(PathBuf::from(value))
+        }
+    }
+}
+
+/// Make sure eSpeak-ng data is available, downloading it unless `offline` is set.
+///
+/// `mirrors` are base URLs tried in order before the archive's own URL. With `languages`, only
+/// their dictionaries are extracted from the archive, along with the data every language shares.
+pub async fn ensure_runtime_assets(
+    offline: bool,
+    mirrors: &[String],
+    languages: Option<&[Language]>,
+) -> Result<()> {
+    let dictionaries = languages.map(subset_dictionaries);
+    ensure_espeak_data_directory(offline, mirrors, dictionaries.as_ref()).await
+}
+
+/// Dictionaries a language subset extracts. English is always kept: eSpeak-ng switches to it for
+/// English words inside other languages and falls back to it when a voice has no language.
+fn subset_dictionaries(languages: &[Language]) -> BTreeSet<String> {
+    languages
+        .iter()
+        .flat_map(|&language| espeak_dictionaries(language))
+        .chain(espeak_dictionaries(Language::AmericanEnglish))
+        .map(|name| name.to_string())
+        .collect()
 }
 
-async fn ensure_espeak_data_directory() -> Result<()> {
+async fn ensure_espeak_data_directory(
+    offline: bool,
+    mirrors: &[String],
+    dictionaries: Option<&BTreeSet<String>>,
+) -> Result<()> {
     if let Some(existing) = std::env::var_os(ESPEAK_HOME_ENV) {
         let existing = PathBuf::from(existing);
         validate_espeak_home(&existing).with_context(|| {
@@ -23,11 +82,13 @@ async fn ensure_espeak_data_directory() -> Result<()> {
                 existing.display()
             )
         })?;
+        warn_missing_dictionaries(&existing, dictionaries);
         info!(path = %existing.display(), "Using configured eSpeak-ng data directory");
         return Ok(());
     }
 
     if let Some(existing_home) = discover_existing_espeak_home()? {
+        warn_missing_dictionaries(&existing_home, dictionaries);
         std::env::set_var(ESPEAK_HOME_ENV, &existing_home);
         info!(path = %existing_home.display(), "Discovered local eSpeak-ng data directory");
         return Ok(());
@@ -39,22 +100,79 @@ async fn ensure_espeak_data_directory() -> Result<()> {
         .join("runtime");
     fs::create_dir_all(&runtime_root).context("Failed to create runtime cache directory")?;
 
+    // Replicas sharing the cache extract one at a time; the rest find the finished directory
+    let _lock = lock_download(&runtime_root.join("piper-phonemize")).await?;
+
+    // Bundled data replaces the download unless an archive is explicitly configured
+    #[cfg(feature = "embedded-espeak-data")]
+    if std::env::var_os(PIPER_URL_ENV).is_none() {
+        let embedded_home = ensure_embedded_espeak_data(&runtime_root)?;
+        std::env::set_var(ESPEAK_HOME_ENV, &embedded_home);
+        info!(path = %embedded_home.display(), "Using embedded eSpeak-ng data directory");
+        return Ok(());
+    }
+
     let install_root = runtime_root.join("piper-phonemize");
     let install_share = install_root.join("share");
-    if validate_espeak_home(&install_share).is_ok() {
-        std::env::set_var(ESPEAK_HOME_ENV, &install_share);
-        info!(path = %install_share.display(), "Using cached eSpeak-ng data directory");
-        return Ok(());
+    let cached = validate_espeak_home(&install_share).is_ok();
+    if cached {
+        if has_dictionaries(&install_root, dictionaries) {
+            std::env::set_var(ESPEAK_HOME_ENV, &install_share);
+            info!(path = %install_share.display(), "Using cached eSpeak-ng data directory");
+            return Ok(());
+        }
+        info!("Cached eSpeak-ng data lacks dictionaries for the configured languages; extracting again");
     }
 
-    let download_url = match std::env::var(PIPER_URL_ENV) {
-        Ok(url) => url,
-        Err(_) => default_piper_url()?,
+    let source = match std::env::var(PIPER_URL_ENV) {
+        Ok(value) => ArchiveSource::parse(&value),
+        Err(_) => ArchiveSource::Url(default_piper_url()?),
+    };
+    let expected_sha256 = expected_archive_sha256(&source, PIPER_ARCHIVE_SHA256)?;
+    let (archive_path, downloaded) = match source {
+        ArchiveSource::File(path) => {
+            if !path.is_file() {
+                anyhow::bail!(
+                    "{} points to {}, which is not a file",
+                    PIPER_URL_ENV,
+                    path.display()
+                );
+            }
+            info!(path = %path.display(), "Extracting local runtime phonemizer archive");
+            (path, false)
+        }
+        // A language added since the cache was extracted needs the archive again
+        ArchiveSource::Url(url) if offline && cached => anyhow::bail!(
+            "Cached eSpeak-ng data in {} has no dictionary for {} and downloads are disabled \
+             (KOKORO_OFFLINE). Download {} and set {} to its local path, or remove the language \
+             from KOKORO_ESPEAK_LANGUAGES",
+            install_share.display(),
+            match missing_dictionaries(&install_share, dictionaries) {
+                missing if missing.is_empty() => "every language".to_string(),
+                missing => missing.join(", "),
+            },
+            url,
+            PIPER_URL_ENV
+        ),
+        ArchiveSource::Url(url) if offline => anyhow::bail!(
+            "eSpeak-ng data not found and downloads are disabled (KOKORO_OFFLINE). Set {} to a \
+             directory containing espeak-ng-data, place espeak-ng-data in the working directory \
+             or next to the executable, or download {} and set {} to its local path",
+            ESPEAK_HOME_ENV,
+            url,
+            PIPER_URL_ENV
+        ),
+        ArchiveSource::Url(url) => {
+            info!(url = %url, "Downloading runtime phonemizer assets");
+            let archive_path = runtime_root.join("piper-phonemize.tar.gz");
+            download_from_any(&mirror_urls(&url, mirrors), &archive_path, None)
+                .await
+                .context("Failed to download phonemizer archive")?;
+            (archive_path, true)
+        }
     };
-    info!(url = %download_url, "Downloading runtime phonemizer assets");
 
-    let archive_path = runtime_root.join("piper-phonemize.tar.gz");
-    download_to_file(&download_url, &archive_path).await?;
+    verify_archive(&archive_path, expected_sha256.as_deref(), downloaded)?;
 
     let extract_tmp = runtime_root.join("piper-phonemize.tmp");
     if extract_tmp.exists() {
@@ -62,7 +180,7 @@ async fn ensure_espeak_data_directory() -> Result<()> {
     }
     fs::create_dir_all(&extract_tmp).context("Failed to create temporary extraction dir")?;
 
-    extract_tar_gz(&archive_path, &extract_tmp)?;
+    extract_tar_gz(&archive_path, &extract_tmp, dictionaries)?;
 
     let extracted_root = extract_tmp.join("piper-phonemize");
     let extracted_share = extracted_root.join("share");
@@ -73,11 +191,23 @@ async fn ensure_espeak_data_directory() -> Result<()> {
         )
     })?;
 
+    if let Some(dictionaries) = dictionaries {
+        let names = dictionaries.iter().cloned().collect::<Vec<_>>();
+        fs::write(extracted_root.join(DICTIONARIES_MARKER), names.join("\n"))
+            .context("Failed to record extracted eSpeak-ng dictionaries")?;
+        info!(
+            dictionaries = %names.join(","),
+            "Extracted eSpeak-ng data for the configured languages only"
+        );
+    }
+
     if install_root.exists() {
         fs::remove_dir_all(&install_root).context("Failed to replace existing phonemizer cache")?;
     }
     fs::rename(&extracted_root, &install_root).context("Failed to finalize phonemizer assets")?;
-    let _ = fs::remove_file(&archive_path);
+    if downloaded {
+        let _ = fs::remove_file(&archive_path);
+    }
     let _ = fs::remove_dir_all(&extract_tmp);
 
     std::env::set_var(ESPEAK_HOME_ENV, &install_share);
@@ -86,6 +216,105 @@ async fn ensure_espeak_data_directory() -> Result<()> {
     Ok(())
 }
 
+/// SHA256 the archive must match: `KOKORO_PIPER_PHONEMIZE_SHA256`, else the built-in digest for
+/// the default release URL.
+///
+/// Downloads are never extracted unverified, so another URL needs the variable. Only a local
+/// archive, which the operator placed, may go without a digest.
+fn expected_archive_sha256(
+    source: &ArchiveSource,
+    builtin: &[(&str, &str)],
+) -> Result<Option<String>> {
+    match std::env::var(PIPER_SHA256_ENV) {
+        Ok(digest) if is_sha256_hex(&digest) => return Ok(Some(digest)),
+        Ok(_) => anyhow::bail!(
+            "{} must be a 64 character hex SHA256 digest",
+            PIPER_SHA256_ENV
+        ),
+        Err(_) => {}
+    }
+
+    let url = match source {
+        ArchiveSource::File(_) => return Ok(None),
+        ArchiveSource::Url(url) => url,
+    };
+    if *url != default_piper_url()? {
+        anyhow::bail!(
+            "{} is set to a download URL; set {} to the archive's SHA256 so it can be verified",
+            PIPER_URL_ENV,
+            PIPER_SHA256_ENV
+        );
+    }
+    let archive_name = piper_archive_name_for_target()?;
+    match builtin.iter().find(|(name, _)| *name == archive_name) {
+        Some((_, digest)) => Ok(Some(digest.to_string())),
+        None => anyhow::bail!(
+            "No built-in SHA256 for {} at release {}; set {} to its digest",
+            archive_name,
+            PIPER_PHONEMIZE_TAG,
+            PIPER_SHA256_ENV
+        ),
+    }
+}
+
+/// Check the archive against the pinned digest before anything is extracted from it.
+///
+/// A mismatching download is deleted; a local archive is left for the operator to inspect.
+/// A local archive without a pinned digest is extracted, and its digest logged so it can be pinned.
+fn verify_archive(archive_path: &Path, expected: Option<&str>, downloaded: bool) -> Result<()> {
+    let actual = sha256_file(archive_path)?;
+    let Some(expected) = expected else {
+        warn!(
+            path = %archive_path.display(),
+            sha256 = %actual,
+            "Phonemizer archive is not verified; set {} to pin it",
+            PIPER_SHA256_ENV
+        );
+        return Ok(());
+    };
+
+    if !expected.eq_ignore_ascii_case(&actual) {
+        if downloaded {
+            let _ = fs::remove_file(archive_path);
+        }
+        anyhow::bail!(
+            "Phonemizer archive {} does not match {} (expected {}, got {}); it may be corrupted or tampered with",
+            archive_path.display(),
+            PIPER_SHA256_ENV,
+            expected,
+            actual
+        );
+    }
+
+    info!(sha256 = %actual, "Verified phonemizer archive checksum");
+    Ok(())
+}
+
+/// Extract the bundled espeak-ng-data into the cache, once per server version
+#[cfg(feature = "embedded-espeak-data")]
+fn ensure_embedded_espeak_data(runtime_root: &Path) -> Result<PathBuf> {
+    let embedded_root = runtime_root.join("embedded");
+    let home = embedded_root.join(env!("CARGO_PKG_VERSION"));
+    if validate_espeak_home(&home).is_ok() {
+        return Ok(home);
+    }
+
+    let extract_tmp = embedded_root.join(format!("{}.tmp", env!("CARGO_PKG_VERSION")));
+    if extract_tmp.exists() {
+        fs::remove_dir_all(&extract_tmp).context("Failed to clear temporary extraction dir")?;
+    }
+    fs::create_dir_all(&extract_tmp).context("Failed to create temporary extraction dir")?;
+    Archive::new(GzDecoder::new(EMBEDDED_ESPEAK_DATA))
+        .unpack(&extract_tmp)
+        .context("Failed to unpack embedded espeak-ng-data")?;
+
+    if home.exists() {
+        fs::remove_dir_all(&home).context("Failed to replace embedded eSpeak-ng data")?;
+    }
+    fs::rename(&extract_tmp, &home).context("Failed to finalize embedded eSpeak-ng data")?;
+    Ok(home)
+}
+
 fn discover_existing_espeak_home() -> Result<Option<PathBuf>> {
     let cwd = std::env::current_dir().context("Failed to read current directory")?;
     if has_espeak_ng_data(&cwd) {
@@ -106,6 +335,11 @@ fn discover_existing_espeak_home() -> Result<Option<PathBuf>> {
     Ok(None)
 }
 
+/// Whether the espeak-ng data directory the phonemizer was pointed at still holds its data
+pub fn espeak_data_valid() -> bool {
+    std::env::var_os(ESPEAK_HOME_ENV).is_some_and(|home| has_espeak_ng_data(Path::new(&home)))
+}
+
 fn has_espeak_ng_data(home: &Path) -> bool {
     home.join("espeak-ng-data").is_dir()
 }
@@ -121,31 +355,75 @@ fn validate_espeak_home(home: &Path) -> Result<()> {
     );
 }
 
-async fn download_to_file(url: &str, target: &Path) -> Result<()> {
-    let client = reqwest::Client::new();
-    let response = client
-        .get(url)
-        .send()
-        .await
-        .with_context(|| format!("Failed to request phonemizer archive from {url}"))?
-        .error_for_status()
-        .with_context(|| format!("Phonemizer archive download failed for {url}"))?;
+/// espeak-ng dictionaries (`<name>_dict` in espeak-ng-data) a language is phonemized with
+fn espeak_dictionaries(language: Language) -> &'static [&'static str] {
+    match language {
+        Language::AmericanEnglish | Language::BritishEnglish => &["en"],
+        Language::Spanish => &["es"],
+        Language::French => &["fr"],
+        Language::Hindi => &["hi"],
+        Language::Italian => &["it"],
+        Language::Japanese => &["ja"],
+        Language::Portuguese => &["pt"],
+        Language::Chinese => &["cmn", "zh"],
+    }
+}
 
-    let bytes = response
-        .bytes()
-        .await
-        .with_context(|| format!("Failed to read phonemizer archive response body from {url}"))?;
+/// Wanted dictionaries that `home` has no `<name>_dict` file for
+fn missing_dictionaries(home: &Path, wanted: Option<&BTreeSet<String>>) -> Vec<String> {
+    let data = home.join("espeak-ng-data");
+    wanted
+        .into_iter()
+        .flatten()
+        .filter(|name| !data.join(format!("{name}_dict")).is_file())
+        .cloned()
+        .collect()
+}
 
-    fs::write(target, &bytes).with_context(|| {
-        format!(
-            "Failed to persist downloaded phonemizer archive to {}",
-            target.display()
-        )
-    })?;
-    Ok(())
+/// Data the server did not extract is used as is, but a missing language is worth a warning
+fn warn_missing_dictionaries(home: &Path, wanted: Option<&BTreeSet<String>>) {
+    let missing = missing_dictionaries(home, wanted);
+    if !missing.is_empty() {
+        warn!(
+            path = %home.display(),
+            dictionaries = %missing.join(","),
+            "eSpeak-ng data has no dictionary for some configured languages"
+        );
+    }
 }
 
-fn extract_tar_gz(archive_path: &Path, target_dir: &Path) -> Result<()> {
+/// Whether the phonemizer cache at `install_root` holds `wanted` dictionaries (`None` for all)
+fn has_dictionaries(install_root: &Path, wanted: Option<&BTreeSet<String>>) -> bool {
+    let Ok(kept) = fs::read_to_string(install_root.join(DICTIONARIES_MARKER)) else {
+        // Without the marker every dictionary was extracted
+        return true;
+    };
+    let kept: BTreeSet<&str> = kept.lines().collect();
+    wanted.is_some_and(|wanted| wanted.iter().all(|name| kept.contains(name.as_str())))
+}
+
+/// Whether a language subset keeps an archive entry: everything under `share` except the
+/// dictionaries of other languages. The library, headers, and tools beside it are never used.
+fn keep_entry(path: &Path, dictionaries: &BTreeSet<String>) -> bool {
+    let parts: Vec<_> = path
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+    match parts.as_slice() {
+        [_, share, ..] if share != "share" => false,
+        [_, _, data, file] if data == "espeak-ng-data" => file
+            .strip_suffix("_dict")
+            .is_none_or(|name| dictionaries.contains(name)),
+        _ => true,
+    }
+}
+
+fn extract_tar_gz(
+    archive_path: &Path,
+    target_dir: &Path,
+    dictionaries: Option<&BTreeSet<String>>,
+) -> Result<()> {
     let file = fs::File::open(archive_path).with_context(|| {
         format!(
             "Failed to open downloaded archive at {}",
@@ -154,7 +432,19 @@ fn extract_tar_gz(archive_path: &Path, target_dir: &Path) -> Result<()> {
     })?;
     let decoder = GzDecoder::new(file);
     let mut archive = Archive::new(decoder);
-    archive.unpack(target_dir).with_context(|| {
+    let unpacked = match dictionaries {
+        None => archive.unpack(target_dir),
+        Some(dictionaries) => archive.entries().and_then(|entries| {
+            for entry in entries {
+                let mut entry = entry?;
+                if keep_entry(&entry.path()?, dictionaries) {
+                    entry.unpack_in(target_dir)?;
+                }
+            }
+            Ok(())
+        }),
+    };
+    unpacked.with_context(|| {
         format!(
             "Failed to unpack archive {} into {}",
             archive_path.display(),
@@ -194,6 +484,112 @@ mod tests {
         assert!(url.contains(PIPER_PHONEMIZE_TAG));
     }
 
+    #[test]
+    fn test_archive_source_parse() {
+        assert_eq!(
+            ArchiveSource::parse("https://example.com/piper.tar.gz"),
+            ArchiveSource::Url("https://example.com/piper.tar.gz".to_string())
+        );
+        assert_eq!(
+            ArchiveSource::parse("file:///opt/piper.tar.gz"),
+            ArchiveSource::File(PathBuf::from("/opt/piper.tar.gz"))
+        );
+        assert_eq!(
+            ArchiveSource::parse("assets/piper.tar.gz"),
+            ArchiveSource::File(PathBuf::from("assets/piper.tar.gz"))
+        );
+    }
+
+    #[test]
+    fn test_expected_archive_sha256() {
+        let archive_name = piper_archive_name_for_target().unwrap();
+        let digest = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        let builtin = [(archive_name, digest)];
+        let default = ArchiveSource::Url(default_piper_url().unwrap());
+
+        assert_eq!(
+            expected_archive_sha256(&default, &builtin)
+                .unwrap()
+                .as_deref(),
+            Some(digest)
+        );
+        assert!(expected_archive_sha256(&default, &[]).is_err());
+        let other = ArchiveSource::Url("https://example.com/piper.tar.gz".to_string());
+        assert!(expected_archive_sha256(&other, &builtin).is_err());
+        let local = ArchiveSource::File(PathBuf::from("/opt/piper.tar.gz"));
+        assert_eq!(expected_archive_sha256(&local, &[]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_verify_archive() {
+        let path = std::env::temp_dir().join(format!("piper-{}.tar.gz", uuid::Uuid::new_v4()));
+        fs::write(&path, b"abc").unwrap();
+        let digest = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        assert!(verify_archive(&path, None, true).is_ok());
+        assert!(verify_archive(&path, Some(&digest.to_uppercase()), true).is_ok());
+        assert!(verify_archive(&path, Some(&"0".repeat(64)), false).is_err());
+        assert!(path.exists());
+        assert!(verify_archive(&path, Some(&"0".repeat(64)), true).is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_language_subset_entries() {
+        let dictionaries = subset_dictionaries(&[Language::Spanish]);
+        let keeps = |path: &str| keep_entry(Path::new(path), &dictionaries);
+        assert!(keeps("piper-phonemize/"));
+        assert!(keeps("piper-phonemize/share/espeak-ng-data/es_dict"));
+        assert!(keeps("./piper-phonemize/share/espeak-ng-data/phondata"));
+        assert!(keeps("piper-phonemize/share/espeak-ng-data/lang/roa/es"));
+        assert!(!keeps("piper-phonemize/share/espeak-ng-data/ru_dict"));
+        // A non-English subset still keeps the English dictionary
+        assert!(keeps("piper-phonemize/share/espeak-ng-data/en_dict"));
+        assert!(!keeps("piper-phonemize/lib/libonnxruntime.so"));
+    }
+
+    #[test]
+    fn test_cached_dictionaries_cover_languages() {
+        let install_root =
+            std::env::temp_dir().join(format!("piper-phonemize-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&install_root).unwrap();
+        let wanted = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+
+        // A full extraction has every dictionary
+        assert!(has_dictionaries(&install_root, None));
+        assert!(has_dictionaries(&install_root, Some(&wanted(&["ja"]))));
+
+        fs::write(install_root.join(DICTIONARIES_MARKER), "en\nes").unwrap();
+        assert!(has_dictionaries(&install_root, Some(&wanted(&["es"]))));
+        assert!(!has_dictionaries(
+            &install_root,
+            Some(&wanted(&["es", "fr"]))
+        ));
+        assert!(!has_dictionaries(&install_root, None));
+
+        let data = install_root.join("share").join("espeak-ng-data");
+        fs::create_dir_all(&data).unwrap();
+        fs::write(data.join("es_dict"), b"").unwrap();
+        let share = install_root.join("share");
+        assert!(missing_dictionaries(&share, None).is_empty());
+        assert_eq!(
+            missing_dictionaries(&share, Some(&wanted(&["es", "fr"]))),
+            ["fr"]
+        );
+        let _ = fs::remove_dir_all(&install_root);
+    }
+
+    #[cfg(feature = "embedded-espeak-data")]
+    #[test]
+    fn test_embedded_espeak_data_extracts() {
+        let runtime_root =
+            std::env::temp_dir().join(format!("kokoro-runtime-{}", uuid::Uuid::new_v4()));
+        let home = ensure_embedded_espeak_data(&runtime_root).unwrap();
+        assert!(has_espeak_ng_data(&home));
+        assert_eq!(ensure_embedded_espeak_data(&runtime_root).unwrap(), home);
+        let _ = fs::remove_dir_all(&runtime_root);
+    }
+
     #[test]
     fn test_archive_name_for_target_supported_in_tests() {
         let name = piper_archive_name_for_target().unwrap();
@@ -0,0 +1,278 @@
+use crate::{
+    api::{presented_api_key, validate_speech_request, AppState, SpeechRequest},
+    crypto::{hex, hmac_sha256, verify_hmac_sha256},
+    error::{ApiResult, AppError},
+    extract::ApiJson,
+    feeds::base_url,
+};
+use axum::{
+    extract::State,
+    http::{HeaderMap, Uri},
+    Json,
+};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+use utoipa::ToSchema;
+
+/// Path that signed URLs fetch speech from
+pub const SPEECH_PATH: &str = "/v1/audio/speech";
+
+const DEFAULT_EXPIRES_IN_SECS: u64 = 24 * 60 * 60;
+/// Longest a signed URL may stay valid
+const MAX_EXPIRES_IN_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Query parameters the server adds when signing, which callers may not set
+const SIGNING_PARAMS: [&str; 2] = ["expires", "signature"];
+
+/// Request body for POST /v1/audio/speech/sign: the fields of a speech request, plus
+/// `expires_in`
+#[derive(Debug, Deserialize)]
+pub struct SignSpeechUrlRequest {
+    /// Seconds until the URL expires
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(flatten)]
+    params: Map<String, Value>,
+}
+
+/// Response body for POST /v1/audio/speech/sign and POST /feeds/{feed}/sign
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SignedUrl {
+    /// URL that fetches the speech or feed with `GET`
+    pub url: String,
+    /// Unix time the URL stops working; absent when API keys are not required and the URL
+    /// needs no signature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+}
+
+/// Create a URL that fetches speech with `GET`, signed with the caller's API key so it works
+/// without an Authorization header
+#[utoipa::path(
+    post,
+    path = "/v1/audio/speech/sign",
+    tag = "speech",
+    request_body(
+        content = SpeechRequest,
+        description = "Fields of a speech request, plus `expires_in` seconds (default 86400, at most 2592000)"
+    ),
+    responses(
+        (status = 200, description = "Signed speech URL", body = SignedUrl),
+        (status = 400, description = "Invalid request, model, voice, or format", body = crate::error::OpenAIError),
+        (status = 401, description = "Missing or invalid API key", body = crate::error::OpenAIError)
+    )
+)]
+pub async fn sign_speech_url_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    uri: Uri,
+    ApiJson(req): ApiJson<SignSpeechUrlRequest>,
+) -> ApiResult<Json<SignedUrl>> {
+    if let Some(name) = SIGNING_PARAMS
+        .into_iter()
+        .find(|name| req.params.contains_key(*name))
+    {
+        return Err(AppError::invalid_request(format!(
+            "'{}' is added by the server when signing and cannot be set",
+            name
+        )));
+    }
+    // A URL that would be refused is refused now, while the caller can still see why
+    let speech: SpeechRequest = serde_json::from_value(Value::Object(req.params.clone()))
+        .map_err(|e| AppError::invalid_json(e.to_string(), None))?;
+    validate_speech_request(&state, speech)?;
+
+    let expires_at = expires_at(req.expires_in)?;
+    let mut url = Url::parse(&format!(
+        "{}{}",
+        base_url(&headers, state.trust_forwarded_headers),
+        SPEECH_PATH
+    ))
+    .map_err(|_| AppError::invalid_request("Host header is not a valid host"))?;
+    {
+        let mut query = url.query_pairs_mut();
+        for (name, value) in &req.params {
+            match value {
+                Value::Null => {}
+                Value::String(text) => {
+                    query.append_pair(name, text);
+                }
+                other => {
+                    query.append_pair(name, &other.to_string());
+                }
+            }
+        }
+    }
+
+    let key = presented_api_key(&headers, &uri).filter(|_| state.api_keys.is_required());
+    let expires_at = key.map(|key| {
+        sign(&mut url, key, expires_at);
+        expires_at
+    });
+
+    Ok(Json(SignedUrl {
+        url: url.into(),
+        expires_at,
+    }))
+}
+
+/// Unix time a URL signed now expires, `expires_in` seconds from now
+pub fn expires_at(expires_in: Option<u64>) -> ApiResult<u64> {
+    let expires_in = expires_in.unwrap_or(DEFAULT_EXPIRES_IN_SECS);
+    if expires_in == 0 || expires_in > MAX_EXPIRES_IN_SECS {
+        return Err(AppError::invalid_request(format!(
+            "expires_in must be between 1 and {} seconds",
+            MAX_EXPIRES_IN_SECS
+        )));
+    }
+    Ok(unix_now() + expires_in)
+}
+
+/// Whether `GET` requests to `path` may authenticate with a signed URL: speech, podcast feeds,
+/// and the job chapters feeds link to
+pub fn signable(path: &str) -> bool {
+    path == SPEECH_PATH
+        || path.starts_with("/feeds/")
+        || (path.starts_with("/v1/audio/jobs/") && path.contains("/chapters/"))
+}
+
+/// Append `expires` and a `signature` made with `secret` to `url`'s query
+pub fn sign(url: &mut Url, secret: &str, expires_at: u64) {
+    url.query_pairs_mut()
+        .append_pair("expires", &expires_at.to_string());
+    let signature = signature(secret, url.path(), url.query().unwrap_or_default());
+    url.query_pairs_mut().append_pair("signature", &signature);
+}
+
+/// Which of `secrets` made the unexpired signature on a `GET` of `path` with `query`.
+///
+/// The signature covers the query string exactly as sent, up to the `signature` parameter,
+/// which must come last and appear once, as must `expires`.
+pub fn verify<'a>(secrets: &'a [String], path: &str, query: &str) -> Option<&'a str> {
+    let (signed, provided) = query.rsplit_once("&signature=")?;
+    let mut expires = signed
+        .split('&')
+        .filter_map(|pair| pair.strip_prefix("expires="));
+    let (Some(expires), None) = (expires.next(), expires.next()) else {
+        return None;
+    };
+    let unexpired = expires
+        .parse::<u64>()
+        .is_ok_and(|expires| expires > unix_now());
+    if !unexpired || signed.split('&').any(|pair| pair.starts_with("signature=")) {
+        return None;
+    }
+    // Every secret is checked, so timing does not reveal which one matched
+    secrets.iter().fold(None, |found, secret| {
+        let message = signed_message(path, signed);
+        let matches = verify_hmac_sha256(secret.as_bytes(), message.as_bytes(), provided);
+        found.or(matches.then_some(secret.as_str()))
+    })
+}
+
+/// Hex HMAC-SHA256 of the method, path, and query string of a `GET` request
+fn signature(secret: &str, path: &str, query: &str) -> String {
+    let message = signed_message(path, query);
+    hex(&hmac_sha256(secret.as_bytes(), message.as_bytes()))
+}
+
+/// What a signature covers: the method, path, and query string of a `GET` request
+fn signed_message(path: &str, query: &str) -> String {
+    format!("GET\n{}\n{}", path, query)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_signed_query() {
+        let secrets = ["sk-one".to_string(), "sk-two".to_string()];
+        let signed = |secret: &str, expires: u64| {
+            let query = format!("model=kokoro&input=Hello+there&expires={}", expires);
+            format!(
+                "{}&signature={}",
+                query,
+                signature(secret, SPEECH_PATH, &query)
+            )
+        };
+        let later = unix_now() + 60;
+        assert_eq!(
+            verify(&secrets, SPEECH_PATH, &signed("sk-two", later)),
+            Some("sk-two")
+        );
+        assert_eq!(
+            verify(&secrets, SPEECH_PATH, &signed("sk-three", later)),
+            None
+        );
+        assert_eq!(
+            verify(&secrets, SPEECH_PATH, &signed("sk-one", unix_now() - 1)),
+            None
+        );
+        assert_eq!(
+            verify(&secrets, "/feeds/news.xml", &signed("sk-one", later)),
+            None
+        );
+
+        let tampered = signed("sk-one", later).replace("Hello", "Goodbye");
+        assert_eq!(verify(&secrets, SPEECH_PATH, &tampered), None);
+        let query = "model=kokoro&input=Hi";
+        let unexpiring = format!(
+            "{}&signature={}",
+            query,
+            signature("sk-one", SPEECH_PATH, query)
+        );
+        assert_eq!(verify(&secrets, SPEECH_PATH, &unexpiring), None);
+
+        // A second `expires` or `signature` is never trusted, even inside the signed query
+        let doubled = |extra: &str| {
+            let query = format!("input=Hi&expires={}&{}", later, extra);
+            format!(
+                "{}&signature={}",
+                query,
+                signature("sk-one", SPEECH_PATH, &query)
+            )
+        };
+        assert_eq!(
+            verify(&secrets, SPEECH_PATH, &doubled("model=kokoro")),
+            Some("sk-one")
+        );
+        assert_eq!(verify(&secrets, SPEECH_PATH, &doubled("expires=1")), None);
+        assert_eq!(
+            verify(&secrets, SPEECH_PATH, &doubled("signature=ab")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sign_chapter_url() {
+        let secrets = ["sk-one".to_string()];
+        let mut url = Url::parse("http://tts.local/v1/audio/jobs/job_1/chapters/0").unwrap();
+        sign(&mut url, "sk-one", unix_now() + 60);
+        assert!(signable(url.path()));
+        assert_eq!(
+            verify(&secrets, url.path(), url.query().unwrap()),
+            Some("sk-one")
+        );
+        assert!(!signable("/v1/audio/jobs/job_1/content"));
+    }
+
+    #[test]
+    fn test_signature_covers_method_and_path() {
+        let query = "input=Hi&expires=100";
+        let expected = hex(&hmac_sha256(
+            b"sk-one",
+            b"GET\n/v1/audio/speech\ninput=Hi&expires=100",
+        ));
+        assert_eq!(signature("sk-one", SPEECH_PATH, query), expected);
+        assert_eq!(signature("sk-one", SPEECH_PATH, query).len(), 64);
+    }
+}
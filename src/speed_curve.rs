@@ -0,0 +1,143 @@
+use crate::{
+    error::{ApiResult, AppError},
+    validation::validate_speed,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Most points a speed curve may have
+const MAX_POINTS: usize = 16;
+
+/// One point of a speed curve
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SpeedPoint {
+    /// Position through the input, from 0.0 at its start to 1.0 at its end
+    pub at: f32,
+    /// Speed multiplier at that position (0.25 to 4.0)
+    pub speed: f32,
+}
+
+/// Speed varying across a request, interpolated linearly between points and held flat before
+/// the first and after the last.
+///
+/// Speech is synthesized a chunk at a time, so each chunk is read at the curve's speed at its
+/// middle rather than changing within a sentence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeedCurve {
+    points: Vec<SpeedPoint>,
+}
+
+impl SpeedCurve {
+    /// Validate the points of a request's `speed_curve`
+    pub fn new(points: Vec<SpeedPoint>) -> ApiResult<Self> {
+        if points.is_empty() || points.len() > MAX_POINTS {
+            return Err(AppError::InvalidSpeed(format!(
+                "speed_curve must have between 1 and {} points",
+                MAX_POINTS
+            )));
+        }
+        for point in &points {
+            if !(0.0..=1.0).contains(&point.at) {
+                return Err(AppError::InvalidSpeed(format!(
+                    "speed_curve positions must be between 0.0 and 1.0, got {}",
+                    point.at
+                )));
+            }
+            validate_speed(point.speed)?;
+        }
+        if points.windows(2).any(|pair| pair[0].at >= pair[1].at) {
+            return Err(AppError::InvalidSpeed(
+                "speed_curve positions must be in ascending order".to_string(),
+            ));
+        }
+        Ok(Self { points })
+    }
+
+    /// Speed at `position` through the input, from 0.0 to 1.0
+    pub fn speed_at(&self, position: f32) -> f32 {
+        let first = self.points[0];
+        if position <= first.at {
+            return first.speed;
+        }
+        for pair in self.points.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            if position <= to.at {
+                let t = (position - from.at) / (to.at - from.at);
+                return from.speed + (to.speed - from.speed) * t;
+            }
+        }
+        self.points[self.points.len() - 1].speed
+    }
+
+    /// Speed for each chunk, read at the chunk's middle by characters
+    pub fn chunk_speeds(&self, chunks: &[String]) -> Vec<f32> {
+        let lengths: Vec<usize> = chunks.iter().map(|chunk| chunk.chars().count()).collect();
+        let total = lengths.iter().sum::<usize>().max(1) as f32;
+        let mut before = 0usize;
+        lengths
+            .iter()
+            .map(|&length| {
+                let middle = before as f32 + length as f32 / 2.0;
+                before += length;
+                self.speed_at(middle / total)
+            })
+            .collect()
+    }
+
+    /// Canonical form for cache keys, e.g. `0:1,1:0.85`
+    pub fn describe(&self) -> String {
+        self.points
+            .iter()
+            .map(|point| format!("{}:{}", point.at, point.speed))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// Speed for each chunk of a request read at `speed`, or along `curve` when it has one
+pub fn chunk_speeds(speed: f32, curve: Option<&SpeedCurve>, chunks: &[String]) -> Vec<f32> {
+    match curve {
+        Some(curve) => curve.chunk_speeds(chunks),
+        None => vec![speed; chunks.len()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(at: f32, speed: f32) -> SpeedPoint {
+        SpeedPoint { at, speed }
+    }
+
+    #[test]
+    fn test_speed_curve_interpolates_per_chunk() {
+        let curve = SpeedCurve::new(vec![point(0.0, 1.0), point(1.0, 0.8)]).unwrap();
+        assert_eq!(curve.speed_at(0.0), 1.0);
+        assert!((curve.speed_at(0.5) - 0.9).abs() < 1e-6);
+        assert_eq!(curve.speed_at(1.0), 0.8);
+
+        let chunks = ["a".repeat(10), "b".repeat(20), "c".repeat(10)];
+        let speeds = curve.chunk_speeds(&chunks);
+        assert!((speeds[0] - 0.975).abs() < 1e-6);
+        assert!((speeds[1] - 0.9).abs() < 1e-6);
+        assert!((speeds[2] - 0.825).abs() < 1e-6);
+        assert_eq!(chunk_speeds(1.2, None, &chunks), vec![1.2; 3]);
+
+        // Flat outside the points
+        let held = SpeedCurve::new(vec![point(0.25, 1.0), point(0.75, 0.5)]).unwrap();
+        assert_eq!(held.speed_at(0.1), 1.0);
+        assert_eq!(held.speed_at(0.9), 0.5);
+        assert_eq!(held.describe(), "0.25:1,0.75:0.5");
+    }
+
+    #[test]
+    fn test_speed_curve_validation() {
+        assert!(SpeedCurve::new(Vec::new()).is_err());
+        assert!(SpeedCurve::new(vec![point(-0.1, 1.0)]).is_err());
+        assert!(SpeedCurve::new(vec![point(0.5, 5.0)]).is_err());
+        assert!(SpeedCurve::new(vec![point(0.5, 1.0), point(0.5, 0.9)]).is_err());
+        assert!(SpeedCurve::new(vec![point(0.0, 1.0); MAX_POINTS + 1]).is_err());
+        assert!(SpeedCurve::new(vec![point(0.5, 0.9)]).is_ok());
+    }
+}
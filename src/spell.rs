@@ -0,0 +1,77 @@
+use crate::{language::Language, numbers::cardinal};
+
+/// Spoken names of symbols common in codes, call signs, and serial numbers
+const ENGLISH_SYMBOLS: &[(char, &str)] = &[
+    ('-', "dash"),
+    ('_', "underscore"),
+    ('.', "dot"),
+    ('/', "slash"),
+    ('\\', "backslash"),
+    ('@', "at"),
+    ('#', "hash"),
+    ('+', "plus"),
+    ('&', "and"),
+    ('*', "star"),
+    (':', "colon"),
+    ('=', "equals"),
+    ('%', "percent"),
+    ('$', "dollar"),
+];
+
+/// Rewrite `text` to be read one character at a time, for confirmation codes, call signs, and
+/// serial numbers.
+///
+/// Letters are capitalized so the engine says their names, digits become number words where
+/// the language has them, and in English common symbols are named. Characters are
+/// separated by commas and whitespace-separated groups by periods, so `AB-12 x` reads as
+/// "A, B, dash, one, two. X"
+pub fn spell_out(text: &str, language: Language) -> String {
+    let english = matches!(
+        language,
+        Language::AmericanEnglish | Language::BritishEnglish
+    );
+    let mut groups = Vec::new();
+    for group in text.split_whitespace() {
+        let names: Vec<String> = group
+            .chars()
+            .filter_map(|c| character_name(c, language, english))
+            .collect();
+        if !names.is_empty() {
+            groups.push(names.join(", "));
+        }
+    }
+    groups.join(". ")
+}
+
+fn character_name(c: char, language: Language, english: bool) -> Option<String> {
+    if let Some(digit) = c.to_digit(10) {
+        return Some(cardinal(u64::from(digit), language).unwrap_or_else(|| c.to_string()));
+    }
+    if c.is_alphanumeric() {
+        return Some(c.to_uppercase().collect());
+    }
+    // Other punctuation and symbols have no name the engine would say, so they are left out
+    ENGLISH_SYMBOLS
+        .iter()
+        .find(|(symbol, _)| english && *symbol == c)
+        .map(|(_, name)| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spell_out() {
+        assert_eq!(
+            spell_out("AB-12 x", Language::AmericanEnglish),
+            "A, B, dash, one, two. X"
+        );
+        assert_eq!(
+            spell_out("KD9 ! qrz", Language::BritishEnglish),
+            "K, D, nine. Q, R, Z"
+        );
+        assert_eq!(spell_out("a-3", Language::Spanish), "A, tres");
+        assert_eq!(spell_out("  ", Language::AmericanEnglish), "");
+    }
+}
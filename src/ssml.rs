@@ -0,0 +1,111 @@
+use crate::{language::Language, spell::spell_out};
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+static TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").expect("valid regex"));
+static SPELL_OUT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?s)<say-as\b[^>]*?\binterpret-as\s*=\s*["'](?:characters|spell-out)["'][^>]*>(.*?)</say-as\s*>"#,
+    )
+    .expect("valid regex")
+});
+static VOICE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<voice\b[^>]*?\bname\s*=\s*["']([^"']+)["']"#).expect("valid regex")
+});
+
+/// Reduce an SSML document to the text it speaks.
+///
+/// Kokoro has no SSML support, so markup such as `<prosody>` and `<break>` is
+/// dropped rather than interpreted. Tags become spaces so that adjacent words
+/// separated only by markup stay separate. The exception is
+/// `<say-as interpret-as="characters">` (or `"spell-out"`), whose text is
+/// spelled out with the names of digits and symbols in `language`.
+pub fn to_plain_text(ssml: &str, language: Language) -> String {
+    let spelled = SPELL_OUT_REGEX.replace_all(ssml, |captures: &Captures| {
+        let text = decode_entities(&TAG_REGEX.replace_all(&captures[1], " "));
+        spell_out(&text, language)
+    });
+    let without_tags = TAG_REGEX.replace_all(&spelled, " ");
+    let text = without_tags
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    decode_entities(&text)
+}
+
+/// Name of the first `<voice>` element, if the document has one
+pub fn voice_name(ssml: &str) -> Option<String> {
+    VOICE_REGEX
+        .captures(ssml)
+        .map(|captures| captures[1].trim().to_string())
+}
+
+/// Decode the five predefined XML entities; `&amp;` goes last so it cannot create new ones
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_plain_text() {
+        assert_eq!(
+            to_plain_text(
+                "<speak>Hello<break time=\"1s\"/>world. <prosody rate=\"slow\">Fish &amp; chips</prosody></speak>",
+                Language::AmericanEnglish
+            ),
+            "Hello world. Fish & chips"
+        );
+        assert_eq!(
+            to_plain_text("&amp;lt; is escaped", Language::AmericanEnglish),
+            "&lt; is escaped"
+        );
+        assert_eq!(
+            to_plain_text("plain text", Language::AmericanEnglish),
+            "plain text"
+        );
+    }
+
+    #[test]
+    fn test_say_as_characters_is_spelled_out() {
+        assert_eq!(
+            to_plain_text(
+                "<speak>Your code is <say-as interpret-as=\"characters\">X7-b</say-as>.</speak>",
+                Language::AmericanEnglish
+            ),
+            "Your code is X, seven, dash, B."
+        );
+        assert_eq!(
+            to_plain_text(
+                "<say-as interpret-as=\"characters\">a-3</say-as>",
+                Language::Spanish
+            ),
+            "A, tres"
+        );
+        assert_eq!(
+            to_plain_text(
+                "<say-as interpret-as='date'>2024-01-05</say-as>",
+                Language::AmericanEnglish
+            ),
+            "2024-01-05"
+        );
+    }
+
+    #[test]
+    fn test_voice_name() {
+        assert_eq!(
+            voice_name(
+                "<speak><voice xml:lang='en-US' name='en-US-JennyNeural'>Hi</voice></speak>"
+            )
+            .as_deref(),
+            Some("en-US-JennyNeural")
+        );
+        assert_eq!(voice_name("<speak>Hi</speak>"), None);
+    }
+}
@@ -1,16 +1,257 @@
-use crate::{backend::KokoroBackend, error::AppError, validation::DEFAULT_SAMPLE_RATE};
+use crate::{
+    backend::{BackendUnavailable, KokoroBackend},
+    comfort_noise::SilenceFiller,
+    effects::Effects,
+    error::AppError,
+    language::{Language, LANGUAGES},
+    pcm::{pcm_i24_from_f32, write_pcm16, BitDepth},
+    presets::silence_samples,
+    redact,
+    speed_curve::{chunk_speeds, SpeedCurve},
+};
 use axum::body::{Body, Bytes};
+use axum::http::{HeaderMap, HeaderValue};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use bytes::BytesMut;
+use http_body::Frame;
+use http_body_util::StreamBody;
 use regex::Regex;
-use std::collections::BTreeMap;
-use std::sync::{Arc, LazyLock};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tokio::task::JoinSet;
 use tracing::{debug, error, info, warn};
 
 const STREAM_CHANNEL_CAPACITY: usize = 8;
-const BREAK_WORDS: &[&str] = &[
+/// How far into each end of a chunk to look for a zero crossing to cut at
+pub const ZERO_CROSSING_WINDOW_MS: u32 = 5;
+/// Chunk output buffers kept for reuse, shared by every stream
+const MAX_POOLED_BUFFERS: usize = 32;
+/// Most planned chunks an adaptive stream merges into one synthesis call
+const MAX_MERGED_CHUNKS: usize = 8;
+/// Longest text an adaptive stream merges into one synthesis call, in characters
+const MAX_MERGED_CHARS: usize = 300;
+/// Audio buffered ahead of playback, as a multiple of the next chunk's expected synthesis time,
+/// before chunks grow
+const GROW_HEADROOM: f64 = 2.0;
+/// Weight of the newest measurement in the per-chunk synthesis time
+const SYNTHESIS_TIME_SMOOTHING: f64 = 0.5;
+/// Comfort noise sent at a time while playback has run dry and the next chunk is not ready
+const DRY_FILL_MS: u32 = 100;
+/// Trailer carrying `complete` or `error` at the end of a streamed body
+pub const STREAM_STATUS_TRAILER: &str = "x-stream-status";
+/// Trailer carrying the failure message when `x-stream-status` is `error`
+pub const STREAM_ERROR_TRAILER: &str = "x-stream-error";
+/// Words long chunks are split before; a chunk never ends on one
+const DEFAULT_BREAK_WORDS: &[&str] = &[
     "and", "or", "but", "&", "because", "if", "since", "though", "although", "however", "which",
 ];
+/// Punctuation that ends a chunk wherever it falls
+const DEFAULT_TERMINATORS: &str = ".!?:;";
+/// Punctuation that ends a chunk once it is long enough
+const DEFAULT_SOFT_BREAKS: &str = ",";
+
+/// Where the chunker splits text into the pieces synthesized one call at a time
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkRules {
+    /// Punctuation that ends a chunk wherever it falls
+    pub terminators: Vec<char>,
+    /// Punctuation that ends a chunk once it has `min_words` words
+    pub soft_breaks: Vec<char>,
+    /// Lowercase words long chunks are split before; a chunk never ends on one
+    pub break_words: Vec<String>,
+    /// Words a chunk needs before a soft break ends it
+    pub min_words: usize,
+    /// Chunks with at least this many words are split at a soft break or break word
+    pub split_words: usize,
+    /// The script has no spaces between words (Japanese, Chinese): text is split at punctuation
+    /// alone, and `min_words` and `split_words` count characters
+    pub spaceless: bool,
+}
+
+impl Default for ChunkRules {
+    fn default() -> Self {
+        Self {
+            terminators: DEFAULT_TERMINATORS.chars().collect(),
+            soft_breaks: DEFAULT_SOFT_BREAKS.chars().collect(),
+            break_words: DEFAULT_BREAK_WORDS.iter().map(|w| w.to_string()).collect(),
+            min_words: 10,
+            split_words: 12,
+            spaceless: false,
+        }
+    }
+}
+
+impl ChunkRules {
+    /// Rules that let chunks run twice as long before they are split, for draft synthesis
+    pub fn coarser(&self) -> Self {
+        Self {
+            min_words: self.min_words * 2,
+            split_words: self.split_words * 2,
+            ..self.clone()
+        }
+    }
+
+    /// Built-in rules for a language; `None` for English, whose rules come from configuration
+    pub fn for_language(language: Language) -> Option<Self> {
+        let spaced = |terminators: &str, soft_breaks: &str, break_words: &[&str]| Self {
+            terminators: terminators.chars().collect(),
+            soft_breaks: soft_breaks.chars().collect(),
+            break_words: break_words.iter().map(|w| w.to_string()).collect(),
+            ..Self::default()
+        };
+        let spaceless = |terminators: &str, soft_breaks: &str| Self {
+            terminators: terminators.chars().collect(),
+            soft_breaks: soft_breaks.chars().collect(),
+            break_words: Vec::new(),
+            min_words: 20,
+            split_words: 60,
+            spaceless: true,
+        };
+        match language {
+            Language::AmericanEnglish | Language::BritishEnglish => None,
+            Language::Spanish => Some(spaced(
+                ".!?:;",
+                ",",
+                &[
+                    "y", "o", "pero", "porque", "aunque", "sino", "pues", "cuando",
+                ],
+            )),
+            Language::French => Some(spaced(
+                ".!?:;",
+                ",",
+                &[
+                    "et", "ou", "mais", "car", "donc", "puisque", "lorsque", "quand",
+                ],
+            )),
+            Language::Italian => Some(spaced(
+                ".!?:;",
+                ",",
+                &[
+                    "e", "o", "ma", "perché", "però", "quindi", "sebbene", "quando",
+                ],
+            )),
+            Language::Portuguese => Some(spaced(
+                ".!?:;",
+                ",",
+                &[
+                    "e", "ou", "mas", "porque", "porém", "pois", "embora", "quando",
+                ],
+            )),
+            Language::Hindi => Some(spaced(
+                "।॥.!?:;",
+                ",",
+                &["और", "या", "लेकिन", "परंतु", "क्योंकि", "अगर", "जब"],
+            )),
+            Language::Japanese => Some(spaceless("。！？!?．", "、，,")),
+            Language::Chinese => Some(spaceless("。！？!?；;", "，、,")),
+        }
+    }
+
+    fn is_break_word(&self, word: &str) -> bool {
+        self.break_words.contains(&word.to_lowercase())
+    }
+
+    /// Text placed between planned chunks merged into one
+    fn separator(&self) -> &'static str {
+        if self.spaceless {
+            ""
+        } else {
+            " "
+        }
+    }
+}
+
+/// Chunk rules for each language: the configured rules for English, built-in profiles for the
+/// others unless they are turned off
+#[derive(Debug)]
+pub struct ChunkProfiles {
+    configured: Arc<ChunkRules>,
+    languages: HashMap<Language, Arc<ChunkRules>>,
+}
+
+impl ChunkProfiles {
+    /// With `by_language` false every language uses `configured`
+    pub fn new(configured: ChunkRules, by_language: bool) -> Self {
+        let languages = if by_language {
+            LANGUAGES
+                .iter()
+                .filter_map(|&language| {
+                    ChunkRules::for_language(language).map(|rules| (language, Arc::new(rules)))
+                })
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        Self {
+            configured: Arc::new(configured),
+            languages,
+        }
+    }
+
+    pub fn rules(&self, language: Language) -> Arc<ChunkRules> {
+        self.languages
+            .get(&language)
+            .unwrap_or(&self.configured)
+            .clone()
+    }
+}
+
+/// How a stream handles a chunk whose synthesis fails or stalls
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkRetryPolicy {
+    /// Extra attempts after the first failure
+    pub retries: u32,
+    /// Delay before the first retry; doubled for each further retry
+    pub backoff: Duration,
+    /// Drop a chunk that still fails instead of aborting the stream
+    pub skip_failed: bool,
+    /// End the stream with an error when no chunk finishes for this long
+    pub stall_timeout: Option<Duration>,
+}
+
+impl ChunkRetryPolicy {
+    pub fn backoff_for(&self, retry: u32) -> Duration {
+        self.backoff.saturating_mul(1u32 << retry.min(16))
+    }
+}
+
+/// Scheduling options shared by every chunk of a stream
+#[derive(Debug, Clone)]
+pub struct StreamOptions {
+    /// Maximum chunks synthesized concurrently
+    pub parallelism: usize,
+    pub retry_policy: ChunkRetryPolicy,
+    /// Report completion or failure in HTTP trailers; the client must have sent `TE: trailers`
+    pub trailers: bool,
+    /// Silence sent after the last chunk, in samples at the output rate
+    pub trailing_silence: usize,
+    /// Resample the audio to this rate; `None` keeps the model's rate
+    pub sample_rate: Option<u32>,
+    /// Post-processing applied to each chunk
+    pub effects: Effects,
+    /// Sample encoding of the streamed audio
+    pub bit_depth: BitDepth,
+    /// Merge planned chunks into larger ones once the stream is ahead of real time
+    pub adaptive_chunks: bool,
+    /// Where the text is split into chunks
+    pub chunk_rules: Arc<ChunkRules>,
+    /// Language each chunk is phonemized in
+    pub language: Language,
+    /// Speed across the text, replacing the stream's speed for each chunk
+    pub speed_curve: Option<SpeedCurve>,
+    /// Read emphasis markup in each chunk as stress
+    pub emphasis: bool,
+    /// Silence sent between chunks, in milliseconds
+    pub sentence_gap_ms: Option<u32>,
+    /// Fill each chunk's silence with comfort noise
+    pub comfort_noise: bool,
+    /// End the stream with `output_limit_exceeded` once this many seconds have been sent
+    pub max_duration_s: Option<u32>,
+}
 
 /// Create a PCM audio stream
 pub async fn create_pcm_stream(
@@ -20,45 +261,66 @@ pub async fn create_pcm_stream(
     speed: f32,
     initial_silence: Option<usize>,
     request_id: String,
-    parallelism: usize,
+    options: StreamOptions,
 ) -> Result<Body, AppError> {
-    // Chunk the text by sentences/phrases
-    let chunks = chunk_text(&text);
+    let trailers = options.trailers;
+    let rx = spawn_pcm_chunks(
+        backend,
+        text,
+        voice,
+        speed,
+        initial_silence,
+        request_id,
+        options,
+    );
+
+    Ok(body_from_channel(rx, trailers))
+}
+
+/// Synthesize `text` chunk by chunk in the background, delivering PCM at `options.bit_depth` in order.
+///
+/// Each message carries one chunk, in order; a skipped chunk arrives empty. The channel closes
+/// after the last chunk; a failed chunk arrives as an `Err` and ends it.
+pub fn spawn_pcm_chunks(
+    backend: Arc<KokoroBackend>,
+    text: String,
+    voice: String,
+    speed: f32,
+    initial_silence: Option<usize>,
+    request_id: String,
+    options: StreamOptions,
+) -> mpsc::Receiver<Result<Bytes, std::io::Error>> {
+    let chunks = chunk_text(&text, &options.chunk_rules);
+    spawn_synthesis(
+        chunks,
+        StreamSynthesisConfig::new(
+            voice,
+            speed,
+            initial_silence,
+            request_id,
+            options,
+            StreamKind::Pcm,
+        ),
+        backend,
+    )
+}
 
+fn spawn_synthesis(
+    chunks: Vec<String>,
+    config: StreamSynthesisConfig,
+    backend: Arc<KokoroBackend>,
+) -> mpsc::Receiver<Result<Bytes, std::io::Error>> {
     debug!(
-        request_id = %request_id,
+        request_id = %config.request_id,
         num_chunks = chunks.len(),
-        "Creating PCM stream with {} chunks",
+        kind = ?config.stream_kind,
+        "Creating stream with {} chunks",
         chunks.len()
     );
 
-    let (tx, mut rx) = mpsc::channel::<Result<Bytes, std::io::Error>>(STREAM_CHANNEL_CAPACITY);
-
-    tokio::spawn(async move {
-        stream_synthesis_chunks(
-            chunks,
-            StreamSynthesisConfig {
-                voice,
-                speed,
-                initial_silence,
-                request_id,
-                parallelism,
-                stream_kind: StreamKind::Pcm,
-            },
-            backend,
-            tx,
-        )
-        .await;
-    });
-
-    // Create body from receiver stream
-    let stream = async_stream::stream! {
-        while let Some(result) = rx.recv().await {
-            yield result;
-        }
-    };
-
-    Ok(Body::from_stream(stream))
+    let (tx, rx) = mpsc::channel::<Result<Bytes, std::io::Error>>(STREAM_CHANNEL_CAPACITY);
+    tokio::spawn(stream_synthesis_chunks(chunks, config, backend, tx));
+    rx
 }
 
 /// Create a WAV audio stream
@@ -69,54 +331,176 @@ pub async fn create_wav_stream(
     speed: f32,
     initial_silence: Option<usize>,
     request_id: String,
-    parallelism: usize,
+    options: StreamOptions,
 ) -> Result<Body, AppError> {
-    // For WAV streaming, we need to:
-    // 1. Write WAV header first
-    // 2. Stream PCM chunks
-    // 3. Update header with final size (optional for streaming)
+    // The WAV header goes out first with placeholder sizes, then the PCM chunks
+    let trailers = options.trailers;
+    let rx = spawn_synthesis(
+        chunk_text(&text, &options.chunk_rules),
+        StreamSynthesisConfig::new(
+            voice,
+            speed,
+            initial_silence,
+            request_id,
+            options,
+            StreamKind::Wav,
+        ),
+        backend,
+    );
 
-    let chunks = chunk_text(&text);
+    Ok(body_from_channel(rx, trailers))
+}
 
-    debug!(
-        request_id = %request_id,
-        num_chunks = chunks.len(),
-        "Creating WAV stream with {} chunks",
-        chunks.len()
+/// Create a Server-Sent Events stream of base64 PCM with progress metadata.
+///
+/// Each chunk becomes one `speech.audio.delta` event carrying its position in the plan and the
+/// audio streamed so far. The stream ends with `speech.audio.done`, or an `error` event when a
+/// chunk fails.
+pub async fn create_sse_stream(
+    backend: Arc<KokoroBackend>,
+    text: String,
+    voice: String,
+    speed: f32,
+    initial_silence: Option<usize>,
+    request_id: String,
+    mut options: StreamOptions,
+) -> Result<Body, AppError> {
+    // Events are numbered against the plan, so every planned chunk is sent on its own
+    options.adaptive_chunks = false;
+    let chunks = chunk_text(&text, &options.chunk_rules);
+    let total_chunks = chunks.len();
+    let sample_rate = options.sample_rate.unwrap_or_else(|| backend.sample_rate());
+    let bytes_per_sample = options.bit_depth.bytes_per_sample();
+    let mut rx = spawn_synthesis(
+        chunks,
+        StreamSynthesisConfig::new(
+            voice,
+            speed,
+            initial_silence,
+            request_id,
+            options,
+            StreamKind::Pcm,
+        ),
+        backend,
     );
 
-    let (tx, mut rx) = mpsc::channel::<Result<Bytes, std::io::Error>>(STREAM_CHANNEL_CAPACITY);
-
-    tokio::spawn(async move {
-        stream_synthesis_chunks(
-            chunks,
-            StreamSynthesisConfig {
-                voice,
-                speed,
-                initial_silence,
-                request_id,
-                parallelism,
-                stream_kind: StreamKind::Wav,
-            },
-            backend,
-            tx,
-        )
-        .await;
-    });
-
-    // Create body from receiver stream
     let stream = async_stream::stream! {
+        let mut chunk = 0usize;
+        let mut audio_bytes = 0usize;
         while let Some(result) = rx.recv().await {
-            yield result;
+            match result {
+                Ok(pcm) => {
+                    chunk += 1;
+                    audio_bytes += pcm.len();
+                    yield Ok::<_, Infallible>(sse_event(&json!({
+                        "type": "speech.audio.delta",
+                        "audio": BASE64.encode(&pcm),
+                        "chunk": chunk,
+                        "total_chunks": total_chunks,
+                        "audio_seconds": pcm_seconds(audio_bytes, bytes_per_sample, sample_rate),
+                    })));
+                }
+                Err(err) => {
+                    let mut error = json!({ "message": err.to_string() });
+                    if let Some(code) = stream_error_code(&err) {
+                        error["code"] = json!(code);
+                    }
+                    yield Ok(sse_event(&json!({ "type": "error", "error": error })));
+                    return;
+                }
+            }
         }
+        yield Ok(sse_event(&json!({
+            "type": "speech.audio.done",
+            "total_chunks": total_chunks,
+            "audio_seconds": pcm_seconds(audio_bytes, bytes_per_sample, sample_rate),
+        })));
     };
 
     Ok(Body::from_stream(stream))
 }
 
+fn sse_event(data: &serde_json::Value) -> Bytes {
+    Bytes::from(format!("data: {}\n\n", data))
+}
+
+/// Length of mono PCM in seconds
+fn pcm_seconds(bytes: usize, bytes_per_sample: usize, sample_rate: u32) -> f64 {
+    (bytes / bytes_per_sample.max(1)) as f64 / f64::from(sample_rate.max(1))
+}
+
+/// Turn the chunk channel into a response body.
+///
+/// With trailers, the body always ends cleanly and `X-Stream-Status` tells the client whether the
+/// audio is complete. Without them, a failure aborts the body so the chunked encoding is left
+/// unterminated rather than looking like a normal end of stream.
+fn body_from_channel(
+    mut rx: mpsc::Receiver<Result<Bytes, std::io::Error>>,
+    trailers: bool,
+) -> Body {
+    let stream = async_stream::stream! {
+        while let Some(result) = rx.recv().await {
+            match result {
+                Ok(bytes) => yield Ok(Frame::data(bytes)),
+                Err(err) if trailers => {
+                    yield Ok(Frame::trailers(stream_trailers(Some(&err))));
+                    return;
+                }
+                Err(err) => {
+                    yield Err(err);
+                    return;
+                }
+            }
+        }
+        if trailers {
+            yield Ok(Frame::trailers(stream_trailers(None)));
+        }
+    };
+
+    Body::new(StreamBody::new(stream))
+}
+
+/// Error code of a stream failure the client can act on, such as `output_limit_exceeded`
+fn stream_error_code(error: &std::io::Error) -> Option<&'static str> {
+    error
+        .get_ref()?
+        .downcast_ref::<AppError>()
+        .map(AppError::code)
+}
+
+fn stream_trailers(error: Option<&std::io::Error>) -> HeaderMap {
+    let mut trailers = HeaderMap::new();
+    let status = if error.is_some() { "error" } else { "complete" };
+    trailers.insert(STREAM_STATUS_TRAILER, HeaderValue::from_static(status));
+    if let Some(error) = error {
+        let message = match stream_error_code(error) {
+            Some(code) => format!("{} ({})", error, code),
+            None => error.to_string(),
+        };
+        let message: String = message
+            .chars()
+            .map(|c| {
+                if c.is_ascii_graphic() || c == ' ' {
+                    c
+                } else {
+                    '?'
+                }
+            })
+            .collect();
+        if let Ok(value) = HeaderValue::from_str(&message) {
+            trailers.insert(STREAM_ERROR_TRAILER, value);
+        }
+    }
+    trailers
+}
+
 /// Chunk text into sentences/phrases for streaming
-fn chunk_text(text: &str) -> Vec<String> {
-    let mut chunks = split_text_into_speech_chunks(text, 10);
+pub fn chunk_text(text: &str, rules: &ChunkRules) -> Vec<String> {
+    let mut chunks = if rules.spaceless {
+        split_spaceless_chunks(text, rules)
+    } else {
+        split_text_into_speech_chunks(text, rules)
+    };
     if chunks.is_empty() && !text.trim().is_empty() {
         chunks.push(text.trim().to_string());
     }
@@ -132,100 +516,264 @@ enum StreamKind {
 #[derive(Clone)]
 struct StreamSynthesisConfig {
     voice: String,
+    language: Language,
     speed: f32,
+    speed_curve: Option<SpeedCurve>,
+    emphasis: bool,
     initial_silence: Option<usize>,
+    trailing_silence: usize,
+    sentence_gap_ms: Option<u32>,
+    comfort_noise: bool,
+    max_duration_s: Option<u32>,
+    sample_rate: Option<u32>,
+    effects: Effects,
+    bit_depth: BitDepth,
     request_id: String,
     parallelism: usize,
+    retry_policy: ChunkRetryPolicy,
+    adaptive_chunks: bool,
+    /// Joins planned chunks merged into one synthesis call
+    separator: &'static str,
     stream_kind: StreamKind,
 }
 
+/// Silence added around one chunk; the request's silence goes on its first and last chunks
+#[derive(Debug, Clone, Copy)]
+struct ChunkSilence {
+    initial: Option<usize>,
+    /// Samples at the output rate
+    trailing: usize,
+}
+
+impl StreamSynthesisConfig {
+    fn new(
+        voice: String,
+        speed: f32,
+        initial_silence: Option<usize>,
+        request_id: String,
+        options: StreamOptions,
+        stream_kind: StreamKind,
+    ) -> Self {
+        Self {
+            voice,
+            language: options.language,
+            speed,
+            speed_curve: options.speed_curve,
+            emphasis: options.emphasis,
+            initial_silence,
+            trailing_silence: options.trailing_silence,
+            sentence_gap_ms: options.sentence_gap_ms,
+            comfort_noise: options.comfort_noise,
+            max_duration_s: options.max_duration_s,
+            sample_rate: options.sample_rate,
+            effects: options.effects,
+            bit_depth: options.bit_depth,
+            request_id,
+            parallelism: options.parallelism,
+            retry_policy: options.retry_policy,
+            adaptive_chunks: options.adaptive_chunks,
+            separator: options.chunk_rules.separator(),
+            stream_kind,
+        }
+    }
+}
+
 #[derive(Clone)]
 struct ChunkWorkerContext {
     backend: Arc<KokoroBackend>,
     voice: String,
-    speed: f32,
+    language: Language,
+    emphasis: bool,
+    sample_rate: Option<u32>,
     request_id: String,
+    retry_policy: ChunkRetryPolicy,
 }
 
 async fn stream_synthesis_chunks(
-    chunks: Vec<String>,
+    pieces: Vec<String>,
     config: StreamSynthesisConfig,
     backend: Arc<KokoroBackend>,
     tx: mpsc::Sender<Result<Bytes, std::io::Error>>,
 ) {
     let StreamSynthesisConfig {
         voice,
+        language,
         speed,
+        speed_curve,
+        emphasis,
         initial_silence,
+        trailing_silence,
+        sentence_gap_ms,
+        comfort_noise,
+        max_duration_s,
+        sample_rate,
+        effects,
+        bit_depth,
         request_id,
         parallelism,
+        retry_policy,
+        adaptive_chunks,
+        separator,
         stream_kind,
     } = config;
+    // Merged chunks would lose the gaps between them, so a fixed gap keeps the planned chunks
+    let adaptive_chunks = adaptive_chunks && sentence_gap_ms.is_none();
 
-    if chunks.is_empty() {
+    if pieces.is_empty() {
         info!(request_id = %request_id, "No chunks to stream");
         return;
     }
 
+    let output_rate = sample_rate.unwrap_or_else(|| backend.sample_rate());
+    let gap = sentence_gap_ms.map_or(0, |gap| silence_samples(gap, output_rate));
+    let max_audio_bytes = max_duration_s
+        .map(|limit| limit as usize * output_rate as usize * bit_depth.bytes_per_sample());
     if matches!(stream_kind, StreamKind::Wav) {
-        const BITS_PER_SAMPLE: u16 = 16;
         const NUM_CHANNELS: u16 = 1;
-        let header =
-            create_wav_header_placeholder(DEFAULT_SAMPLE_RATE, BITS_PER_SAMPLE, NUM_CHANNELS);
+        let header = create_wav_header_placeholder(output_rate, bit_depth, NUM_CHANNELS);
         if tx.send(Ok(Bytes::from(header))).await.is_err() {
             warn!(request_id = %request_id, "Stream receiver dropped before WAV header");
             return;
         }
     }
 
-    let max_in_flight = parallelism.max(1).min(chunks.len());
+    let max_in_flight = parallelism.max(1).min(pieces.len());
     debug!(
         request_id = %request_id,
-        chunk_count = chunks.len(),
+        chunk_count = pieces.len(),
         max_in_flight = max_in_flight,
+        adaptive_chunks = adaptive_chunks,
         "Starting bounded parallel synthesis"
     );
 
     let mut join_set = JoinSet::new();
+    // Planned chunks consumed so far; an adaptive stream may merge several into one synthesis
+    let mut next_piece = 0usize;
     let mut next_to_spawn = 0usize;
     let mut next_to_emit = 0usize;
-    let mut completed_chunks = 0usize;
-    let mut pending = BTreeMap::<usize, Bytes>::new();
+    let mut pending = BTreeMap::<usize, Vec<f32>>::new();
+    // Text of each chunk still synthesizing, for the watchdog to report
+    let mut in_flight = BTreeMap::<usize, String>::new();
     let mut audio_bytes_sent = 0usize;
+    let mut sizer = ChunkSizer::default();
+    let speeds = chunk_speeds(speed, speed_curve.as_ref(), &pieces);
+    // Chunks finish out of order, so effects and comfort noise run as chunks are emitted, with
+    // one state per stream
+    let mut effects = effects.start(output_rate);
+    let mut filler = comfort_noise.then(|| SilenceFiller::new(output_rate));
     let worker_context = ChunkWorkerContext {
         backend,
         voice,
-        speed,
+        language,
+        emphasis,
+        sample_rate,
         request_id: request_id.clone(),
+        retry_policy,
     };
 
-    while next_to_spawn < chunks.len() && join_set.len() < max_in_flight {
-        spawn_chunk_task(
-            &mut join_set,
-            &worker_context,
-            chunks[next_to_spawn].clone(),
-            next_to_spawn,
-            if next_to_spawn == 0 {
-                initial_silence
+    loop {
+        while next_piece < pieces.len() && join_set.len() < max_in_flight {
+            let group = if adaptive_chunks {
+                sizer.next_group(Instant::now())
             } else {
-                None
-            },
-        );
-        next_to_spawn += 1;
-    }
+                1
+            };
+            let end = merge_end(&pieces, next_piece, group);
+            // Merged chunks are read at the mean of their planned speeds
+            let group_speed =
+                speeds[next_piece..end].iter().sum::<f32>() / (end - next_piece) as f32;
+            let chunk = pieces[next_piece..end].join(separator);
+            in_flight.insert(next_to_spawn, chunk.clone());
+            spawn_chunk_task(
+                &mut join_set,
+                &worker_context,
+                chunk,
+                group_speed,
+                next_to_spawn,
+                end - next_piece,
+                ChunkSilence {
+                    initial: if next_piece == 0 {
+                        initial_silence
+                    } else {
+                        None
+                    },
+                    trailing: if end == pieces.len() {
+                        trailing_silence
+                    } else {
+                        gap
+                    },
+                },
+            );
+            next_piece = end;
+            next_to_spawn += 1;
+        }
 
-    while completed_chunks < chunks.len() {
-        let joined = match join_set.join_next().await {
+        let stall_deadline = retry_policy
+            .stall_timeout
+            .map(|window| tokio::time::Instant::now() + window);
+        let joined = loop {
+            // With comfort noise, playback running dry before the next chunk is ready is filled
+            // rather than left as dead air
+            let dry_at = filler
+                .as_ref()
+                .and_then(|_| sizer.runs_dry(Instant::now()))
+                .map(tokio::time::Instant::from_std);
+            tokio::select! {
+                joined = join_set.join_next() => break joined,
+                () = tokio::time::sleep_until(stall_deadline.unwrap_or_else(tokio::time::Instant::now)),
+                    if stall_deadline.is_some() =>
+                {
+                    let window = retry_policy.stall_timeout.unwrap_or_default();
+                    // The oldest chunk still running is the one holding up the stream
+                    let (chunk_idx, chunk) = in_flight
+                        .first_key_value()
+                        .map(|(idx, chunk)| (*idx, chunk.as_str()))
+                        .unwrap_or_default();
+                    error!(
+                        request_id = %request_id,
+                        chunk_idx = chunk_idx,
+                        chunk_text = %redact::text(chunk),
+                        stall_timeout_secs = window.as_secs(),
+                        "Stream stalled, no chunk finished in time"
+                    );
+                    let _ = tx
+                        .send(Err(std::io::Error::other(AppError::Timeout(format!(
+                            "Stream stalled: no audio chunk finished within {} seconds",
+                            window.as_secs()
+                        )))))
+                        .await;
+                    return;
+                }
+                () = tokio::time::sleep_until(dry_at.unwrap_or_else(tokio::time::Instant::now)),
+                    if dry_at.is_some() =>
+                {
+                    let mut samples = vec![0.0; silence_samples(DRY_FILL_MS, output_rate)];
+                    if let Some(filler) = &mut filler {
+                        filler.fill(&mut samples);
+                    }
+                    sizer.sent(
+                        Duration::from_millis(u64::from(DRY_FILL_MS)),
+                        Instant::now(),
+                    );
+                    if tx.send(Ok(encode_block(&samples, bit_depth))).await.is_err() {
+                        warn!(request_id = %request_id, "Stream receiver dropped, stopping synthesis");
+                        return;
+                    }
+                }
+            }
+        };
+        let joined = match joined {
             Some(joined) => joined,
             None => break,
         };
 
         match joined {
-            Ok((idx, Ok(bytes))) => {
-                completed_chunks += 1;
-                pending.insert(idx, bytes);
+            Ok((idx, timing, Ok(samples))) => {
+                in_flight.remove(&idx);
+                sizer.observe(timing);
+                pending.insert(idx, samples);
             }
-            Ok((idx, Err(err))) => {
+            Ok((idx, _, Err(err))) => {
                 error!(
                     request_id = %request_id,
                     chunk_idx = idx,
@@ -251,70 +799,262 @@ async fn stream_synthesis_chunks(
             }
         }
 
-        while let Some(bytes) = pending.remove(&next_to_emit) {
+        while let Some(mut samples) = pending.remove(&next_to_emit) {
+            effects.process(&mut samples);
+            if let Some(filler) = &mut filler {
+                filler.fill(&mut samples);
+            }
+            let mut bytes = encode_block(&samples, bit_depth);
+            // Audio up to the cap is still sent, so the client hears where it was cut
+            let over_limit = match max_audio_bytes {
+                Some(limit) if audio_bytes_sent + bytes.len() > limit => {
+                    bytes.truncate(limit - audio_bytes_sent);
+                    true
+                }
+                _ => false,
+            };
             audio_bytes_sent += bytes.len();
-            if tx.send(Ok(bytes)).await.is_err() {
+            sizer.sent(
+                Duration::from_secs_f64(pcm_seconds(
+                    bytes.len(),
+                    bit_depth.bytes_per_sample(),
+                    output_rate,
+                )),
+                Instant::now(),
+            );
+            if !bytes.is_empty() && tx.send(Ok(bytes)).await.is_err() {
                 warn!(request_id = %request_id, "Stream receiver dropped, stopping synthesis");
                 return;
             }
+            if let (true, Some(limit)) = (over_limit, max_duration_s) {
+                warn!(
+                    request_id = %request_id,
+                    max_duration_s = limit,
+                    "Stream reached its duration limit, stopping synthesis"
+                );
+                let _ = tx
+                    .send(Err(std::io::Error::other(AppError::OutputLimitExceeded(
+                        limit,
+                    ))))
+                    .await;
+                return;
+            }
             next_to_emit += 1;
         }
-
-        while next_to_spawn < chunks.len() && join_set.len() < max_in_flight {
-            spawn_chunk_task(
-                &mut join_set,
-                &worker_context,
-                chunks[next_to_spawn].clone(),
-                next_to_spawn,
-                if next_to_spawn == 0 {
-                    initial_silence
-                } else {
-                    None
-                },
-            );
-            next_to_spawn += 1;
-        }
     }
 
     info!(
         request_id = %request_id,
+        planned_chunks = pieces.len(),
         streamed_chunks = next_to_emit,
         bytes_sent = audio_bytes_sent,
         "Streaming synthesis complete"
     );
 }
 
+/// Samples encoded for the stream, in a buffer from the shared pool
+fn encode_block(samples: &[f32], bit_depth: BitDepth) -> Bytes {
+    let mut buffer = CHUNK_BUFFERS.take();
+    encode_samples(samples, bit_depth, &mut buffer);
+    let bytes = buffer.split().freeze();
+    CHUNK_BUFFERS.put(buffer);
+    bytes
+}
+
+/// A finished chunk task: its index, timing, and samples at the output rate
+type ChunkOutcome = (usize, ChunkTiming, Result<Vec<f32>, std::io::Error>);
+
+/// How long one synthesis call took and how many planned chunks it covered
+#[derive(Debug, Clone, Copy)]
+struct ChunkTiming {
+    pieces: usize,
+    elapsed: Duration,
+}
+
+/// Decides how many planned chunks an adaptive stream merges into its next synthesis call.
+///
+/// The first chunks go out one at a time so audio starts quickly. Once the audio already sent is
+/// far enough ahead of playback to cover synthesizing a larger chunk, the group doubles, which
+/// cuts per-call overhead and the prosody breaks at chunk edges. It halves again when the lead
+/// shrinks below the expected synthesis time.
+#[derive(Debug)]
+struct ChunkSizer {
+    group: usize,
+    /// Audio sent so far
+    audio_sent: Duration,
+    /// When the first audio was sent; playback is assumed to start then
+    playback_started: Option<Instant>,
+    /// Smoothed synthesis time per planned chunk, in seconds
+    secs_per_piece: Option<f64>,
+}
+
+impl Default for ChunkSizer {
+    fn default() -> Self {
+        Self {
+            group: 1,
+            audio_sent: Duration::ZERO,
+            playback_started: None,
+            secs_per_piece: None,
+        }
+    }
+}
+
+impl ChunkSizer {
+    fn observe(&mut self, timing: ChunkTiming) {
+        let sample = timing.elapsed.as_secs_f64() / timing.pieces.max(1) as f64;
+        self.secs_per_piece = Some(match self.secs_per_piece {
+            Some(previous) => previous + SYNTHESIS_TIME_SMOOTHING * (sample - previous),
+            None => sample,
+        });
+    }
+
+    fn sent(&mut self, audio: Duration, now: Instant) {
+        self.playback_started.get_or_insert(now);
+        self.audio_sent += audio;
+    }
+
+    /// When playback runs out of the audio sent so far; `None` before any has been sent
+    fn runs_dry(&self, now: Instant) -> Option<Instant> {
+        self.playback_started.map(|_| now + self.buffered(now))
+    }
+
+    /// Audio sent but not yet played at `now`
+    fn buffered(&self, now: Instant) -> Duration {
+        self.playback_started.map_or(Duration::ZERO, |started| {
+            self.audio_sent
+                .saturating_sub(now.saturating_duration_since(started))
+        })
+    }
+
+    fn next_group(&mut self, now: Instant) -> usize {
+        let Some(secs_per_piece) = self.secs_per_piece else {
+            return self.group;
+        };
+        let buffered = self.buffered(now).as_secs_f64();
+        let expected = |group: usize| secs_per_piece * group as f64;
+        if self.group < MAX_MERGED_CHUNKS && buffered >= GROW_HEADROOM * expected(self.group * 2) {
+            self.group = (self.group * 2).min(MAX_MERGED_CHUNKS);
+        } else if self.group > 1 && buffered < expected(self.group) {
+            self.group /= 2;
+        }
+        self.group
+    }
+}
+
+/// End of the planned chunks merged from `start`: up to `group` of them, stopping before the text
+/// would exceed `MAX_MERGED_CHARS`. At least one is always taken.
+fn merge_end(pieces: &[String], start: usize, group: usize) -> usize {
+    let mut end = start + 1;
+    let mut chars = pieces[start].chars().count();
+    while end < pieces.len() && end - start < group {
+        chars += 1 + pieces[end].chars().count();
+        if chars > MAX_MERGED_CHARS {
+            break;
+        }
+        end += 1;
+    }
+    end
+}
+
+/// Synthesize one chunk and bring it to the output rate, padded with its trailing silence
 fn spawn_chunk_task(
-    join_set: &mut JoinSet<(usize, Result<Bytes, std::io::Error>)>,
+    join_set: &mut JoinSet<ChunkOutcome>,
     context: &ChunkWorkerContext,
     chunk: String,
+    speed: f32,
     chunk_idx: usize,
-    initial_silence: Option<usize>,
+    pieces: usize,
+    silence: ChunkSilence,
 ) {
     let backend = context.backend.clone();
     let voice = context.voice.clone();
-    let speed = context.speed;
+    let language = context.language;
+    let emphasis = context.emphasis;
+    let sample_rate = context.sample_rate;
     let request_id = context.request_id.clone();
+    let policy = context.retry_policy;
 
     join_set.spawn(async move {
         debug!(
             request_id = %request_id,
             chunk_idx = chunk_idx,
-            chunk_text = %chunk,
+            chunk_text = %redact::text(&chunk),
             "Synthesizing chunk"
         );
 
-        let bytes = backend
-            .synthesize(&chunk, &voice, speed, initial_silence)
-            .await
-            .map(|audio| Bytes::from(samples_to_pcm_bytes(&audio.samples)))
-            .map_err(|e| std::io::Error::other(format!("Synthesis failed: {}", e)));
+        let started = Instant::now();
+        let mut retry = 0;
+        let result = loop {
+            let synthesized = if emphasis {
+                crate::emphasis::synthesize(
+                    &backend,
+                    &chunk,
+                    &voice,
+                    language,
+                    speed,
+                    silence.initial,
+                )
+                .await
+            } else {
+                backend
+                    .synthesize(&chunk, &voice, language, speed, silence.initial)
+                    .await
+            };
+            match synthesized {
+                Ok(audio) => break Ok(audio),
+                Err(e) if retry < policy.retries && !e.is::<BackendUnavailable>() => {
+                    let delay = policy.backoff_for(retry);
+                    retry += 1;
+                    warn!(
+                        request_id = %request_id,
+                        chunk_idx = chunk_idx,
+                        attempt = retry,
+                        retry_in_ms = delay.as_millis() as u64,
+                        error = %redact::message(&e.to_string()),
+                        "Chunk synthesis failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => break Err(e),
+            }
+        };
+        let timing = ChunkTiming {
+            pieces,
+            elapsed: started.elapsed(),
+        };
+
+        let samples = match result {
+            Ok(mut audio) => {
+                let window = silence_samples(ZERO_CROSSING_WINDOW_MS, audio.sample_rate);
+                trim_to_zero_crossings(&mut audio.samples, window);
+                let mut audio = match sample_rate {
+                    Some(rate) => audio.resampled(rate),
+                    None => audio,
+                };
+                audio.pad_end(silence.trailing);
+                Ok(audio.samples)
+            }
+            Err(e) if policy.skip_failed => {
+                warn!(
+                    request_id = %request_id,
+                    chunk_idx = chunk_idx,
+                    chunk_text = %redact::text(&chunk),
+                    error = %redact::message(&e.to_string()),
+                    "Skipping chunk that failed after retries"
+                );
+                Ok(Vec::new())
+            }
+            Err(e) => Err(std::io::Error::other(format!(
+                "Synthesis failed: {}",
+                redact::message(&e.to_string())
+            ))),
+        };
 
-        (chunk_idx, bytes)
+        (chunk_idx, timing, samples)
     });
 }
 
-fn split_text_into_speech_chunks(text: &str, words_per_chunk: usize) -> Vec<String> {
+fn split_text_into_speech_chunks(text: &str, rules: &ChunkRules) -> Vec<String> {
     let mut chunks = Vec::new();
     let mut current_chunk = String::new();
     let mut word_count = 0;
@@ -338,15 +1078,11 @@ fn split_text_into_speech_chunks(text: &str, words_per_chunk: usize) -> Vec<Stri
         current_chunk.push_str(word);
         word_count += 1;
 
-        let ends_with_unconditional = word.ends_with('.')
-            || word.ends_with('!')
-            || word.ends_with('?')
-            || word.ends_with(':')
-            || word.ends_with(';');
-        let ends_with_conditional = word.ends_with(',');
+        let ends_with_unconditional = word.ends_with(rules.terminators.as_slice());
+        let ends_with_conditional = word.ends_with(rules.soft_breaks.as_slice());
 
         if (ends_with_unconditional && !is_numbered_break)
-            || (ends_with_conditional && word_count >= words_per_chunk)
+            || (ends_with_conditional && word_count >= rules.min_words)
         {
             let trimmed = current_chunk.trim().to_string();
             if !trimmed.is_empty() {
@@ -363,9 +1099,8 @@ fn split_text_into_speech_chunks(text: &str, words_per_chunk: usize) -> Vec<Stri
 
     let mut final_chunks = Vec::new();
     for (index, chunk) in chunks.iter().enumerate() {
-        let threshold = 12;
         let use_punctuation = index < 2;
-        let split_chunks = split_long_chunk_with_depth(chunk, threshold, use_punctuation, 0);
+        let split_chunks = split_long_chunk_with_depth(chunk, rules, use_punctuation, 0);
         final_chunks.extend(split_chunks);
     }
 
@@ -373,7 +1108,7 @@ fn split_text_into_speech_chunks(text: &str, words_per_chunk: usize) -> Vec<Stri
         let current = &final_chunks[i];
         let words: Vec<&str> = current.split_whitespace().collect();
         if let Some(last_word) = words.last() {
-            if BREAK_WORDS.contains(&last_word.to_lowercase().as_str()) && words.len() > 1 {
+            if rules.is_break_word(last_word) && words.len() > 1 {
                 let new_current = words[..words.len() - 1].join(" ");
                 let next_chunk = &final_chunks[i + 1];
                 let new_next = format!("{} {}", last_word, next_chunk);
@@ -387,6 +1122,44 @@ fn split_text_into_speech_chunks(text: &str, words_per_chunk: usize) -> Vec<Stri
     final_chunks
 }
 
+/// Closing quotes and brackets kept with the sentence they end
+const CLOSING_MARKS: &[char] = &['」', '』', '）', '】', '〉', '》', ')', '"', '\'', '”', '’'];
+
+/// Split text written without spaces at terminators, at soft breaks once a chunk has
+/// `min_words` characters, and anywhere once it reaches `split_words` characters
+fn split_spaceless_chunks(text: &str, rules: &ChunkRules) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut length = 0;
+    let mut ending = false;
+
+    for ch in text.chars() {
+        if ending && !CLOSING_MARKS.contains(&ch) {
+            push_trimmed(&mut chunks, &mut current);
+            length = 0;
+            ending = false;
+        }
+        current.push(ch);
+        if !ch.is_whitespace() {
+            length += 1;
+        }
+        ending = ending
+            || rules.terminators.contains(&ch)
+            || (rules.soft_breaks.contains(&ch) && length >= rules.min_words)
+            || length >= rules.split_words;
+    }
+    push_trimmed(&mut chunks, &mut current);
+    chunks
+}
+
+fn push_trimmed(chunks: &mut Vec<String>, current: &mut String) {
+    let trimmed = current.trim();
+    if !trimmed.is_empty() {
+        chunks.push(trimmed.to_string());
+    }
+    current.clear();
+}
+
 fn is_numbered_list_item(word: &str) -> bool {
     static NUMBERED_REGEX: LazyLock<Regex> =
         LazyLock::new(|| Regex::new(r"^\(?[0-9]+[.\)\:],?$").expect("valid regex"));
@@ -395,7 +1168,7 @@ fn is_numbered_list_item(word: &str) -> bool {
 
 fn split_long_chunk_with_depth(
     chunk: &str,
-    threshold: usize,
+    rules: &ChunkRules,
     use_punctuation: bool,
     depth: usize,
 ) -> Vec<String> {
@@ -404,27 +1177,27 @@ fn split_long_chunk_with_depth(
     }
 
     let words: Vec<&str> = chunk.split_whitespace().collect();
-    if words.len() < threshold {
+    if words.len() < rules.split_words {
         return vec![chunk.to_string()];
     }
 
     let center = words.len() / 2;
 
     if use_punctuation {
-        if let Some(pos) = find_closest_punctuation(&words, center, &[","]) {
+        if let Some(pos) = find_closest_punctuation(&words, center, &rules.soft_breaks) {
             if pos >= 3 && pos < words.len() {
                 let first_chunk = words[..pos].join(" ");
                 let second_chunk = words[pos..].join(" ");
                 let mut result = Vec::new();
                 result.extend(split_long_chunk_with_depth(
                     &first_chunk,
-                    threshold,
+                    rules,
                     use_punctuation,
                     depth + 1,
                 ));
                 result.extend(split_long_chunk_with_depth(
                     &second_chunk,
-                    threshold,
+                    rules,
                     use_punctuation,
                     depth + 1,
                 ));
@@ -433,20 +1206,20 @@ fn split_long_chunk_with_depth(
         }
     }
 
-    if let Some(pos) = find_closest_break_word(&words, center, BREAK_WORDS) {
+    if let Some(pos) = find_closest_break_word(&words, center, rules) {
         if pos >= 3 && pos < words.len() {
             let first_chunk = words[..pos].join(" ");
             let second_chunk = words[pos..].join(" ");
             let mut result = Vec::new();
             result.extend(split_long_chunk_with_depth(
                 &first_chunk,
-                threshold,
+                rules,
                 use_punctuation,
                 depth + 1,
             ));
             result.extend(split_long_chunk_with_depth(
                 &second_chunk,
-                threshold,
+                rules,
                 use_punctuation,
                 depth + 1,
             ));
@@ -457,12 +1230,12 @@ fn split_long_chunk_with_depth(
     vec![chunk.to_string()]
 }
 
-fn find_closest_punctuation(words: &[&str], center: usize, punctuation: &[&str]) -> Option<usize> {
+fn find_closest_punctuation(words: &[&str], center: usize, punctuation: &[char]) -> Option<usize> {
     let mut closest_pos = None;
     let mut min_distance = usize::MAX;
 
     for (i, word) in words.iter().enumerate() {
-        if punctuation.iter().any(|p| word.ends_with(p)) {
+        if word.ends_with(punctuation) {
             let distance = center.abs_diff(i);
             if distance < min_distance {
                 min_distance = distance;
@@ -474,12 +1247,12 @@ fn find_closest_punctuation(words: &[&str], center: usize, punctuation: &[&str])
     closest_pos
 }
 
-fn find_closest_break_word(words: &[&str], center: usize, break_words: &[&str]) -> Option<usize> {
+fn find_closest_break_word(words: &[&str], center: usize, rules: &ChunkRules) -> Option<usize> {
     let mut closest_pos = None;
     let mut min_distance = usize::MAX;
 
     for (i, word) in words.iter().enumerate() {
-        if break_words.contains(&word.to_lowercase().as_str()) {
+        if rules.is_break_word(word) {
             let distance = center.abs_diff(i);
             if distance < min_distance {
                 min_distance = distance;
@@ -491,33 +1264,104 @@ fn find_closest_break_word(words: &[&str], center: usize, break_words: &[&str])
     closest_pos
 }
 
-/// Convert f32 samples [-1.0, 1.0] to 16-bit PCM bytes
-fn samples_to_pcm_bytes(samples: &[f32]) -> Vec<u8> {
-    let mut bytes = Vec::with_capacity(samples.len() * 2);
+/// Trim `samples` to begin and end at a zero crossing, so consecutive chunks join
+/// without a DC step that clicks.
+///
+/// Only the first and last `window` samples are searched; an edge without a
+/// crossing in reach is left as it is.
+pub fn trim_to_zero_crossings(samples: &mut Vec<f32>, window: usize) {
+    let window = window.min(samples.len() / 2);
+    let crosses = |a: f32, b: f32| a == 0.0 || b == 0.0 || (a < 0.0) != (b < 0.0);
+
+    // Of the two samples around a crossing, cut at the one nearer zero
+    let start = (0..window)
+        .find(|&i| crosses(samples[i], samples[i + 1]))
+        .map_or(0, |i| {
+            if samples[i].abs() <= samples[i + 1].abs() {
+                i
+            } else {
+                i + 1
+            }
+        });
+    let end = (samples.len() - window..samples.len())
+        .rev()
+        .find(|&i| crosses(samples[i - 1], samples[i]))
+        .map_or(samples.len(), |i| {
+            if samples[i].abs() <= samples[i - 1].abs() {
+                i + 1
+            } else {
+                i
+            }
+        });
+
+    samples.truncate(end);
+    samples.drain(..start);
+}
+
+static CHUNK_BUFFERS: LazyLock<BufferPool> = LazyLock::new(|| BufferPool::new(MAX_POOLED_BUFFERS));
 
-    for &sample in samples {
-        let int_sample = pcm_i16_from_f32(sample);
-        bytes.extend_from_slice(&int_sample.to_le_bytes());
+/// Recycled chunk buffers, so steady streaming stops allocating once warmed up.
+///
+/// A chunk is frozen out of a pooled buffer with `split().freeze()` and the emptied buffer goes
+/// straight back. The next `reserve` reclaims the allocation in place once the body has sent
+/// and dropped that chunk, and only allocates while it is still in flight.
+struct BufferPool {
+    buffers: Mutex<Vec<BytesMut>>,
+    limit: usize,
+}
+
+impl BufferPool {
+    fn new(limit: usize) -> Self {
+        Self {
+            buffers: Mutex::new(Vec::with_capacity(limit)),
+            limit,
+        }
     }
 
-    bytes
+    fn take(&self) -> BytesMut {
+        self.buffers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop()
+            .unwrap_or_default()
+    }
+
+    fn put(&self, buffer: BytesMut) {
+        let mut buffers = self.buffers.lock().unwrap_or_else(PoisonError::into_inner);
+        if buffers.len() < self.limit {
+            buffers.push(buffer);
+        }
+    }
 }
 
-fn pcm_i16_from_f32(sample: f32) -> i16 {
-    let clamped = sample.clamp(-1.0, 1.0);
-    if clamped <= -1.0 {
-        i16::MIN
-    } else {
-        (clamped * i16::MAX as f32).round() as i16
+/// Write f32 samples [-1.0, 1.0] into `out` as little-endian PCM at `bit_depth`
+fn encode_samples(samples: &[f32], bit_depth: BitDepth, out: &mut BytesMut) {
+    out.clear();
+    out.resize(samples.len() * bit_depth.bytes_per_sample(), 0);
+    match bit_depth {
+        BitDepth::Int16 => write_pcm16(samples, out),
+        BitDepth::Int24 => {
+            for (bytes, &sample) in out.chunks_exact_mut(3).zip(samples) {
+                bytes.copy_from_slice(&pcm_i24_from_f32(sample).to_le_bytes()[..3]);
+            }
+        }
+        BitDepth::Float32 => {
+            for (bytes, &sample) in out.chunks_exact_mut(4).zip(samples) {
+                bytes.copy_from_slice(&sample.clamp(-1.0, 1.0).to_le_bytes());
+            }
+        }
     }
 }
 
 /// Create WAV header placeholder for streaming
 fn create_wav_header_placeholder(
     sample_rate: u32,
-    bits_per_sample: u16,
+    bit_depth: BitDepth,
     num_channels: u16,
 ) -> Vec<u8> {
+    let bits_per_sample = bit_depth.bits();
+    // WAVE_FORMAT_IEEE_FLOAT for float samples, WAVE_FORMAT_PCM otherwise
+    let audio_format: u16 = if bit_depth.is_float() { 3 } else { 1 };
     let byte_rate = sample_rate * num_channels as u32 * (bits_per_sample / 8) as u32;
     let block_align = num_channels * (bits_per_sample / 8);
 
@@ -531,7 +1375,7 @@ fn create_wav_header_placeholder(
     // fmt sub-chunk
     header.extend_from_slice(b"fmt ");
     header.extend_from_slice(&16u32.to_le_bytes()); // Subchunk1Size (16 for PCM)
-    header.extend_from_slice(&1u16.to_le_bytes()); // AudioFormat (1 for PCM)
+    header.extend_from_slice(&audio_format.to_le_bytes());
     header.extend_from_slice(&num_channels.to_le_bytes());
     header.extend_from_slice(&sample_rate.to_le_bytes());
     header.extend_from_slice(&byte_rate.to_le_bytes());
@@ -549,10 +1393,138 @@ fn create_wav_header_placeholder(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_stream_trailers() {
+        let complete = stream_trailers(None);
+        assert_eq!(complete[STREAM_STATUS_TRAILER], "complete");
+        assert!(complete.get(STREAM_ERROR_TRAILER).is_none());
+
+        let failed = stream_trailers(Some(&std::io::Error::other("Synthesis failed:\nboom")));
+        assert_eq!(failed[STREAM_STATUS_TRAILER], "error");
+        assert_eq!(failed[STREAM_ERROR_TRAILER], "Synthesis failed:?boom");
+
+        let capped = stream_trailers(Some(&std::io::Error::other(AppError::OutputLimitExceeded(
+            30,
+        ))));
+        assert_eq!(
+            capped[STREAM_ERROR_TRAILER],
+            "Audio exceeded the 30 second duration limit (output_limit_exceeded)"
+        );
+    }
+
+    #[cfg(feature = "mock-backend")]
+    fn mock_options() -> StreamOptions {
+        StreamOptions {
+            parallelism: 1,
+            retry_policy: ChunkRetryPolicy {
+                retries: 0,
+                backoff: Duration::ZERO,
+                skip_failed: false,
+                stall_timeout: None,
+            },
+            trailers: false,
+            trailing_silence: 0,
+            sample_rate: None,
+            effects: Effects::default(),
+            bit_depth: BitDepth::Int16,
+            adaptive_chunks: false,
+            chunk_rules: Arc::new(ChunkRules::default()),
+            language: Language::AmericanEnglish,
+            speed_curve: None,
+            emphasis: false,
+            sentence_gap_ms: None,
+            comfort_noise: false,
+            max_duration_s: None,
+        }
+    }
+
+    /// Audio bytes received before the stream closed, and the error that ended it
+    #[cfg(feature = "mock-backend")]
+    async fn drain(
+        mut rx: mpsc::Receiver<Result<Bytes, std::io::Error>>,
+    ) -> (usize, Option<std::io::Error>) {
+        let mut audio = 0;
+        while let Some(message) = rx.recv().await {
+            match message {
+                Ok(bytes) => audio += bytes.len(),
+                Err(e) => return (audio, Some(e)),
+            }
+        }
+        (audio, None)
+    }
+
+    #[cfg(feature = "mock-backend")]
+    #[tokio::test]
+    async fn test_stream_stops_at_duration_cap() {
+        let backend = crate::api::AppState::for_tests().await.backend;
+        let sample_rate = backend.sample_rate() as usize;
+        let rx = spawn_pcm_chunks(
+            backend,
+            "One more sentence. ".repeat(10),
+            "af_alloy".to_string(),
+            1.0,
+            None,
+            "test".to_string(),
+            StreamOptions {
+                max_duration_s: Some(1),
+                ..mock_options()
+            },
+        );
+
+        let (audio, error) = drain(rx).await;
+        assert_eq!(audio, sample_rate * 2);
+        assert_eq!(
+            stream_error_code(&error.unwrap()),
+            Some("output_limit_exceeded")
+        );
+    }
+
+    #[cfg(feature = "mock-backend")]
+    #[tokio::test]
+    async fn test_stalled_chunk_ends_stream() {
+        let backend = crate::api::AppState::for_tests().await.backend;
+        let text = format!(
+            "This first sentence is read as usual. This one will {} in the engine. The last \
+             sentence is never reached.",
+            crate::mock::HANG_MARKER
+        );
+        let mut options = mock_options();
+        options.retry_policy.stall_timeout = Some(Duration::from_millis(200));
+        let rx = spawn_pcm_chunks(
+            backend,
+            text,
+            "af_alloy".to_string(),
+            1.0,
+            None,
+            "test".to_string(),
+            options,
+        );
+
+        let (audio, error) = drain(rx).await;
+        assert!(audio > 0);
+        assert_eq!(
+            stream_error_code(&error.unwrap()),
+            Some("synthesis_timeout")
+        );
+    }
+
+    #[test]
+    fn test_chunk_retry_backoff_doubles() {
+        let policy = ChunkRetryPolicy {
+            retries: 3,
+            backoff: Duration::from_millis(250),
+            skip_failed: false,
+            stall_timeout: None,
+        };
+        assert_eq!(policy.backoff_for(0), Duration::from_millis(250));
+        assert_eq!(policy.backoff_for(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff_for(2), Duration::from_millis(1000));
+    }
+
     #[test]
     fn test_chunk_text() {
         let text = "Hello world! This is a test. How are you?";
-        let chunks = chunk_text(text);
+        let chunks = chunk_text(text, &ChunkRules::default());
         assert_eq!(chunks.len(), 3);
         assert_eq!(chunks[0], "Hello world!");
         assert_eq!(chunks[1], "This is a test.");
@@ -562,7 +1534,7 @@ mod tests {
     #[test]
     fn test_chunk_text_no_delimiters() {
         let text = "Hello world this is a test";
-        let chunks = chunk_text(text);
+        let chunks = chunk_text(text, &ChunkRules::default());
         assert_eq!(chunks.len(), 1);
         assert_eq!(chunks[0], "Hello world this is a test");
     }
@@ -570,33 +1542,151 @@ mod tests {
     #[test]
     fn test_chunk_text_numbered_list() {
         let text = "1. First item 2. Second item";
-        let chunks = chunk_text(text);
+        let chunks = chunk_text(text, &ChunkRules::default());
         assert_eq!(chunks.len(), 2);
         assert_eq!(chunks[0], "1. First item");
         assert_eq!(chunks[1], "2. Second item");
     }
 
     #[test]
-    fn test_samples_to_pcm_bytes() {
-        let samples = vec![0.0, 0.5, -0.5, 1.0, -1.0];
-        let bytes = samples_to_pcm_bytes(&samples);
-        assert_eq!(bytes.len(), 10); // 5 samples * 2 bytes
+    fn test_chunk_text_custom_rules() {
+        let rules = ChunkRules {
+            terminators: vec!['。'],
+            soft_breaks: vec!['،'],
+            break_words: vec!["y".to_string()],
+            min_words: 2,
+            split_words: 6,
+            spaceless: false,
+        };
+        assert_eq!(
+            chunk_text(
+                "Hola amigos، qué tal。 Uno dos tres cuatro y cinco seis",
+                &rules
+            ),
+            [
+                "Hola amigos،",
+                "qué tal。",
+                "Uno dos tres cuatro",
+                "y cinco seis"
+            ]
+        );
+        // The default terminators no longer split
+        assert_eq!(chunk_text("One. Two.", &rules), ["One. Two."]);
+    }
+
+    #[test]
+    fn test_chunk_text_spaceless() {
+        let rules = ChunkRules::for_language(Language::Japanese).unwrap();
+        assert_eq!(
+            chunk_text("こんにちは。「元気ですか？」はい、元気です。", &rules),
+            ["こんにちは。", "「元気ですか？」", "はい、元気です。"]
+        );
+
+        let rules = ChunkRules::for_language(Language::Chinese).unwrap();
+        let long = "我".repeat(130);
+        let chunks = chunk_text(&long, &rules);
+        assert_eq!(
+            chunks.iter().map(|c| c.chars().count()).collect::<Vec<_>>(),
+            [60, 60, 10]
+        );
+    }
+
+    #[test]
+    fn test_chunk_profiles() {
+        let profiles = ChunkProfiles::new(ChunkRules::default(), true);
+        assert_eq!(
+            *profiles.rules(Language::BritishEnglish),
+            ChunkRules::default()
+        );
+        assert!(profiles.rules(Language::Chinese).spaceless);
+        assert_eq!(
+            chunk_text(
+                "Tengo hambre y sed y también sueño porque no dormí nada anoche",
+                &profiles.rules(Language::Spanish)
+            ),
+            [
+                "Tengo hambre y sed y también sueño",
+                "porque no dormí nada anoche"
+            ]
+        );
+
+        let flat = ChunkProfiles::new(ChunkRules::default(), false);
+        assert!(!flat.rules(Language::Chinese).spaceless);
+    }
+
+    #[test]
+    fn test_chunk_sizer_grows_when_ahead() {
+        let start = Instant::now();
+        let mut sizer = ChunkSizer::default();
+        assert_eq!(sizer.next_group(start), 1);
+
+        sizer.observe(ChunkTiming {
+            pieces: 1,
+            elapsed: Duration::from_millis(500),
+        });
+        sizer.sent(Duration::from_secs(10), start);
+        assert_eq!(sizer.next_group(start), 2);
+        assert_eq!(sizer.next_group(start), 4);
+        assert_eq!(sizer.next_group(start), 8);
+        assert_eq!(sizer.next_group(start), MAX_MERGED_CHUNKS);
+
+        // Playback caught up: 0.5 s buffered cannot cover 4 s of synthesis
+        let later = start + Duration::from_millis(9_500);
+        assert_eq!(sizer.buffered(later), Duration::from_millis(500));
+        assert_eq!(sizer.next_group(later), 4);
+    }
 
-        // Check that 0.0 maps to 0
-        assert_eq!(bytes[0..2], [0, 0]);
+    #[test]
+    fn test_merge_end() {
+        let pieces = ["One.", "Two.", "Three."].map(String::from);
+        assert_eq!(merge_end(&pieces, 0, 1), 1);
+        assert_eq!(merge_end(&pieces, 0, 2), 2);
+        assert_eq!(merge_end(&pieces, 1, 8), 3);
+
+        let long = ["a".repeat(200), "b".repeat(200)];
+        assert_eq!(merge_end(&long, 0, 2), 1);
+    }
 
-        // Check that 1.0 maps to i16::MAX
-        let max_val = i16::MAX.to_le_bytes();
-        assert_eq!(bytes[6..8], max_val);
+    #[test]
+    fn test_sse_event() {
+        let event = sse_event(&json!({ "type": "speech.audio.done", "total_chunks": 2 }));
+        assert_eq!(
+            &event[..],
+            b"data: {\"total_chunks\":2,\"type\":\"speech.audio.done\"}\n\n"
+        );
+        assert_eq!(pcm_seconds(48_000, 2, 24_000), 1.0);
+        assert_eq!(pcm_seconds(72_000, 3, 24_000), 1.0);
+    }
 
-        // Check that -1.0 maps to i16::MIN
-        let min_val = i16::MIN.to_le_bytes();
-        assert_eq!(bytes[8..10], min_val);
+    #[test]
+    fn test_trim_to_zero_crossings() {
+        // Starts and ends mid-cycle, well away from zero
+        let mut samples: Vec<f32> = (0..100)
+            .map(|i| (std::f32::consts::TAU * (i as f32 + 5.0) / 40.0).sin())
+            .collect();
+        trim_to_zero_crossings(&mut samples, 20);
+        assert!(samples.len() < 100 && samples.len() > 60);
+        assert!(samples[0].abs() < 0.1);
+        assert!(samples[samples.len() - 1].abs() < 0.1);
+
+        // Silence at the edges is already a crossing
+        let mut padded = vec![0.0, 0.0, 0.5, 0.6, 0.5, 0.0];
+        trim_to_zero_crossings(&mut padded, 3);
+        assert_eq!(padded, vec![0.0, 0.0, 0.5, 0.6, 0.5, 0.0]);
+
+        // No crossing within reach leaves the edge alone
+        let mut offset = vec![0.5f32; 10];
+        trim_to_zero_crossings(&mut offset, 4);
+        assert_eq!(offset.len(), 10);
+
+        let mut empty: Vec<f32> = Vec::new();
+        trim_to_zero_crossings(&mut empty, 4);
+        assert!(empty.is_empty());
     }
 
     #[test]
     fn test_wav_header() {
-        let header = create_wav_header_placeholder(24000, 16, 1);
+        let header = create_wav_header_placeholder(24000, BitDepth::Int16, 1);
         assert_eq!(header.len(), 44);
 
         // Check RIFF header
@@ -605,8 +1695,68 @@ mod tests {
 
         // Check fmt chunk
         assert_eq!(&header[12..16], b"fmt ");
+        assert_eq!(header[20..22], 1u16.to_le_bytes());
 
         // Check data chunk
         assert_eq!(&header[36..40], b"data");
+
+        // Float samples use the IEEE float format tag and 4-byte blocks
+        let header = create_wav_header_placeholder(24000, BitDepth::Float32, 1);
+        assert_eq!(header[20..22], 3u16.to_le_bytes());
+        assert_eq!(header[28..32], 96_000u32.to_le_bytes());
+        assert_eq!(header[32..34], 4u16.to_le_bytes());
+        assert_eq!(header[34..36], 32u16.to_le_bytes());
+    }
+
+    #[test]
+    fn test_encode_samples() {
+        let encode = |samples: &[f32], bit_depth| {
+            let mut out = BytesMut::new();
+            encode_samples(samples, bit_depth, &mut out);
+            out.to_vec()
+        };
+        let samples = [0.0, 1.0, -1.0];
+        assert_eq!(
+            encode(&samples, BitDepth::Int16),
+            crate::pcm::samples_to_pcm16(&samples)
+        );
+        assert_eq!(
+            encode(&samples, BitDepth::Int24),
+            [0, 0, 0, 0xff, 0xff, 0x7f, 0x00, 0x00, 0x80]
+        );
+        let float = encode(&[0.5, 2.0], BitDepth::Float32);
+        assert_eq!(float, [0.5f32.to_le_bytes(), 1.0f32.to_le_bytes()].concat());
+    }
+
+    #[test]
+    fn test_buffer_pool_reuses_sent_chunks() {
+        let pool = BufferPool::new(1);
+        let samples = vec![0.25f32; 4_800];
+
+        let mut buffer = pool.take();
+        encode_samples(&samples, BitDepth::Int16, &mut buffer);
+        let first = buffer.split().freeze();
+        let address = first.as_ptr();
+        pool.put(buffer);
+
+        // Once the chunk has been sent and dropped, the next one lands in the same allocation
+        drop(first);
+        let mut buffer = pool.take();
+        encode_samples(&samples, BitDepth::Int16, &mut buffer);
+        let second = buffer.split().freeze();
+        assert_eq!(second.as_ptr(), address);
+        assert_eq!(second.len(), 9_600);
+
+        // A chunk still in flight is never overwritten
+        pool.put(buffer);
+        let mut buffer = pool.take();
+        encode_samples(&samples[..10], BitDepth::Int16, &mut buffer);
+        assert_ne!(buffer.as_ptr(), address);
+        assert_eq!(second[..2], crate::pcm::samples_to_pcm16(&samples[..1])[..]);
+
+        // The pool keeps at most `limit` buffers
+        pool.put(BytesMut::new());
+        pool.put(BytesMut::new());
+        assert_eq!(pool.buffers.lock().unwrap().len(), 1);
     }
 }
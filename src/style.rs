@@ -0,0 +1,85 @@
+use crate::effects::{Compressor, Filter};
+
+/// Delivery styles selectable in a request's `style` field
+pub const STYLE_PRESETS: [&str; 4] = ["newscaster", "calm", "excited", "whisper"];
+
+/// Named delivery approximated with post-processing until the model can be conditioned on
+/// style: a speed change, a pitch shift, EQ, and compression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Brisk, slightly lower, and bright, with speech compression
+    Newscaster,
+    /// Unhurried and lower, lightly compressed
+    Calm,
+    /// Quicker and higher, forward and compressed
+    Excited,
+    /// Slower and thinned out, without the low end of a full voice
+    Whisper,
+}
+
+impl Style {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "newscaster" => Some(Self::Newscaster),
+            "calm" => Some(Self::Calm),
+            "excited" => Some(Self::Excited),
+            "whisper" => Some(Self::Whisper),
+            _ => None,
+        }
+    }
+
+    /// Multiplier applied to the request's speed
+    pub fn speed_factor(self) -> f32 {
+        match self {
+            Self::Newscaster => 1.08,
+            Self::Calm => 0.9,
+            Self::Excited => 1.15,
+            Self::Whisper => 0.9,
+        }
+    }
+
+    /// Pitch shift in semitones, `None` to leave the pitch alone
+    pub fn pitch_semitones(self) -> Option<f32> {
+        match self {
+            Self::Newscaster => Some(-1.0),
+            Self::Calm => Some(-1.5),
+            Self::Excited => Some(1.5),
+            Self::Whisper => None,
+        }
+    }
+
+    /// EQ used when the request picks no `effects` of its own
+    pub fn filters(self) -> Vec<Filter> {
+        match self {
+            Self::Newscaster => vec![Filter::HighPass, Filter::Presence],
+            Self::Calm => vec![Filter::HighPass],
+            Self::Excited => vec![Filter::Presence],
+            Self::Whisper => vec![Filter::HighPass, Filter::Presence],
+        }
+    }
+
+    /// Compressor preset used when the request picks no `compressor` of its own
+    pub fn compressor(self) -> Option<(String, Compressor)> {
+        let name = match self {
+            Self::Newscaster | Self::Excited => "speech",
+            Self::Calm | Self::Whisper => "gentle",
+        };
+        Some((name.to_string(), Compressor::preset(name)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_style_presets() {
+        for name in STYLE_PRESETS {
+            let style = Style::from_name(name).unwrap();
+            assert!((0.5..=1.5).contains(&style.speed_factor()));
+            assert!(style.compressor().is_some());
+        }
+        assert_eq!(Style::from_name("CALM"), Some(Style::Calm));
+        assert!(Style::from_name("angry").is_none());
+    }
+}
@@ -0,0 +1,338 @@
+use anyhow::{Context, Result};
+use ogg::{
+    reading::PacketReader,
+    writing::{PacketWriteEndInfo, PacketWriter},
+};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use utoipa::ToSchema;
+
+/// Descriptive tags written into encoded audio so downloaded files are self-describing
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct AudioMetadata {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub album: Option<String>,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+/// Where each field is stored: ID3v2 frame, Vorbis comment field, and RIFF INFO chunk
+struct TagIds {
+    id3: &'static [u8; 4],
+    vorbis: &'static str,
+    riff: &'static [u8; 4],
+}
+
+const TITLE: TagIds = TagIds {
+    id3: b"TIT2",
+    vorbis: "TITLE",
+    riff: b"INAM",
+};
+const ARTIST: TagIds = TagIds {
+    id3: b"TPE1",
+    vorbis: "ARTIST",
+    riff: b"IART",
+};
+const ALBUM: TagIds = TagIds {
+    id3: b"TALB",
+    vorbis: "ALBUM",
+    riff: b"IPRD",
+};
+const COMMENT: TagIds = TagIds {
+    id3: b"COMM",
+    vorbis: "COMMENT",
+    riff: b"ICMT",
+};
+
+impl AudioMetadata {
+    /// No field has a value
+    pub fn is_empty(&self) -> bool {
+        self.values().next().is_none()
+    }
+
+    /// Fields that have a value, as written to the file
+    pub fn values(&self) -> impl Iterator<Item = &str> {
+        self.fields().map(|(_, value)| value)
+    }
+
+    fn fields(&self) -> impl Iterator<Item = (&'static TagIds, &str)> {
+        [
+            (&TITLE, &self.title),
+            (&ARTIST, &self.artist),
+            (&ALBUM, &self.album),
+            (&COMMENT, &self.comment),
+        ]
+        .into_iter()
+        .filter_map(|(ids, value)| {
+            value
+                .as_deref()
+                .filter(|value| !value.is_empty())
+                .map(|value| (ids, value))
+        })
+    }
+}
+
+/// Whether output in `format` has a container that can carry metadata
+pub fn supports_metadata(format: &str) -> bool {
+    matches!(format, "mp3" | "wav" | "opus" | "discord")
+}
+
+/// Write `metadata` into audio already encoded as `format`
+pub fn tag_audio(format: &str, audio: &[u8], metadata: &AudioMetadata) -> Result<Vec<u8>> {
+    match format {
+        "mp3" => Ok([id3v2(metadata), audio.to_vec()].concat()),
+        "wav" => tag_wav(audio, metadata),
+        "opus" | "discord" => tag_ogg_opus(audio, metadata),
+        _ => anyhow::bail!("'{}' output cannot carry metadata", format),
+    }
+}
+
+/// ID3v2.4 tag with UTF-8 text frames, placed before the first MPEG frame
+fn id3v2(metadata: &AudioMetadata) -> Vec<u8> {
+    const UTF8: u8 = 3;
+
+    let mut frames = Vec::new();
+    for (ids, value) in metadata.fields() {
+        let mut body = vec![UTF8];
+        if ids.id3 == COMMENT.id3 {
+            // Language, then an empty short description
+            body.extend_from_slice(b"eng\0");
+        }
+        body.extend_from_slice(value.as_bytes());
+        frames.extend_from_slice(ids.id3);
+        frames.extend_from_slice(&synchsafe(body.len()));
+        frames.extend_from_slice(&[0, 0]);
+        frames.extend_from_slice(&body);
+    }
+
+    let mut tag = b"ID3\x04\x00\x00".to_vec();
+    tag.extend_from_slice(&synchsafe(frames.len()));
+    tag.extend_from_slice(&frames);
+    tag
+}
+
+/// ID3v2 size: 28 bits spread over four bytes with the high bit of each clear
+fn synchsafe(size: usize) -> [u8; 4] {
+    [21, 14, 7, 0].map(|shift| ((size >> shift) & 0x7f) as u8)
+}
+
+/// Insert a LIST/INFO chunk before the `data` chunk of a RIFF WAVE file
+fn tag_wav(wav: &[u8], metadata: &AudioMetadata) -> Result<Vec<u8>> {
+    if wav.len() < 12 || &wav[..4] != b"RIFF" || &wav[8..12] != b"WAVE" {
+        anyhow::bail!("not a RIFF WAVE file");
+    }
+    let mut offset = 12;
+    while &wav
+        .get(offset..offset + 4)
+        .context("WAV file has no data chunk")?
+        != b"data"
+    {
+        let size = u32::from_le_bytes(
+            wav.get(offset + 4..offset + 8)
+                .context("truncated WAV chunk header")?
+                .try_into()?,
+        ) as usize;
+        // Chunks are padded to an even length
+        offset += 8 + size + (size & 1);
+    }
+
+    let mut info = b"INFO".to_vec();
+    for (ids, value) in metadata.fields() {
+        let size = value.len() + 1;
+        info.extend_from_slice(ids.riff);
+        info.extend_from_slice(&(size as u32).to_le_bytes());
+        info.extend_from_slice(value.as_bytes());
+        info.push(0);
+        if size % 2 == 1 {
+            info.push(0);
+        }
+    }
+
+    let mut tagged = Vec::with_capacity(wav.len() + 8 + info.len());
+    tagged.extend_from_slice(&wav[..offset]);
+    tagged.extend_from_slice(b"LIST");
+    tagged.extend_from_slice(&(info.len() as u32).to_le_bytes());
+    tagged.extend_from_slice(&info);
+    tagged.extend_from_slice(&wav[offset..]);
+    let riff_size = (tagged.len() - 8) as u32;
+    tagged[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Ok(tagged)
+}
+
+/// Rewrite an Ogg Opus stream with `metadata` added to its OpusTags comment header, keeping
+/// every other packet on the page it was on
+fn tag_ogg_opus(ogg: &[u8], metadata: &AudioMetadata) -> Result<Vec<u8>> {
+    let mut reader = PacketReader::new(Cursor::new(ogg));
+    let mut writer = PacketWriter::new(Vec::with_capacity(ogg.len() + 256));
+    let mut tagged = false;
+    while let Some(packet) = reader.read_packet().context("invalid Ogg stream")? {
+        let end_info = if packet.last_in_stream() {
+            PacketWriteEndInfo::EndStream
+        } else if packet.last_in_page() {
+            PacketWriteEndInfo::EndPage
+        } else {
+            PacketWriteEndInfo::NormalPacket
+        };
+        let (serial, granule) = (packet.stream_serial(), packet.absgp_page());
+        let data = if !tagged && packet.data.starts_with(b"OpusTags") {
+            tagged = true;
+            opus_tags_with(&packet.data, metadata)?
+        } else {
+            packet.data
+        };
+        writer.write_packet(data.into_boxed_slice(), serial, end_info, granule)?;
+    }
+    if !tagged {
+        anyhow::bail!("Ogg stream has no OpusTags header");
+    }
+    Ok(writer.into_inner())
+}
+
+/// OpusTags header (RFC 7845 section 5.2) with `metadata` appended to its user comments
+fn opus_tags_with(tags: &[u8], metadata: &AudioMetadata) -> Result<Vec<u8>> {
+    let read_u32 = |offset: usize| -> Result<usize> {
+        let bytes = tags
+            .get(offset..offset + 4)
+            .context("truncated OpusTags header")?;
+        Ok(u32::from_le_bytes(bytes.try_into()?) as usize)
+    };
+    let vendor_end = 12 + read_u32(8)?;
+    let count = read_u32(vendor_end)?;
+    let mut comments_end = vendor_end + 4;
+    for _ in 0..count {
+        comments_end += 4 + read_u32(comments_end)?;
+    }
+    if comments_end > tags.len() {
+        anyhow::bail!("truncated OpusTags header");
+    }
+
+    let added: Vec<String> = metadata
+        .fields()
+        .map(|(ids, value)| format!("{}={}", ids.vorbis, value))
+        .collect();
+    let mut out = tags[..vendor_end].to_vec();
+    out.extend_from_slice(&((count + added.len()) as u32).to_le_bytes());
+    out.extend_from_slice(&tags[vendor_end + 4..comments_end]);
+    for comment in &added {
+        out.extend_from_slice(&(comment.len() as u32).to_le_bytes());
+        out.extend_from_slice(comment.as_bytes());
+    }
+    // Anything after the comments is binary data other tools may have stored
+    out.extend_from_slice(&tags[comments_end..]);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> AudioMetadata {
+        AudioMetadata {
+            title: Some("Morning News".to_string()),
+            artist: Some("Kokoro".to_string()),
+            album: None,
+            comment: Some("Read aloud".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_metadata_fields() {
+        assert!(AudioMetadata::default().is_empty());
+        let empty_title = AudioMetadata {
+            title: Some(String::new()),
+            ..AudioMetadata::default()
+        };
+        assert!(empty_title.is_empty());
+        assert_eq!(
+            metadata().values().collect::<Vec<_>>(),
+            ["Morning News", "Kokoro", "Read aloud"]
+        );
+        assert!(supports_metadata("mp3"));
+        assert!(!supports_metadata("pcm"));
+    }
+
+    #[test]
+    fn test_id3v2_tag() {
+        let tagged = tag_audio("mp3", b"\xff\xfb", &metadata()).unwrap();
+        assert_eq!(&tagged[..6], b"ID3\x04\x00\x00");
+        let size = tagged[6..10]
+            .iter()
+            .fold(0usize, |size, &byte| (size << 7) | usize::from(byte));
+        assert_eq!(size, tagged.len() - 10 - 2);
+        assert_eq!(&tagged[10..14], b"TIT2");
+        assert_eq!(&tagged[14..18], &[0, 0, 0, 13]);
+        assert_eq!(&tagged[20..33], b"\x03Morning News");
+        assert!(tagged.windows(9).any(|window| window == b"\x03eng\0Read"));
+        assert!(tagged.ends_with(b"\xff\xfb"));
+        assert_eq!(synchsafe(300), [0, 0, 2, 44]);
+    }
+
+    #[test]
+    fn test_wav_info_chunk() {
+        let mut wav = b"RIFF\0\0\0\0WAVEfmt \x02\0\0\0\x01\x02data\x02\0\0\0\x00\x00".to_vec();
+        let size = (wav.len() - 8) as u32;
+        wav[4..8].copy_from_slice(&size.to_le_bytes());
+
+        let tagged = tag_wav(&wav, &metadata()).unwrap();
+        let list = tagged.windows(4).position(|w| w == b"LIST").unwrap();
+        let data = tagged.windows(4).position(|w| w == b"data").unwrap();
+        assert_eq!(list, 22);
+        assert!(list < data);
+        assert_eq!(&tagged[list + 8..list + 12], b"INFO");
+        // "Morning News" plus its terminator is odd, so it is padded
+        assert_eq!(&tagged[list + 12..list + 20], b"INAM\x0d\0\0\0");
+        assert_eq!(&tagged[list + 20..list + 34], b"Morning News\0\0");
+        assert_eq!(
+            u32::from_le_bytes(tagged[4..8].try_into().unwrap()) as usize,
+            tagged.len() - 8
+        );
+        assert!(tag_wav(b"RIFF\0\0\0\0WAVE", &metadata()).is_err());
+    }
+
+    #[test]
+    fn test_ogg_opus_tags() {
+        let mut writer = PacketWriter::new(Vec::new());
+        let mut head = b"OpusHead\x01\x01".to_vec();
+        head.extend_from_slice(&[0; 9]);
+        writer
+            .write_packet(head.into_boxed_slice(), 7, PacketWriteEndInfo::EndPage, 0)
+            .unwrap();
+        let mut tags = b"OpusTags\x04\0\0\0test\x01\0\0\0".to_vec();
+        tags.extend_from_slice(b"\x0b\0\0\0ENCODER=lib");
+        writer
+            .write_packet(tags.into_boxed_slice(), 7, PacketWriteEndInfo::EndPage, 0)
+            .unwrap();
+        writer
+            .write_packet(
+                vec![0xfc; 3].into_boxed_slice(),
+                7,
+                PacketWriteEndInfo::EndStream,
+                960,
+            )
+            .unwrap();
+
+        let tagged = tag_audio("opus", &writer.into_inner(), &metadata()).unwrap();
+        let mut reader = PacketReader::new(Cursor::new(tagged));
+        assert!(reader
+            .read_packet()
+            .unwrap()
+            .unwrap()
+            .data
+            .starts_with(b"OpusHead"));
+        let tags = reader.read_packet().unwrap().unwrap().data;
+        assert_eq!(&tags[16..20], &[4, 0, 0, 0]);
+        let text = String::from_utf8_lossy(&tags);
+        assert!(text.contains("ENCODER=lib"));
+        assert!(text.contains("TITLE=Morning News"));
+        assert!(text.contains("COMMENT=Read aloud"));
+        let audio = reader.read_packet().unwrap().unwrap();
+        assert_eq!(audio.data, [0xfc; 3]);
+        assert_eq!(audio.absgp_page(), 960);
+        assert!(audio.last_in_stream());
+        assert!(tag_audio("pcm", &[0; 4], &metadata()).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+use crate::{
+    api::{validate_speech_request, AppState, SpeechRequest},
+    comfort_noise::ComfortNoise,
+    pcm::{pcm_i16_from_f32, BitDepth},
+    streaming::{spawn_pcm_chunks, StreamOptions},
+};
+use anyhow::{Context, Result};
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::Response,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Twilio media streams carry 8 kHz mono mu-law
+const TWILIO_SAMPLE_RATE: u32 = 8000;
+/// 20 ms of 8 kHz mu-law, the frame size Twilio itself sends
+const FRAME_BYTES: usize = 160;
+/// mu-law encoding of a zero sample, used to pad the final frame without comfort noise
+const MULAW_SILENCE: u8 = 0xFF;
+/// Mark sent after the last frame; Twilio echoes it once playback finishes
+const END_MARK: &str = "kokoro-end";
+
+/// Inbound Twilio message; only the fields this server uses are kept
+#[derive(Debug, Deserialize)]
+struct TwilioMessage {
+    event: String,
+    #[serde(rename = "streamSid", default)]
+    stream_sid: Option<String>,
+    #[serde(default)]
+    start: Option<StartMetadata>,
+    #[serde(default)]
+    mark: Option<MarkMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StartMetadata {
+    #[serde(rename = "customParameters", default)]
+    custom_parameters: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MarkMetadata {
+    name: String,
+}
+
+/// WebSocket endpoint for `<Connect><Stream>` in TwiML.
+///
+/// Text and voice come from the stream's `<Parameter>` elements since Twilio
+/// does not forward query strings or custom headers.
+pub async fn media_stream_handler(State(state): State<AppState>, ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(move |socket| async move {
+        let request_id = Uuid::new_v4().to_string();
+        if let Err(e) = run_media_stream(state, socket, &request_id).await {
+            warn!(request_id = %request_id, "Twilio media stream ended: {:#}", e);
+        }
+    })
+}
+
+async fn run_media_stream(state: AppState, mut socket: WebSocket, request_id: &str) -> Result<()> {
+    let Some((stream_sid, parameters)) = wait_for_start(&mut socket).await? else {
+        return Ok(());
+    };
+
+    if state.api_keys.is_required() {
+        let provided_key = parameters.get("api_key").map_or("", String::as_str);
+        if !state.api_keys.verify(provided_key) {
+            anyhow::bail!("Missing or invalid api_key stream parameter");
+        }
+    }
+
+    let params = validate_speech_request(&state, speech_request(&parameters))
+        .map_err(|e| anyhow::anyhow!("{} ({})", e, e.code()))?;
+
+    info!(
+        request_id = %request_id,
+        stream_sid = %stream_sid,
+        voice = %params.voice,
+        "Twilio media stream started"
+    );
+
+    let backend = state.backend_for(params.quality).clone();
+    let mut decimator = Decimator::new(backend.sample_rate())?;
+    let mut rx = spawn_pcm_chunks(
+        backend.clone(),
+        params.input,
+        params.voice,
+        params.speed,
+        params.initial_silence,
+        request_id.to_string(),
+        StreamOptions {
+            parallelism: backend.worker_limit(),
+            retry_policy: state.chunk_retry,
+            trailers: false,
+            trailing_silence: params.trailing_silence,
+            // The decimator expects the model's native rate
+            sample_rate: None,
+            effects: params.effects.clone(),
+            // The decimator reads 16-bit samples
+            bit_depth: BitDepth::Int16,
+            adaptive_chunks: state.adaptive_chunks,
+            chunk_rules: params.chunk_rules.clone(),
+            language: params.language,
+            speed_curve: params.speed_curve.clone(),
+            emphasis: params.emphasis,
+            sentence_gap_ms: params.sentence_gap_ms,
+            comfort_noise: params.comfort_noise,
+            max_duration_s: params.max_duration_s,
+        },
+    );
+
+    let mut pending = Vec::new();
+    while let Some(chunk) = rx.recv().await {
+        let chunk = chunk.context("Synthesis failed")?;
+        pending.extend(decimator.process(&chunk).into_iter().map(mulaw_encode));
+
+        let full = pending.len() - pending.len() % FRAME_BYTES;
+        for frame in pending[..full].chunks(FRAME_BYTES) {
+            send_json(&mut socket, media_message(&stream_sid, frame)).await?;
+        }
+        pending.drain(..full);
+    }
+    if !pending.is_empty() {
+        if params.comfort_noise {
+            let mut noise = ComfortNoise::default();
+            let missing = FRAME_BYTES - pending.len();
+            pending
+                .extend((0..missing).map(|_| mulaw_encode(pcm_i16_from_f32(noise.next_sample()))));
+        } else {
+            pending.resize(FRAME_BYTES, MULAW_SILENCE);
+        }
+        send_json(&mut socket, media_message(&stream_sid, &pending)).await?;
+    }
+    send_json(
+        &mut socket,
+        serde_json::json!({
+            "event": "mark",
+            "streamSid": stream_sid,
+            "mark": { "name": END_MARK },
+        }),
+    )
+    .await?;
+
+    // Hang up the stream only after Twilio has played everything back
+    while let Some(message) = socket.recv().await {
+        let Message::Text(text) = message.context("WebSocket receive failed")? else {
+            continue;
+        };
+        let Ok(message) = serde_json::from_str::<TwilioMessage>(&text) else {
+            continue;
+        };
+        let played = message.mark.is_some_and(|mark| mark.name == END_MARK);
+        if played || message.event == "stop" {
+            break;
+        }
+    }
+
+    debug!(request_id = %request_id, "Twilio media stream complete");
+    let _ = socket.send(Message::Close(None)).await;
+    Ok(())
+}
+
+/// Read messages until `start`, returning the stream SID and custom parameters.
+///
+/// Returns `None` when the call ends first.
+async fn wait_for_start(
+    socket: &mut WebSocket,
+) -> Result<Option<(String, HashMap<String, String>)>> {
+    while let Some(message) = socket.recv().await {
+        let Message::Text(text) = message.context("WebSocket receive failed")? else {
+            continue;
+        };
+        let message: TwilioMessage =
+            serde_json::from_str(&text).context("Invalid Twilio message")?;
+        match message.event.as_str() {
+            "start" => {
+                let stream_sid = message
+                    .stream_sid
+                    .context("Twilio start message has no streamSid")?;
+                let parameters = message
+                    .start
+                    .map(|start| start.custom_parameters)
+                    .unwrap_or_default();
+                return Ok(Some((stream_sid, parameters)));
+            }
+            "stop" => return Ok(None),
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+/// Build a speech request from `<Parameter>` values, using the REST defaults
+fn speech_request(parameters: &HashMap<String, String>) -> SpeechRequest {
+    let get = |name: &str, default: &str| {
+        parameters
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| default.to_string())
+    };
+
+    SpeechRequest {
+        model: get("model", "kokoro"),
+        input: get("input", ""),
+        voice: Some(get("voice", "af_alloy")),
+        response_format: Some("pcm".to_string()),
+        // An unparsable speed falls through to validation as NaN and is rejected there
+        speed: parameters
+            .get("speed")
+            .map(|speed| speed.parse().unwrap_or(f32::NAN)),
+        comfort_noise: parameters.get("comfort_noise").map(|value| value == "true"),
+        stream: Some(true),
+        ..Default::default()
+    }
+}
+
+fn media_message(stream_sid: &str, frame: &[u8]) -> serde_json::Value {
+    serde_json::json!({
+        "event": "media",
+        "streamSid": stream_sid,
+        "media": { "payload": BASE64.encode(frame) },
+    })
+}
+
+async fn send_json(socket: &mut WebSocket, message: serde_json::Value) -> Result<()> {
+    socket
+        .send(Message::Text(message.to_string().into()))
+        .await
+        .context("WebSocket send failed")
+}
+
+/// Downsamples 16-bit PCM to 8 kHz by averaging each group of input samples.
+///
+/// The averaging doubles as a crude low-pass filter, which is adequate for
+/// telephone-band audio. Partial groups carry over between chunks.
+struct Decimator {
+    ratio: usize,
+    sum: i32,
+    count: usize,
+}
+
+impl Decimator {
+    fn new(sample_rate: u32) -> Result<Self> {
+        if !sample_rate.is_multiple_of(TWILIO_SAMPLE_RATE) {
+            anyhow::bail!(
+                "Sample rate {} is not a multiple of {} Hz",
+                sample_rate,
+                TWILIO_SAMPLE_RATE
+            );
+        }
+        Ok(Self {
+            ratio: (sample_rate / TWILIO_SAMPLE_RATE) as usize,
+            sum: 0,
+            count: 0,
+        })
+    }
+
+    /// Consume little-endian 16-bit PCM bytes
+    fn process(&mut self, pcm: &[u8]) -> Vec<i16> {
+        let mut out = Vec::with_capacity(pcm.len() / 2 / self.ratio + 1);
+        for bytes in pcm.chunks_exact(2) {
+            self.sum += i32::from(i16::from_le_bytes([bytes[0], bytes[1]]));
+            self.count += 1;
+            if self.count == self.ratio {
+                out.push((self.sum / self.ratio as i32) as i16);
+                self.sum = 0;
+                self.count = 0;
+            }
+        }
+        out
+    }
+}
+
+/// G.711 mu-law encoding of one 16-bit sample
+fn mulaw_encode(sample: i16) -> u8 {
+    const BIAS: i32 = 0x84;
+    const CLIP: i32 = 32635;
+
+    let magnitude = i32::from(sample).abs().min(CLIP) + BIAS;
+    let sign = if sample < 0 { 0x80 } else { 0 };
+    // `magnitude` is at least BIAS, so its top bit is between 7 and 14
+    let exponent = (31 - magnitude.leading_zeros()) - 7;
+    let mantissa = (magnitude >> (exponent + 3)) & 0x0F;
+
+    !(sign | ((exponent as u8) << 4) | mantissa as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mulaw_encode() {
+        assert_eq!(mulaw_encode(0), MULAW_SILENCE);
+        assert_eq!(mulaw_encode(i16::MAX), 0x80);
+        assert_eq!(mulaw_encode(i16::MIN), 0x00);
+        assert_eq!(mulaw_encode(-1), 0x7F);
+        assert_eq!(mulaw_encode(1000), 0xCE);
+    }
+
+    #[test]
+    fn test_decimator_carries_partial_groups() {
+        let mut decimator = Decimator::new(24_000).unwrap();
+        let pcm: Vec<u8> = [300i16, 600, 900, -300, -600]
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect();
+        assert_eq!(decimator.process(&pcm), vec![600]);
+        assert_eq!(decimator.process(&(-900i16).to_le_bytes()), vec![-600]);
+        assert!(Decimator::new(22_050).is_err());
+    }
+
+    #[test]
+    fn test_start_message_parameters() {
+        let message: TwilioMessage = serde_json::from_str(
+            r#"{"event":"start","sequenceNumber":"1","streamSid":"MZ123","start":{"callSid":"CA1","customParameters":{"input":"Hello caller","voice":"bf_emma","speed":"1.25"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(message.stream_sid.as_deref(), Some("MZ123"));
+
+        let request = speech_request(&message.start.unwrap().custom_parameters);
+        assert_eq!(request.input, "Hello caller");
+        assert_eq!(request.voice.as_deref(), Some("bf_emma"));
+        assert_eq!(request.model, "kokoro");
+        assert_eq!(request.speed, Some(1.25));
+    }
+}
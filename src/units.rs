@@ -0,0 +1,500 @@
+use crate::{config::MeasurementSystem, language::Language};
+
+/// The word a quantity is read with, in the singular and plural
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Noun {
+    /// Read before the number instead of after it, as in 摂氏二十度 and 百分之五十
+    pub prefix: &'static str,
+    pub one: &'static str,
+    pub many: &'static str,
+    /// The number agrees with a feminine noun: "una libra", "duas milhas"
+    pub feminine: bool,
+}
+
+const fn masculine(one: &'static str, many: &'static str) -> Noun {
+    Noun {
+        prefix: "",
+        one,
+        many,
+        feminine: false,
+    }
+}
+
+const fn feminine(one: &'static str, many: &'static str) -> Noun {
+    Noun {
+        prefix: "",
+        one,
+        many,
+        feminine: true,
+    }
+}
+
+/// A noun without a plural form, as in Japanese and Chinese
+const fn word(word: &'static str) -> Noun {
+    masculine(word, word)
+}
+
+const fn around(prefix: &'static str, word: &'static str) -> Noun {
+    Noun {
+        prefix,
+        one: word,
+        many: word,
+        feminine: false,
+    }
+}
+
+/// Currencies read out in full, written as a symbol or an ISO code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Currency {
+    Dollar,
+    Euro,
+    Pound,
+    Yen,
+    Yuan,
+    Rupee,
+}
+
+impl Currency {
+    /// `¥` is the yuan in Chinese text and the yen elsewhere
+    pub fn from_symbol(symbol: &str, language: Language) -> Option<Self> {
+        match symbol.trim() {
+            "$" | "USD" => Some(Self::Dollar),
+            "€" | "EUR" => Some(Self::Euro),
+            "£" | "GBP" => Some(Self::Pound),
+            "¥" if language == Language::Chinese => Some(Self::Yuan),
+            "¥" | "JPY" => Some(Self::Yen),
+            "CNY" | "RMB" => Some(Self::Yuan),
+            "₹" | "INR" => Some(Self::Rupee),
+            _ => None,
+        }
+    }
+
+    /// Names of the currency and of its hundredth part, when amounts are read with one
+    pub fn names(self, language: Language) -> Option<(Noun, Option<Noun>)> {
+        use Language::*;
+        let names = match (self, language) {
+            (Self::Dollar, AmericanEnglish | BritishEnglish) => (
+                masculine("dollar", "dollars"),
+                Some(masculine("cent", "cents")),
+            ),
+            (Self::Dollar, Spanish) => (
+                masculine("dólar", "dólares"),
+                Some(masculine("centavo", "centavos")),
+            ),
+            (Self::Dollar, French) => (
+                masculine("dollar", "dollars"),
+                Some(masculine("cent", "cents")),
+            ),
+            (Self::Dollar, Italian) => (
+                masculine("dollaro", "dollari"),
+                Some(masculine("centesimo", "centesimi")),
+            ),
+            (Self::Dollar, Portuguese) => (
+                masculine("dólar", "dólares"),
+                Some(masculine("centavo", "centavos")),
+            ),
+            (Self::Dollar, Japanese) => (word("ドル"), Some(word("セント"))),
+            (Self::Dollar, Chinese) => (word("美元"), Some(word("美分"))),
+            (Self::Euro, AmericanEnglish | BritishEnglish) => {
+                (masculine("euro", "euros"), Some(masculine("cent", "cents")))
+            }
+            (Self::Euro, Spanish) => (
+                masculine("euro", "euros"),
+                Some(masculine("céntimo", "céntimos")),
+            ),
+            (Self::Euro, French) => (
+                masculine("euro", "euros"),
+                Some(masculine("centime", "centimes")),
+            ),
+            (Self::Euro, Italian) => (
+                masculine("euro", "euro"),
+                Some(masculine("centesimo", "centesimi")),
+            ),
+            (Self::Euro, Portuguese) => (
+                masculine("euro", "euros"),
+                Some(masculine("centavo", "centavos")),
+            ),
+            (Self::Euro, Japanese) => (word("ユーロ"), Some(word("セント"))),
+            (Self::Euro, Chinese) => (word("欧元"), Some(word("欧分"))),
+            (Self::Pound, AmericanEnglish | BritishEnglish) => (
+                masculine("pound", "pounds"),
+                Some(masculine("penny", "pence")),
+            ),
+            (Self::Pound, Spanish) => (
+                feminine("libra", "libras"),
+                Some(masculine("penique", "peniques")),
+            ),
+            (Self::Pound, French) => (
+                feminine("livre", "livres"),
+                Some(masculine("penny", "pence")),
+            ),
+            (Self::Pound, Italian) => (
+                feminine("sterlina", "sterline"),
+                Some(masculine("penny", "pence")),
+            ),
+            (Self::Pound, Portuguese) => (
+                feminine("libra", "libras"),
+                Some(masculine("pêni", "pence")),
+            ),
+            (Self::Pound, Japanese) => (word("ポンド"), Some(word("ペンス"))),
+            (Self::Pound, Chinese) => (word("英镑"), Some(word("便士"))),
+            (Self::Yen, AmericanEnglish | BritishEnglish | Italian) => (word("yen"), None),
+            (Self::Yen, Spanish) => (masculine("yen", "yenes"), None),
+            (Self::Yen, French) => (masculine("yen", "yens"), None),
+            (Self::Yen, Portuguese) => (masculine("iene", "ienes"), None),
+            (Self::Yen, Japanese) => (word("円"), None),
+            (Self::Yen, Chinese) => (word("日元"), None),
+            (Self::Yuan, AmericanEnglish | BritishEnglish | Italian) => (word("yuan"), None),
+            (Self::Yuan, Spanish) => (masculine("yuan", "yuanes"), None),
+            (Self::Yuan, French) => (masculine("yuan", "yuans"), None),
+            (Self::Yuan, Portuguese) => (masculine("iuane", "iuanes"), None),
+            (Self::Yuan, Japanese | Chinese) => (word("元"), None),
+            (Self::Rupee, AmericanEnglish | BritishEnglish) => (
+                masculine("rupee", "rupees"),
+                Some(masculine("paisa", "paise")),
+            ),
+            (Self::Rupee, Spanish) => (
+                feminine("rupia", "rupias"),
+                Some(feminine("paisa", "paisas")),
+            ),
+            (Self::Rupee, French) => (
+                feminine("roupie", "roupies"),
+                Some(feminine("paisa", "paisas")),
+            ),
+            (Self::Rupee, Italian) => (
+                feminine("rupia", "rupie"),
+                Some(masculine("paisa", "paise")),
+            ),
+            (Self::Rupee, Portuguese) => (
+                feminine("rupia", "rupias"),
+                Some(feminine("paisa", "paisas")),
+            ),
+            (Self::Rupee, Japanese) => (word("ルピー"), Some(word("パイサ"))),
+            (Self::Rupee, Chinese) => (word("卢比"), Some(word("派萨"))),
+            (_, Hindi) => return None,
+        };
+        Some(names)
+    }
+}
+
+/// A multiplier written after an amount of money: `$3.2M`, `€5K`, `£2bn`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scale {
+    Thousand,
+    Million,
+    Billion,
+}
+
+impl Scale {
+    pub fn from_suffix(suffix: &str) -> Option<Self> {
+        match suffix {
+            "K" | "k" | "thousand" => Some(Self::Thousand),
+            "M" | "mn" | "million" => Some(Self::Million),
+            "B" | "bn" | "billion" => Some(Self::Billion),
+            _ => None,
+        }
+    }
+
+    pub fn name(self, language: Language) -> Option<Noun> {
+        use Language::*;
+        Some(match (self, language) {
+            (Self::Thousand, AmericanEnglish | BritishEnglish) => word("thousand"),
+            (Self::Million, AmericanEnglish | BritishEnglish) => word("million"),
+            (Self::Billion, AmericanEnglish | BritishEnglish) => word("billion"),
+            (Self::Thousand, Spanish | Portuguese) => word("mil"),
+            (Self::Million, Spanish) => masculine("millón", "millones"),
+            // Spanish counts thousands of millions rather than billions
+            (Self::Billion, Spanish) => word("mil millones"),
+            (Self::Thousand, French) => word("mille"),
+            (Self::Million, French) => masculine("million", "millions"),
+            (Self::Billion, French) => masculine("milliard", "milliards"),
+            (Self::Thousand, Italian) => masculine("mille", "mila"),
+            (Self::Million, Italian) => masculine("milione", "milioni"),
+            (Self::Billion, Italian) => masculine("miliardo", "miliardi"),
+            (Self::Million, Portuguese) => masculine("milhão", "milhões"),
+            (Self::Billion, Portuguese) => masculine("bilhão", "bilhões"),
+            (Self::Thousand, Japanese | Chinese) => word("千"),
+            (Self::Million, Japanese | Chinese) => word("百万"),
+            (Self::Billion, Japanese) => word("十億"),
+            (Self::Billion, Chinese) => word("十亿"),
+            (_, Hindi) => return None,
+        })
+    }
+}
+
+/// Units of measure read out in full after a number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Kilometer,
+    Meter,
+    Centimeter,
+    Millimeter,
+    Mile,
+    Foot,
+    Kilogram,
+    Gram,
+    Milligram,
+    Pound,
+    Ounce,
+    Liter,
+    Milliliter,
+    KilometersPerHour,
+    MilesPerHour,
+    Celsius,
+    Fahrenheit,
+    /// A bare `°`, which may be an angle as well as a temperature
+    Degree,
+    Percent,
+    Tonne,
+    ShortTon,
+}
+
+impl Unit {
+    /// Unit written as `symbol`; `t` is read in `system`
+    pub fn from_symbol(symbol: &str, system: MeasurementSystem) -> Option<Self> {
+        let imperial = system == MeasurementSystem::Imperial;
+        match symbol {
+            "km" => Some(Self::Kilometer),
+            "m" => Some(Self::Meter),
+            "cm" => Some(Self::Centimeter),
+            "mm" => Some(Self::Millimeter),
+            "mi" => Some(Self::Mile),
+            "ft" => Some(Self::Foot),
+            "kg" => Some(Self::Kilogram),
+            "g" => Some(Self::Gram),
+            "mg" => Some(Self::Milligram),
+            "lb" | "lbs" => Some(Self::Pound),
+            "oz" => Some(Self::Ounce),
+            "l" | "L" => Some(Self::Liter),
+            "ml" | "mL" => Some(Self::Milliliter),
+            "km/h" | "kph" => Some(Self::KilometersPerHour),
+            "mph" => Some(Self::MilesPerHour),
+            "°C" => Some(Self::Celsius),
+            "°F" => Some(Self::Fahrenheit),
+            "°" => Some(Self::Degree),
+            "%" => Some(Self::Percent),
+            "t" if imperial => Some(Self::ShortTon),
+            "t" => Some(Self::Tonne),
+            _ => None,
+        }
+    }
+
+    pub fn name(self, language: Language) -> Option<Noun> {
+        Some(match language {
+            Language::AmericanEnglish => self.english(),
+            Language::BritishEnglish => match self {
+                Self::Kilometer => masculine("kilometre", "kilometres"),
+                Self::Meter => masculine("metre", "metres"),
+                Self::Centimeter => masculine("centimetre", "centimetres"),
+                Self::Millimeter => masculine("millimetre", "millimetres"),
+                Self::Liter => masculine("litre", "litres"),
+                Self::Milliliter => masculine("millilitre", "millilitres"),
+                Self::KilometersPerHour => masculine("kilometre per hour", "kilometres per hour"),
+                Self::Percent => word("per cent"),
+                _ => self.english(),
+            },
+            Language::Spanish => match self {
+                Self::Kilometer => masculine("kilómetro", "kilómetros"),
+                Self::Meter => masculine("metro", "metros"),
+                Self::Centimeter => masculine("centímetro", "centímetros"),
+                Self::Millimeter => masculine("milímetro", "milímetros"),
+                Self::Mile => feminine("milla", "millas"),
+                Self::Foot => masculine("pie", "pies"),
+                Self::Kilogram => masculine("kilogramo", "kilogramos"),
+                Self::Gram => masculine("gramo", "gramos"),
+                Self::Milligram => masculine("miligramo", "miligramos"),
+                Self::Pound => feminine("libra", "libras"),
+                Self::Ounce => feminine("onza", "onzas"),
+                Self::Liter => masculine("litro", "litros"),
+                Self::Milliliter => masculine("mililitro", "mililitros"),
+                Self::KilometersPerHour => masculine("kilómetro por hora", "kilómetros por hora"),
+                Self::MilesPerHour => feminine("milla por hora", "millas por hora"),
+                Self::Celsius => masculine("grado Celsius", "grados Celsius"),
+                Self::Fahrenheit => masculine("grado Fahrenheit", "grados Fahrenheit"),
+                Self::Degree => masculine("grado", "grados"),
+                Self::Percent => word("por ciento"),
+                Self::Tonne => feminine("tonelada", "toneladas"),
+                Self::ShortTon => feminine("tonelada corta", "toneladas cortas"),
+            },
+            Language::French => match self {
+                Self::Kilometer => masculine("kilomètre", "kilomètres"),
+                Self::Meter => masculine("mètre", "mètres"),
+                Self::Centimeter => masculine("centimètre", "centimètres"),
+                Self::Millimeter => masculine("millimètre", "millimètres"),
+                Self::Mile => masculine("mile", "miles"),
+                Self::Foot => masculine("pied", "pieds"),
+                Self::Kilogram => masculine("kilogramme", "kilogrammes"),
+                Self::Gram => masculine("gramme", "grammes"),
+                Self::Milligram => masculine("milligramme", "milligrammes"),
+                Self::Pound => feminine("livre", "livres"),
+                Self::Ounce => feminine("once", "onces"),
+                Self::Liter => masculine("litre", "litres"),
+                Self::Milliliter => masculine("millilitre", "millilitres"),
+                Self::KilometersPerHour => masculine("kilomètre par heure", "kilomètres par heure"),
+                Self::MilesPerHour => masculine("mile par heure", "miles par heure"),
+                Self::Celsius => masculine("degré Celsius", "degrés Celsius"),
+                Self::Fahrenheit => masculine("degré Fahrenheit", "degrés Fahrenheit"),
+                Self::Degree => masculine("degré", "degrés"),
+                Self::Percent => word("pour cent"),
+                Self::Tonne => feminine("tonne", "tonnes"),
+                Self::ShortTon => feminine("tonne courte", "tonnes courtes"),
+            },
+            Language::Italian => match self {
+                Self::Kilometer => masculine("chilometro", "chilometri"),
+                Self::Meter => masculine("metro", "metri"),
+                Self::Centimeter => masculine("centimetro", "centimetri"),
+                Self::Millimeter => masculine("millimetro", "millimetri"),
+                Self::Mile => masculine("miglio", "miglia"),
+                Self::Foot => masculine("piede", "piedi"),
+                Self::Kilogram => masculine("chilogrammo", "chilogrammi"),
+                Self::Gram => masculine("grammo", "grammi"),
+                Self::Milligram => masculine("milligrammo", "milligrammi"),
+                Self::Pound => feminine("libbra", "libbre"),
+                Self::Ounce => feminine("oncia", "once"),
+                Self::Liter => masculine("litro", "litri"),
+                Self::Milliliter => masculine("millilitro", "millilitri"),
+                Self::KilometersPerHour => masculine("chilometro orario", "chilometri orari"),
+                Self::MilesPerHour => masculine("miglio orario", "miglia orarie"),
+                Self::Celsius => masculine("grado Celsius", "gradi Celsius"),
+                Self::Fahrenheit => masculine("grado Fahrenheit", "gradi Fahrenheit"),
+                Self::Degree => masculine("grado", "gradi"),
+                Self::Percent => word("per cento"),
+                Self::Tonne => feminine("tonnellata", "tonnellate"),
+                Self::ShortTon => feminine("tonnellata corta", "tonnellate corte"),
+            },
+            Language::Portuguese => match self {
+                Self::Kilometer => masculine("quilômetro", "quilômetros"),
+                Self::Meter => masculine("metro", "metros"),
+                Self::Centimeter => masculine("centímetro", "centímetros"),
+                Self::Millimeter => masculine("milímetro", "milímetros"),
+                Self::Mile => feminine("milha", "milhas"),
+                Self::Foot => masculine("pé", "pés"),
+                Self::Kilogram => masculine("quilograma", "quilogramas"),
+                Self::Gram => masculine("grama", "gramas"),
+                Self::Milligram => masculine("miligrama", "miligramas"),
+                Self::Pound => feminine("libra", "libras"),
+                Self::Ounce => feminine("onça", "onças"),
+                Self::Liter => masculine("litro", "litros"),
+                Self::Milliliter => masculine("mililitro", "mililitros"),
+                Self::KilometersPerHour => masculine("quilômetro por hora", "quilômetros por hora"),
+                Self::MilesPerHour => feminine("milha por hora", "milhas por hora"),
+                Self::Celsius => masculine("grau Celsius", "graus Celsius"),
+                Self::Fahrenheit => masculine("grau Fahrenheit", "graus Fahrenheit"),
+                Self::Degree => masculine("grau", "graus"),
+                Self::Percent => word("por cento"),
+                Self::Tonne => feminine("tonelada", "toneladas"),
+                Self::ShortTon => feminine("tonelada curta", "toneladas curtas"),
+            },
+            Language::Japanese => match self {
+                Self::Kilometer => word("キロメートル"),
+                Self::Meter => word("メートル"),
+                Self::Centimeter => word("センチメートル"),
+                Self::Millimeter => word("ミリメートル"),
+                Self::Mile => word("マイル"),
+                Self::Foot => word("フィート"),
+                Self::Kilogram => word("キログラム"),
+                Self::Gram => word("グラム"),
+                Self::Milligram => word("ミリグラム"),
+                Self::Pound => word("ポンド"),
+                Self::Ounce => word("オンス"),
+                Self::Liter => word("リットル"),
+                Self::Milliliter => word("ミリリットル"),
+                Self::KilometersPerHour => around("時速", "キロメートル"),
+                Self::MilesPerHour => around("時速", "マイル"),
+                Self::Celsius => around("摂氏", "度"),
+                Self::Fahrenheit => around("華氏", "度"),
+                Self::Degree => word("度"),
+                Self::Percent => word("パーセント"),
+                Self::Tonne => word("トン"),
+                Self::ShortTon => word("米トン"),
+            },
+            Language::Chinese => match self {
+                Self::Kilometer => word("公里"),
+                Self::Meter => word("米"),
+                Self::Centimeter => word("厘米"),
+                Self::Millimeter => word("毫米"),
+                Self::Mile => word("英里"),
+                Self::Foot => word("英尺"),
+                Self::Kilogram => word("公斤"),
+                Self::Gram => word("克"),
+                Self::Milligram => word("毫克"),
+                Self::Pound => word("磅"),
+                Self::Ounce => word("盎司"),
+                Self::Liter => word("升"),
+                Self::Milliliter => word("毫升"),
+                Self::KilometersPerHour => around("时速", "公里"),
+                Self::MilesPerHour => around("时速", "英里"),
+                Self::Celsius => around("摄氏", "度"),
+                Self::Fahrenheit => around("华氏", "度"),
+                Self::Degree => word("度"),
+                Self::Percent => around("百分之", ""),
+                Self::Tonne => word("吨"),
+                Self::ShortTon => word("短吨"),
+            },
+            Language::Hindi => return None,
+        })
+    }
+
+    fn english(self) -> Noun {
+        match self {
+            Self::Kilometer => masculine("kilometer", "kilometers"),
+            Self::Meter => masculine("meter", "meters"),
+            Self::Centimeter => masculine("centimeter", "centimeters"),
+            Self::Millimeter => masculine("millimeter", "millimeters"),
+            Self::Mile => masculine("mile", "miles"),
+            Self::Foot => masculine("foot", "feet"),
+            Self::Kilogram => masculine("kilogram", "kilograms"),
+            Self::Gram => masculine("gram", "grams"),
+            Self::Milligram => masculine("milligram", "milligrams"),
+            Self::Pound => masculine("pound", "pounds"),
+            Self::Ounce => masculine("ounce", "ounces"),
+            Self::Liter => masculine("liter", "liters"),
+            Self::Milliliter => masculine("milliliter", "milliliters"),
+            Self::KilometersPerHour => masculine("kilometer per hour", "kilometers per hour"),
+            Self::MilesPerHour => masculine("mile per hour", "miles per hour"),
+            Self::Celsius => masculine("degree Celsius", "degrees Celsius"),
+            Self::Fahrenheit => masculine("degree Fahrenheit", "degrees Fahrenheit"),
+            Self::Degree => masculine("degree", "degrees"),
+            Self::Percent => word("percent"),
+            Self::Tonne => masculine("tonne", "tonnes"),
+            Self::ShortTon => masculine("ton", "tons"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ambiguous_units_follow_measurement_system() {
+        assert_eq!(
+            Unit::from_symbol("°", MeasurementSystem::Imperial),
+            Some(Unit::Degree)
+        );
+        assert_eq!(
+            Unit::from_symbol("°C", MeasurementSystem::Imperial),
+            Some(Unit::Celsius)
+        );
+        assert_eq!(
+            Unit::from_symbol("t", MeasurementSystem::Imperial),
+            Some(Unit::ShortTon)
+        );
+    }
+
+    #[test]
+    fn test_yen_sign_is_yuan_in_chinese() {
+        assert_eq!(
+            Currency::from_symbol("¥", Language::Japanese),
+            Some(Currency::Yen)
+        );
+        assert_eq!(
+            Currency::from_symbol("¥", Language::Chinese),
+            Some(Currency::Yuan)
+        );
+        assert_eq!(
+            Currency::from_symbol("USD ", Language::Spanish),
+            Some(Currency::Dollar)
+        );
+    }
+}
@@ -1,11 +1,152 @@
-use crate::config::Config;
+use crate::config::KOKORO_DRAFT_MODEL_ID;
 use crate::error::{ApiResult, AppError};
+use crate::language::{Language, LANGUAGE_CODES};
+use crate::pcm::BitDepth;
+use crate::tags::{supports_metadata, AudioMetadata};
+use crate::voice_blend;
+use anyhow::Context;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::Path;
 use std::sync::LazyLock;
 
+/// ElevenLabs premade voice IDs mapped to the closest Kokoro voice
+pub const ELEVENLABS_VOICE_ALIASES: [(&str, &str); 16] = [
+    ("21m00Tcm4TlvDq8ikWAM", "af_heart"),
+    ("EXAVITQu4vr4xnSDxMaL", "af_river"),
+    ("AZnzlk1XvdvUeBnXmlld", "af_nova"),
+    ("MF3mGyEYCl7XYWbV9V6O", "af_shimmer"),
+    ("piTKgcLEGmPE4e6mEKli", "af_alloy"),
+    ("pNInz6obpgDQGcFmaJgB", "am_adam"),
+    ("ErXwobaYiR019PkySvjV", "am_fenrir"),
+    ("TxGEqnHWrfWFTfGW9XjX", "am_echo"),
+    ("VR6AewLTigWG4xSOukaG", "am_onyx"),
+    ("yoZ06aMxZJJ28mfd3POQ", "am_puck"),
+    ("TX3LPaxmHKxFdv7VOQHJ", "am_echo"),
+    ("cjVigY5qzO86Huf0OWal", "am_fenrir"),
+    ("JBFqnCBsd6RMkjVDRZzb", "bm_george"),
+    ("onwK4e9ZLuTAKqWW03F9", "bm_daniel"),
+    ("pFZP5JQG7iQjIQuC4Bku", "bf_lily"),
+    ("Xb7hH8MSUJpSbSDYk0k2", "bf_alice"),
+];
+
+/// Polly voice IDs mapped to the closest Kokoro voice
+pub const POLLY_VOICE_ALIASES: [(&str, &str); 24] = [
+    ("Joanna", "af_heart"),
+    ("Salli", "af_nova"),
+    ("Kimberly", "af_river"),
+    ("Kendra", "af_shimmer"),
+    ("Ivy", "af_alloy"),
+    ("Danielle", "af_river"),
+    ("Ruth", "af_heart"),
+    ("Matthew", "am_adam"),
+    ("Joey", "am_echo"),
+    ("Justin", "am_puck"),
+    ("Kevin", "am_puck"),
+    ("Stephen", "am_onyx"),
+    ("Gregory", "am_fenrir"),
+    ("Amy", "bf_emma"),
+    ("Emma", "bf_alice"),
+    ("Brian", "bm_george"),
+    ("Arthur", "bm_lewis"),
+    ("Lucia", "ef_dora"),
+    ("Sergio", "em_alex"),
+    ("Lea", "ff_siwis"),
+    ("Mizuki", "jf_alpha"),
+    ("Kazuha", "jf_nezumi"),
+    ("Takumi", "jm_kumo"),
+    ("Zhiyu", "zf_xiaoxiao"),
+];
+
+/// Azure neural voice names mapped to the closest Kokoro voice
+pub const AZURE_VOICE_ALIASES: [(&str, &str); 18] = [
+    ("en-US-JennyNeural", "af_heart"),
+    ("en-US-AriaNeural", "af_nova"),
+    ("en-US-AvaNeural", "af_river"),
+    ("en-US-EmmaNeural", "af_shimmer"),
+    ("en-US-SaraNeural", "af_alloy"),
+    ("en-US-GuyNeural", "am_adam"),
+    ("en-US-DavisNeural", "am_onyx"),
+    ("en-US-AndrewNeural", "am_echo"),
+    ("en-US-BrianNeural", "am_fenrir"),
+    ("en-US-TonyNeural", "am_puck"),
+    ("en-GB-SoniaNeural", "bf_emma"),
+    ("en-GB-LibbyNeural", "bf_alice"),
+    ("en-GB-RyanNeural", "bm_george"),
+    ("en-GB-ThomasNeural", "bm_lewis"),
+    ("es-ES-ElviraNeural", "ef_dora"),
+    ("fr-FR-DeniseNeural", "ff_siwis"),
+    ("ja-JP-NanamiNeural", "jf_alpha"),
+    ("zh-CN-XiaoxiaoNeural", "zf_xiaoxiao"),
+];
+
+/// Google Cloud voice names mapped to the closest Kokoro voice
+pub const GOOGLE_VOICE_ALIASES: [(&str, &str); 17] = [
+    ("en-US-Neural2-F", "af_heart"),
+    ("en-US-Neural2-C", "af_nova"),
+    ("en-US-Neural2-E", "af_river"),
+    ("en-US-Neural2-H", "af_shimmer"),
+    ("en-US-Neural2-G", "af_alloy"),
+    ("en-US-Neural2-D", "am_adam"),
+    ("en-US-Neural2-A", "am_echo"),
+    ("en-US-Neural2-I", "am_onyx"),
+    ("en-US-Neural2-J", "am_fenrir"),
+    ("en-GB-Neural2-A", "bf_emma"),
+    ("en-GB-Neural2-C", "bf_alice"),
+    ("en-GB-Neural2-B", "bm_george"),
+    ("en-GB-Neural2-D", "bm_lewis"),
+    ("es-ES-Neural2-A", "ef_dora"),
+    ("fr-FR-Neural2-A", "ff_siwis"),
+    ("ja-JP-Neural2-B", "jf_alpha"),
+    ("cmn-CN-Wavenet-A", "zf_xiaoxiao"),
+];
+
+/// Speech API whose voice names a compatibility route accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoiceVendor {
+    ElevenLabs,
+    Polly,
+    Azure,
+    Google,
+}
+
+impl VoiceVendor {
+    /// Vendor voice names mapped to the closest Kokoro voice
+    pub fn aliases(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::ElevenLabs => &ELEVENLABS_VOICE_ALIASES,
+            Self::Polly => &POLLY_VOICE_ALIASES,
+            Self::Azure => &AZURE_VOICE_ALIASES,
+            Self::Google => &GOOGLE_VOICE_ALIASES,
+        }
+    }
+
+    /// Map a vendor voice name to a Kokoro voice; Kokoro IDs and OpenAI aliases pass through.
+    /// Azure voice names match in any case, as Azure itself accepts them.
+    pub fn kokoro_voice(self, name: &str) -> &str {
+        self.aliases()
+            .iter()
+            .find(|(id, _)| match self {
+                Self::Azure => id.eq_ignore_ascii_case(name),
+                _ => *id == name,
+            })
+            .map_or(name, |(_, voice)| voice)
+    }
+}
+
 /// Valid response formats
 pub const VALID_RESPONSE_FORMATS: [&str; 4] = ["wav", "pcm", "mp3", "opus"];
 
-/// OpenAI voice aliases mapped to Kokoro voice identifiers.
+/// Output profiles; each one fixes the encoding and is used as the internal format name
+pub const VALID_PROFILES: [&str; 1] = ["discord"];
+
+/// Values of a request's `quality`
+pub const VALID_QUALITIES: [&str; 2] = ["high", "draft"];
+
+/// Longest value of a single metadata field
+pub const MAX_METADATA_CHARS: usize = 1000;
+
+/// OpenAI voice aliases mapped to Kokoro voice identifiers; the defaults for [`VoiceAliases`].
 pub const OPENAI_VOICE_ALIASES: [(&str, &str); 13] = [
     ("alloy", "af_alloy"),
     ("echo", "am_echo"),
@@ -25,7 +166,7 @@ pub const OPENAI_VOICE_ALIASES: [(&str, &str); 13] = [
 /// Default sample rate for Kokoro TTS
 pub const DEFAULT_SAMPLE_RATE: u32 = 24000;
 
-/// All available Kokoro voices - lazily initialized once
+/// Built-in voice list, used when the loaded voice pack cannot be enumerated - lazily initialized once
 pub static AVAILABLE_VOICES: LazyLock<Vec<Voice>> = LazyLock::new(|| {
     vec![
         Voice {
@@ -256,6 +397,94 @@ pub fn validate_response_format(format: &str) -> ApiResult<String> {
     }
 }
 
+/// Validate an output profile, returning the format it encodes to
+pub fn validate_profile(profile: &str) -> ApiResult<String> {
+    let profile_lower = profile.to_lowercase();
+    if VALID_PROFILES.contains(&profile_lower.as_str()) {
+        Ok(profile_lower)
+    } else {
+        Err(AppError::unsupported_profile(profile))
+    }
+}
+
+/// Validate a WAV sample depth in bits
+pub fn validate_bit_depth(bits: u16) -> ApiResult<BitDepth> {
+    BitDepth::from_bits(bits).ok_or_else(|| {
+        AppError::invalid_request(format!(
+            "bit_depth {} not supported. Supported values: 16, 24, 32 (float)",
+            bits
+        ))
+    })
+}
+
+/// Validate the tags to write into output in `format`; fields left empty are ignored
+pub fn validate_metadata(metadata: &AudioMetadata, format: &str) -> ApiResult<()> {
+    if !supports_metadata(format) {
+        return Err(AppError::invalid_request(format!(
+            "metadata is not supported for '{}' response_format",
+            format
+        )));
+    }
+    for value in metadata.values() {
+        if value.chars().count() > MAX_METADATA_CHARS {
+            return Err(AppError::invalid_request(format!(
+                "metadata values must be at most {} characters",
+                MAX_METADATA_CHARS
+            )));
+        }
+        if value.contains('\0') {
+            return Err(AppError::invalid_request(
+                "metadata values must not contain NUL characters",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Resolve the language of a request: its `language` field, else the voice's language, else
+/// American English
+pub fn validate_language(code: Option<&str>, voice: &str) -> ApiResult<Language> {
+    match code {
+        Some(code) => Language::from_code(code).ok_or_else(|| {
+            AppError::invalid_request(format!(
+                "language '{}' not supported. Supported values: {}",
+                code,
+                LANGUAGE_CODES.join(", ")
+            ))
+        }),
+        None => Ok(Language::of_voice(voice).unwrap_or_default()),
+    }
+}
+
+/// Whether `c` is never spoken and only gets in the way: control codes other than line breaks
+/// and tabs, zero-width characters, and the bidi controls that reorder how text is displayed.
+///
+/// Control codes can crash the phonemizer, and bidi overrides and zero-width characters let
+/// crafted input display differently in logs than it reads.
+pub fn is_unspoken_char(c: char) -> bool {
+    match c {
+        '\n' | '\r' | '\t' => false,
+        // Zero-width space, non-joiner, and joiner; word joiner; byte order mark
+        '\u{200b}'..='\u{200d}' | '\u{2060}' | '\u{feff}' | '\u{180e}' => true,
+        // Marks, embeddings, overrides, and isolates that set text direction
+        '\u{200e}'
+        | '\u{200f}'
+        | '\u{061c}'
+        | '\u{202a}'..='\u{202e}'
+        | '\u{2066}'..='\u{2069}' => true,
+        c => c.is_control(),
+    }
+}
+
+/// Input with every [`is_unspoken_char`] removed, borrowed when there are none
+pub fn sanitize_input(input: &str) -> Cow<'_, str> {
+    if input.chars().any(is_unspoken_char) {
+        Cow::Owned(input.chars().filter(|&c| !is_unspoken_char(c)).collect())
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
 /// Validate input text
 pub fn validate_input(input: &str, max_chars: usize) -> ApiResult<()> {
     if input.is_empty() {
@@ -265,53 +494,188 @@ pub fn validate_input(input: &str, max_chars: usize) -> ApiResult<()> {
     let input_chars = input.chars().count();
 
     if input_chars > max_chars {
-        return Err(AppError::invalid_request(format!(
-            "Input text exceeds maximum length of {} characters",
-            max_chars
-        )));
+        return Err(AppError::InputTooLong(max_chars));
     }
 
     Ok(())
 }
 
-/// Validate model ID
-pub fn validate_model(model: &str) -> ApiResult<String> {
-    let accepted = Config::accepted_model_ids();
-    if accepted.contains(&model) {
+/// Validate model ID against the accepted IDs from [`crate::config::accepted_model_ids`]
+pub fn validate_model(model: &str, accepted: &[String]) -> ApiResult<String> {
+    if accepted.iter().any(|id| id == model) {
         Ok(model.to_string())
     } else {
         Err(AppError::model_not_found(model))
     }
 }
 
-/// Validate voice ID against available voices
-pub fn validate_voice(voice: &str, available_voices: &[Voice]) -> ApiResult<String> {
-    let resolved_voice = resolve_legacy_voice_alias(voice);
+/// Which model a request is synthesized with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Quality {
+    /// The full model
+    #[default]
+    High,
+    /// The quantized model from `KOKORO_DRAFT_MODEL_PATH`, with longer chunks, for previews
+    Draft,
+}
+
+/// Validate a request's `quality` against its model ID.
+///
+/// The [`KOKORO_DRAFT_MODEL_ID`] model selects the draft tier on its own; `draft` needs a
+/// draft model to be loaded.
+pub fn validate_quality(
+    quality: Option<&str>,
+    model: &str,
+    draft_available: bool,
+) -> ApiResult<Quality> {
+    let draft_model = model == KOKORO_DRAFT_MODEL_ID;
+    let quality = match quality.map(str::to_lowercase).as_deref() {
+        None if draft_model => Quality::Draft,
+        None => Quality::High,
+        Some("high") if draft_model => {
+            return Err(AppError::invalid_request(format!(
+                "quality 'high' is not available with model '{}'",
+                KOKORO_DRAFT_MODEL_ID
+            )))
+        }
+        Some("high") => Quality::High,
+        Some("draft") => Quality::Draft,
+        Some(other) => {
+            return Err(AppError::invalid_request(format!(
+                "quality '{}' not supported. Supported values: {}",
+                other,
+                VALID_QUALITIES.join(", ")
+            )))
+        }
+    };
+    if quality == Quality::Draft && !draft_available {
+        return Err(AppError::invalid_request(
+            "quality 'draft' requires a draft model, which is not loaded on this server \
+             (set KOKORO_DRAFT_MODEL_PATH)",
+        ));
+    }
+    Ok(quality)
+}
+
+/// Validate voice ID against available voices, resolving aliases first.
+///
+/// A blend such as `af_heart.6+am_adam.4` is checked voice by voice and returned with each
+/// voice resolved.
+pub fn validate_voice(
+    voice: &str,
+    available_voices: &[Voice],
+    aliases: &VoiceAliases,
+) -> ApiResult<String> {
+    if let Some(parts) = voice_blend::parse(voice)? {
+        let resolved = parts
+            .into_iter()
+            .map(|(id, weight)| Ok((validate_voice(id, available_voices, aliases)?, weight)))
+            .collect::<ApiResult<Vec<_>>>()?;
+        return Ok(voice_blend::format(&resolved));
+    }
+    let resolved_voice = aliases.resolve(voice);
 
     if available_voices.iter().any(|v| v.id == resolved_voice) {
-        Ok(resolved_voice)
+        Ok(resolved_voice.to_string())
     } else {
         Err(AppError::voice_not_found(voice))
     }
 }
 
-fn resolve_legacy_voice_alias(voice: &str) -> String {
-    OPENAI_VOICE_ALIASES
-        .iter()
-        .find(|(alias, _)| alias.eq_ignore_ascii_case(voice))
-        .map(|(_, kokoro)| (*kokoro).to_string())
-        .unwrap_or_else(|| voice.to_string())
+/// Validate a voice, using `fallback` instead of rejecting an unknown one.
+///
+/// Returns the resolved voice and whether the fallback was substituted.
+pub fn validate_voice_or_fallback(
+    voice: &str,
+    available_voices: &[Voice],
+    aliases: &VoiceAliases,
+    fallback: Option<&str>,
+) -> ApiResult<(String, bool)> {
+    match validate_voice(voice, available_voices, aliases) {
+        Ok(voice) => Ok((voice, false)),
+        // A malformed blend is the client's mistake, not a voice this server lacks
+        Err(e @ AppError::VoiceNotFound(_)) => {
+            let fallback = fallback.ok_or(e)?;
+            Ok((validate_voice(fallback, available_voices, aliases)?, true))
+        }
+        Err(e) => Err(e),
+    }
 }
 
-pub fn openai_alias_voices() -> Vec<Voice> {
-    OPENAI_VOICE_ALIASES
-        .iter()
-        .map(|(alias, kokoro)| Voice {
-            id: (*alias).to_string(),
-            name: format!("{} (OpenAI alias for {})", alias, kokoro),
-            preview_url: None,
-        })
-        .collect()
+/// Alias voice names accepted in place of Kokoro voice IDs.
+///
+/// Starts from [`OPENAI_VOICE_ALIASES`]; `KOKORO_VOICE_ALIASES_FILE` overrides entries and
+/// adds new ones. Aliases are matched case-insensitively.
+#[derive(Debug, Clone)]
+pub struct VoiceAliases {
+    entries: Vec<(String, String)>,
+}
+
+impl Default for VoiceAliases {
+    fn default() -> Self {
+        Self {
+            entries: OPENAI_VOICE_ALIASES
+                .iter()
+                .map(|(alias, kokoro)| (alias.to_string(), kokoro.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl VoiceAliases {
+    /// Defaults merged with a JSON object mapping alias names to Kokoro voice IDs
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let overrides: BTreeMap<String, String> =
+            serde_json::from_str(&text).with_context(|| {
+                format!(
+                    "{} must be a JSON object mapping alias names to voice IDs",
+                    path.display()
+                )
+            })?;
+
+        let mut aliases = Self::default();
+        for (alias, kokoro) in overrides {
+            if alias.trim().is_empty() || kokoro.trim().is_empty() {
+                anyhow::bail!("{} contains an empty alias or voice ID", path.display());
+            }
+            aliases.insert(alias, kokoro);
+        }
+        Ok(aliases)
+    }
+
+    fn insert(&mut self, alias: String, kokoro: String) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|(existing, _)| existing.eq_ignore_ascii_case(&alias))
+        {
+            Some(entry) => entry.1 = kokoro,
+            None => self.entries.push((alias, kokoro)),
+        }
+    }
+
+    /// The Kokoro voice an alias maps to; anything else is returned unchanged
+    pub fn resolve<'a>(&'a self, voice: &'a str) -> &'a str {
+        self.entries
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(voice))
+            .map_or(voice, |(_, kokoro)| kokoro.as_str())
+    }
+
+    /// Alias voices whose Kokoro voice is in `available`, for voice listings
+    pub fn voices(&self, available: &[Voice]) -> Vec<Voice> {
+        self.entries
+            .iter()
+            .filter(|(_, kokoro)| available.iter().any(|voice| voice.id == *kokoro))
+            .map(|(alias, kokoro)| Voice {
+                id: alias.clone(),
+                name: format!("{} (alias for {})", alias, kokoro),
+                preview_url: None,
+            })
+            .collect()
+    }
 }
 
 /// Validate speed parameter (0.25 to 4.0)
@@ -320,11 +684,13 @@ pub fn validate_speed(speed: f32) -> ApiResult<f32> {
     const MAX_SPEED: f32 = 4.0;
 
     if speed.is_nan() || speed.is_infinite() {
-        return Err(AppError::invalid_request("Speed must be a finite number"));
+        return Err(AppError::InvalidSpeed(
+            "Speed must be a finite number".to_string(),
+        ));
     }
 
     if !(MIN_SPEED..=MAX_SPEED).contains(&speed) {
-        return Err(AppError::invalid_request(format!(
+        return Err(AppError::InvalidSpeed(format!(
             "Speed must be between {} and {}, got {}",
             MIN_SPEED, MAX_SPEED, speed
         )));
@@ -334,7 +700,7 @@ pub fn validate_speed(speed: f32) -> ApiResult<f32> {
 }
 
 /// Voice information
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct Voice {
     pub id: String,
     pub name: String,
@@ -346,6 +712,89 @@ pub fn get_available_voices() -> &'static [Voice] {
     AVAILABLE_VOICES.as_slice()
 }
 
+/// Operator restrictions on which voices are listed and accepted.
+///
+/// Entries are voice IDs or prefixes ending in `*`, e.g. `a*` for American English or
+/// `bf_*` for British female voices. A voice must match the allow list when one is set,
+/// and must not match the deny list.
+#[derive(Debug, Clone, Default)]
+pub struct VoiceFilter {
+    allow: Vec<String>,
+    deny: Vec<String>,
+}
+
+impl VoiceFilter {
+    pub fn new(allow: Vec<String>, deny: Vec<String>) -> Self {
+        Self { allow, deny }
+    }
+
+    pub fn permits(&self, id: &str) -> bool {
+        let matches = |pattern: &String| match pattern.strip_suffix('*') {
+            Some(prefix) => id.starts_with(prefix),
+            None => pattern == id,
+        };
+        (self.allow.is_empty() || self.allow.iter().any(matches)) && !self.deny.iter().any(matches)
+    }
+
+    /// The voices this filter permits, in their original order
+    pub fn apply(&self, voices: &[Voice]) -> Vec<Voice> {
+        voices
+            .iter()
+            .filter(|voice| self.permits(&voice.id))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Voice entries for the IDs found in a voice pack
+pub fn voices_from_ids(ids: &[String]) -> Vec<Voice> {
+    ids.iter()
+        .map(|id| Voice {
+            id: id.clone(),
+            name: describe_voice(id),
+            preview_url: None,
+        })
+        .collect()
+}
+
+/// Display name from the Kokoro naming scheme, e.g. `bf_emma` is "Emma (Female, British)".
+///
+/// IDs that do not follow `<language><gender>_<name>` are shown as-is.
+fn describe_voice(id: &str) -> String {
+    let Some((prefix, name)) = id.split_once('_') else {
+        return id.to_string();
+    };
+    let mut prefix = prefix.chars();
+    let language = match prefix.next() {
+        Some('a') => "American",
+        Some('b') => "British",
+        Some('e') => "Spanish",
+        Some('f') => "French",
+        Some('h') => "Hindi",
+        Some('i') => "Italian",
+        Some('j') => "Japanese",
+        Some('p') => "Portuguese",
+        Some('z') => "Chinese",
+        _ => return id.to_string(),
+    };
+    let gender = match (prefix.next(), prefix.next()) {
+        (Some('f'), None) => "Female",
+        (Some('m'), None) => "Male",
+        _ => return id.to_string(),
+    };
+    let mut name_chars = name.chars();
+    let Some(first) = name_chars.next() else {
+        return id.to_string();
+    };
+    format!(
+        "{}{} ({}, {})",
+        first.to_uppercase(),
+        name_chars.as_str(),
+        gender,
+        language
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -363,6 +812,49 @@ mod tests {
         assert!(validate_response_format("flac").is_err());
     }
 
+    #[test]
+    fn test_validate_profile() {
+        assert_eq!(validate_profile("Discord").unwrap(), "discord");
+        assert!(validate_profile("telegram").is_err());
+    }
+
+    #[test]
+    fn test_validate_bit_depth() {
+        assert_eq!(validate_bit_depth(24).unwrap(), BitDepth::Int24);
+        assert_eq!(validate_bit_depth(32).unwrap(), BitDepth::Float32);
+        assert!(validate_bit_depth(8).is_err());
+    }
+
+    #[test]
+    fn test_validate_metadata() {
+        let metadata = |title: &str| AudioMetadata {
+            title: Some(title.to_string()),
+            ..AudioMetadata::default()
+        };
+        assert!(validate_metadata(&metadata("News"), "mp3").is_ok());
+        assert!(validate_metadata(&metadata("News"), "discord").is_ok());
+        assert!(validate_metadata(&metadata("News"), "pcm").is_err());
+        assert!(validate_metadata(&metadata("a\0b"), "wav").is_err());
+        assert!(validate_metadata(&metadata(&"a".repeat(1001)), "opus").is_err());
+    }
+
+    #[test]
+    fn test_validate_language() {
+        assert_eq!(
+            validate_language(None, "ef_dora").unwrap(),
+            Language::Spanish
+        );
+        assert_eq!(
+            validate_language(Some("ja"), "af_heart").unwrap(),
+            Language::Japanese
+        );
+        assert_eq!(
+            validate_language(None, "custom").unwrap(),
+            Language::AmericanEnglish
+        );
+        assert!(validate_language(Some("klingon"), "af_heart").is_err());
+    }
+
     #[test]
     fn test_validate_input() {
         assert!(validate_input("Hello", 100).is_ok());
@@ -370,11 +862,71 @@ mod tests {
         assert!(validate_input("a".repeat(101).as_str(), 100).is_err());
     }
 
+    #[test]
+    fn test_sanitize_input() {
+        assert!(matches!(sanitize_input("Hello\nworld"), Cow::Borrowed(_)));
+        assert_eq!(
+            sanitize_input("pay\u{202e}321\u{202c} now\u{200b}\u{7}\u{1b}[2J"),
+            "pay321 now[2J"
+        );
+        assert_eq!(sanitize_input("\u{feff}\u{0}\u{2066}"), "");
+    }
+
     #[test]
     fn test_validate_model() {
-        assert!(validate_model("tts-1").is_ok());
-        assert!(validate_model("kokoro").is_ok());
-        assert!(validate_model("invalid").is_err());
+        let accepted = crate::config::accepted_model_ids(&["tts-1".to_string()], false);
+        assert!(validate_model("tts-1", &accepted).is_ok());
+        assert!(validate_model("kokoro", &accepted).is_ok());
+        assert!(validate_model("tts-1-hd", &accepted).is_err());
+        assert!(validate_model("invalid", &accepted).is_err());
+        assert!(validate_model("kokoro-draft", &accepted).is_err());
+    }
+
+    #[test]
+    fn test_vendor_voices_resolve() {
+        for vendor in [
+            VoiceVendor::ElevenLabs,
+            VoiceVendor::Polly,
+            VoiceVendor::Azure,
+            VoiceVendor::Google,
+        ] {
+            for (name, _) in vendor.aliases() {
+                assert!(validate_voice(
+                    vendor.kokoro_voice(name),
+                    get_available_voices(),
+                    &VoiceAliases::default()
+                )
+                .is_ok());
+            }
+            assert_eq!(vendor.kokoro_voice("bf_emma"), "bf_emma");
+        }
+        assert_eq!(VoiceVendor::Polly.kokoro_voice("Joanna"), "af_heart");
+        assert_eq!(
+            VoiceVendor::Azure.kokoro_voice("en-us-jennyneural"),
+            "af_heart"
+        );
+        // Only Azure names are case-insensitive
+        assert_eq!(VoiceVendor::Polly.kokoro_voice("joanna"), "joanna");
+    }
+
+    #[test]
+    fn test_validate_quality() {
+        assert_eq!(
+            validate_quality(None, "tts-1", false).unwrap(),
+            Quality::High
+        );
+        assert_eq!(
+            validate_quality(Some("Draft"), "tts-1", true).unwrap(),
+            Quality::Draft
+        );
+        assert_eq!(
+            validate_quality(None, "kokoro-draft", true).unwrap(),
+            Quality::Draft
+        );
+        assert!(validate_quality(Some("high"), "kokoro-draft", true).is_err());
+        // Draft needs a draft model
+        assert!(validate_quality(Some("draft"), "tts-1", false).is_err());
+        assert!(validate_quality(Some("best"), "tts-1", true).is_err());
     }
 
     #[test]
@@ -457,19 +1009,58 @@ mod tests {
             },
         ];
 
-        assert_eq!(validate_voice("alloy", &voices).unwrap(), "af_alloy");
-        assert_eq!(validate_voice("echo", &voices).unwrap(), "am_echo");
-        assert_eq!(validate_voice("fable", &voices).unwrap(), "bm_fable");
-        assert_eq!(validate_voice("nova", &voices).unwrap(), "af_nova");
-        assert_eq!(validate_voice("onyx", &voices).unwrap(), "am_onyx");
-        assert_eq!(validate_voice("shimmer", &voices).unwrap(), "af_shimmer");
-        assert_eq!(validate_voice("ash", &voices).unwrap(), "am_adam");
-        assert_eq!(validate_voice("ballad", &voices).unwrap(), "am_michael");
-        assert_eq!(validate_voice("verse", &voices).unwrap(), "am_eric");
-        assert_eq!(validate_voice("cedar", &voices).unwrap(), "am_liam");
-        assert_eq!(validate_voice("coral", &voices).unwrap(), "af_nicole");
-        assert_eq!(validate_voice("sage", &voices).unwrap(), "af_sarah");
-        assert_eq!(validate_voice("marin", &voices).unwrap(), "af_river");
+        assert_eq!(
+            validate_voice("alloy", &voices, &VoiceAliases::default()).unwrap(),
+            "af_alloy"
+        );
+        assert_eq!(
+            validate_voice("echo", &voices, &VoiceAliases::default()).unwrap(),
+            "am_echo"
+        );
+        assert_eq!(
+            validate_voice("fable", &voices, &VoiceAliases::default()).unwrap(),
+            "bm_fable"
+        );
+        assert_eq!(
+            validate_voice("nova", &voices, &VoiceAliases::default()).unwrap(),
+            "af_nova"
+        );
+        assert_eq!(
+            validate_voice("onyx", &voices, &VoiceAliases::default()).unwrap(),
+            "am_onyx"
+        );
+        assert_eq!(
+            validate_voice("shimmer", &voices, &VoiceAliases::default()).unwrap(),
+            "af_shimmer"
+        );
+        assert_eq!(
+            validate_voice("ash", &voices, &VoiceAliases::default()).unwrap(),
+            "am_adam"
+        );
+        assert_eq!(
+            validate_voice("ballad", &voices, &VoiceAliases::default()).unwrap(),
+            "am_michael"
+        );
+        assert_eq!(
+            validate_voice("verse", &voices, &VoiceAliases::default()).unwrap(),
+            "am_eric"
+        );
+        assert_eq!(
+            validate_voice("cedar", &voices, &VoiceAliases::default()).unwrap(),
+            "am_liam"
+        );
+        assert_eq!(
+            validate_voice("coral", &voices, &VoiceAliases::default()).unwrap(),
+            "af_nicole"
+        );
+        assert_eq!(
+            validate_voice("sage", &voices, &VoiceAliases::default()).unwrap(),
+            "af_sarah"
+        );
+        assert_eq!(
+            validate_voice("marin", &voices, &VoiceAliases::default()).unwrap(),
+            "af_river"
+        );
     }
 
     #[test]
@@ -480,6 +1071,96 @@ mod tests {
             preview_url: None,
         }];
 
-        assert_eq!(validate_voice("EcHo", &voices).unwrap(), "am_echo");
+        assert_eq!(
+            validate_voice("EcHo", &voices, &VoiceAliases::default()).unwrap(),
+            "am_echo"
+        );
+    }
+
+    #[test]
+    fn test_validate_voice_or_fallback() {
+        let voices = voices_from_ids(&["af_heart".to_string(), "am_echo".to_string()]);
+        let aliases = VoiceAliases::default();
+
+        assert_eq!(
+            validate_voice_or_fallback("echo", &voices, &aliases, Some("af_heart")).unwrap(),
+            ("am_echo".to_string(), false)
+        );
+        assert_eq!(
+            validate_voice_or_fallback("gandalf", &voices, &aliases, Some("af_heart")).unwrap(),
+            ("af_heart".to_string(), true)
+        );
+        assert!(validate_voice_or_fallback("gandalf", &voices, &aliases, None).is_err());
+        assert!(validate_voice_or_fallback("gandalf", &voices, &aliases, Some("bf_emma")).is_err());
+    }
+
+    #[test]
+    fn test_voice_aliases_file() {
+        let path =
+            std::env::temp_dir().join(format!("kokoro-aliases-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&path, r#"{"ALLOY": "af_heart", "narrator": "bm_george"}"#).unwrap();
+        let aliases = VoiceAliases::load(&path).unwrap();
+        assert_eq!(aliases.resolve("alloy"), "af_heart");
+        assert_eq!(aliases.resolve("Narrator"), "bm_george");
+        assert_eq!(aliases.resolve("echo"), "am_echo");
+        assert_eq!(aliases.resolve("bf_emma"), "bf_emma");
+
+        let available = voices_from_ids(&["af_heart".to_string()]);
+        let listed: Vec<_> = aliases
+            .voices(&available)
+            .into_iter()
+            .map(|voice| voice.id)
+            .collect();
+        assert_eq!(listed, ["alloy"]);
+
+        std::fs::write(&path, r#"["alloy"]"#).unwrap();
+        assert!(VoiceAliases::load(&path).is_err());
+        std::fs::write(&path, r#"{"alloy": ""}"#).unwrap();
+        assert!(VoiceAliases::load(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_voice_filter() {
+        let voices =
+            voices_from_ids(&["af_heart", "am_santa", "bf_emma", "jf_alpha"].map(String::from));
+        let ids = |filter: VoiceFilter| {
+            filter
+                .apply(&voices)
+                .into_iter()
+                .map(|voice| voice.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(VoiceFilter::default()).len(), 4);
+        assert_eq!(
+            ids(VoiceFilter::new(
+                vec!["a*".to_string(), "b*".to_string()],
+                vec!["am_santa".to_string()]
+            )),
+            ["af_heart", "bf_emma"]
+        );
+        assert_eq!(
+            ids(VoiceFilter::new(Vec::new(), vec!["a*".to_string()])),
+            ["bf_emma", "jf_alpha"]
+        );
+        assert!(ids(VoiceFilter::new(vec!["af".to_string()], Vec::new())).is_empty());
+    }
+
+    #[test]
+    fn test_voices_from_ids() {
+        for voice in get_available_voices() {
+            assert_eq!(describe_voice(&voice.id), voice.name);
+        }
+
+        let ids = ["af_bella", "custom", "xf_test", "af_"].map(String::from);
+        let names: Vec<_> = voices_from_ids(&ids)
+            .into_iter()
+            .map(|voice| voice.name)
+            .collect();
+        assert_eq!(
+            names,
+            ["Bella (Female, American)", "custom", "xf_test", "af_"]
+        );
     }
 }
@@ -0,0 +1,370 @@
+use crate::{api::AppState, config::VerifyArgs, language::Language, pcm::samples_to_pcm16};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use tracing::info;
+
+/// Corpus written by `verify --update` when the golden file does not exist yet: a few voices
+/// across both accents, with numbers, dates, and punctuation that exercise normalization
+const CORPUS: [(&str, &str); 4] = [
+    ("af_heart", "The quick brown fox jumps over the lazy dog."),
+    (
+        "am_michael",
+        "On March 3rd, 2024, the meeting started at 10:30 a.m. and cost $1,250.",
+    ),
+    (
+        "bf_emma",
+        "Is this the right way? Yes, turn left, then right!",
+    ),
+    (
+        "bm_george",
+        "She sells seashells by the seashore; the shells she sells are surely seashells.",
+    ),
+];
+
+/// Equal slices of a clip summarized in its spectrum
+const SEGMENTS: usize = 12;
+/// Frequencies sampled in each segment, log-spaced from `LOWEST_BAND_HZ` to `HIGHEST_BAND_HZ`
+const BANDS: usize = 16;
+const LOWEST_BAND_HZ: f32 = 100.0;
+const HIGHEST_BAND_HZ: f32 = 8000.0;
+/// Analysis window, about 10 ms at the model's 24 kHz
+const WINDOW: usize = 256;
+/// Level given to silent bands so the log stays finite
+const FLOOR_DB: f32 = -100.0;
+/// Largest change in length accepted when the PCM hash differs
+const DURATION_TOLERANCE: f64 = 0.05;
+
+/// Golden values file read and written by the `verify` subcommand
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GoldenFile {
+    #[serde(rename = "case", default)]
+    cases: Vec<GoldenCase>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GoldenCase {
+    voice: String,
+    text: String,
+    #[serde(default = "default_speed")]
+    speed: f32,
+    /// SHA256 of the clip as 16-bit little-endian PCM
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+    /// Band levels in dB, `BANDS` per segment, compared when the hash differs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    spectrum: Option<Vec<f32>>,
+}
+
+fn default_speed() -> f32 {
+    1.0
+}
+
+/// What a clip is compared by
+struct Measurement {
+    sha256: String,
+    duration_ms: u64,
+    spectrum: Vec<f32>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Identical,
+    /// Different samples within tolerance, with the spectral distance in dB
+    Close(f32),
+    Failed(String),
+}
+
+/// Synthesize every case in the golden file and compare it with the stored values, or with
+/// `--update` store the current values instead. Fails if any case is out of tolerance.
+pub async fn run(state: AppState, args: &VerifyArgs) -> Result<()> {
+    let mut golden = match std::fs::read_to_string(&args.golden) {
+        Ok(contents) => toml::from_str::<GoldenFile>(&contents)
+            .with_context(|| format!("Invalid golden file {}", args.golden.display()))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && args.update => GoldenFile {
+            cases: CORPUS
+                .iter()
+                .map(|(voice, text)| GoldenCase {
+                    voice: voice.to_string(),
+                    text: text.to_string(),
+                    speed: default_speed(),
+                    sha256: None,
+                    duration_ms: None,
+                    spectrum: None,
+                })
+                .collect(),
+        },
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", args.golden.display()))
+        }
+    };
+    if golden.cases.is_empty() {
+        anyhow::bail!("{} has no cases", args.golden.display());
+    }
+
+    let mut failed = 0;
+    for (index, case) in golden.cases.iter_mut().enumerate() {
+        let audio = state
+            .backend
+            .synthesize(
+                &case.text,
+                &case.voice,
+                Language::of_voice(&case.voice).unwrap_or_default(),
+                case.speed,
+                None,
+            )
+            .await
+            .with_context(|| format!("Case {} ({}) failed to synthesize", index + 1, case.voice))?;
+        let measured = measure(&audio.samples, audio.sample_rate);
+
+        if args.update {
+            println!(
+                "case {}: {} {} ms {}",
+                index + 1,
+                case.voice,
+                measured.duration_ms,
+                measured.sha256
+            );
+            case.sha256 = Some(measured.sha256);
+            case.duration_ms = Some(measured.duration_ms);
+            case.spectrum = Some(measured.spectrum);
+            continue;
+        }
+
+        match compare(case, &measured, args.tolerance) {
+            Outcome::Identical => println!("case {}: {} identical", index + 1, case.voice),
+            Outcome::Close(distance) => println!(
+                "case {}: {} close (spectral distance {:.2} dB)",
+                index + 1,
+                case.voice,
+                distance
+            ),
+            Outcome::Failed(reason) => {
+                failed += 1;
+                println!("case {}: {} FAILED: {}", index + 1, case.voice, reason);
+            }
+        }
+    }
+
+    if args.update {
+        write_golden(&args.golden, &golden)?;
+        info!(
+            "Wrote {} golden case(s) to {}",
+            golden.cases.len(),
+            args.golden.display()
+        );
+        return Ok(());
+    }
+    if failed > 0 {
+        anyhow::bail!(
+            "{} of {} case(s) differ from the golden audio",
+            failed,
+            golden.cases.len()
+        );
+    }
+    info!("All {} golden case(s) match", golden.cases.len());
+    Ok(())
+}
+
+fn write_golden(path: &Path, golden: &GoldenFile) -> Result<()> {
+    let contents = toml::to_string_pretty(golden).context("Failed to serialize golden values")?;
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn measure(samples: &[f32], sample_rate: u32) -> Measurement {
+    Measurement {
+        sha256: format!("{:x}", Sha256::digest(samples_to_pcm16(samples))),
+        duration_ms: samples.len() as u64 * 1000 / u64::from(sample_rate.max(1)),
+        spectrum: spectrum(samples, sample_rate),
+    }
+}
+
+fn compare(case: &GoldenCase, measured: &Measurement, tolerance: f32) -> Outcome {
+    let (Some(sha256), Some(duration_ms), Some(spectrum)) =
+        (&case.sha256, case.duration_ms, &case.spectrum)
+    else {
+        return Outcome::Failed("no golden values; run with --update".to_string());
+    };
+    if *sha256 == measured.sha256 {
+        return Outcome::Identical;
+    }
+
+    let change =
+        (measured.duration_ms as f64 - duration_ms as f64).abs() / duration_ms.max(1) as f64;
+    if change > DURATION_TOLERANCE {
+        return Outcome::Failed(format!(
+            "length changed from {} ms to {} ms",
+            duration_ms, measured.duration_ms
+        ));
+    }
+    match spectral_distance(spectrum, &measured.spectrum) {
+        Some(distance) if distance <= tolerance => Outcome::Close(distance),
+        Some(distance) => Outcome::Failed(format!(
+            "spectral distance {:.2} dB exceeds {:.2} dB",
+            distance, tolerance
+        )),
+        None => Outcome::Failed("golden spectrum has the wrong number of bands".to_string()),
+    }
+}
+
+/// Average level of each band in each of `SEGMENTS` equal slices of the clip, in dB rounded to
+/// 0.1 dB. Slices scale with the clip, so small timing shifts move little energy between them.
+fn spectrum(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    let frequencies = (0..BANDS)
+        .map(|band| {
+            LOWEST_BAND_HZ
+                * (HIGHEST_BAND_HZ / LOWEST_BAND_HZ).powf(band as f32 / (BANDS - 1) as f32)
+        })
+        .collect::<Vec<_>>();
+    let hann = (0..WINDOW)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (WINDOW - 1) as f32).cos())
+        .collect::<Vec<_>>();
+
+    let mut levels = Vec::with_capacity(SEGMENTS * BANDS);
+    let segment_len = samples.len() / SEGMENTS;
+    for segment in 0..SEGMENTS {
+        let segment = &samples[segment * segment_len..(segment + 1) * segment_len];
+        let windows = segment.chunks_exact(WINDOW).collect::<Vec<_>>();
+        for &frequency in &frequencies {
+            let power = windows
+                .iter()
+                .map(|window| goertzel_power(window, &hann, frequency, sample_rate))
+                .sum::<f32>()
+                / windows.len().max(1) as f32;
+            let level = if power > 0.0 {
+                (10.0 * power.log10()).max(FLOOR_DB)
+            } else {
+                FLOOR_DB
+            };
+            levels.push((level * 10.0).round() / 10.0);
+        }
+    }
+    levels
+}
+
+/// Power of `window` at `frequency`, normalized by the window length
+fn goertzel_power(window: &[f32], hann: &[f32], frequency: f32, sample_rate: u32) -> f32 {
+    let coefficient = 2.0 * (2.0 * std::f32::consts::PI * frequency / sample_rate as f32).cos();
+    let (mut previous, mut before) = (0.0f32, 0.0f32);
+    for (sample, weight) in window.iter().zip(hann) {
+        let current = sample * weight + coefficient * previous - before;
+        before = previous;
+        previous = current;
+    }
+    let power = previous * previous + before * before - coefficient * previous * before;
+    power.max(0.0) / (window.len() * window.len()) as f32
+}
+
+/// Root-mean-square difference between two spectra in dB; `None` if their shapes differ
+fn spectral_distance(expected: &[f32], actual: &[f32]) -> Option<f32> {
+    if expected.len() != actual.len() || expected.is_empty() {
+        return None;
+    }
+    let sum = expected
+        .iter()
+        .zip(actual)
+        .map(|(a, b)| (a - b) * (a - b))
+        .sum::<f32>();
+    Some((sum / expected.len() as f32).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(frequency: f32, amplitude: f32) -> Vec<f32> {
+        (0..24_000)
+            .map(|i| {
+                amplitude * (2.0 * std::f32::consts::PI * frequency * i as f32 / 24_000.0).sin()
+            })
+            .collect()
+    }
+
+    fn golden(samples: &[f32]) -> GoldenCase {
+        let measured = measure(samples, 24_000);
+        GoldenCase {
+            voice: "af_heart".to_string(),
+            text: "Test.".to_string(),
+            speed: 1.0,
+            sha256: Some(measured.sha256),
+            duration_ms: Some(measured.duration_ms),
+            spectrum: Some(measured.spectrum),
+        }
+    }
+
+    #[test]
+    fn test_spectrum_follows_the_signal() {
+        let low = spectrum(&tone(200.0, 0.5), 24_000);
+        assert_eq!(low.len(), SEGMENTS * BANDS);
+        // The band nearest 200 Hz is far louder than the top band
+        assert!(low[3] > low[BANDS - 1] + 30.0);
+        assert!(spectrum(&[0.0; 24_000], 24_000)
+            .iter()
+            .all(|level| *level == FLOOR_DB));
+    }
+
+    #[test]
+    fn test_compare() {
+        let samples = tone(440.0, 0.5);
+        let case = golden(&samples);
+        assert_eq!(
+            compare(&case, &measure(&samples, 24_000), 1.5),
+            Outcome::Identical
+        );
+
+        // A tiny level change alters the samples but not the spectrum beyond tolerance
+        let quieter = samples.iter().map(|s| s * 0.99).collect::<Vec<_>>();
+        assert!(matches!(
+            compare(&case, &measure(&quieter, 24_000), 1.5),
+            Outcome::Close(_)
+        ));
+
+        let different = tone(3000.0, 0.5);
+        assert!(matches!(
+            compare(&case, &measure(&different, 24_000), 1.5),
+            Outcome::Failed(_)
+        ));
+
+        let longer = tone(440.0, 0.5).repeat(2);
+        assert!(matches!(
+            compare(&case, &measure(&longer, 24_000), 1.5),
+            Outcome::Failed(reason) if reason.contains("length")
+        ));
+
+        let missing = GoldenCase {
+            sha256: None,
+            ..case
+        };
+        assert!(matches!(
+            compare(&missing, &measure(&samples, 24_000), 1.5),
+            Outcome::Failed(_)
+        ));
+    }
+
+    #[test]
+    fn test_golden_file_round_trip() {
+        let file = GoldenFile {
+            cases: vec![golden(&tone(440.0, 0.5))],
+        };
+        let parsed: GoldenFile = toml::from_str(&toml::to_string_pretty(&file).unwrap()).unwrap();
+        assert_eq!(parsed.cases.len(), 1);
+        assert_eq!(parsed.cases[0].sha256, file.cases[0].sha256);
+        assert_eq!(parsed.cases[0].spectrum, file.cases[0].spectrum);
+
+        // Hand-written cases need only a voice and text
+        let minimal: GoldenFile =
+            toml::from_str("[[case]]\nvoice = \"af_heart\"\ntext = \"Hello.\"\n").unwrap();
+        assert_eq!(minimal.cases[0].speed, 1.0);
+        assert!(minimal.cases[0].sha256.is_none());
+    }
+}
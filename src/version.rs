@@ -0,0 +1,287 @@
+use crate::{
+    backend::KokoroBackend, config::BackendKind, download::sha256_file,
+    model_assets::model_quantization, runtime_assets::espeak_data_valid,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::SystemTime;
+use tracing::warn;
+use utoipa::ToSchema;
+
+/// Response body for GET /version
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VersionResponse {
+    /// Crate version
+    pub version: &'static str,
+    pub build: BuildInfo,
+    /// Absent with the mock backend, which never loads ONNX Runtime
+    pub onnxruntime: Option<OnnxRuntimeInfo>,
+    pub execution_provider: ExecutionProviderInfo,
+    pub model: ModelInfo,
+    /// Voices the server accepts after the allow and deny lists
+    pub voices: usize,
+}
+
+/// How the binary was built
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BuildInfo {
+    /// Commit the binary was built from, or "unknown" outside a git checkout
+    pub git_sha: &'static str,
+    pub target: &'static str,
+    /// Cargo profile, e.g. "release"
+    pub profile: &'static str,
+    pub rustc: &'static str,
+    /// Cargo features compiled in
+    pub features: Vec<&'static str>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OnnxRuntimeInfo {
+    /// Release of the loaded ONNX Runtime library, e.g. "1.22.0"
+    pub version: Option<String>,
+    /// C API version the server was compiled against
+    pub api_version: u32,
+    /// Build string reported by the loaded library
+    pub build_info: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExecutionProviderInfo {
+    /// `KOKORO_ACCELERATION` as configured, possibly "auto"
+    pub requested: String,
+    /// Provider registered first once "auto" and missing features are resolved
+    pub selected: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModelInfo {
+    pub path: String,
+    /// SHA256 of the model file; computed on the first request after a model change
+    pub sha256: Option<String>,
+    /// Weight precision, e.g. "fp32", "fp16", or "int8"
+    pub quantization: &'static str,
+    /// Whether the model is resident (false while lazily unloaded)
+    pub loaded: bool,
+}
+
+/// What a replica is serving, reported by GET /health so a fleet can spot mismatched replicas
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AssetFingerprints {
+    /// SHA256 of the model file; null until it has been hashed in the background
+    pub model_sha256: Option<String>,
+    /// SHA256 of the voice pack, which carries no version of its own; null until hashed
+    pub voices_sha256: Option<String>,
+    /// Whether the espeak-ng data directory holds `espeak-ng-data`; absent with the mock backend
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub espeak_data_valid: Option<bool>,
+    /// Provider registered first, e.g. "cuda" or "cpu"
+    pub execution_provider: String,
+}
+
+/// Cargo features this binary was built with
+const FEATURES: [(&str, bool); 9] = [
+    ("coreml", cfg!(feature = "coreml")),
+    ("cuda", cfg!(feature = "cuda")),
+    ("directml", cfg!(feature = "directml")),
+    ("rocm", cfg!(feature = "rocm")),
+    ("openvino", cfg!(feature = "openvino")),
+    ("cpu", cfg!(feature = "cpu")),
+    ("mock-backend", cfg!(feature = "mock-backend")),
+    (
+        "embedded-espeak-data",
+        cfg!(feature = "embedded-espeak-data"),
+    ),
+    ("grpc", cfg!(feature = "grpc")),
+];
+
+/// Digest of each hashed file, reused while the file is unchanged
+static FILE_DIGESTS: LazyLock<tokio::sync::Mutex<HashMap<PathBuf, (FileStamp, String)>>> =
+    LazyLock::new(Default::default);
+
+/// Identifies a file version without reading it
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileStamp {
+    path: PathBuf,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileStamp {
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            path: path.to_path_buf(),
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+pub async fn version(backend: &KokoroBackend, model_url: &str) -> VersionResponse {
+    let files = backend.model_files();
+    VersionResponse {
+        version: env!("CARGO_PKG_VERSION"),
+        build: BuildInfo {
+            git_sha: env!("KOKORO_BUILD_GIT_SHA"),
+            target: env!("KOKORO_BUILD_TARGET"),
+            profile: env!("KOKORO_BUILD_PROFILE"),
+            rustc: env!("KOKORO_BUILD_RUSTC"),
+            features: FEATURES
+                .iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(name, _)| *name)
+                .collect(),
+        },
+        // Only the Kokoro backend loads the library; asking without it would try to load it here
+        onnxruntime: (backend.kind() == BackendKind::Kokoro).then(|| {
+            let build_info = ort::info();
+            OnnxRuntimeInfo {
+                version: ort_release(build_info).map(str::to_string),
+                api_version: ort::MINOR_VERSION,
+                build_info: build_info.to_string(),
+            }
+        }),
+        execution_provider: ExecutionProviderInfo {
+            requested: backend.requested_acceleration().to_string(),
+            selected: backend.execution_provider().to_string(),
+        },
+        model: ModelInfo {
+            sha256: file_sha256(&files.model_path).await,
+            quantization: model_quantization(&files, model_url),
+            loaded: backend.is_loaded(),
+            path: files.model_path.to_string_lossy().to_string(),
+        },
+        voices: backend.voices().len(),
+    }
+}
+
+/// Fingerprints for GET /health, which must answer quickly, so digests not yet computed for the
+/// current files are reported as `None` while they are hashed in the background
+pub fn asset_fingerprints(backend: &KokoroBackend) -> AssetFingerprints {
+    let files = backend.model_files();
+    AssetFingerprints {
+        model_sha256: cached_sha256(&files.model_path),
+        voices_sha256: cached_sha256(&files.voices_path),
+        espeak_data_valid: (backend.kind() == BackendKind::Kokoro).then(espeak_data_valid),
+        execution_provider: backend.execution_provider().to_string(),
+    }
+}
+
+/// Release version from an ONNX Runtime build string such as
+/// `ORT Build Info: git-branch=rel-1.22.0, git-commit-id=..., build type=Release`
+fn ort_release(build_info: &str) -> Option<&str> {
+    build_info
+        .split([',', ' '])
+        .find_map(|field| field.strip_prefix("git-branch=rel-"))
+        .filter(|version| !version.is_empty())
+}
+
+/// Hash a file off the runtime, once per file version; `None` if it cannot be read
+async fn file_sha256(path: &Path) -> Option<String> {
+    let stamp = FileStamp::read(path)?;
+    // Held while hashing so concurrent first requests share one pass over the file
+    let mut cached = FILE_DIGESTS.lock().await;
+    if let Some((cached_stamp, digest)) = cached.get(path) {
+        if *cached_stamp == stamp {
+            return Some(digest.clone());
+        }
+    }
+
+    let hash_path = stamp.path.clone();
+    match tokio::task::spawn_blocking(move || sha256_file(&hash_path)).await {
+        Ok(Ok(digest)) => {
+            cached.insert(path.to_path_buf(), (stamp, digest.clone()));
+            Some(digest)
+        }
+        Ok(Err(e)) => {
+            warn!(path = %path.display(), error = %e, "Failed to hash file");
+            None
+        }
+        Err(e) => {
+            warn!(error = %e, "File hash task panicked");
+            None
+        }
+    }
+}
+
+/// Digest of the current version of `path` if it has already been computed; otherwise start
+/// hashing it in the background and return `None`
+fn cached_sha256(path: &Path) -> Option<String> {
+    let stamp = FileStamp::read(path)?;
+    // Busy means a hash is running, which will fill the cache when it finishes
+    let cached = FILE_DIGESTS.try_lock().ok()?;
+    if let Some((cached_stamp, digest)) = cached.get(path) {
+        if *cached_stamp == stamp {
+            return Some(digest.clone());
+        }
+    }
+    drop(cached);
+
+    let path = path.to_path_buf();
+    tokio::spawn(async move { file_sha256(&path).await });
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ort_release() {
+        assert_eq!(
+            ort_release(
+                "ORT Build Info: git-branch=rel-1.22.0, git-commit-id=f217402897f, build type=Release"
+            ),
+            Some("1.22.0")
+        );
+        assert_eq!(
+            ort_release("ORT Build Info: git-branch=main, build type=Debug"),
+            None
+        );
+        assert_eq!(ort_release(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_file_sha256_follows_file_changes() {
+        let path =
+            std::env::temp_dir().join(format!("kokoro-version-{}.onnx", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            file_sha256(&path).await.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+
+        std::fs::write(&path, b"abcd").unwrap();
+        assert_eq!(
+            file_sha256(&path).await.as_deref(),
+            Some("88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589")
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(file_sha256(&path).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_cached_sha256_hashes_in_background() {
+        let path =
+            std::env::temp_dir().join(format!("kokoro-version-{}.onnx", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"abc").unwrap();
+        assert_eq!(cached_sha256(&path), None);
+
+        let mut digest = None;
+        for _ in 0..100 {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            digest = cached_sha256(&path);
+            if digest.is_some() {
+                break;
+            }
+        }
+        assert_eq!(
+            digest.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
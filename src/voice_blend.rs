@@ -0,0 +1,177 @@
+use crate::error::{ApiResult, AppError};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
+use utoipa::ToSchema;
+
+/// Most voices one blend may mix
+pub const MAX_BLEND_VOICES: usize = 4;
+/// Kokoro mixes voices in tenths: `af_heart.6` is 0.6 of `af_heart`
+const WEIGHT_STEPS: u32 = 10;
+
+/// One voice of a blend and its share of the mix
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BlendPart {
+    /// Voice ID or alias
+    pub id: String,
+    /// Share of the mix, in steps of 0.1; the weights of a blend sum to 1.0
+    pub weight: f32,
+}
+
+/// A voice ID, a blend string such as `af_heart.6+am_adam.4`, or a blend as a list of voices
+/// with weights
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum VoiceSpec {
+    Id(String),
+    Blend(Vec<BlendPart>),
+}
+
+/// Deserialize a request's `voice`, turning a structured blend into its string form so the
+/// rest of the pipeline sees one voice string either way
+pub fn deserialize_voice<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<VoiceSpec>::deserialize(deserializer)? {
+        Some(VoiceSpec::Id(id)) => Ok(Some(id)),
+        Some(VoiceSpec::Blend(parts)) => blend_string(&parts).map(Some).map_err(D::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// Blend string for `parts`, with the weights checked by [`parse`] once the request is validated
+fn blend_string(parts: &[BlendPart]) -> Result<String, String> {
+    let mut blend = Vec::with_capacity(parts.len());
+    for part in parts {
+        let steps = part.weight * WEIGHT_STEPS as f32;
+        if !(0.0..=WEIGHT_STEPS as f32).contains(&steps) || (steps - steps.round()).abs() > 0.01 {
+            return Err(format!(
+                "voice weight {} for '{}' must be between 0.1 and 1.0 in steps of 0.1",
+                part.weight, part.id
+            ));
+        }
+        blend.push((part.id.clone(), steps.round() as u32));
+    }
+    Ok(format(&blend))
+}
+
+/// Voices and weights, in tenths, of a blend string; `None` for a plain voice ID.
+///
+/// A blend names up to [`MAX_BLEND_VOICES`] different voices with weights that sum to 1.0.
+pub fn parse(voice: &str) -> ApiResult<Option<Vec<(&str, u32)>>> {
+    if !voice.contains(['+', '.']) {
+        return Ok(None);
+    }
+    let mut parts: Vec<(&str, u32)> = Vec::new();
+    for part in voice.split('+') {
+        let (id, weight) = part
+            .trim()
+            .split_once('.')
+            .and_then(|(id, weight)| Some((id, weight.parse::<u32>().ok()?)))
+            .filter(|(id, weight)| !id.is_empty() && (1..=WEIGHT_STEPS).contains(weight))
+            .ok_or_else(|| {
+                AppError::invalid_request(format!(
+                    "voice blend part '{}' must be a voice and a weight in tenths, such as 'af_heart.6'",
+                    part
+                ))
+            })?;
+        if parts.iter().any(|(seen, _)| seen.eq_ignore_ascii_case(id)) {
+            return Err(AppError::invalid_request(format!(
+                "voice blend names '{}' more than once",
+                id
+            )));
+        }
+        parts.push((id, weight));
+    }
+    if parts.len() > MAX_BLEND_VOICES {
+        return Err(AppError::invalid_request(format!(
+            "voice blend may mix at most {} voices",
+            MAX_BLEND_VOICES
+        )));
+    }
+    let total: u32 = parts.iter().map(|(_, weight)| weight).sum();
+    if total != WEIGHT_STEPS {
+        return Err(AppError::invalid_request(format!(
+            "voice blend weights must sum to 1.0, got {:.1}",
+            total as f32 / WEIGHT_STEPS as f32
+        )));
+    }
+    Ok(Some(parts))
+}
+
+/// Kokoro's blend string for voices and weights in tenths: `af_heart.6+am_adam.4` for 0.6
+/// and 0.4. A single voice at full weight is just its ID.
+pub fn format(parts: &[(String, u32)]) -> String {
+    if let [(id, WEIGHT_STEPS)] = parts {
+        return id.clone();
+    }
+    parts
+        .iter()
+        .map(|(id, weight)| format!("{}.{}", id, weight))
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Request {
+        #[serde(default, deserialize_with = "deserialize_voice")]
+        voice: Option<String>,
+    }
+
+    fn voice(json: &str) -> Result<Option<String>, serde_json::Error> {
+        serde_json::from_str::<Request>(json).map(|request| request.voice)
+    }
+
+    #[test]
+    fn test_structured_blend_becomes_blend_string() {
+        assert_eq!(
+            voice(r#"{"voice": [{"id": "af_heart", "weight": 0.6}, {"id": "am_adam", "weight": 0.4}]}"#)
+                .unwrap()
+                .as_deref(),
+            Some("af_heart.6+am_adam.4")
+        );
+        assert_eq!(
+            voice(r#"{"voice": [{"id": "af_heart", "weight": 1.0}]}"#)
+                .unwrap()
+                .as_deref(),
+            Some("af_heart")
+        );
+        assert_eq!(
+            voice(r#"{"voice": "af_heart"}"#).unwrap().as_deref(),
+            Some("af_heart")
+        );
+        assert_eq!(voice("{}").unwrap(), None);
+        assert!(voice(r#"{"voice": [{"id": "af_heart", "weight": 0.65}]}"#).is_err());
+        // Checked when the request is validated
+        assert_eq!(
+            voice(r#"{"voice": [{"id": "af_heart", "weight": 0.6}]}"#)
+                .unwrap()
+                .as_deref(),
+            Some("af_heart.6")
+        );
+    }
+
+    #[test]
+    fn test_parse_blend() {
+        assert_eq!(parse("af_heart").unwrap(), None);
+        assert_eq!(
+            parse("af_heart.6+am_adam.4").unwrap(),
+            Some(vec![("af_heart", 6), ("am_adam", 4)])
+        );
+        // Weights must sum to 1.0
+        assert!(parse("af_heart.6+am_adam.6").is_err());
+        assert!(parse("af_heart.6").is_err());
+        assert_eq!(parse("af_heart.10").unwrap(), Some(vec![("af_heart", 10)]));
+        assert!(parse("af_heart.6+am_adam").is_err());
+        assert!(parse("af_heart.5+af_heart.5").is_err());
+        assert!(parse("a.2+b.2+c.2+d.2+e.2").is_err());
+        assert_eq!(
+            format(&[("af_heart".to_string(), 6), ("am_adam".to_string(), 4)]),
+            "af_heart.6+am_adam.4"
+        );
+        assert_eq!(format(&[("af_heart".to_string(), 10)]), "af_heart");
+    }
+}
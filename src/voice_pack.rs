@@ -0,0 +1,190 @@
+use anyhow::{bail, Context, Result};
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+/// Length of a voice style vector, fixed by the Kokoro v1.0 model
+const STYLE_DIM: usize = 256;
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Names of the voices in a Kokoro voice pack, after checking its layout.
+///
+/// The pack (`voices.json` on disk, despite the name) is a NumPy `.npz` archive
+/// holding one little-endian float32 array of shape `(N, 1, 256)` per voice.
+/// Only the array headers are read, so this is cheap enough to run before
+/// every model load.
+pub fn voice_names(path: &Path) -> Result<Vec<String>> {
+    let file = File::open(path).with_context(|| format!("cannot open {}", path.display()))?;
+    read_voice_names(file)
+}
+
+fn read_voice_names<R: Read + Seek>(reader: R) -> Result<Vec<String>> {
+    let mut archive = zip::ZipArchive::new(reader).context("not a NumPy .npz archive")?;
+
+    let mut names = Vec::with_capacity(archive.len());
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        let Some(name) = entry.name().strip_suffix(".npy").map(str::to_string) else {
+            bail!("unexpected archive entry '{}'", entry.name());
+        };
+        let size = entry.size();
+        check_voice_array(&mut entry, size).with_context(|| format!("voice '{}'", name))?;
+        names.push(name);
+    }
+
+    if names.is_empty() {
+        bail!("archive contains no voices");
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Check an `.npy` header describes a float32 `(N, 1, 256)` array and the data is complete
+fn check_voice_array(entry: &mut impl Read, size: u64) -> Result<()> {
+    let mut preamble = [0u8; 8];
+    entry
+        .read_exact(&mut preamble)
+        .context("truncated array header")?;
+    if &preamble[..6] != NPY_MAGIC {
+        bail!("not a NumPy array");
+    }
+
+    // Format 1.0 uses a 2-byte header length, 2.0 and later a 4-byte one
+    let (header_len, prefix_len) = if preamble[6] == 1 {
+        let mut len = [0u8; 2];
+        entry
+            .read_exact(&mut len)
+            .context("truncated array header")?;
+        (usize::from(u16::from_le_bytes(len)), 10)
+    } else {
+        let mut len = [0u8; 4];
+        entry
+            .read_exact(&mut len)
+            .context("truncated array header")?;
+        (u32::from_le_bytes(len) as usize, 12)
+    };
+    let mut header = vec![0u8; header_len];
+    entry
+        .read_exact(&mut header)
+        .context("truncated array header")?;
+    let header = String::from_utf8_lossy(&header);
+
+    let descr = dict_value(&header, "descr").context("array header has no dtype")?;
+    if descr.trim_matches(['\'', '"']) != "<f4" {
+        bail!("expected float32 values, found dtype {}", descr);
+    }
+    if dict_value(&header, "fortran_order") == Some("True") {
+        bail!("array is stored in Fortran order");
+    }
+    let shape = parse_shape(&header).context("array header has no valid shape")?;
+    if shape.len() != 3 || shape[1] != 1 || shape[2] != STYLE_DIM || shape[0] == 0 {
+        bail!("expected shape (N, 1, {}), found {:?}", STYLE_DIM, shape);
+    }
+
+    let expected = (prefix_len + header_len + shape.iter().product::<usize>() * 4) as u64;
+    if size != expected {
+        bail!(
+            "array data is {} bytes, expected {}; the file may be truncated",
+            size,
+            expected
+        );
+    }
+    Ok(())
+}
+
+/// Raw value of `key` in the Python dict literal of an `.npy` header
+fn dict_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let start = header
+        .find(&format!("'{}':", key))
+        .map(|at| at + key.len() + 3)?;
+    let rest = header[start..].trim_start();
+    let end = rest.find([',', '}']).unwrap_or(rest.len());
+    Some(rest[..end].trim())
+}
+
+fn parse_shape(header: &str) -> Option<Vec<usize>> {
+    let start = header.find("'shape':")? + "'shape':".len();
+    let rest = header[start..].trim_start().strip_prefix('(')?;
+    let dims = &rest[..rest.find(')')?];
+    dims.split(',')
+        .map(str::trim)
+        .filter(|dim| !dim.is_empty())
+        .map(|dim| dim.parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+
+    /// `.npy` bytes for a zero-filled array; `values` may differ from the shape to simulate truncation
+    fn npy(descr: &str, shape: &str, values: usize) -> Vec<u8> {
+        let mut header = format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+            descr, shape
+        );
+        while (10 + header.len() + 1) % 64 != 0 {
+            header.push(' ');
+        }
+        header.push('\n');
+
+        let mut bytes = NPY_MAGIC.to_vec();
+        bytes.extend([1, 0]);
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        bytes.extend(vec![0u8; values * 4]);
+        bytes
+    }
+
+    fn npz(entries: &[(&str, Vec<u8>)]) -> Cursor<Vec<u8>> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(data).unwrap();
+        }
+        Cursor::new(writer.finish().unwrap().into_inner())
+    }
+
+    #[test]
+    fn test_valid_voice_pack() {
+        let pack = npz(&[
+            ("bm_george.npy", npy("<f4", "(2, 1, 256)", 512)),
+            ("af_heart.npy", npy("<f4", "(2, 1, 256)", 512)),
+        ]);
+        assert_eq!(
+            read_voice_names(pack).unwrap(),
+            vec!["af_heart".to_string(), "bm_george".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_invalid_voice_pack() {
+        let error = |pack| format!("{:#}", read_voice_names(pack).unwrap_err());
+
+        assert!(error(Cursor::new(b"{\"af_heart\": []}".to_vec())).contains("not a NumPy .npz"));
+        assert!(error(npz(&[])).contains("no voices"));
+        assert!(error(npz(&[("readme.txt", Vec::new())])).contains("unexpected archive entry"));
+        assert!(
+            error(npz(&[("af_heart.npy", npy("<f8", "(2, 1, 256)", 512))])).contains("float32")
+        );
+        assert!(
+            error(npz(&[("af_heart.npy", npy("<f4", "(2, 256)", 512))])).contains("expected shape")
+        );
+        assert!(
+            error(npz(&[("af_heart.npy", npy("<f4", "(2, 1, 256)", 100))])).contains("truncated")
+        );
+    }
+
+    #[test]
+    fn test_parse_shape() {
+        let header = "{'descr': '<f4', 'fortran_order': False, 'shape': (510, 1, 256), }";
+        assert_eq!(parse_shape(header), Some(vec![510, 1, 256]));
+        assert_eq!(dict_value(header, "descr"), Some("'<f4'"));
+        assert_eq!(parse_shape("{'shape': (7,), }"), Some(vec![7]));
+        assert_eq!(parse_shape("{'shape': (a, b), }"), None);
+    }
+}
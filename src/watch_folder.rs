@@ -0,0 +1,353 @@
+use crate::{
+    api::{encode_audio, validate_speech_request_within, AppState, SpeechRequest},
+    audiobook::synthesize_chapter,
+    backend::AudioData,
+    chapters::{split_front_matter, split_markdown, split_text},
+    load_shed::admit_batch,
+    redact,
+};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info, warn};
+
+/// Audio format when neither the front matter nor its preset picks one
+const DEFAULT_WATCH_FORMAT: &str = "mp3";
+
+/// Settings read from a file's front matter; other keys such as `title` or `tags` are ignored
+#[derive(Debug, Default, PartialEq)]
+struct NoteOptions {
+    voice: Option<String>,
+    speed: Option<f32>,
+    preset: Option<String>,
+    response_format: Option<String>,
+    compressor: Option<String>,
+    language: Option<String>,
+}
+
+/// Identifies a source file version without reading it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+/// Decides which files to synthesize from successive directory scans
+#[derive(Debug, Default)]
+struct Tracker {
+    /// Stamps from the previous scan
+    pending: HashMap<PathBuf, FileStamp>,
+    /// Stamps already synthesized or given up on
+    handled: HashMap<PathBuf, FileStamp>,
+}
+
+impl Tracker {
+    /// Files unchanged since the previous scan and not handled at that version. Waiting a scan
+    /// keeps half-written files from being read.
+    fn ready(&mut self, scan: HashMap<PathBuf, FileStamp>) -> Vec<PathBuf> {
+        let mut ready = scan
+            .iter()
+            .filter(|(path, stamp)| {
+                self.pending.get(*path) == Some(*stamp) && self.handled.get(*path) != Some(*stamp)
+            })
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        ready.sort();
+
+        self.handled.retain(|path, _| scan.contains_key(path));
+        for path in &ready {
+            self.handled.insert(path.clone(), scan[path]);
+        }
+        self.pending = scan;
+        ready
+    }
+}
+
+/// Synthesize `.txt` and `.md` files dropped into `dir` in the background, writing the audio
+/// beside each file
+pub fn start(state: AppState, dir: PathBuf, interval: Duration) {
+    info!(
+        "  Watch directory: {} (every {:?})",
+        dir.display(),
+        interval
+    );
+    tokio::spawn(run(state, dir, interval));
+}
+
+async fn run(state: AppState, dir: PathBuf, interval: Duration) {
+    let mut tracker = Tracker::default();
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        // Files are left for a later scan while interactive requests need the capacity
+        if admit_batch(&state).is_err() {
+            debug!("Load shedding; deferring the watch directory scan");
+            continue;
+        }
+        let scan = match scan(&dir).await {
+            Ok(scan) => scan,
+            Err(e) => {
+                warn!("Failed to scan watch directory: {:#}", e);
+                continue;
+            }
+        };
+        for path in tracker.ready(scan) {
+            process(&state, &path).await;
+        }
+    }
+}
+
+/// Stamps of the `.txt` and `.md` files directly inside `dir`
+async fn scan(dir: &Path) -> Result<HashMap<PathBuf, FileStamp>> {
+    let mut entries = tokio::fs::read_dir(dir)
+        .await
+        .with_context(|| format!("Failed to read {}", dir.display()))?;
+    let mut files = HashMap::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if source_kind(&path).is_none() {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata().await else {
+            continue;
+        };
+        if metadata.is_file() {
+            files.insert(
+                path,
+                FileStamp {
+                    len: metadata.len(),
+                    modified: metadata.modified().ok(),
+                },
+            );
+        }
+    }
+    Ok(files)
+}
+
+/// Whether a watched file is Markdown (`true`) or plain text (`false`); `None` for other files
+fn source_kind(path: &Path) -> Option<bool> {
+    let extension = path.extension()?.to_str()?;
+    if extension.eq_ignore_ascii_case("md") || extension.eq_ignore_ascii_case("markdown") {
+        Some(true)
+    } else if extension.eq_ignore_ascii_case("txt") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+async fn process(state: &AppState, path: &Path) {
+    let started = std::time::Instant::now();
+    match synthesize_file(state, path).await {
+        Ok(Some(output)) => info!(
+            source = %path.display(),
+            output = %output.display(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Watched file synthesized"
+        ),
+        Ok(None) => debug!(source = %path.display(), "Watched file audio is up to date"),
+        Err(e) => warn!(
+            source = %path.display(),
+            error = %redact::message(&format!("{:#}", e)),
+            "Failed to synthesize watched file"
+        ),
+    }
+}
+
+/// Synthesize one file unless its audio is newer; returns the audio path when written
+async fn synthesize_file(state: &AppState, path: &Path) -> Result<Option<PathBuf>> {
+    let document = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let (front_matter, body) = split_front_matter(&document);
+    let options = front_matter
+        .map(parse_front_matter)
+        .transpose()?
+        .unwrap_or_default();
+    let chapters = if source_kind(path) == Some(true) {
+        split_markdown(body)
+    } else {
+        split_text(body)
+    };
+    if chapters.is_empty() {
+        anyhow::bail!("File has no text to read");
+    }
+
+    let document = chapters
+        .iter()
+        .map(|chapter| chapter.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let params = validate_speech_request_within(
+        state,
+        SpeechRequest {
+            model: "kokoro".to_string(),
+            input: document,
+            voice: options.voice,
+            response_format: options.response_format.or_else(|| {
+                options
+                    .preset
+                    .is_none()
+                    .then(|| DEFAULT_WATCH_FORMAT.to_string())
+            }),
+            speed: options.speed,
+            preset: options.preset,
+            compressor: options.compressor,
+            language: options.language,
+            ..Default::default()
+        },
+        state.max_document_chars,
+    )?;
+
+    let output = path.with_extension(&params.format);
+    if is_up_to_date(path, &output).await {
+        return Ok(None);
+    }
+
+    // Shares the long-document runner with audiobook jobs
+    let _slot = state.jobs.run_slot().await?;
+    let source = path.display().to_string();
+    let mut audio = AudioData {
+        samples: Vec::new(),
+        sample_rate: state.backend.sample_rate(),
+    };
+    for (index, chapter) in chapters.iter().enumerate() {
+        let chapter = synthesize_chapter(state, &source, index, &chapter.text, &params).await?;
+        audio.sample_rate = chapter.sample_rate;
+        audio.samples.extend_from_slice(&chapter.samples);
+    }
+
+    // Written beside the output and renamed so readers never see a partial file
+    let mut part_path = output.as_os_str().to_owned();
+    part_path.push(".part");
+    let part_path = PathBuf::from(part_path);
+    let format = params.format.clone();
+    let bit_depth = params.bit_depth;
+    let target = output.clone();
+    tokio::task::spawn_blocking(move || -> Result<()> {
+        let encoded = encode_audio(&format, &audio, bit_depth)?;
+        std::fs::write(&part_path, &encoded.bytes)
+            .with_context(|| format!("Failed to write {}", part_path.display()))?;
+        std::fs::rename(&part_path, &target)
+            .with_context(|| format!("Failed to move audio to {}", target.display()))
+    })
+    .await
+    .context("Encoding task panicked")??;
+    Ok(Some(output))
+}
+
+/// Whether `output` exists and was written after `source` last changed
+async fn is_up_to_date(source: &Path, output: &Path) -> bool {
+    let modified = |path: &Path| {
+        let path = path.to_path_buf();
+        async move { tokio::fs::metadata(path).await.and_then(|m| m.modified()) }
+    };
+    match (modified(source).await, modified(output).await) {
+        (Ok(source), Ok(output)) => output >= source,
+        _ => false,
+    }
+}
+
+/// Read `key: value` lines from a front matter block
+fn parse_front_matter(block: &str) -> Result<NoteOptions> {
+    let mut options = NoteOptions::default();
+    for line in block.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().trim_matches(['"', '\'']).to_string();
+        match key.trim() {
+            "voice" => options.voice = Some(value),
+            "speed" => {
+                options.speed = Some(
+                    value
+                        .parse()
+                        .with_context(|| format!("Invalid speed '{}' in front matter", value))?,
+                )
+            }
+            "preset" => options.preset = Some(value),
+            "response_format" | "format" => options.response_format = Some(value),
+            "compressor" => options.compressor = Some(value),
+            "language" | "lang" => options.language = Some(value),
+            _ => {}
+        }
+    }
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_front_matter() {
+        let options = parse_front_matter(
+            "title: Weekly notes\nvoice: \"bf_emma\"\nspeed: 1.2\n# comment\nformat: wav\nlang: en-gb\ntags: [a, b]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            options,
+            NoteOptions {
+                voice: Some("bf_emma".to_string()),
+                speed: Some(1.2),
+                response_format: Some("wav".to_string()),
+                language: Some("en-gb".to_string()),
+                ..Default::default()
+            }
+        );
+        assert!(parse_front_matter("speed: fast").is_err());
+    }
+
+    #[test]
+    fn test_source_kind() {
+        assert_eq!(source_kind(Path::new("notes/today.md")), Some(true));
+        assert_eq!(source_kind(Path::new("notes/today.TXT")), Some(false));
+        assert_eq!(source_kind(Path::new("notes/today.mp3")), None);
+        assert_eq!(source_kind(Path::new("notes/README")), None);
+    }
+
+    #[test]
+    fn test_tracker_waits_for_stable_files() {
+        let stamp = |len| FileStamp {
+            len,
+            modified: None,
+        };
+        let path = PathBuf::from("note.md");
+        let mut tracker = Tracker::default();
+
+        // Seen once, then unchanged on the next scan
+        assert!(tracker
+            .ready(HashMap::from([(path.clone(), stamp(10))]))
+            .is_empty());
+        assert_eq!(
+            tracker.ready(HashMap::from([(path.clone(), stamp(10))])),
+            vec![path.clone()]
+        );
+        assert!(tracker
+            .ready(HashMap::from([(path.clone(), stamp(10))]))
+            .is_empty());
+
+        // An edit is picked up once it settles
+        assert!(tracker
+            .ready(HashMap::from([(path.clone(), stamp(20))]))
+            .is_empty());
+        assert_eq!(
+            tracker.ready(HashMap::from([(path.clone(), stamp(20))])),
+            vec![path.clone()]
+        );
+
+        // A deleted and recreated file is synthesized again
+        assert!(tracker.ready(HashMap::new()).is_empty());
+        tracker.ready(HashMap::from([(path.clone(), stamp(20))]));
+        assert_eq!(
+            tracker.ready(HashMap::from([(path.clone(), stamp(20))])),
+            vec![path]
+        );
+    }
+}